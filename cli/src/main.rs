@@ -91,6 +91,21 @@ fn main() {
     if assert_content {
         compiler.enable_content_assertions();
     }
+    if matches.opt_present("report") {
+        match compiler.compile_corpus(schema, &mut schemas) {
+            Ok(report) => {
+                println!("{report:#}");
+                if !report.is_ok() {
+                    process::exit(2);
+                }
+                process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("error reading directory {schema}: {e}");
+                process::exit(1);
+            }
+        }
+    }
     let sch = match compiler.compile(schema, &mut schemas) {
         Ok(sch) => {
             println!("schema {schema}: ok");
@@ -201,6 +216,11 @@ fn options() -> Options {
         "<FILE>",
     );
     opts.optflag("k", "insecure", "Use insecure TLS connection");
+    opts.optflag(
+        "",
+        "report",
+        "Compile all schemas in directory SCHEMA and print report as json",
+    );
     opts
 }
 
@@ -0,0 +1,212 @@
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+use crate::{util::*, *};
+
+/// Message payload schema found by [`Compiler::compile_asyncapi`].
+#[derive(Debug, Clone)]
+pub struct AsyncApiPayload {
+    /// absolute location of the message object.
+    pub message: String,
+    /// `name` of the message, or else its key in `messages` map.
+    pub name: Option<String>,
+    /// index of the compiled payload schema.
+    pub sch_index: SchemaIndex,
+}
+
+impl Compiler {
+    /**
+    Compiles payload schemas of all messages in AsyncAPI 2.x/3.x document
+    at `loc`.
+
+    Messages are collected from `components/messages` and from channels,
+    i.e. `publish`/`subscribe` messages including their `oneOf` for 2.x and
+    `messages` for 3.x, following `$ref` of message objects. Each message
+    is reported once, in document order.
+
+    Schema objects are compiled as draft-07, unless the document says
+    otherwise using `$schema`. AsyncAPI specific keywords such as
+    `discriminator`, `externalDocs` and `deprecated` are treated as
+    annotations. Payloads in other schema formats, such as Avro or RAML,
+    are skipped. Both `schemaFormat` of 2.x message and Multi Format Schema
+    Object of 3.x are recognized.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let doc = json!({
+        "asyncapi": "2.6.0",
+        "info": {"title": "users", "version": "1.0.0"},
+        "channels": {
+            "user/signedup": {
+                "subscribe": {
+                    "message": {
+                        "name": "UserSignedUp",
+                        "payload": {
+                            "type": "object",
+                            "required": ["email"],
+                            "properties": {"email": {"type": "string"}}
+                        }
+                    }
+                }
+            }
+        }
+    });
+    compiler.add_resource("asyncapi.json", doc)?;
+    let payloads = compiler.compile_asyncapi("asyncapi.json", &mut schemas)?;
+    assert_eq!(payloads[0].name.as_deref(), Some("UserSignedUp"));
+
+    let event = json!({"email": "john@example.com"});
+    assert!(schemas.validate(&event, payloads[0].sch_index).is_ok());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn compile_asyncapi(
+        &mut self,
+        loc: &str,
+        target: &mut Schemas,
+    ) -> Result<Vec<AsyncApiPayload>, CompileError> {
+        let uf = UrlFrag::absolute(loc)?;
+        let invalid = |src: &str| CompileError::InvalidAsyncApi {
+            url: uf.url.to_string(),
+            src: src.into(),
+        };
+        let doc = self.roots.loader.load(&uf.url)?.clone();
+        let v3 = match doc.get("asyncapi").and_then(Value::as_str) {
+            Some(version) if version.starts_with("2.") => false,
+            Some(version) if version.starts_with("3.") => true,
+            Some(version) => return Err(invalid(&format!("unsupported version {version:?}"))),
+            None => return Err(invalid("asyncapi field missing")),
+        };
+
+        // schema objects are draft-07 superset
+        if self.roots.get(&uf.url).is_none() {
+            let default_draft = self.roots.default_draft;
+            self.roots.default_draft = Draft::V7.internal();
+            let result = self.roots.or_load(uf.url.clone());
+            self.roots.default_draft = default_draft;
+            result?;
+        }
+
+        // collect messages --
+        let mut messages = vec![]; // (ptr, key)
+        let root = JsonPointer::from("");
+        if let Some(Value::Object(channels)) = doc.get("channels") {
+            let channels_ptr = root.append("channels");
+            for (cname, channel) in channels {
+                let ptr = channels_ptr.append(cname.as_str());
+                if v3 {
+                    if let Some(Value::Object(map)) = channel.get("messages") {
+                        let ptr = ptr.append("messages");
+                        for (mname, _) in map {
+                            messages.push((ptr.append(mname.as_str()), Some(mname)));
+                        }
+                    }
+                    continue;
+                }
+                for op in ["publish", "subscribe"] {
+                    let Some(msg) = channel.get(op).and_then(|op| op.get("message")) else {
+                        continue;
+                    };
+                    let ptr = ptr.append2(op, "message");
+                    match msg.get("oneOf") {
+                        Some(Value::Array(list)) => {
+                            for i in 0..list.len() {
+                                messages.push((ptr.append2("oneOf", &i.to_string()), None));
+                            }
+                        }
+                        _ => messages.push((ptr, None)),
+                    }
+                }
+            }
+        }
+        if let Some(Value::Object(map)) = doc.pointer("/components/messages") {
+            let ptr = root.append2("components", "messages");
+            for (mname, _) in map {
+                messages.push((ptr.append(mname.as_str()), Some(mname)));
+            }
+        }
+
+        // compile payloads --
+        let mut payloads = vec![];
+        let mut seen = HashSet::new();
+        for (ptr, key) in messages {
+            let (mut up, mut key) = (
+                UrlPtr {
+                    url: uf.url.clone(),
+                    ptr,
+                },
+                key.cloned(),
+            );
+            // follow $ref
+            let mut msg = self.roots.loader.load(&up.url)?.clone();
+            msg = up.ptr.lookup(&msg, &up.url)?.clone();
+            let mut refs = HashSet::new();
+            while let Some(Value::String(ref_)) = msg.get("$ref") {
+                let ref_ = ref_.clone();
+                let ref_uf = UrlFrag::join(&up.url, &ref_)?;
+                let Fragment::JsonPointer(ptr) = ref_uf.frag else {
+                    return Err(invalid(&format!(
+                        "message $ref {ref_:?} is not json-pointer"
+                    )));
+                };
+                up = UrlPtr {
+                    url: ref_uf.url,
+                    ptr,
+                };
+                if !refs.insert(up.to_string()) {
+                    return Err(invalid(&format!("$ref cycle at {up}")));
+                }
+                key =
+                    up.ptr.as_str().rsplit('/').next().and_then(|tok| {
+                        JsonPointer::unescape(tok).ok().map(|tok| tok.into_owned())
+                    });
+                let doc = self.roots.loader.load(&up.url)?;
+                msg = up.ptr.lookup(doc, &up.url)?.clone();
+            }
+            if !seen.insert(up.to_string()) {
+                continue;
+            }
+            let Some(payload) = msg.get("payload") else {
+                continue;
+            };
+            let mut format = msg.get("schemaFormat").and_then(Value::as_str);
+            let mut payload_ptr = up.ptr.append("payload");
+            if v3 && payload.get("schema").is_some() {
+                if let Some(Value::String(f)) = payload.get("schemaFormat") {
+                    format = Some(f);
+                    payload_ptr = payload_ptr.append("schema");
+                }
+            }
+            if !format.map_or(true, is_json_schema_format) {
+                continue;
+            }
+            let loc = UrlPtr {
+                url: up.url.clone(),
+                ptr: payload_ptr,
+            };
+            let sch_index = self.compile(&loc.to_string(), target)?;
+            let name = msg.get("name").and_then(Value::as_str).map(str::to_owned);
+            payloads.push(AsyncApiPayload {
+                message: up.to_string(),
+                name: name.or(key),
+                sch_index,
+            });
+        }
+        Ok(payloads)
+    }
+}
+
+// tells whether payloads in `schemaFormat` can be compiled.
+fn is_json_schema_format(format: &str) -> bool {
+    let format = format.replace(' ', "");
+    format.starts_with("application/vnd.aai.asyncapi")
+        || format.starts_with("application/schema+json;version=draft-07")
+        || format.starts_with("application/schema+yaml;version=draft-07")
+}
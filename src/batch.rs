@@ -0,0 +1,82 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use serde_json::Value;
+
+use crate::{SchemaIndex, Schemas, ValidationError};
+
+impl Schemas {
+    /**
+    Validates each of `instances` with schema identified by `sch_index`,
+    returning results in the same order.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let sch_index = compiler.compile_value(json!({"type": "string"}), &mut schemas)?;
+    let instances = [json!("a"), json!(1)];
+    let results = schemas.validate_all(&instances, sch_index);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn validate_all<'s, 'v>(
+        &'s self,
+        instances: &'v [Value],
+        sch_index: SchemaIndex,
+    ) -> Vec<Result<(), ValidationError<'s, 'v>>> {
+        instances
+            .iter()
+            .map(|v| self.validate(v, sch_index))
+            .collect()
+    }
+
+    /**
+    Same as [`Schemas::validate_all`], but validates instances
+    concurrently using [rayon](https://docs.rs/rayon).
+
+    Validation errors cannot be sent across threads, so errors are
+    collected by validating failed instances once more on the calling
+    thread. This is fast when most instances are valid.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    #[cfg(feature = "rayon")]
+    pub fn validate_all_parallel<'s, 'v>(
+        &'s self,
+        instances: &'v [Value],
+        sch_index: SchemaIndex,
+    ) -> Vec<Result<(), ValidationError<'s, 'v>>> {
+        use crate::{validator, ValidationOptions};
+
+        let Some(sch) = self.list.get(sch_index.0) else {
+            panic!("Schemas::validate: schema index out of bounds");
+        };
+        let options = ValidationOptions::default();
+        let valid = instances
+            .par_iter()
+            .map(|v| validator::is_valid(v, sch, self, &options))
+            .collect::<Vec<_>>();
+        instances
+            .iter()
+            .zip(valid)
+            .map(|(v, valid)| match valid {
+                true => Ok(()),
+                false => self.validate(v, sch_index),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,111 @@
+use serde_json::Value;
+
+// keywords supported by MongoDB `$jsonSchema`.
+pub(crate) const MONGODB_KEYWORDS: [&str; 29] = [
+    "additionalItems",
+    "additionalProperties",
+    "allOf",
+    "anyOf",
+    "bsonType",
+    "dependencies",
+    "description",
+    "enum",
+    "exclusiveMaximum",
+    "exclusiveMinimum",
+    "items",
+    "maximum",
+    "maxItems",
+    "maxLength",
+    "maxProperties",
+    "minimum",
+    "minItems",
+    "minLength",
+    "minProperties",
+    "multipleOf",
+    "not",
+    "oneOf",
+    "pattern",
+    "patternProperties",
+    "properties",
+    "required",
+    "title",
+    "type",
+    "uniqueItems",
+];
+
+// aliases accepted by `bsonType`.
+pub(crate) const BSON_TYPES: [&str; 21] = [
+    "double",
+    "string",
+    "object",
+    "array",
+    "binData",
+    "undefined",
+    "objectId",
+    "bool",
+    "date",
+    "null",
+    "regex",
+    "dbPointer",
+    "javascript",
+    "symbol",
+    "int",
+    "timestamp",
+    "long",
+    "decimal",
+    "minKey",
+    "maxKey",
+    "number",
+];
+
+/// Returns BSON type alias of `v`, recognizing
+/// [MongoDB Extended JSON](https://www.mongodb.com/docs/manual/reference/mongodb-extended-json/).
+pub(crate) fn bson_type(v: &Value) -> &'static str {
+    match v {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(n) => {
+            if n.as_i64().is_some_and(|n| i32::try_from(n).is_ok()) {
+                "int"
+            } else if n.is_i64() {
+                "long"
+            } else {
+                "double"
+            }
+        }
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(obj) => {
+            let mut keys = obj.keys();
+            let wrapper = match (keys.next(), keys.next(), keys.next()) {
+                (Some(k), None, None) => k.as_str(),
+                (Some(k1), Some(k2), None) if k1 == "$code" || k2 == "$code" => "$code",
+                _ => "",
+            };
+            match wrapper {
+                "$oid" => "objectId",
+                "$date" => "date",
+                "$binary" => "binData",
+                "$regularExpression" => "regex",
+                "$timestamp" => "timestamp",
+                "$numberInt" => "int",
+                "$numberLong" => "long",
+                "$numberDouble" => "double",
+                "$numberDecimal" => "decimal",
+                "$minKey" => "minKey",
+                "$maxKey" => "maxKey",
+                "$code" => "javascript",
+                "$symbol" => "symbol",
+                "$undefined" => "undefined",
+                "$dbPointer" => "dbPointer",
+                _ => "object",
+            }
+        }
+    }
+}
+
+// checks whether `v` is of bson type `alias`.
+pub(crate) fn is_bson_type(v: &Value, alias: &str) -> bool {
+    let t = bson_type(v);
+    t == alias || (alias == "number" && matches!(t, "int" | "long" | "double" | "decimal"))
+}
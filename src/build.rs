@@ -0,0 +1,174 @@
+use std::{
+    collections::HashSet,
+    env,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+use url::Url;
+
+use crate::*;
+
+/**
+Compiles schema files from build script, failing the build if any of them
+is invalid.
+
+Each schema file is bundled with the documents it references, using
+[`Compiler::bundle`], and written to `$OUT_DIR/boon/<file-name>`. The
+bundle gets `$id` of its file url, if it does not have one, so it
+can be compiled using [`Compiler::compile_value`] without any file access.
+
+File urls in bundles are replaced with `boon://<package>/<path>`, where
+path is relative to the common directory of the crate and all schema
+files used. So host paths do not leak into binaries, and builds are
+reproducible.
+Use [`include_schema!`](crate::include_schema) to embed it.
+
+Only the bundled json is embedded, not the compiled schema. The build
+script guarantees that it compiles, but the crate still parses and
+compiles it at runtime, with the same cost as any other schema.
+Compile it once, and reuse the [`Schemas`].
+
+`cargo:rerun-if-changed` is printed for every local file used, so that
+the build script reruns when any of them changes.
+
+In `build.rs`:
+
+```no_run
+# use std::error::Error;
+# fn main() -> Result<(), Box<dyn Error>> {
+let mut compiler = boon::Compiler::new();
+boon::build_schemas(&mut compiler, &["schemas/person.json"])?;
+# Ok(())
+# }
+```
+
+In crate:
+
+```ignore
+let mut schemas = boon::Schemas::new();
+let mut compiler = boon::Compiler::new();
+let schema = serde_json::from_str(boon::include_schema!("person.json"))?;
+let sch_index = compiler.compile_value(schema, &mut schemas)?;
+```
+
+# Errors
+
+returns error if `OUT_DIR` is not set, if two schema files have same
+name, or if any schema fails to compile.
+*/
+#[cfg(not(target_arch = "wasm32"))]
+pub fn build_schemas<P: AsRef<Path>>(
+    compiler: &mut Compiler,
+    paths: &[P],
+) -> Result<(), Box<dyn Error>> {
+    let out_dir = env::var_os("OUT_DIR").ok_or("OUT_DIR not set, call from build script")?;
+    let out_dir = Path::new(&out_dir).join("boon");
+    fs::create_dir_all(&out_dir)?;
+
+    let mut schemas = Schemas::new();
+    let mut names = HashSet::new();
+    let mut bundles = vec![];
+    for path in paths {
+        let path = path.as_ref();
+        let Some(name) = path.file_name() else {
+            Err(format!("{} is not a file", path.display()))?
+        };
+        if !names.insert(name.to_owned()) {
+            Err(format!("duplicate schema file name {}", path.display()))?;
+        }
+        let sch_index = compiler
+            .compile_file(path, &mut schemas)
+            .map_err(|e| format!("{}: {e:#}", path.display()))?;
+        let url = UrlFrag::absolute(schemas.location(sch_index))?.url;
+        let mut bundle = compiler.bundle(url.as_str())?;
+        if let Value::Object(obj) = &mut bundle {
+            let id = compiler.roots.get(&url).map_or("$id", |root| root.draft.id);
+            if !obj.contains_key(id) {
+                obj.insert(id.to_owned(), Value::String(url.to_string()));
+            }
+        }
+        bundles.push((name.to_owned(), bundle));
+    }
+
+    // file urls are replaced, so that host paths do not leak into binaries
+    let files = schemas
+        .docs
+        .keys()
+        .filter_map(|url| url.to_file_path().ok())
+        .collect::<Vec<_>>();
+    let mut base = env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from);
+    for file in &files {
+        let dir = file.parent().unwrap_or(file);
+        base = Some(match base {
+            Some(base) => common_ancestor(&base, dir),
+            None => dir.to_owned(),
+        });
+    }
+    let base = base.and_then(|base| Url::from_directory_path(base).ok());
+    let pkg = env::var("CARGO_PKG_NAME").unwrap_or_else(|_| "build".to_owned());
+    for (name, mut bundle) in bundles {
+        if let Some(base) = &base {
+            stable_ids(&mut bundle, base.as_str(), &format!("boon://{pkg}/"));
+        }
+        fs::write(out_dir.join(name), serde_json::to_string(&bundle)?)?;
+    }
+
+    for path in files {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+    Ok(())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn common_ancestor(a: &Path, b: &Path) -> PathBuf {
+    a.components()
+        .zip(b.components())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a)
+        .collect()
+}
+
+// replaces `prefix` of urls in `v` with `replacement`.
+#[cfg(not(target_arch = "wasm32"))]
+fn stable_ids(v: &mut Value, prefix: &str, replacement: &str) {
+    match v {
+        Value::Object(obj) => {
+            for (kw, v) in obj.iter_mut() {
+                match v {
+                    Value::String(s)
+                        if matches!(
+                            kw.as_str(),
+                            "$id" | "id" | "$ref" | "$dynamicRef" | "$recursiveRef"
+                        ) =>
+                    {
+                        if let Some(rel) = s.strip_prefix(prefix) {
+                            *s = format!("{replacement}{rel}");
+                        }
+                    }
+                    _ => stable_ids(v, prefix, replacement),
+                }
+            }
+        }
+        Value::Array(arr) => arr
+            .iter_mut()
+            .for_each(|v| stable_ids(v, prefix, replacement)),
+        _ => {}
+    }
+}
+
+/**
+Returns contents of schema bundle generated by [`build_schemas`], as
+`&'static str`.
+
+The argument is the file name of schema passed to [`build_schemas`].
+The returned json must still be parsed and compiled at runtime.
+*/
+#[macro_export]
+macro_rules! include_schema {
+    ($name:literal) => {
+        include_str!(concat!(env!("OUT_DIR"), "/boon/", $name))
+    };
+}
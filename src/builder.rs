@@ -0,0 +1,199 @@
+use std::error::Error;
+
+use serde_json::Value;
+
+use crate::*;
+
+/**
+Builder for [`Compiler`], to configure it in one expression.
+
+Each method corresponds to a setter on [`Compiler`], which documents
+it in detail.
+
+```
+# use boon::*;
+let compiler = Compiler::builder()
+    .default_draft(Draft::V2020_12)
+    .assert_format(true)
+    .format_assertion("hostname", false)
+    .loader(Box::new(FileLoader))
+    .build();
+```
+*/
+#[derive(Default)]
+pub struct CompilerBuilder {
+    compiler: Compiler,
+}
+
+impl Compiler {
+    /// Returns [`CompilerBuilder`] with default configuration.
+    pub fn builder() -> CompilerBuilder {
+        CompilerBuilder::default()
+    }
+}
+
+impl CompilerBuilder {
+    /// see [`Compiler::set_default_draft`].
+    pub fn default_draft(mut self, d: Draft) -> Self {
+        self.compiler.set_default_draft(d);
+        self
+    }
+
+    /// see [`Compiler::enable_format_assertions`].
+    pub fn assert_format(mut self, enable: bool) -> Self {
+        self.compiler.assert_format = enable;
+        self
+    }
+
+    /// see [`Compiler::enable_format_assertion`] and [`Compiler::disable_format_assertion`].
+    pub fn format_assertion(mut self, format: &str, enable: bool) -> Self {
+        match enable {
+            true => self.compiler.enable_format_assertion(format),
+            false => self.compiler.disable_format_assertion(format),
+        }
+        self
+    }
+
+    /// see [`Compiler::enable_content_assertions`].
+    pub fn assert_content(mut self, enable: bool) -> Self {
+        self.compiler.assert_content = enable;
+        self
+    }
+
+    /// see [`Compiler::enable_data_refs`].
+    pub fn data_refs(mut self, enable: bool) -> Self {
+        self.compiler.roots.data = enable;
+        self
+    }
+
+    /// see [`Compiler::use_regex_engine`].
+    pub fn regex_engine(mut self, engine: RegexEngine) -> Self {
+        self.compiler.use_regex_engine(engine);
+        self
+    }
+
+    /// see [`Compiler::set_compile_limits`].
+    pub fn compile_limits(mut self, limits: CompileLimits) -> Self {
+        self.compiler.set_compile_limits(limits);
+        self
+    }
+
+    /// see [`Compiler::set_regex_limits`].
+    pub fn regex_limits(mut self, limits: RegexLimits) -> Self {
+        self.compiler.set_regex_limits(limits);
+        self
+    }
+
+    /// see [`Compiler::enable_offline`].
+    pub fn offline(mut self, enable: bool) -> Self {
+        self.compiler.roots.loader.offline = enable;
+        self
+    }
+
+    /// see [`Compiler::disable_metaschema_validation`].
+    pub fn metaschema_validation(mut self, enable: bool) -> Self {
+        self.compiler.roots.validate = enable;
+        self
+    }
+
+    /// see [`Compiler::enable_strict_mode`].
+    pub fn strict_mode(mut self, enable: bool) -> Self {
+        self.compiler.strict = enable;
+        self
+    }
+
+    /// see [`Compiler::enable_ref_inlining`].
+    pub fn ref_inlining(mut self, enable: bool) -> Self {
+        self.compiler.inline_refs = enable;
+        self
+    }
+
+    /// see [`Compiler::enable_subschema_dedup`].
+    pub fn subschema_dedup(mut self, enable: bool) -> Self {
+        self.compiler.dedup_subschemas = enable;
+        self
+    }
+
+    /// see [`Compiler::enable_lazy_compilation`].
+    pub fn lazy_compilation(mut self, enable: bool) -> Self {
+        self.compiler.lazy = enable;
+        self
+    }
+
+    /// see [`Compiler::allow_keyword`].
+    pub fn allow_keyword(mut self, keyword: &str) -> Self {
+        self.compiler.allow_keyword(keyword);
+        self
+    }
+
+    /// see [`Compiler::use_loader`].
+    pub fn loader(mut self, url_loader: Box<dyn UrlLoader>) -> Self {
+        self.compiler.use_loader(url_loader);
+        self
+    }
+
+    /// see [`Compiler::set_load_policy`].
+    pub fn load_policy(mut self, scheme: &str, policy: LoadPolicy) -> Self {
+        self.compiler.set_load_policy(scheme, policy);
+        self
+    }
+
+    /// see [`Compiler::use_dialect`].
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.compiler.use_dialect(dialect);
+        self
+    }
+
+    /// see [`Compiler::register_format`].
+    pub fn format(mut self, format: Format) -> Self {
+        self.compiler.register_format(format);
+        self
+    }
+
+    /// see [`Compiler::register_format_fn`].
+    pub fn format_fn<F>(mut self, name: &'static str, func: F) -> Self
+    where
+        F: Fn(&Value) -> Result<(), Box<dyn Error>> + Send + Sync + 'static,
+    {
+        self.compiler.register_format_fn(name, func);
+        self
+    }
+
+    /// see [`Compiler::register_keyword`].
+    pub fn keyword<F>(mut self, name: &'static str, phase: Phase, compile: F) -> Self
+    where
+        F: Fn(&Value) -> Result<Box<dyn Keyword>, Box<dyn Error>> + Send + Sync + 'static,
+    {
+        self.compiler.register_keyword(name, phase, compile);
+        self
+    }
+
+    /// see [`Compiler::set_email_options`].
+    pub fn email_options(mut self, opts: EmailOptions) -> Self {
+        self.compiler.set_email_options(opts);
+        self
+    }
+
+    /// see [`Compiler::set_hostname_options`].
+    pub fn hostname_options(mut self, opts: HostnameOptions) -> Self {
+        self.compiler.set_hostname_options(opts);
+        self
+    }
+
+    /// see [`Compiler::register_content_encoding`].
+    pub fn content_encoding(mut self, decoder: Decoder) -> Self {
+        self.compiler.register_content_encoding(decoder);
+        self
+    }
+
+    /// see [`Compiler::register_content_media_type`].
+    pub fn content_media_type(mut self, media_type: MediaType) -> Self {
+        self.compiler.register_content_media_type(media_type);
+        self
+    }
+
+    /// Returns the [`Compiler`] configured.
+    pub fn build(self) -> Compiler {
+        self.compiler
+    }
+}
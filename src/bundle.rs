@@ -0,0 +1,382 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+use url::Url;
+
+use crate::{compiler::CompileError, util::*, Compiler};
+
+/// Result of [`Compiler::dereference`].
+#[derive(Debug, Clone)]
+pub struct Dereferenced {
+    /// schema with references inlined.
+    pub schema: Value,
+    /// json-pointers in `schema` of cyclic references, which are not inlined.
+    pub cycles: Vec<String>,
+}
+
+// external document to be embedded in bundle.
+struct Doc {
+    url: Url,
+    id: Url, // `$id` of the document, in bundle
+    value: Value,
+    refs: Vec<(JsonPointer, &'static str, UrlFrag)>, // external refs
+}
+
+impl Compiler {
+    /**
+    Returns a self-contained schema document, with all the documents
+    referenced from `loc` embedded into it.
+
+    Each external document referenced, directly or indirectly, is placed
+    under `$defs` (`definitions` for drafts before 2019-09) of the root
+    document, with `$id` set to its absolute url. `$ref` and `$dynamicRef`
+    values are rewritten only where they no longer resolve to the embedded
+    document, so the resulting document compiles without any [`UrlLoader`]
+    and validates exactly like the original.
+
+    Documents are loaded the same way as in [`Compiler::compile`], so
+    resources added via [`Compiler::add_resource`] are used. Standard
+    metaschemas are not embedded, since they are always available.
+
+    Note that for drafts before 2019-09, embedded documents are interpreted
+    using the draft of the root document.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://example.com/person.json", json!({
+        "properties": {"address": {"$ref": "address.json"}}
+    }))?;
+    compiler.add_resource("http://example.com/address.json", json!({
+        "required": ["city"]
+    }))?;
+    let bundle = compiler.bundle("http://example.com/person.json")?;
+
+    // compiles with no remote documents
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://example.com/person.json", bundle)?;
+    let sch_index = compiler.compile("http://example.com/person.json", &mut schemas)?;
+    let instance = json!({"address": {}});
+    assert!(schemas.validate(&instance, sch_index).is_err());
+    # Ok(())
+    # }
+    ```
+
+    [`UrlLoader`]: crate::UrlLoader
+    */
+    pub fn bundle(&mut self, loc: &str) -> Result<Value, CompileError> {
+        let url = UrlFrag::absolute(loc)?.url;
+        let mut docs = vec![self.bundle_doc(url)?];
+        let mut i = 0;
+        while i < docs.len() {
+            let urls = docs[i]
+                .refs
+                .iter()
+                .map(|(_, _, uf)| uf.url.clone())
+                .collect::<Vec<_>>();
+            for url in urls {
+                if !docs.iter().any(|doc| doc.url == url) {
+                    docs.push(self.bundle_doc(url)?);
+                }
+            }
+            i += 1;
+        }
+
+        // rewrite refs to use ids of embedded documents
+        let ids = docs
+            .iter()
+            .map(|doc| (doc.url.clone(), doc.id.clone()))
+            .collect::<HashMap<_, _>>();
+        for doc in &mut docs {
+            for (ptr, kw, uf) in &doc.refs {
+                let id = &ids[&uf.url];
+                if *id == uf.url {
+                    continue;
+                }
+                if let Some(Value::Object(obj)) = doc.value.pointer_mut(ptr.as_str()) {
+                    let ref_ = UrlFrag::format(id, uf.frag.as_str());
+                    obj.insert(kw.to_string(), Value::String(ref_));
+                }
+            }
+        }
+
+        let mut docs = docs.into_iter();
+        let Some(root) = docs.next() else {
+            return Err(CompileError::Bug("bundle root missing".into()));
+        };
+        let mut bundle = root.value;
+        if docs.len() == 0 {
+            return Ok(bundle);
+        }
+
+        let Some(root_draft) = self.roots.get(&root.url).map(|r| r.draft) else {
+            return Err(CompileError::Bug("or_load didn't add".into()));
+        };
+        let Value::Object(obj) = &mut bundle else {
+            return Err(CompileError::Bug("boolean schema with refs".into()));
+        };
+        let defs_kw = match root_draft.version {
+            2019.. => "$defs",
+            _ => "definitions",
+        };
+        let defs = obj
+            .entry(defs_kw)
+            .or_insert_with(|| Value::Object(Map::new()));
+        let Value::Object(defs) = defs else {
+            return Err(CompileError::Bug(format!("{defs_kw} is not object").into()));
+        };
+        for doc in docs {
+            let Some(draft) = self.roots.get(&doc.url).map(|r| r.draft) else {
+                return Err(CompileError::Bug("or_load didn't add".into()));
+            };
+            let mut obj = match doc.value {
+                Value::Object(obj) => obj,
+                Value::Bool(true) => Map::new(),
+                _ => Map::from_iter([("not".to_owned(), Value::Object(Map::new()))]),
+            };
+            obj.remove(draft.id);
+            obj.insert(root_draft.id.to_owned(), Value::String(doc.id.to_string()));
+            if root_draft.version >= 2019 && draft.version != root_draft.version {
+                obj.entry("$schema")
+                    .or_insert_with(|| Value::String(draft.url.to_owned()));
+            }
+            defs.insert(def_name(&doc.url, defs), Value::Object(obj));
+        }
+        Ok(bundle)
+    }
+
+    /**
+    Returns schema at `loc` with all references inlined, for tools which
+    cannot handle references at all.
+
+    `$ref`, `$dynamicRef` and `$recursiveRef` are replaced with the
+    schema they resolve to, treating dynamic references as static. When
+    `$ref` has sibling keywords (draft 2019-09 onwards), the referenced
+    schema is appended to `allOf` instead. `$defs`, `definitions` and
+    identifiers such as `$id` and `$anchor` of inlined schemas are removed,
+    since nothing refers to them. References to standard metaschemas are
+    left as is.
+
+    A reference to a schema which is being inlined, i.e. a cycle, cannot
+    be inlined. It is rewritten as `$ref` to the location where that schema
+    is inlined, and reported in [`Dereferenced::cycles`].
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_resource("tree.json", json!({
+        "properties": {
+            "name": {"$ref": "#/$defs/name"},
+            "children": {"items": {"$ref": "#"}}
+        },
+        "$defs": {"name": {"type": "string"}}
+    }))?;
+    let deref = compiler.dereference("tree.json")?;
+    assert_eq!(deref.schema, json!({
+        "properties": {
+            "name": {"type": "string"},
+            "children": {"items": {"$ref": "#"}}
+        }
+    }));
+    assert_eq!(deref.cycles, ["/properties/children/items"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn dereference(&mut self, loc: &str) -> Result<Dereferenced, CompileError> {
+        let uf = UrlFrag::absolute(loc)?;
+        let up = self.roots.resolve_fragment(uf)?;
+        let mut stack = vec![(up.clone(), JsonPointer::from(""))];
+        let mut cycles = vec![];
+        let schema = self.inline(&up, &mut stack, &mut cycles)?;
+        Ok(Dereferenced { schema, cycles })
+    }
+
+    // returns schema at `up` with refs inlined. `stack` has schemas being
+    // inlined along with their location in result, last being `up`.
+    fn inline(
+        &mut self,
+        up: &UrlPtr,
+        stack: &mut Vec<(UrlPtr, JsonPointer)>,
+        cycles: &mut Vec<String>,
+    ) -> Result<Value, CompileError> {
+        self.roots.ensure_subschema(up)?;
+        let Some(root) = self.roots.get(&up.url) else {
+            return Err(CompileError::Bug("or_load didn't add".into()));
+        };
+        let draft = root.draft;
+        let doc = self.roots.loader.load(&up.url)?;
+        let mut v = up.ptr.lookup(doc, &up.url)?.clone();
+
+        // subschemas, skipping those in $defs and those ignored for $ref
+        let mut ptrs = vec![];
+        draft.collect_subschemas(&v, JsonPointer::from(""), &mut ptrs);
+        let mut skip = vec![];
+        for ptr in &ptrs {
+            let Some(Value::Object(obj)) = v.pointer(ptr.as_str()) else {
+                continue;
+            };
+            for kw in ["$defs", "definitions"] {
+                if obj.contains_key(kw) {
+                    skip.push(format!("{ptr}/{kw}/"));
+                }
+            }
+            if draft.version < 2019 && obj.contains_key("$ref") {
+                skip.push(format!("{ptr}/"));
+            }
+        }
+        ptrs.retain(|ptr| {
+            let ptr = format!("{ptr}/");
+            !skip
+                .iter()
+                .any(|prefix| ptr.starts_with(prefix) && ptr != *prefix)
+        });
+
+        let mut refs = vec![];
+        for ptr in &ptrs {
+            let Some(Value::Object(obj)) = v.pointer(ptr.as_str()) else {
+                continue;
+            };
+            for kw in ["$ref", "$dynamicRef", "$recursiveRef"] {
+                let Some(Value::String(ref_)) = obj.get(kw) else {
+                    continue;
+                };
+                let uf = UrlFrag::join(root.base_url(&up.ptr.concat(ptr)), ref_)?;
+                if matches!(uf.url.host_str(), Some("json-schema.org")) {
+                    continue;
+                }
+                refs.push((ptr.clone(), kw, uf));
+            }
+        }
+
+        // remove keywords which are no longer needed
+        let top = stack.len() == 1;
+        for ptr in &ptrs {
+            if let Some(Value::Object(obj)) = v.pointer_mut(ptr.as_str()) {
+                if !top || !ptr.is_empty() {
+                    obj.remove(draft.id);
+                    obj.remove("$schema");
+                }
+                for kw in [
+                    "$defs",
+                    "definitions",
+                    "$anchor",
+                    "$dynamicAnchor",
+                    "$recursiveAnchor",
+                ] {
+                    obj.remove(kw);
+                }
+            }
+        }
+
+        // refs are in post-order, so that replacing one does not affect others
+        let base = stack
+            .last()
+            .map_or_else(|| "".into(), |(_, loc)| loc.clone());
+        for (ptr, kw, uf) in refs {
+            let target = match self.roots.get(&up.url).map(|root| root.resolve(&uf)) {
+                Some(Ok(Some(target))) => target,
+                Some(Err(e)) => return Err(e),
+                _ => self.roots.resolve_fragment(uf)?,
+            };
+            let Some(Value::Object(obj)) = v.pointer_mut(ptr.as_str()) else {
+                continue;
+            };
+            obj.remove(kw);
+
+            let loc = base.concat(&ptr);
+            if let Some((_, target_loc)) = stack.iter().find(|(up, _)| *up == target) {
+                let ref_ = format!("#{}", Fragment::encode(target_loc.as_str()));
+                obj.insert("$ref".to_owned(), Value::String(ref_));
+                cycles.push(loc.to_string());
+                continue;
+            }
+            if obj.is_empty() || draft.version < 2019 {
+                stack.push((target.clone(), loc));
+                let inlined = self.inline(&target, stack, cycles)?;
+                stack.pop();
+                if let Some(sch) = v.pointer_mut(ptr.as_str()) {
+                    *sch = inlined;
+                }
+            } else {
+                let all_of = obj.entry("allOf").or_insert_with(|| Value::Array(vec![]));
+                let Value::Array(all_of) = all_of else {
+                    continue;
+                };
+                let loc = loc.append2("allOf", &all_of.len().to_string());
+                all_of.push(Value::Null);
+                stack.push((target.clone(), loc));
+                let inlined = self.inline(&target, stack, cycles)?;
+                stack.pop();
+                if let Some(Value::Object(obj)) = v.pointer_mut(ptr.as_str()) {
+                    if let Some(Value::Array(all_of)) = obj.get_mut("allOf") {
+                        if let Some(last) = all_of.last_mut() {
+                            *last = inlined;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(v)
+    }
+
+    fn bundle_doc(&mut self, url: Url) -> Result<Doc, CompileError> {
+        self.roots.or_load(url.clone())?;
+        let Some(root) = self.roots.get(&url) else {
+            return Err(CompileError::Bug("or_load didn't add".into()));
+        };
+        let value = self.roots.loader.load(&url)?.clone();
+        let mut ptrs = vec![];
+        root.draft
+            .collect_subschemas(&value, JsonPointer::from(""), &mut ptrs);
+
+        let mut refs = vec![];
+        for ptr in ptrs {
+            let Some(Value::Object(obj)) = value.pointer(ptr.as_str()) else {
+                continue;
+            };
+            for kw in ["$ref", "$dynamicRef"] {
+                let Some(Value::String(ref_)) = obj.get(kw) else {
+                    continue;
+                };
+                let uf = UrlFrag::join(root.base_url(&ptr), ref_)?;
+                if matches!(uf.url.host_str(), Some("json-schema.org")) {
+                    continue;
+                }
+                if root.resolve(&uf)?.is_none() {
+                    refs.push((ptr.clone(), kw, uf));
+                }
+            }
+        }
+        Ok(Doc {
+            id: root.base_url(&JsonPointer::from("")).clone(),
+            url,
+            value,
+            refs,
+        })
+    }
+}
+
+// returns unused name in `defs`, derived from last path segment of `url`.
+fn def_name(url: &Url, defs: &Map<String, Value>) -> String {
+    let name = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .map(|s| s.split_once('.').map_or(s, |(name, _)| name))
+        .filter(|s| !s.is_empty())
+        .unwrap_or("schema");
+    let mut unique = name.to_owned();
+    let mut i = 1;
+    while defs.contains_key(&unique) {
+        i += 1;
+        unique = format!("{name}{i}");
+    }
+    unique
+}
@@ -0,0 +1,215 @@
+use serde_json::{Map, Value};
+
+use crate::{util::*, *};
+
+// annotations removed by `Compiler::canonicalize`. `default`, `deprecated`,
+// `readOnly` and `writeOnly` are not removed, since they affect behavior.
+const DOC_ANNOTATIONS: [&str; 4] = ["title", "description", "$comment", "examples"];
+
+impl Compiler {
+    /**
+    Returns schema document at `loc` in canonical form, so that equivalent
+    documents can be hashed or diffed.
+
+    The canonical form:
+    - has keys of all objects sorted
+    - has `$ref` and `$dynamicRef` resolved to absolute urls
+    - spells keywords, which have equivalent forms, in one way:
+      - `type` with single type is string, otherwise sorted array
+      - `enum` with single value is `const`, in draft-06 onwards
+      - `required` is sorted, without duplicates
+      - `definitions` is `$defs` and `dependencies` is `dependentRequired`
+        and `dependentSchemas`, in draft 2019-09 onwards
+      - `$id` has no empty fragment
+    - has no `title`, `description`, `$comment` and `examples`, if
+      `strip_annotations` is true
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://example.com/a.json", json!({
+        "title": "person",
+        "properties": {"name": {"$ref": "#/definitions/name"}},
+        "definitions": {"name": {"type": ["string"]}}
+    }))?;
+    let canonical = compiler.canonicalize("http://example.com/a.json", true)?;
+    assert_eq!(canonical, json!({
+        "$defs": {"name": {"type": "string"}},
+        "properties": {"name": {"$ref": "http://example.com/a.json#/$defs/name"}}
+    }));
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn canonicalize(
+        &mut self,
+        loc: &str,
+        strip_annotations: bool,
+    ) -> Result<Value, CompileError> {
+        let url = UrlFrag::absolute(loc)?.url;
+        self.roots.or_load(url.clone())?;
+        let Some(root) = self.roots.get(&url) else {
+            return Err(CompileError::Bug("or_load didn't add".into()));
+        };
+        let version = root.draft.version;
+        let mut v = self.roots.loader.load(&url)?.clone();
+        let mut ptrs = vec![];
+        root.draft
+            .collect_subschemas(&v, JsonPointer::from(""), &mut ptrs);
+
+        // locations of schemas whose definitions are renamed
+        let mut renames = vec![];
+        if version >= 2019 {
+            for ptr in &ptrs {
+                if let Some(Value::Object(obj)) = v.pointer(ptr.as_str()) {
+                    if obj.contains_key("definitions") && !conflicts(obj, "definitions", "$defs") {
+                        renames.push(ptr.as_str());
+                    }
+                }
+            }
+        }
+        // maps location before renames, to location after them
+        let relocate = |ptr: &str| -> String {
+            let (mut old, mut new) = (String::new(), String::new());
+            for tok in ptr.split('/').skip(1) {
+                let renamed = tok == "definitions" && renames.contains(&old.as_str());
+                new.push('/');
+                new.push_str(if renamed { "$defs" } else { tok });
+                old.push('/');
+                old.push_str(tok);
+            }
+            new
+        };
+
+        let mut refs = vec![];
+        for ptr in &ptrs {
+            let Some(Value::Object(obj)) = v.pointer(ptr.as_str()) else {
+                continue;
+            };
+            for kw in ["$ref", "$dynamicRef"] {
+                let Some(Value::String(ref_)) = obj.get(kw) else {
+                    continue;
+                };
+                let uf = UrlFrag::join(root.base_url(ptr), ref_)?;
+                let new = match (&uf.frag, root.resolve(&uf)?) {
+                    (Fragment::JsonPointer(_), Some(target)) => {
+                        let res = root.resource(&target.ptr);
+                        let (res_ptr, ptr) =
+                            (relocate(res.ptr.as_str()), relocate(target.ptr.as_str()));
+                        UrlFrag::format(&res.id, &ptr[res_ptr.len()..])
+                    }
+                    _ => UrlFrag::format(&uf.url, uf.frag.as_str()),
+                };
+                refs.push((ptr.clone(), kw, new));
+            }
+        }
+
+        // subschemas are in post-order, so that editing one does not
+        // affect location of others
+        let draft_id = root.draft.id;
+        let mut refs = refs.into_iter().peekable();
+        for ptr in &ptrs {
+            let Some(Value::Object(obj)) = v.pointer_mut(ptr.as_str()) else {
+                continue;
+            };
+            while let Some((_, kw, new)) = refs.next_if(|(p, _, _)| p == ptr) {
+                obj.insert(kw.to_owned(), Value::String(new));
+            }
+            if let Some(Value::String(id)) = obj.get_mut(draft_id) {
+                if id.ends_with('#') {
+                    id.pop();
+                }
+            }
+            canonicalize_keywords(obj, version);
+            if strip_annotations {
+                for kw in DOC_ANNOTATIONS {
+                    obj.remove(kw);
+                }
+            }
+        }
+        sort_keys(&mut v);
+        Ok(v)
+    }
+}
+
+// tells whether entries of `kw` cannot be merged into `target`.
+fn conflicts(obj: &Map<String, Value>, kw: &str, target: &str) -> bool {
+    match (obj.get(kw), obj.get(target)) {
+        (_, None) => false,
+        (Some(Value::Object(m1)), Some(Value::Object(m2))) => m1.keys().any(|k| m2.contains_key(k)),
+        _ => true,
+    }
+}
+
+fn canonicalize_keywords(obj: &mut Map<String, Value>, version: usize) {
+    if let Some(Value::Array(types)) = obj.get_mut("type") {
+        if types.iter().all(Value::is_string) {
+            types.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+            types.dedup();
+            if types.len() == 1 {
+                let t = types.remove(0);
+                obj.insert("type".into(), t);
+            }
+        }
+    }
+    if let Some(Value::Array(required)) = obj.get_mut("required") {
+        if required.iter().all(Value::is_string) {
+            required.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+            required.dedup();
+        }
+    }
+    if version >= 6 && !obj.contains_key("const") {
+        if let Some(Value::Array(values)) = obj.get("enum") {
+            if let [value] = values.as_slice() {
+                let value = value.clone();
+                obj.remove("enum");
+                obj.insert("const".into(), value);
+            }
+        }
+    }
+    if version < 2019 {
+        return;
+    }
+    if !conflicts(obj, "definitions", "$defs") {
+        if let Some(Value::Object(defs)) = obj.remove("definitions") {
+            if let Value::Object(m) = obj.entry("$defs").or_insert_with(|| Map::new().into()) {
+                m.extend(defs);
+            }
+        }
+    }
+    let deps_conflict = conflicts(obj, "dependencies", "dependentRequired")
+        || conflicts(obj, "dependencies", "dependentSchemas");
+    if !deps_conflict && matches!(obj.get("dependencies"), Some(Value::Object(_))) {
+        if let Some(Value::Object(deps)) = obj.remove("dependencies") {
+            for (name, dep) in deps {
+                let kw = match dep {
+                    Value::Array(_) => "dependentRequired",
+                    _ => "dependentSchemas",
+                };
+                if let Value::Object(m) = obj.entry(kw).or_insert_with(|| Map::new().into()) {
+                    m.insert(name, dep);
+                }
+            }
+        }
+    }
+}
+
+// sorts keys of all objects in `v`, which matters only with `preserve_order`
+// feature of serde_json.
+fn sort_keys(v: &mut Value) {
+    match v {
+        Value::Object(obj) => {
+            let mut entries = std::mem::take(obj).into_iter().collect::<Vec<_>>();
+            entries.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
+            for (k, mut v) in entries {
+                sort_keys(&mut v);
+                obj.insert(k, v);
+            }
+        }
+        Value::Array(arr) => arr.iter_mut().for_each(sort_keys),
+        _ => {}
+    }
+}
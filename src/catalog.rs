@@ -0,0 +1,97 @@
+use serde_json::Value;
+use url::Url;
+
+use crate::{util::*, *};
+
+impl Compiler {
+    /**
+    Adds mappings of schema urls to local files or embedded schemas,
+    from catalog file at `path`.
+
+    The catalog is json object of the form:
+
+    ```json
+    {
+        "uri": {
+            "https://example.com/person.json": "schemas/person.json",
+            "https://example.com/name.json": {"type": "string"}
+        },
+        "rewriteUri": {
+            "https://schemas.example.com/": "mirror/"
+        }
+    }
+    ```
+
+    - `uri` maps a url to the file or url it is loaded from. If the value
+      is an object or boolean, it is the schema itself, and is added
+      using [`Compiler::add_resource`].
+    - `rewriteUri` maps url prefixes, like [`Compiler::map_url_prefix`].
+
+    Relative paths are resolved against the location of the catalog. The
+    mappings are applied lazily, when the url is first loaded, and the
+    document loaded is still identified by its original url.
+
+    # Errors
+
+    returns [`CompileError`] if catalog cannot be loaded, or is invalid.
+    */
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn add_catalog(&mut self, path: impl AsRef<std::path::Path>) -> Result<(), CompileError> {
+        let path = path.as_ref();
+        let url = UrlFrag::absolute(&path.to_string_lossy())?.url;
+        let catalog = FileLoader
+            .load(url.as_str())
+            .map_err(|src| CompileError::LoadUrlError {
+                url: url.to_string(),
+                src,
+            })?;
+        self.add_catalog_value(&url, &catalog)
+            .map_err(|src| CompileError::LoadUrlError {
+                url: url.to_string(),
+                src,
+            })
+    }
+
+    fn add_catalog_value(
+        &mut self,
+        base: &Url,
+        catalog: &Value,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let Value::Object(obj) = catalog else {
+            Err("catalog must be object")?
+        };
+        for (key, v) in obj {
+            let Value::Object(entries) = v else {
+                Err(format!("{key} must be object"))?
+            };
+            match key.as_str() {
+                "uri" => {
+                    for (from, to) in entries {
+                        let from = Url::parse(from)?;
+                        match to {
+                            Value::String(to) => {
+                                let to = base.join(to)?;
+                                let aliases = &mut self.roots.loader.url_aliases;
+                                aliases.insert(from.into(), to.into());
+                            }
+                            Value::Object(_) | Value::Bool(_) => {
+                                self.add_resource(from.as_str(), to.clone())?;
+                            }
+                            _ => Err(format!("invalid uri entry {from}"))?,
+                        }
+                    }
+                }
+                "rewriteUri" => {
+                    for (from, to) in entries {
+                        let Value::String(to) = to else {
+                            Err(format!("invalid rewriteUri entry {from}"))?
+                        };
+                        self.map_url_prefix(from, base.join(to)?.as_str())?;
+                    }
+                }
+                _ => Err(format!("unknown catalog entry {key}"))?,
+            }
+        }
+        Ok(())
+    }
+}
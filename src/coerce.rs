@@ -0,0 +1,174 @@
+use serde_json::{Number, Value};
+
+use crate::{defaults::*, util::*, *};
+
+impl Schemas {
+    /**
+    Coerces values in `v` to the `type` expected by schema identified by
+    `sch_index`, and returns json-pointers of the locations coerced.
+
+    This is useful for validating query-string or form data, which
+    arrives as strings. Coercion follows ajv's `coerceTypes: "array"`:
+
+    | from                 | to        | example                         |
+    |----------------------|-----------|---------------------------------|
+    | string, boolean, null| `number`  | `"4.5"` → `4.5`, `true` → `1`   |
+    | string, boolean, null| `integer` | `"42"` → `42`, `null` → `0`     |
+    | number, boolean, null| `string`  | `42` → `"42"`, `null` → `""`    |
+    | string, number, null | `boolean` | `"true"` → `true`, `0` → `false`|
+    | string, number, boolean | `null` | `""`, `0`, `false` → `null`     |
+    | scalar               | `array`   | `"a"` → `["a"]`                 |
+
+    A value is coerced only if it does not match any of the expected types.
+    Types are tried in the order `number`, `integer`, `string`, `boolean`,
+    `null`, `array`, and the first one applicable is used. Values which
+    cannot be coerced are left as is.
+
+    `type` is taken from the schema and the subschemas it applies
+    unconditionally i.e. `$ref` and `allOf`, recursing into properties and
+    array items.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let schema = json!({
+        "properties": {
+            "page": {"type": "integer"},
+            "tags": {"type": "array", "items": {"type": "string"}}
+        }
+    });
+    compiler.add_resource("query.json", schema)?;
+    let sch_index = compiler.compile("query.json", &mut schemas)?;
+
+    let mut query = json!({"page": "2", "tags": "rust"});
+    let coerced = schemas.coerce_types(&mut query, sch_index);
+    assert_eq!(query, json!({"page": 2, "tags": ["rust"]}));
+    assert_eq!(coerced, ["/page", "/tags"]);
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn coerce_types(&self, v: &mut Value, sch_index: SchemaIndex) -> Vec<String> {
+        let mut coerced = vec![];
+        self.coerce(v, sch_index, &JsonPointer::from(""), &mut coerced);
+        coerced
+    }
+
+    /**
+    Same as [`Schemas::coerce_types`] followed by [`Schemas::validate`]
+    of the resulting `v`.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn validate_coerced<'s, 'v>(
+        &'s self,
+        v: &'v mut Value,
+        sch_index: SchemaIndex,
+    ) -> Result<Vec<String>, ValidationError<'s, 'v>> {
+        let coerced = self.coerce_types(v, sch_index);
+        let v: &'v Value = v;
+        self.validate(v, sch_index).map(|_| coerced)
+    }
+
+    fn coerce(
+        &self,
+        v: &mut Value,
+        sch: SchemaIndex,
+        ptr: &JsonPointer,
+        coerced: &mut Vec<String>,
+    ) {
+        let list = self.applied_schemas(sch);
+        for &sch in &list {
+            let types = self.get(sch).types;
+            if types.is_empty() || matches_type(v, types) {
+                continue;
+            }
+            let to = COERCIBLE
+                .iter()
+                .filter(|t| types.contains(**t))
+                .find_map(|t| coerce_to(v, *t));
+            if let Some(to) = to {
+                *v = to;
+                coerced.push(ptr.to_string());
+            }
+        }
+
+        match v {
+            Value::Object(obj) => {
+                for (pname, pvalue) in obj.iter_mut() {
+                    for &sch in &list {
+                        for psch in prop_schemas(self.get(sch), pname) {
+                            self.coerce(pvalue, psch, &ptr.append(pname), coerced);
+                        }
+                    }
+                }
+            }
+            Value::Array(arr) => {
+                for (i, item) in arr.iter_mut().enumerate() {
+                    for &sch in &list {
+                        if let Some(isch) = item_schema(self, self.get(sch), i) {
+                            self.coerce(item, isch, &ptr.append(&i.to_string()), coerced);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// types to which values are coerced, in order of preference.
+const COERCIBLE: [Type; 6] = [
+    Type::Number,
+    Type::Integer,
+    Type::String,
+    Type::Boolean,
+    Type::Null,
+    Type::Array,
+];
+
+fn matches_type(v: &Value, types: Types) -> bool {
+    types.contains(Type::of(v)) || (types.contains(Type::Integer) && is_integer(v))
+}
+
+// returns `v` coerced to type `t`, if possible.
+fn coerce_to(v: &Value, t: Type) -> Option<Value> {
+    match (t, v) {
+        (Type::Number | Type::Integer, Value::String(s)) => {
+            let n = s.parse::<Number>().ok()?;
+            let n = Value::Number(n);
+            (t == Type::Number || is_integer(&n)).then_some(n)
+        }
+        (Type::Number | Type::Integer, Value::Bool(b)) => Some(Value::from(u8::from(*b))),
+        (Type::Number | Type::Integer, Value::Null) => Some(Value::from(0)),
+        (Type::String, Value::Number(n)) => Some(Value::String(n.to_string())),
+        (Type::String, Value::Bool(b)) => Some(Value::String(b.to_string())),
+        (Type::String, Value::Null) => Some(Value::String(String::new())),
+        (Type::Boolean, Value::String(s)) => match s.as_str() {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        (Type::Boolean, Value::Number(n)) if n.as_f64() == Some(1.0) => Some(Value::Bool(true)),
+        (Type::Boolean, Value::Number(n)) if n.as_f64() == Some(0.0) => Some(Value::Bool(false)),
+        (Type::Boolean, Value::Null) => Some(Value::Bool(false)),
+        (Type::Null, Value::String(s)) if s.is_empty() => Some(Value::Null),
+        (Type::Null, Value::Number(n)) if n.as_f64() == Some(0.0) => Some(Value::Null),
+        (Type::Null, Value::Bool(false)) => Some(Value::Null),
+        (Type::Array, Value::Array(_) | Value::Object(_)) => None,
+        (Type::Array, v) => Some(Value::Array(vec![v.clone()])),
+        _ => None,
+    }
+}
@@ -1,10 +1,21 @@
-use std::{cmp::Ordering, collections::HashMap, error::Error, fmt::Display};
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::Display,
+    sync::{
+        atomic::{AtomicUsize, Ordering as AtomicOrdering},
+        Arc,
+    },
+};
 
-use regex::Regex;
 use serde_json::{Map, Value};
 use url::Url;
 
-use crate::{content::*, draft::*, ecma, formats::*, root::*, roots::*, util::*, *};
+use crate::{
+    bson::*, content::*, data::*, draft::*, formats::*, keyword::*, overlay::*, regexp::*, root::*,
+    roots::*, util::*, *,
+};
 
 /// Supported draft versions
 #[non_exhaustive]
@@ -52,6 +63,43 @@ impl Draft {
         }
     }
 
+    /**
+    Detects [`Draft`] of schema document `doc`.
+
+    The draft is taken from `$schema`, if it refers to standard metaschema.
+    Otherwise, the draft is guessed from the keywords used in `doc` and its
+    subschemas. For example, `prefixItems` implies [`Draft::V2020_12`] and
+    `items` with array value implies a draft before it. The newest draft
+    consistent with the keywords is returned.
+
+    Returns `None`, if no keyword is specific to some drafts.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    let doc = json!({"$schema": "http://json-schema.org/draft-07/schema#"});
+    assert_eq!(Draft::detect(&doc), Some(Draft::V7));
+    let doc = json!({"items": [{"type": "string"}], "additionalItems": false});
+    assert_eq!(Draft::detect(&doc), Some(Draft::V2019_09));
+    let doc = json!({"properties": {"age": {"minimum": 0, "exclusiveMinimum": true}}});
+    assert_eq!(Draft::detect(&doc), Some(Draft::V4));
+    assert_eq!(Draft::detect(&json!({"type": "string"})), None);
+    ```
+    */
+    pub fn detect(doc: &Value) -> Option<Draft> {
+        crate::draft::detect(doc).map(|d| Draft::from_version(d.version))
+    }
+
+    pub(crate) fn from_version(version: usize) -> Draft {
+        match version {
+            4 => Draft::V4,
+            6 => Draft::V6,
+            7 => Draft::V7,
+            2019 => Draft::V2019_09,
+            _ => Draft::V2020_12,
+        }
+    }
+
     pub(crate) fn internal(&self) -> &'static crate::draft::Draft {
         match self {
             Draft::V4 => &DRAFT4,
@@ -73,12 +121,87 @@ impl Default for Draft {
 /// JsonSchema compiler.
 #[derive(Default)]
 pub struct Compiler {
-    roots: Roots,
-    assert_format: bool,
-    assert_content: bool,
-    formats: HashMap<&'static str, Format>,
+    pub(crate) roots: Roots,
+    pub(crate) assert_format: bool,
+    format_assertions: HashMap<String, bool>,
+    pub(crate) assert_content: bool,
+    formats: HashMap<&'static str, CompiledFormat>,
+    pub(crate) keywords: HashMap<&'static str, KeywordDef>, // custom
+    email_options: EmailOptions,
+    regex_engine: RegexEngine,
+    regex_limits: RegexLimits,
+    compile_limits: CompileLimits,
+    hostname_options: HostnameOptions,
     decoders: HashMap<&'static str, Decoder>,
     media_types: HashMap<&'static str, MediaType>,
+    unknown_refs: Vec<UnknownRef>,
+    warnings: Vec<CompileWarning>,
+    ref_anchors: HashSet<(Url, String)>, // anchors referenced
+    reported_anchors: HashSet<(Url, String)>, // unreferenced anchors warned
+    regexes: HashMap<String, Regex>,     // cache
+    overlays: HashMap<Url, Overlay>,
+    pub(crate) dialect: Option<Dialect>,
+    pub(crate) strict: bool,
+    pub(crate) inline_refs: bool,
+    pub(crate) dedup_subschemas: bool,
+    pub(crate) lazy: bool,
+    allowed_keywords: HashSet<String>, // in strict mode
+}
+
+/// Reference to unknown `format`, `contentEncoding` or `contentMediaType`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownRef {
+    /// location of the schema with the reference.
+    pub schema_url: String,
+    /// keyword used for the reference.
+    pub keyword: &'static str,
+    /// name referenced.
+    pub name: String,
+}
+
+/// Non-fatal problem found during compilation, see [`Compiler::take_warnings`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompileWarning {
+    /// Reference to unknown `format`, `contentEncoding` or `contentMediaType`.
+    UnknownRef(UnknownRef),
+    /// `keyword` at `url` is defined by other drafts, but not by `draft`
+    /// used to compile the schema. For example `$defs` in draft-07. Such
+    /// keywords are ignored.
+    IgnoredKeyword {
+        url: String,
+        keyword: String,
+        draft: Draft,
+    },
+    /// `anchor` defined in resource `url` is not referenced by any schema
+    /// compiled so far.
+    UnreferencedAnchor { url: String, anchor: String },
+}
+
+impl Display for CompileWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownRef(r) => write!(
+                f,
+                "unknown {} {} at {}",
+                r.keyword,
+                quote(&r.name),
+                r.schema_url
+            ),
+            Self::IgnoredKeyword {
+                url,
+                keyword,
+                draft,
+            } => write!(
+                f,
+                "keyword {} at {url} is ignored, as it is not defined in {draft:?}",
+                quote(keyword)
+            ),
+            Self::UnreferencedAnchor { url, anchor } => {
+                write!(f, "anchor {} in {url} is never referenced", quote(anchor))
+            }
+        }
+    }
 }
 
 impl Compiler {
@@ -115,6 +238,36 @@ impl Compiler {
         self.assert_format = true;
     }
 
+    /**
+    Always enable assertion of given `format`, irrespective of
+    [`Compiler::enable_format_assertions`] and draft defaults.
+
+    ```
+    # use boon::*;
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertion("date-time");
+    ```
+    */
+    pub fn enable_format_assertion(&mut self, format: &str) {
+        self.format_assertions.insert(format.to_owned(), true);
+    }
+
+    /**
+    Never assert given `format`, i.e. treat it as annotation only,
+    irrespective of [`Compiler::enable_format_assertions`] and draft
+    defaults.
+
+    ```
+    # use boon::*;
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions();
+    compiler.disable_format_assertion("hostname");
+    ```
+    */
+    pub fn disable_format_assertion(&mut self, format: &str) {
+        self.format_assertions.insert(format.to_owned(), false);
+    }
+
     /**
     Always enable content assertions.
 
@@ -129,6 +282,391 @@ impl Compiler {
         self.assert_content = true;
     }
 
+    /**
+    Enables `$data` references, which allow keyword values to be
+    resolved from the instance at validation time.
+
+    `$data` is not part of JSON Schema specification. It follows
+    [ajv](https://ajv.js.org/guide/combining-schemas.html#data-reference),
+    and its value is json-pointer from the instance root, or relative
+    json-pointer from the instance being validated.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_data_refs();
+    let schema = json!({
+        "properties": {
+            "larger": {"minimum": {"$data": "1/smaller"}}
+        }
+    });
+    compiler.add_resource("schema.json", schema)?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+    let instance = json!({"smaller": 5, "larger": 7});
+    assert!(schemas.validate(&instance, sch_index).is_ok());
+    let instance = json!({"smaller": 5, "larger": 3});
+    assert!(schemas.validate(&instance, sch_index).is_err());
+    # Ok(())
+    # }
+    ```
+
+    Supported keywords are `const`, `minimum`, `maximum`, `exclusiveMinimum`,
+    `exclusiveMaximum`, `multipleOf`, `minLength`, `maxLength`, `minItems`,
+    `maxItems`, `minProperties` and `maxProperties`. The keyword is ignored
+    if `$data` resolves to nothing.
+
+    This must be called before compiling schemas which use `$data`,
+    otherwise they fail validation against metaschema.
+    */
+    pub fn enable_data_refs(&mut self) {
+        self.roots.data = true;
+    }
+
+    /**
+    Overrides the regex engine used for `pattern` and `patternProperties`.
+
+    By default [`RegexEngine::Regex`] is used, which fails to compile
+    patterns with lookaround or backreferences.
+
+    Compiled regexes are cached by pattern, and shared by all schemas
+    compiled with this compiler. Changing the engine clears the cache.
+    */
+    pub fn use_regex_engine(&mut self, engine: RegexEngine) {
+        if self.regex_engine != engine {
+            self.regex_engine = engine;
+            self.regexes.clear();
+        }
+    }
+
+    /**
+    Overrides the limits on regexes compiled for `pattern` and
+    `patternProperties`.
+
+    Use this to cap memory and matching time when compiling schemas
+    from untrusted sources. Changing the limits clears the cache of
+    compiled regexes.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.set_regex_limits(RegexLimits {
+        size_limit: 1 << 10,
+        ..Default::default()
+    });
+    compiler.add_resource("schema.json", json!({"pattern": "^\\w{1000}$"})).unwrap();
+    let result = compiler.compile("schema.json", &mut schemas);
+    assert!(matches!(result, Err(CompileError::InvalidRegex { .. })));
+    ```
+    */
+    pub fn set_regex_limits(&mut self, limits: RegexLimits) {
+        if self.regex_limits != limits {
+            self.regex_limits = limits;
+            self.regexes.clear();
+        }
+    }
+
+    /**
+    Sets limits on resources used by single [`Compiler::compile`] call.
+
+    This protects registries compiling schemas from untrusted sources
+    against schemas referencing huge number of schemas or documents.
+    Schemas compiled by earlier calls do not count towards the limits.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.set_compile_limits(CompileLimits {
+        max_schemas: Some(2),
+        ..Default::default()
+    });
+    compiler.add_resource("schema.json", json!({"allOf": [true, true]})).unwrap();
+    let err = compiler.compile("schema.json", &mut schemas).unwrap_err();
+    assert!(matches!(
+        err,
+        CompileError::LimitExceeded { limit: CompileLimit::Schemas(2), .. }
+    ));
+    ```
+    */
+    pub fn set_compile_limits(&mut self, limits: CompileLimits) {
+        self.compile_limits = limits;
+    }
+
+    /**
+    Inlines `$ref`s to leaf schemas within the same document, such as
+    `{"type": "string"}` in `$defs`, to save an indirection during
+    validation.
+
+    The assertions of the target are copied into the schema with `$ref`,
+    provided it has no assertions of its own. Targets with subschemas,
+    references or annotations such as `title` and `default` are not
+    inlined, so that metadata is not affected. Note that errors from
+    inlined assertions are reported at the schema with `$ref`, rather than
+    at the target.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_ref_inlining();
+    compiler.add_resource("http://example.com/schema.json", json!({
+        "properties": {"name": {"$ref": "#/$defs/name"}},
+        "$defs": {"name": {"type": "string"}}
+    })).unwrap();
+    let sch_index = compiler.compile("http://example.com/schema.json", &mut schemas).unwrap();
+    let instance = json!({"name": 1});
+    let err = schemas.validate(&instance, sch_index).unwrap_err();
+    assert_eq!(err.causes[0].schema_url, "http://example.com/schema.json#/properties/name");
+    ```
+    */
+    pub fn enable_ref_inlining(&mut self) {
+        self.inline_refs = true;
+    }
+
+    /**
+    Compiles structurally identical subschemas only once, such as those
+    repeated by schema generators, to save memory.
+
+    A subschema shares the compiled schema of an identical one, found
+    earlier in the same resource. Note that errors from such subschema
+    are reported at the location of the one it shares.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_subschema_dedup();
+    compiler.add_resource("http://example.com/schema.json", json!({
+        "properties": {
+            "first": {"type": "string"},
+            "last": {"type": "string"}
+        }
+    })).unwrap();
+    let sch_index = compiler.compile("http://example.com/schema.json", &mut schemas).unwrap();
+    let instance = json!({"last": 1});
+    let err = schemas.validate(&instance, sch_index).unwrap_err();
+    assert_eq!(err.causes[0].schema_url, "http://example.com/schema.json#/properties/first");
+    ```
+    */
+    pub fn enable_subschema_dedup(&mut self) {
+        self.dedup_subschemas = true;
+    }
+
+    /**
+    Defers compilation of `$ref` targets, such as rarely used `oneOf`
+    branches, until validation reaches them, to reduce startup time for
+    large documents.
+
+    Subschemas not compiled yet are reported by [`Schemas`] as empty.
+    Validation reaching them aborts with [`ErrorKind::NotCompiled`].
+    Use [`Compiler::validate_lazily`] instead, which compiles them on
+    first use.
+    */
+    pub fn enable_lazy_compilation(&mut self) {
+        self.lazy = true;
+    }
+
+    /**
+    Validates `v` with schema identified by `sch_index`, compiling the
+    subschemas deferred by [`Compiler::enable_lazy_compilation`] as
+    validation reaches them.
+
+    Validation is retried after each such compilation, and once more if
+    `v` is invalid, to return the errors. Once every subschema needed is
+    compiled, this is as fast as [`Schemas::validate_with`].
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_lazy_compilation();
+    compiler.add_resource("http://example.com/schema.json", json!({
+        "oneOf": [{"type": "string"}, {"$ref": "#/$defs/cold"}],
+        "$defs": {"cold": {"type": "integer"}}
+    }))?;
+    let sch_index = compiler.compile("http://example.com/schema.json", &mut schemas)?;
+    let options = ValidationOptions::default();
+    assert!(compiler.validate_lazily(&json!("x"), sch_index, &mut schemas, &options)?.is_ok());
+    assert!(compiler.validate_lazily(&json!(1), sch_index, &mut schemas, &options)?.is_ok());
+    assert!(compiler.validate_lazily(&json!(true), sch_index, &mut schemas, &options)?.is_err());
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    returns [`CompileError`] if compilation of any subschema fails.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for `schemas`.
+    */
+    pub fn validate_lazily<'s, 'v>(
+        &mut self,
+        v: &'v Value,
+        sch_index: SchemaIndex,
+        schemas: &'s mut Schemas,
+        options: &ValidationOptions,
+    ) -> Result<Result<(), ValidationError<'s, 'v>>, CompileError> {
+        loop {
+            match validator::validate_lazy(v, schemas.get(sch_index), schemas, options) {
+                Ok(true) => return Ok(Ok(())),
+                Ok(false) => break,
+                Err(lazy) => self.compile_lazy(lazy, schemas)?,
+            }
+        }
+        let schemas: &'s Schemas = schemas;
+        Ok(schemas.validate_with(v, sch_index, options))
+    }
+
+    /**
+    Forbids loading of any url, other than the resources added using
+    [`Compiler::add_resource`], the standard metaschemas and `data:` urls.
+
+    References to any other url fail with [`CompileError::OfflineLoadError`],
+    irrespective of the [`UrlLoader`] in use. This makes compilation
+    deterministic, which is useful for reproducible builds and
+    security review.
+
+    Note that [`Compiler::compile_file`] still reads the file passed to it,
+    but not the files it references.
+    */
+    pub fn enable_offline(&mut self) {
+        self.roots.loader.offline = true;
+    }
+
+    /**
+    Skips validation of schemas against their metaschema, to reduce
+    compile time for trusted schemas, which are already validated,
+    for example in ci.
+
+    Invalid schemas are then not reported with
+    [`CompileError::ValidationError`]. Such schemas may fail with some
+    other error, or compile with invalid keywords ignored. So use this
+    only for schemas from trusted source.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.disable_metaschema_validation();
+    compiler.add_resource("http://example.com/schema.json", json!({"type": "string"})).unwrap();
+    let sch_index = compiler.compile("http://example.com/schema.json", &mut schemas).unwrap();
+    assert!(schemas.validate(&json!(1), sch_index).is_err());
+    ```
+    */
+    pub fn disable_metaschema_validation(&mut self) {
+        self.roots.validate = false;
+    }
+
+    /**
+    Rejects schemas with keywords which are not defined by the draft or
+    dialect in use, nor allowed using [`Compiler::allow_keyword`].
+
+    By default such keywords are silently treated as annotations, so that
+    a typo like `requird` goes unnoticed. In strict mode, compilation fails
+    with [`CompileError::UnsupportedKeyword`] instead.
+
+    ```
+    # use boon::*;
+    # use serde_json::json;
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_strict_mode();
+    compiler.allow_keyword("x-order");
+    compiler.add_resource("schema.json", json!({"x-order": 1, "requird": ["name"]})).unwrap();
+    let err = compiler.compile("schema.json", &mut schemas).unwrap_err();
+    assert!(matches!(err, CompileError::UnsupportedKeyword { keyword, .. } if keyword == "requird"));
+    ```
+    */
+    pub fn enable_strict_mode(&mut self) {
+        self.strict = true;
+    }
+
+    /// Allows `keyword` in strict mode. see [`Compiler::enable_strict_mode`].
+    pub fn allow_keyword(&mut self, keyword: &str) {
+        self.allowed_keywords.insert(keyword.to_owned());
+    }
+
+    /**
+    Loads urls starting with `from` from `to` instead, by replacing the
+    prefix before passing the url to [`UrlLoader`].
+
+    This allows schemas referring canonical `$id`s to be resolved from a
+    local mirror. The document loaded is still identified by its original
+    url, so references and error locations are not affected. If more than
+    one prefix matches, the longest one is used.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.map_url_prefix("https://schemas.example.com/", "file:///opt/schemas/")?;
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    returns [`CompileError`] if `from` or `to` is not a valid url.
+    */
+    pub fn map_url_prefix(&mut self, from: &str, to: &str) -> Result<(), CompileError> {
+        for url in [from, to] {
+            Url::parse(url).map_err(|e| CompileError::ParseUrlError {
+                url: url.to_owned(),
+                src: e.into(),
+            })?;
+        }
+        let prefixes = &mut self.roots.loader.url_prefixes;
+        prefixes.retain(|(f, _)| f != from);
+        prefixes.push((from.to_owned(), to.to_owned()));
+        prefixes.sort_by_key(|(from, _)| std::cmp::Reverse(from.len()));
+        Ok(())
+    }
+
+    /**
+    Sets retry policy for loading urls with given `scheme`, such as
+    `https`.
+
+    Failed loads are retried with exponential backoff, until retries
+    are exhausted, the retry budget would be exceeded, or failure is
+    permanent such as url not found. see [`LoadPolicy`] for details.
+    The error is then reported as [`CompileError::LoadUrlError`] with
+    [`LoadAttemptsError`] as source, which has details of each attempt.
+
+    Note that the retry budget cannot interrupt an attempt in progress,
+    so [`UrlLoader`] should use its own timeout per request.
+
+    ```
+    # use std::time::Duration;
+    # use boon::*;
+    let mut compiler = Compiler::new();
+    compiler.set_load_policy("https", LoadPolicy {
+        retries: 3,
+        backoff: Duration::from_millis(200),
+        retry_budget: Some(Duration::from_secs(5)),
+    });
+    ```
+    */
+    pub fn set_load_policy(&mut self, scheme: &str, policy: LoadPolicy) {
+        self.roots.loader.policies.insert(scheme.to_owned(), policy);
+    }
+
     /// Overrides default [`UrlLoader`] used to load schema resources
     pub fn use_loader(&mut self, url_loader: Box<dyn UrlLoader>) {
         self.roots.loader.use_loader(url_loader);
@@ -145,10 +683,57 @@ impl Compiler {
     */
     pub fn register_format(&mut self, format: Format) {
         if format.name != "regex" {
-            self.formats.insert(format.name, format);
+            self.formats.insert(format.name, format.into());
         }
     }
 
+    /**
+    Registers custom `format` with validation function which can
+    capture configuration and state.
+
+    ```
+    # use std::{collections::HashSet, sync::Arc};
+    # use boon::*;
+    # use serde_json::Value;
+    let countries: Arc<HashSet<String>> = Arc::new(["IN".into(), "US".into()].into());
+    let mut compiler = Compiler::new();
+    compiler.register_format_fn("country", move |v| match v {
+        Value::String(s) if !countries.contains(s) => Err("unknown country")?,
+        _ => Ok(()),
+    });
+    ```
+
+    # Note
+
+    - `regex` format cannot be overridden
+    -  format assertions are disabled for draft >= 2019-09.
+       see [`Compiler::enable_format_assertions`]
+    */
+    pub fn register_format_fn<F>(&mut self, name: &'static str, func: F)
+    where
+        F: Fn(&Value) -> Result<(), Box<dyn Error>> + Send + Sync + 'static,
+    {
+        if name != "regex" {
+            let func = Arc::new(func);
+            self.formats.insert(name, CompiledFormat { name, func });
+        }
+    }
+
+    /**
+    Sets strictness options for `email` and `idn-email` formats.
+
+    Note that the domain part is checked using options set by
+    [`Compiler::set_hostname_options`].
+    */
+    pub fn set_email_options(&mut self, opts: EmailOptions) {
+        self.email_options = opts;
+    }
+
+    /// Sets strictness options for `hostname` and `idn-hostname` formats.
+    pub fn set_hostname_options(&mut self, opts: HostnameOptions) {
+        self.hostname_options = opts;
+    }
+
     /**
     Registers custom `contentEncoding`
 
@@ -162,29 +747,423 @@ impl Compiler {
     /**
     Registers custom `contentMediaType`
 
+    If it is `json_compatible`, it is also used to parse schema documents
+    loaded with this media type. see [`UrlContent`]
+
     Note that content assertions are disabled by default.
     see [`Compiler::enable_content_assertions`]
     */
     pub fn register_content_media_type(&mut self, media_type: MediaType) {
         self.media_types.insert(media_type.name, media_type);
+        self.roots
+            .loader
+            .media_types
+            .insert(media_type.name, media_type);
+    }
+
+    /// Returns names of all formats available, including custom formats.
+    pub fn formats(&self) -> Vec<&'static str> {
+        let mut names = Format::builtin_names();
+        names.extend(self.formats.keys());
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Returns names of all `contentEncoding`s available, including custom ones.
+    pub fn content_encodings(&self) -> Vec<&'static str> {
+        let mut names = Decoder::builtin_names();
+        names.extend(self.decoders.keys());
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Returns names of all `contentMediaType`s available, including custom ones.
+    pub fn content_media_types(&self) -> Vec<&'static str> {
+        let mut names = MediaType::builtin_names();
+        names.extend(self.media_types.keys());
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    // returns options set, for debugging.
+    pub(crate) fn options(&self) -> Value {
+        let mut format_assertions = self
+            .format_assertions
+            .iter()
+            .map(|(name, assert)| (name.clone(), Value::Bool(*assert)))
+            .collect::<Vec<_>>();
+        format_assertions.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut url_aliases = self
+            .roots
+            .loader
+            .url_aliases
+            .iter()
+            .map(|(from, to)| (from.clone(), Value::String(to.clone())))
+            .collect::<Vec<_>>();
+        url_aliases.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut load_policies = self
+            .roots
+            .loader
+            .policies
+            .iter()
+            .map(|(scheme, p)| (scheme.clone(), Value::String(format!("{p:?}"))))
+            .collect::<Vec<_>>();
+        load_policies.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut allowed_keywords = self.allowed_keywords.iter().collect::<Vec<_>>();
+        allowed_keywords.sort();
+        serde_json::json!({
+            "defaultDraft": self.roots.default_draft.url,
+            "dialect": self.dialect.map(|d| format!("{d:?}")),
+            "assertFormat": self.assert_format,
+            "formatAssertions": Map::from_iter(format_assertions),
+            "assertContent": self.assert_content,
+            "dataRefs": self.roots.data,
+            "offline": self.roots.loader.offline,
+            "metaschemaValidation": self.roots.validate,
+            "strictMode": self.strict,
+            "refInlining": self.inline_refs,
+            "subschemaDedup": self.dedup_subschemas,
+            "lazyCompilation": self.lazy,
+            "allowedKeywords": allowed_keywords,
+            "urlPrefixes": Map::from_iter(
+                self.roots
+                    .loader
+                    .url_prefixes
+                    .iter()
+                    .map(|(from, to)| (from.clone(), Value::String(to.clone())))
+            ),
+            "urlAliases": Map::from_iter(url_aliases),
+            "loadPolicies": Map::from_iter(load_policies),
+            "regexEngine": format!("{:?}", self.regex_engine),
+            "regexLimits": format!("{:?}", self.regex_limits),
+            "compileLimits": format!("{:?}", self.compile_limits),
+            "emailOptions": format!("{:?}", self.email_options),
+            "hostnameOptions": format!("{:?}", self.hostname_options),
+            "formats": self.formats(),
+            "customKeywords": self.custom_keywords(),
+            "contentEncodings": self.content_encodings(),
+            "contentMediaTypes": self.content_media_types(),
+        })
+    }
+
+    /**
+    Returns json document loaded from `url`.
+
+    This returns the documents added using [`Compiler::add_resource`],
+    and those loaded during compilation. It does not load `url`, if not
+    loaded already.
+    */
+    pub fn resource(&self, url: &str) -> Option<&Value> {
+        let uf = UrlFrag::absolute(url).ok()?;
+        self.roots.loader.get_doc(&uf.url)
+    }
+
+    /**
+    Returns references to unknown `format`, `contentEncoding` or
+    `contentMediaType` in schemas compiled so far.
+
+    Such references are silently ignored during validation. Note that
+    they are reported even if format or content assertions are not enabled.
+    */
+    pub fn unknown_refs(&self) -> &[UnknownRef] {
+        &self.unknown_refs
+    }
+
+    /**
+    Returns the warnings found since last call, without failing the
+    compilation.
+
+    Warnings include references to unknown formats, keywords ignored
+    by the draft in use, and anchors which are not referenced by any
+    schema compiled so far. Each anchor is reported at most once.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "$defs": {"name": {"type": "string"}}
+    }))?;
+    compiler.compile("schema.json", &mut schemas)?;
+    let warnings = compiler.take_warnings();
+    assert!(matches!(
+        &warnings[..],
+        [CompileWarning::IgnoredKeyword { keyword, .. }] if keyword == "$defs"
+    ));
+    assert!(compiler.take_warnings().is_empty());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn take_warnings(&mut self) -> Vec<CompileWarning> {
+        let mut warnings = std::mem::take(&mut self.warnings);
+        for root in self.roots.iter() {
+            if crate::loader::load_std_meta(root.url.as_str()).is_some() {
+                continue;
+            }
+            let mut found = vec![];
+            for res in root.resources.values() {
+                for anchor in res.anchors.keys() {
+                    if res.dynamic_anchors.contains(anchor) {
+                        continue;
+                    }
+                    let key = (res.id.clone(), anchor.to_string());
+                    if !self.ref_anchors.contains(&key) && self.reported_anchors.insert(key) {
+                        found.push(CompileWarning::UnreferencedAnchor {
+                            url: res.id.to_string(),
+                            anchor: anchor.to_string(),
+                        });
+                    }
+                }
+            }
+            found.sort_by_key(ToString::to_string);
+            warnings.extend(found);
+        }
+        warnings
+    }
+
+    /**
+    Adds schema resource which used later in reference resoltion
+    If you do not know which schema resources required, then use [`UrlLoader`].
+
+    The argument `loc` can be file path or url. any fragment in `loc` is ignored.
+
+    # Errors
+
+    returns [`CompileError`] if url parsing failed.
+    */
+    pub fn add_resource(&mut self, loc: &str, json: Value) -> Result<(), CompileError> {
+        let uf = UrlFrag::absolute(loc)?;
+        self.roots.loader.add_doc(uf.url, json);
+        Ok(())
+    }
+
+    /**
+    Removes schema resource `loc`, which was added earlier or loaded
+    during compilation.
+
+    If it is referenced by schemas compiled later, it is loaded again
+    using [`UrlLoader`]. Schemas already compiled from it are not affected;
+    use [`Schemas::invalidate`] to flag them stale.
+
+    Returns `true` if the resource was present.
+
+    # Errors
+
+    returns [`CompileError`] if url parsing failed.
+    */
+    pub fn remove_resource(&mut self, loc: &str) -> Result<bool, CompileError> {
+        let uf = UrlFrag::absolute(loc)?;
+        self.roots.remove(&uf.url);
+        self.overlays.remove(&uf.url);
+        Ok(self.roots.loader.remove_doc(&uf.url))
+    }
+
+    /**
+    Replaces schema resource `loc` with `json`, adding it if not present.
+
+    Schemas already compiled from it are not affected; use
+    [`Schemas::invalidate`] to flag them stale, so that they get
+    compiled afresh.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", json!({"type": "string"}))?;
+    let old = compiler.compile("schema.json", &mut schemas)?;
+
+    compiler.replace_resource("schema.json", json!({"type": "number"}))?;
+    schemas.invalidate("schema.json");
+    let new = compiler.compile("schema.json", &mut schemas)?;
+    assert!(schemas.is_stale(old));
+    assert!(schemas.validate(&json!(1), new).is_ok());
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    returns [`CompileError`] if url parsing failed.
+    */
+    pub fn replace_resource(&mut self, loc: &str, json: Value) -> Result<(), CompileError> {
+        let uf = UrlFrag::absolute(loc)?;
+        self.remove_resource(uf.url.as_str())?;
+        self.roots.loader.add_doc(uf.url, json);
+        Ok(())
+    }
+
+    /**
+    Recompiles schemas in `target` affected by change to resource `loc`.
+
+    Use [`Compiler::replace_resource`] to change the resource, or
+    [`Compiler::remove_resource`] to get it loaded again by [`UrlLoader`],
+    before calling this. The schemas compiled from `loc`, and those
+    referring to them directly or indirectly, are flagged stale using
+    [`Schemas::invalidate`] and compiled afresh. Other schemas are left
+    untouched.
+
+    Returns mapping from old [`SchemaIndex`] to new one. Schemas whose
+    location no longer exists in `loc` are not in the mapping.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", json!({"$ref": "port.json"}))?;
+    compiler.add_resource("port.json", json!({"maximum": 100}))?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+    compiler.replace_resource("port.json", json!({"maximum": 1000}))?;
+    let mapping = compiler.recompile("port.json", &mut schemas)?;
+    let sch_index = mapping[&sch_index];
+    assert!(schemas.validate(&json!(500), sch_index).is_ok());
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    returns [`CompileError`] if url parsing failed, or if recompilation
+    of any affected schema fails.
+    */
+    pub fn recompile(
+        &mut self,
+        loc: &str,
+        target: &mut Schemas,
+    ) -> Result<HashMap<SchemaIndex, SchemaIndex>, CompileError> {
+        let uf = UrlFrag::absolute(loc)?;
+        let stale = target.invalidate(uf.url.as_str());
+        let mut mapping = HashMap::new();
+        for old in stale {
+            let loc = target.location(old).to_owned();
+            let suf = UrlFrag::absolute(&loc)?;
+            if suf.url == uf.url {
+                let Fragment::JsonPointer(ptr) = &suf.frag else {
+                    return Err(CompileError::Bug(
+                        "schema location must be json-pointer".into(),
+                    ));
+                };
+                let doc = self.roots.loader.load(&uf.url)?;
+                if ptr.lookup(doc, &uf.url).is_err() {
+                    // no longer exists
+                    continue;
+                }
+            }
+            let new = self.compile(&loc, target)?;
+            mapping.insert(old, new);
+        }
+        Ok(mapping)
     }
 
     /**
-    Adds schema resource which used later in reference resoltion
-    If you do not know which schema resources required, then use [`UrlLoader`].
+    Adds schema resource `loc`, derived by applying `patch` to `base`.
 
-    The argument `loc` can be file path or url. any fragment in `loc` is ignored.
+    The derived schema gets its own `$id` i.e. `loc`, and its relative
+    references are resolved against `loc`. The argument `base` can be file
+    path or url with optional fragment, and is loaded if not added already.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let base = json!({"properties": {"name": {"type": "string"}}});
+    compiler.add_resource("http://example.com/person.json", base)?;
+    let patch = json!({"properties": {"name": {"maxLength": 5}}});
+    compiler.add_overlay(
+        "http://example.com/prod/person.json",
+        "http://example.com/person.json",
+        &SchemaPatch::Merge(patch),
+    )?;
+    let sch_index = compiler.compile("http://example.com/prod/person.json", &mut schemas)?;
+    let instance = json!({"name": "John Doe"});
+    assert!(schemas.validate(&instance, sch_index).is_err());
+    # Ok(())
+    # }
+    ```
+
+    use [`Compiler::provenance`] to find whether a location in
+    derived schema comes from `base` or `patch`. It is also reported
+    in [`ValidationError::provenance`] of errors located in derived schema.
 
     # Errors
 
-    returns [`CompileError`] if url parsing failed.
+    returns [`CompileError`] if `base` could not be loaded, or `patch`
+    could not be applied.
     */
-    pub fn add_resource(&mut self, loc: &str, json: Value) -> Result<(), CompileError> {
+    pub fn add_overlay(
+        &mut self,
+        loc: &str,
+        base: &str,
+        patch: &SchemaPatch,
+    ) -> Result<(), CompileError> {
         let uf = UrlFrag::absolute(loc)?;
-        self.roots.loader.add_doc(uf.url, json);
+        let base = self.roots.resolve_fragment(UrlFrag::absolute(base)?)?;
+        let Some(root) = self.roots.get(&base.url) else {
+            return Err(CompileError::Bug("or_load didn't add".into()));
+        };
+        let draft = root.draft;
+        let mut doc = base.lookup(self.roots.loader.load(&base.url)?)?.clone();
+        let mut patched = patch
+            .apply(&mut doc)
+            .map_err(|src| CompileError::InvalidPatch {
+                url: uf.url.to_string(),
+                src,
+            })?;
+        if let Value::Object(obj) = &mut doc {
+            obj.insert(draft.id.to_owned(), Value::String(uf.url.to_string()));
+            patched.push(JsonPointer::from("").append(draft.id).to_string());
+            if !base.ptr.is_empty() && !obj.contains_key("$schema") {
+                obj.insert("$schema".to_owned(), Value::String(draft.url.to_owned()));
+                patched.push("/$schema".to_owned());
+            }
+        }
+        self.roots.loader.add_doc(uf.url.clone(), doc);
+        self.overlays.insert(uf.url, Overlay { base, patched });
         Ok(())
     }
 
+    /**
+    Returns origin of location `loc` in schema added using
+    [`Compiler::add_overlay`].
+
+    This is useful to map locations in errors, such as
+    [`ValidationError::schema_url`], back to the document they came from.
+
+    returns `None` if `loc` is not in such schema.
+    */
+    pub fn provenance(&self, loc: &str) -> Option<Provenance> {
+        let uf = UrlFrag::absolute(loc).ok()?;
+        let overlay = self.overlays.get(&uf.url)?;
+        let ptr = match &uf.frag {
+            Fragment::JsonPointer(ptr) => ptr.clone(),
+            Fragment::Anchor(_) => {
+                self.roots
+                    .get(&uf.url)?
+                    .resolve_fragment(&uf.frag)
+                    .ok()?
+                    .ptr
+            }
+        };
+        Some(overlay.provenance(ptr.as_str()))
+    }
+
     /**
     Compile given `loc` into `target` and return an identifier to the compiled
     schema.
@@ -201,48 +1180,293 @@ impl Compiler {
         target: &mut Schemas,
     ) -> Result<SchemaIndex, CompileError> {
         let uf = UrlFrag::absolute(loc)?;
+        if let Fragment::Anchor(anchor) = &uf.frag {
+            self.ref_anchors
+                .insert((uf.url.clone(), anchor.to_string()));
+        }
         // resolve anchor
-        let up = self.roots.resolve_fragment(uf)?;
-
-        let result = self.do_compile(up, target);
+        let mut result = self
+            .roots
+            .resolve_fragment(uf)
+            .and_then(|up| self.do_compile(up, target));
         if let Err(bug @ CompileError::Bug(_)) = &result {
             debug_assert!(false, "{bug}");
         }
+        if let Err(CompileError::ValidationError { url, src }) = &mut result {
+            self.set_provenance(url, src);
+        }
         result
     }
 
+    // sets provenance of `e` and its causes, from their instance location
+    // in schema `url` validated against metaschema.
+    fn set_provenance(&self, url: &str, e: &mut ValidationError) {
+        let Ok(uf) = UrlFrag::absolute(url) else {
+            return;
+        };
+        let (Some(overlay), Fragment::JsonPointer(ptr)) = (self.overlays.get(&uf.url), &uf.frag)
+        else {
+            return;
+        };
+        e.provenance = Some(overlay.provenance(&format!("{ptr}{}", e.instance_location)));
+        for cause in &mut e.causes {
+            self.set_provenance(url, cause);
+        }
+    }
+
+    /**
+    Compiles in-memory schema `json` into `target`.
+
+    The schema is added as resource with synthetic url of the form
+    `urn:boon:value:<n>`, which is unique across compilers, so that schemas
+    compiled by different compilers into same `target` do not clash. If the schema
+    has relative references to other resources, set `$id` in it and use
+    [`Compiler::add_resource`] instead.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let sch_index = compiler.compile_value(json!({"type": "string"}), &mut schemas)?;
+    assert!(schemas.validate(&json!("x"), sch_index).is_ok());
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    returns [`CompileError`] if compilation fails.
+    */
+    pub fn compile_value(
+        &mut self,
+        json: Value,
+        target: &mut Schemas,
+    ) -> Result<SchemaIndex, CompileError> {
+        // resources added by compile_value, across compilers
+        static NUM_VALUES: AtomicUsize = AtomicUsize::new(0);
+        let n = NUM_VALUES.fetch_add(1, AtomicOrdering::Relaxed) + 1;
+        let loc = format!("urn:boon:value:{n}");
+        self.add_resource(&loc, json)?;
+        self.compile(&loc, target)
+    }
+
+    /**
+    Validates schema document `doc` against the metaschema of its draft,
+    without compiling it, for linting and editor integrations.
+
+    The draft is detected from `$schema`, defaulting to the one set by
+    [`Compiler::set_default_draft`]. Custom `$schema` urls are loaded
+    using the loader of this compiler. `doc` is validated irrespective
+    of [`Compiler::disable_metaschema_validation`].
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let compiler = Compiler::new();
+    let doc = json!({"type": "string", "minLength": -1});
+    let result = compiler.validate_schema(&doc)?;
+    assert!(result.is_err());
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    returns [`CompileError`], if `$schema` cannot be resolved.
+    */
+    pub fn validate_schema(
+        &self,
+        doc: &Value,
+    ) -> Result<Result<(), ValidationError<'static, 'static>>, CompileError> {
+        let url = Url::parse("urn:boon:schema").map_err(|e| CompileError::Bug(e.into()))?;
+        let (draft, _) = self.roots.get_draft(&url, doc)?;
+        let up = UrlPtr {
+            url,
+            ptr: "".into(),
+        };
+        let result = if self.roots.data {
+            draft.validate(&up, &strip_data(doc))
+        } else {
+            draft.validate(&up, doc)
+        };
+        match result {
+            Ok(()) => Ok(Ok(())),
+            Err(CompileError::ValidationError { src, .. }) => Ok(Err(src)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /**
+    Compiles schema file at `path` into `target`.
+
+    Unlike passing file path to [`Compiler::compile`], `path` is never
+    interpreted as url, so it may contain characters like `#` and `%`.
+    The file is registered under its absolute `file://` url, so that
+    relative references to sibling files resolve, and it is read even if
+    [`Compiler::use_loader`] is not configured for `file` scheme.
+
+    ```no_run
+    # use std::error::Error;
+    # use boon::*;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let sch_index = compiler.compile_file("schemas/person.json", &mut schemas)?;
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    returns [`CompileError`] if the file could not be read, or if
+    compilation fails.
+    */
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn compile_file(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+        target: &mut Schemas,
+    ) -> Result<SchemaIndex, CompileError> {
+        let path = path.as_ref();
+        let to_err = |src: Box<dyn Error>| CompileError::LoadUrlError {
+            url: path.display().to_string(),
+            src,
+        };
+        let abs = std::path::absolute(path).map_err(|e| to_err(e.into()))?;
+        let url = Url::from_file_path(&abs).map_err(|_| to_err("invalid file path".into()))?;
+        if self.roots.loader.get_doc(&url).is_none() {
+            let doc = FileLoader.load_content(url.as_str());
+            let doc = doc
+                .and_then(|c| c.parse(&self.roots.loader.media_types))
+                .map_err(to_err)?;
+            self.roots.loader.add_doc(url.clone(), doc);
+        }
+        let up = self.roots.resolve_fragment(UrlFrag {
+            url,
+            frag: Fragment::JsonPointer("".into()),
+        })?;
+        self.do_compile(up, target)
+    }
+
     fn do_compile(
         &mut self,
         up: UrlPtr,
         target: &mut Schemas,
     ) -> Result<SchemaIndex, CompileError> {
         let mut queue = Queue::new();
-        let mut compiled = Vec::new();
-
+        queue.lazy = self.lazy;
         let index = queue.enqueue_schema(target, up);
         if queue.schemas.is_empty() {
             // already got compiled
             return Ok(index);
         }
+        self.compile_queue(queue, target)?;
+        Ok(index)
+    }
+
+    // compiles lazy schema `sch` in place, see enable_lazy_compilation.
+    fn compile_lazy(&mut self, sch: SchemaIndex, target: &mut Schemas) -> Result<(), CompileError> {
+        let uf = UrlFrag::absolute(target.location(sch))?;
+        let Fragment::JsonPointer(ptr) = uf.frag else {
+            return Err(CompileError::Bug(
+                "schema location must be json-pointer".into(),
+            ));
+        };
+        let mut queue = Queue::new();
+        queue.lazy = true;
+        queue.in_place = Some(sch);
+        queue.schemas.push(UrlPtr { url: uf.url, ptr });
+        queue.ref_depths.push(0);
+        queue.cold.push(false);
+        self.compile_queue(queue, target)
+    }
 
+    fn compile_queue(
+        &mut self,
+        mut queue: Queue,
+        target: &mut Schemas,
+    ) -> Result<(), CompileError> {
+        let mut compiled = Vec::new();
+        let limits = self.compile_limits;
+        let mut docs = HashSet::new();
         while queue.schemas.len() > compiled.len() {
             let up = &queue.schemas[compiled.len()];
+            let limit_exceeded = |limit| CompileError::LimitExceeded {
+                url: up.to_string(),
+                limit,
+            };
+            if let Some(max) = limits.max_schemas {
+                if compiled.len() >= max {
+                    return Err(limit_exceeded(CompileLimit::Schemas(max)));
+                }
+            }
+            if queue.cold[compiled.len()] {
+                let mut s = Schema::new(up.to_string());
+                s.idx = queue.index(target, compiled.len());
+                s.resource = s.idx;
+                s.lazy = true;
+                // not known yet
+                s.evaluates_props = true;
+                s.evaluates_items = true;
+                compiled.push(s);
+                continue;
+            }
+            if let Some(max) = limits.max_documents {
+                if docs.insert(up.url.clone()) && docs.len() > max {
+                    return Err(limit_exceeded(CompileLimit::Documents(max)));
+                }
+            }
+            queue.ref_depth = queue.ref_depths[compiled.len()];
+            if let Some(max) = limits.max_ref_depth {
+                if queue.ref_depth > max {
+                    return Err(limit_exceeded(CompileLimit::RefDepth(max)));
+                }
+            }
             self.roots.ensure_subschema(up)?;
             let Some(root) = self.roots.get(&up.url) else {
                 return Err(CompileError::Bug("or_load didn't add".into()));
             };
             let doc = self.roots.loader.load(&root.url)?;
             let v = up.lookup(doc)?;
-            let sch = self.compile_value(target, v, &up.clone(), root, &mut queue)?;
+            let sch = self.compile_one(target, v, &up.clone(), root, &mut queue)?;
             compiled.push(sch);
             self.roots.insert(&mut queue.roots);
         }
 
-        target.insert(queue.schemas, compiled);
-        Ok(index)
+        for up in &queue.schemas {
+            if !target.docs.contains_key(&up.url) {
+                if let Some(doc) = self.roots.loader.get_shared_doc(&up.url) {
+                    target.docs.insert(up.url.clone(), doc);
+                }
+                if let Some(overlay) = self.overlays.get(&up.url) {
+                    target.overlays.insert(up.url.clone(), overlay.clone());
+                }
+            }
+        }
+        let start = target.size();
+        target.insert(
+            queue.schemas,
+            queue.indexes,
+            queue.names,
+            compiled,
+            queue.in_place,
+        );
+        if self.inline_refs {
+            inline::inline_refs(target, start);
+        }
+        self.unknown_refs.append(&mut queue.unknown_refs);
+        self.warnings.append(&mut queue.warnings);
+        self.ref_anchors.extend(queue.ref_anchors);
+        self.regexes.extend(queue.regexes);
+        Ok(())
     }
 
-    fn compile_value(
+    fn compile_one(
         &self,
         schemas: &Schemas,
         v: &Value,
@@ -266,6 +1490,19 @@ impl Compiler {
             queue.enqueue_schema(schemas, base)
         };
 
+        {
+            let res = root.resource(&up.ptr);
+            if s.idx == s.resource {
+                s.id = Some(res.id.clone());
+            }
+            s.anchors = res
+                .anchors
+                .iter()
+                .filter(|(_, ptr)| **ptr == up.ptr)
+                .map(|(anchor, _)| anchor.0.clone())
+                .collect();
+        }
+
         // if resource, enqueue dynamicAnchors for compilation
         if s.idx == s.resource && root.draft.version >= 2020 {
             let res = root.resource(&up.ptr);
@@ -312,6 +1549,20 @@ impl Compiler {
         } else {
             s.prefix_items.len()
         };
+        // dynamic references may resolve to any schema
+        let dynamic = s.dynamic_ref.is_some() || s.recursive_ref.is_some();
+        s.evaluates_props = dynamic
+            || !s.properties.is_empty()
+            || !s.pattern_properties.is_empty()
+            || s.additional_properties.is_some()
+            || s.unevaluated_properties.is_some();
+        s.evaluates_items = dynamic
+            || s.items.is_some()
+            || s.additional_items.is_some()
+            || !s.prefix_items.is_empty()
+            || s.items2020.is_some()
+            || s.contains.is_some()
+            || s.unevaluated_items.is_some();
 
         Ok(s)
     }
@@ -329,6 +1580,30 @@ struct ObjCompiler<'c, 'v, 'l, 's, 'r, 'q> {
 // compile supported drafts
 impl<'c, 'v, 'l, 's, 'r, 'q> ObjCompiler<'c, 'v, 'l, 's, 'r, 'q> {
     fn compile_obj(&mut self, s: &mut Schema) -> Result<(), CompileError> {
+        if self.c.dialect == Some(Dialect::MongoDb) {
+            self.compile_mongodb(s)?;
+        }
+        let draft = self.root.draft;
+        for kw in self.obj.keys() {
+            let kw = kw.as_str();
+            if draft.keywords.contains(kw) || self.c.keywords.contains_key(kw) {
+                continue;
+            }
+            if ALL_KEYWORDS.contains(kw) {
+                self.queue.warnings.push(CompileWarning::IgnoredKeyword {
+                    url: self.up.format(kw),
+                    keyword: kw.to_owned(),
+                    draft: Draft::from_version(draft.version),
+                });
+            }
+            if self.c.strict && !self.is_allowed_keyword(kw) {
+                return Err(CompileError::UnsupportedKeyword {
+                    url: self.up.format(kw),
+                    keyword: kw.to_owned(),
+                });
+            }
+            s.unknown_keywords.push(kw.to_owned());
+        }
         self.compile_draft4(s)?;
         if self.draft_version() >= 6 {
             self.compile_draft6(s)?;
@@ -342,6 +1617,93 @@ impl<'c, 'v, 'l, 's, 'r, 'q> ObjCompiler<'c, 'v, 'l, 's, 'r, 'q> {
         if self.draft_version() >= 2020 {
             self.compile_draft2020(s)?;
         }
+        if self.c.dialect == Some(Dialect::Kubernetes) {
+            self.compile_kubernetes(s);
+        }
+        if self.c.roots.data && self.has_vocab("validation") {
+            self.compile_data(s)?;
+        }
+        if !self.c.keywords.is_empty() {
+            self.compile_keywords(s)?;
+        }
+        Ok(())
+    }
+
+    fn compile_keywords(&mut self, s: &mut Schema) -> Result<(), CompileError> {
+        if s.ref_.is_some() && self.draft_version() < 2019 {
+            return Ok(()); // siblings of $ref are ignored
+        }
+        for (kw, v) in self.obj {
+            if self.root.draft.keywords.contains(kw.as_str()) {
+                continue;
+            }
+            let Some((&name, def)) = self.c.keywords.get_key_value(kw.as_str()) else {
+                continue;
+            };
+            let keyword = (def.compile)(v).map_err(|src| CompileError::InvalidKeyword {
+                url: self.up.format(kw),
+                keyword: kw.clone(),
+                src,
+            })?;
+            s.keywords.push(CompiledKeyword {
+                name,
+                value: v.clone(),
+                phase: def.phase,
+                keyword: keyword.into(),
+            });
+        }
+        s.keywords.sort_by_key(|k| (k.phase, k.name));
+        Ok(())
+    }
+
+    // `nullable: true` adds null to `type`, as in OpenAPI 3.0.
+    fn compile_kubernetes(&mut self, s: &mut Schema) {
+        if let (Some(Value::Bool(true)), false) = (self.value("nullable"), s.types.is_empty()) {
+            s.types.add(Type::Null);
+        }
+    }
+
+    fn compile_mongodb(&mut self, s: &mut Schema) -> Result<(), CompileError> {
+        for kw in self.obj.keys() {
+            if !MONGODB_KEYWORDS.contains(&kw.as_str()) {
+                return Err(CompileError::UnsupportedKeyword {
+                    url: self.up.format(kw),
+                    keyword: kw.clone(),
+                });
+            }
+        }
+        let aliases = match self.value("bsonType") {
+            Some(Value::String(alias)) => vec![alias.as_str()],
+            Some(Value::Array(arr)) => arr.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+        for alias in aliases {
+            if !BSON_TYPES.contains(&alias) {
+                return Err(CompileError::InvalidBsonType {
+                    url: self.up.format("bsonType"),
+                    bson_type: alias.to_owned(),
+                });
+            }
+            s.bson_types.push(alias.to_owned());
+        }
+        Ok(())
+    }
+
+    fn compile_data(&mut self, s: &mut Schema) -> Result<(), CompileError> {
+        for (kw, version) in DATA_KEYWORDS {
+            if self.draft_version() < version {
+                continue;
+            }
+            if let Some(data) = self.value(kw).and_then(data_ref) {
+                let Some(data_ref) = DataRef::parse(data) else {
+                    return Err(CompileError::InvalidJsonPointer(self.up.format(kw)));
+                };
+                if kw == "const" {
+                    s.constant = None;
+                }
+                s.data.push((kw, data_ref));
+            }
+        }
         Ok(())
     }
 
@@ -370,23 +1732,22 @@ impl<'c, 'v, 'l, 's, 'r, 'q> ObjCompiler<'c, 'v, 'l, 's, 'r, 'q> {
                 }
             }
 
-            s.properties = self.enqueue_map("properties");
+            if let Some(Value::Object(obj)) = self.value("properties") {
+                s.properties.reserve(obj.len());
+                s.property_order.reserve(obj.len());
+                for pname in obj.keys() {
+                    let ptr = self.up.ptr.append2("properties", pname);
+                    let sch = self.enqueue_schema(ptr);
+                    let pname = self.intern(pname);
+                    s.properties.insert(Name::clone(&pname), sch);
+                    s.property_order.push(pname);
+                }
+            }
             s.pattern_properties = {
                 let mut v = vec![];
                 if let Some(Value::Object(obj)) = self.value("patternProperties") {
                     for pname in obj.keys() {
-                        let ecma =
-                            ecma::convert(pname).map_err(|src| CompileError::InvalidRegex {
-                                url: self.up.format("patternProperties"),
-                                regex: pname.to_owned(),
-                                src,
-                            })?;
-                        let regex =
-                            Regex::new(ecma.as_ref()).map_err(|e| CompileError::InvalidRegex {
-                                url: self.up.format("patternProperties"),
-                                regex: ecma.into_owned(),
-                                src: e.into(),
-                            })?;
+                        let regex = self.regex("patternProperties", pname)?;
                         let ptr = self.up.ptr.append2("patternProperties", pname);
                         let sch = self.enqueue_schema(ptr);
                         v.push((regex, sch));
@@ -394,6 +1755,10 @@ impl<'c, 'v, 'l, 's, 'r, 'q> ObjCompiler<'c, 'v, 'l, 's, 'r, 'q> {
                 }
                 v
             };
+            if s.pattern_properties.len() > 4 {
+                let regexes = s.pattern_properties.iter().map(|(regex, _)| regex);
+                s.pattern_properties_set = RegexSet::new(regexes, &self.c.regex_limits);
+            }
 
             s.additional_properties = self.enquue_additional("additionalProperties");
 
@@ -434,14 +1799,7 @@ impl<'c, 'v, 'l, 's, 'r, 'q> ObjCompiler<'c, 'v, 'l, 's, 'r, 'q> {
             }
 
             if let Some(Value::Array(e)) = self.value("enum") {
-                let mut types = Types::default();
-                for item in e {
-                    types.add(Type::of(item));
-                }
-                s.enum_ = Some(Enum {
-                    types,
-                    values: e.clone(),
-                });
+                s.enum_ = Some(Enum::new(e.clone()));
             }
 
             s.multiple_of = self.num("multipleOf");
@@ -468,8 +1826,8 @@ impl<'c, 'v, 'l, 's, 'r, 'q> ObjCompiler<'c, 'v, 'l, 's, 'r, 'q> {
             s.min_length = self.usize("minLength");
 
             if let Some(Value::String(p)) = self.value("pattern") {
-                let p = ecma::convert(p).map_err(CompileError::Bug)?;
-                s.pattern = Some(Regex::new(p.as_ref()).map_err(|e| CompileError::Bug(e.into()))?);
+                // valid ecma regex may not be supported by regex engine
+                s.pattern = Some(self.regex("pattern", p)?);
             }
 
             s.max_items = self.usize("maxItems");
@@ -479,28 +1837,62 @@ impl<'c, 'v, 'l, 's, 'r, 'q> ObjCompiler<'c, 'v, 'l, 's, 'r, 'q> {
             s.max_properties = self.usize("maxProperties");
             s.min_properties = self.usize("minProperties");
 
-            if let Some(req) = self.value("required") {
-                s.required = to_strings(req);
+            if let Some(Value::Array(req)) = self.value("required") {
+                s.required = req
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(|pname| self.intern(pname))
+                    .collect();
+                if s.required.len() > 16 {
+                    let mut bits = FastHashMap::with_capacity(s.required.len());
+                    for pname in &s.required {
+                        let bit = bits.len();
+                        bits.entry(Name::clone(pname)).or_insert(bit);
+                    }
+                    s.required_bits = Some(bits);
+                }
             }
         }
 
+        s.default = self.value("default").cloned();
+        if let Some(Value::Array(examples)) = self.value("examples") {
+            s.examples = examples.clone();
+        }
+        s.title = self.str("title").map(str::to_owned);
+        s.description = self.str("description").map(str::to_owned);
+        s.read_only = self.bool("readOnly");
+        s.write_only = self.bool("writeOnly");
+        s.deprecated = self.bool("deprecated");
+
         // format --
-        if self.c.assert_format
-            || self.has_vocab(match self.draft_version().cmp(&2019) {
-                Ordering::Less => "core",
-                Ordering::Equal => "format",
-                Ordering::Greater => "format-assertion",
-            })
-        {
-            if let Some(Value::String(format)) = self.value("format") {
-                s.format = self
-                    .c
-                    .formats
-                    .get(format.as_str())
-                    .or_else(|| FORMATS.get(format.as_str()))
-                    .cloned();
+        s.format_name = self.str("format").map(str::to_owned);
+        if let Some(Value::String(format)) = self.value("format") {
+            if !self.c.formats.contains_key(format.as_str())
+                && !FORMATS.contains_key(format.as_str())
+            {
+                self.unknown_ref("format", format);
             }
         }
+        if let Some(Value::String(format)) = self.value("format") {
+            let assert = match self.c.format_assertions.get(format.as_str()) {
+                Some(&assert) => assert,
+                None => {
+                    self.c.assert_format
+                        || self.has_vocab(match self.draft_version().cmp(&2019) {
+                            Ordering::Less => "core",
+                            Ordering::Equal => "format",
+                            Ordering::Greater => "format-assertion",
+                        })
+                }
+            };
+            // compiled even if not asserted, so that ValidationOptions
+            // can enforce it.
+            s.format = match self.c.formats.get(format.as_str()) {
+                Some(f) => Some(f.clone()),
+                None => std_format(format, self.c.email_options, self.c.hostname_options),
+            };
+            s.format_assert = assert;
+        }
 
         Ok(())
     }
@@ -531,6 +1923,20 @@ impl<'c, 'v, 'l, 's, 'r, 'q> ObjCompiler<'c, 'v, 'l, 's, 'r, 'q> {
             }
         }
 
+        if let Some(Value::String(encoding)) = self.value("contentEncoding") {
+            if !self.c.decoders.contains_key(encoding.as_str())
+                && !DECODERS.contains_key(encoding.as_str())
+            {
+                self.unknown_ref("contentEncoding", encoding);
+            }
+        }
+        if let Some(Value::String(media_type)) = self.value("contentMediaType") {
+            if !self.c.media_types.contains_key(media_type.as_str())
+                && !MEDIA_TYPES.contains_key(media_type.as_str())
+            {
+                self.unknown_ref("contentMediaType", media_type);
+            }
+        }
         if self.c.assert_content {
             if let Some(Value::String(encoding)) = self.value("contentEncoding") {
                 s.content_encoding = self
@@ -634,6 +2040,11 @@ impl<'c, 'v, 'l, 's, 'r, 'q> ObjCompiler<'c, 'v, 'l, 's, 'r, 'q> {
             url: self.up.url.clone(),
             ptr,
         };
+        if self.c.dedup_subschemas {
+            if let Ok(doc) = self.c.roots.loader.load(&up.url) {
+                return self.queue.enqueue_dedup(self.schemas, up, doc, self.root);
+            }
+        }
         self.queue.enqueue_schema(self.schemas, up)
     }
 
@@ -646,17 +2057,17 @@ impl<'c, 'v, 'l, 's, 'r, 'q> ObjCompiler<'c, 'v, 'l, 's, 'r, 'q> {
         }
     }
 
-    fn enqueue_arr(&mut self, pname: &'static str) -> Vec<SchemaIndex> {
-        if let Some(Value::Array(arr)) = self.obj.get(pname) {
-            (0..arr.len())
-                .map(|i| {
-                    let ptr = self.up.ptr.append2(pname, &i.to_string());
-                    self.enqueue_schema(ptr)
-                })
-                .collect()
-        } else {
-            Vec::new()
+    fn enqueue_arr(&mut self, pname: &'static str) -> IndexRange {
+        let Some(Value::Array(arr)) = self.obj.get(pname) else {
+            return IndexRange::default();
+        };
+        let start = self.schemas.indexes.len() + self.queue.indexes.len();
+        for i in 0..arr.len() {
+            let ptr = self.up.ptr.append2(pname, &i.to_string());
+            let sch = self.enqueue_schema(ptr);
+            self.queue.indexes.push(sch);
         }
+        IndexRange::new(start, arr.len())
     }
 
     fn enqueue_map<T>(&mut self, pname: &'static str) -> T
@@ -682,13 +2093,22 @@ impl<'c, 'v, 'l, 's, 'r, 'q> ObjCompiler<'c, 'v, 'l, 's, 'r, 'q> {
         };
         let base_url = self.root.base_url(&self.up.ptr);
         let abs_ref = UrlFrag::join(base_url, ref_)?;
+        if let Fragment::Anchor(anchor) = &abs_ref.frag {
+            self.queue
+                .ref_anchors
+                .insert((abs_ref.url.clone(), anchor.to_string()));
+        }
         if let Some(resolved_ref) = self.root.resolve(&abs_ref)? {
             // local ref
-            return Ok(Some(self.enqueue_schema(resolved_ref.ptr)));
+            let up = UrlPtr {
+                url: self.up.url.clone(),
+                ptr: resolved_ref.ptr,
+            };
+            return Ok(Some(self.queue.enqueue_ref(self.schemas, up)));
         }
         // remote ref
         let up = self.queue.resolve_anchor(abs_ref, &self.c.roots)?;
-        Ok(Some(self.queue.enqueue_schema(self.schemas, up)))
+        Ok(Some(self.queue.enqueue_ref(self.schemas, up)))
     }
 
     fn enquue_additional(&mut self, pname: &'static str) -> Option<Additional> {
@@ -710,10 +2130,60 @@ impl<'c, 'v, 'l, 's, 'r, 'q> ObjCompiler<'c, 'v, 'l, 's, 'r, 'q> {
         self.root.has_vocab(name)
     }
 
+    // tells whether `kw` is defined by dialect, or is allowed.
+    fn is_allowed_keyword(&self, kw: &str) -> bool {
+        self.c.dialect.is_some_and(|d| d.is_keyword(kw)) || self.c.allowed_keywords.contains(kw)
+    }
+
     fn value(&self, pname: &str) -> Option<&'v Value> {
         self.obj.get(pname)
     }
 
+    // returns `s` interned, reusing the name interned earlier if any.
+    fn intern(&mut self, s: &str) -> Name {
+        match self.schemas.names.get(s) {
+            Some(name) => Name::clone(name),
+            None => self.queue.names.intern(s),
+        }
+    }
+
+    // returns compiled regex, reusing the one compiled earlier if any.
+    fn regex(&mut self, keyword: &'static str, pattern: &str) -> Result<Regex, CompileError> {
+        if let Some(regex) = self.c.regexes.get(pattern) {
+            return Ok(regex.clone());
+        }
+        if let Some(regex) = self.queue.regexes.get(pattern) {
+            return Ok(regex.clone());
+        }
+        let regex = self
+            .c
+            .regex_engine
+            .compile(pattern, &self.c.regex_limits)
+            .map_err(|src| CompileError::InvalidRegex {
+                url: self.up.format(keyword),
+                regex: pattern.to_owned(),
+                src,
+            })?;
+        self.queue.regexes.insert(pattern.to_owned(), regex.clone());
+        Ok(regex)
+    }
+
+    fn unknown_ref(&mut self, keyword: &'static str, name: &str) {
+        let r = UnknownRef {
+            schema_url: self.up.to_string(),
+            keyword,
+            name: name.to_owned(),
+        };
+        self.queue
+            .warnings
+            .push(CompileWarning::UnknownRef(r.clone()));
+        self.queue.unknown_refs.push(r);
+    }
+
+    fn str(&self, pname: &str) -> Option<&'v str> {
+        self.obj.get(pname).and_then(Value::as_str)
+    }
+
     fn bool(&self, pname: &str) -> bool {
         matches!(self.obj.get(pname), Some(Value::Bool(true)))
     }
@@ -748,6 +2218,7 @@ impl<'c, 'v, 'l, 's, 'r, 'q> ObjCompiler<'c, 'v, 'l, 's, 'r, 'q> {
 }
 
 /// Error type for compilation failures.
+#[non_exhaustive]
 #[derive(Debug)]
 pub enum CompileError {
     /// Error in parsing `url`.
@@ -759,6 +2230,10 @@ pub enum CompileError {
     /// no [`UrlLoader`] registered for the `url`
     UnsupportedUrlScheme { url: String },
 
+    /// Loading `url` is not allowed, since offline mode is enabled.
+    /// see [`Compiler::enable_offline`]
+    OfflineLoadError { url: String },
+
     /// Error in parsing `$schema` url.
     InvalidMetaSchemaUrl { url: String, src: Box<dyn Error> },
 
@@ -815,6 +2290,31 @@ pub enum CompileError {
         src: Box<dyn Error>,
     },
 
+    /// `keyword` at `url` is not supported by the dialect in use, or
+    /// is unknown in strict mode. see [`Compiler::enable_strict_mode`]
+    UnsupportedKeyword { url: String, keyword: String },
+
+    /// Value of custom `keyword` at `url` is invalid.
+    /// see [`Compiler::register_keyword`]
+    InvalidKeyword {
+        url: String,
+        keyword: String,
+        src: Box<dyn Error>,
+    },
+
+    /// Unknown `bsonType` alias `bson_type` at `url`.
+    InvalidBsonType { url: String, bson_type: String },
+
+    /// Error in applying patch for overlay `url`.
+    InvalidPatch { url: String, src: Box<dyn Error> },
+
+    /// AsyncAPI document `url` is invalid or not supported.
+    InvalidAsyncApi { url: String, src: Box<dyn Error> },
+
+    /// Compiling schema at `url` exceeds `limit`.
+    /// see [`Compiler::set_compile_limits`]
+    LimitExceeded { url: String, limit: CompileLimit },
+
     /// Encountered bug in compiler implementation. Please report
     /// this as an issue for this crate.
     Bug(Box<dyn Error>),
@@ -827,6 +2327,9 @@ impl Error for CompileError {
             Self::LoadUrlError { src, .. } => Some(src.as_ref()),
             Self::InvalidMetaSchemaUrl { src, .. } => Some(src.as_ref()),
             Self::ValidationError { src, .. } => Some(src),
+            Self::InvalidKeyword { src, .. } => Some(src.as_ref()),
+            Self::InvalidPatch { src, .. } => Some(src.as_ref()),
+            Self::InvalidAsyncApi { src, .. } => Some(src.as_ref()),
             Self::Bug(src) => Some(src.as_ref()),
             _ => None,
         }
@@ -851,6 +2354,9 @@ impl Display for CompileError {
                 }
             }
             Self::UnsupportedUrlScheme { url } => write!(f, "unsupported scheme in {url}"),
+            Self::OfflineLoadError { url } => {
+                write!(f, "loading {url} not allowed in offline mode")
+            }
             Self::InvalidMetaSchemaUrl { url, src } => {
                 if f.alternate() {
                     write!(f, "invalid $schema in {url}: {src}")
@@ -906,6 +2412,36 @@ impl Display for CompileError {
                     write!(f, "invalid regex {} at {url}", quote(regex))
                 }
             }
+            Self::UnsupportedKeyword { url, keyword } => {
+                write!(f, "unsupported keyword {} at {url}", quote(keyword))
+            }
+            Self::InvalidKeyword { url, keyword, src } => {
+                if f.alternate() {
+                    write!(f, "invalid keyword {} at {url}: {src}", quote(keyword))
+                } else {
+                    write!(f, "invalid keyword {} at {url}", quote(keyword))
+                }
+            }
+            Self::InvalidBsonType { url, bson_type } => {
+                write!(f, "invalid bsonType {} at {url}", quote(bson_type))
+            }
+            Self::InvalidPatch { url, src } => {
+                if f.alternate() {
+                    write!(f, "error applying patch for {url}: {src}")
+                } else {
+                    write!(f, "error applying patch for {url}")
+                }
+            }
+            Self::InvalidAsyncApi { url, src } => {
+                if f.alternate() {
+                    write!(f, "invalid asyncapi document {url}: {src}")
+                } else {
+                    write!(f, "invalid asyncapi document {url}")
+                }
+            }
+            Self::LimitExceeded { url, limit } => {
+                write!(f, "compiling {url} exceeds {limit}")
+            }
             Self::Bug(src) => {
                 write!(
                     f,
@@ -916,6 +2452,42 @@ impl Display for CompileError {
     }
 }
 
+/// Limits on single [`Compiler::compile`] call, where `None` means unlimited.
+/// see [`Compiler::set_compile_limits`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompileLimits {
+    /// Maximum number of schemas compiled, counting each subschema.
+    pub max_schemas: Option<usize>,
+    /// Maximum number of documents the compiled schemas belong to,
+    /// including the one being compiled.
+    pub max_documents: Option<usize>,
+    /// Maximum number of references followed to reach any schema from
+    /// the one being compiled.
+    pub max_ref_depth: Option<usize>,
+}
+
+/// Limit exceeded, in [`CompileError::LimitExceeded`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompileLimit {
+    /// see [`CompileLimits::max_schemas`].
+    Schemas(usize),
+    /// see [`CompileLimits::max_documents`].
+    Documents(usize),
+    /// see [`CompileLimits::max_ref_depth`].
+    RefDepth(usize),
+}
+
+impl Display for CompileLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Schemas(max) => write!(f, "maximum of {max} schemas"),
+            Self::Documents(max) => write!(f, "maximum of {max} documents"),
+            Self::RefDepth(max) => write!(f, "maximum reference depth of {max}"),
+        }
+    }
+}
+
 // helpers --
 
 fn to_strings(v: &Value) -> Vec<String> {
@@ -936,14 +2508,38 @@ fn to_strings(v: &Value) -> Vec<String> {
 
 pub(crate) struct Queue {
     pub(crate) schemas: Vec<UrlPtr>,
-    pub(crate) roots: HashMap<Url, Root>,
+    pub(crate) roots: FastHashMap<Url, Root>,
+    pub(crate) unknown_refs: Vec<UnknownRef>,
+    pub(crate) warnings: Vec<CompileWarning>,
+    pub(crate) ref_anchors: HashSet<(Url, String)>,
+    pub(crate) regexes: HashMap<String, Regex>,
+    ref_depths: Vec<usize>,    // number of refs followed to reach schemas[i]
+    ref_depth: usize,          // of the schema being compiled
+    indexes: Vec<SchemaIndex>, // to be appended to Schemas::indexes
+    names: Interner,           // to be added to Schemas::names
+    shapes: FastHashMap<u64, Vec<usize>>, // hash of subschema => positions in schemas
+    lazy: bool,                // compile targets of references lazily
+    cold: Vec<bool>,           // is schemas[i] reached only by references
+    in_place: Option<SchemaIndex>, // lazy schema compiled as schemas[0]
 }
 
 impl Queue {
     fn new() -> Self {
         Self {
             schemas: vec![],
-            roots: HashMap::new(),
+            roots: FastHashMap::new(),
+            unknown_refs: vec![],
+            warnings: vec![],
+            ref_anchors: HashSet::new(),
+            regexes: HashMap::new(),
+            ref_depths: vec![],
+            ref_depth: 0,
+            indexes: vec![],
+            names: Interner::default(),
+            shapes: FastHashMap::new(),
+            lazy: false,
+            cold: vec![],
+            in_place: None,
         }
     }
 
@@ -968,18 +2564,78 @@ impl Queue {
         }
     }
 
+    // index of schema at position `qindex` in queue.
+    fn index(&self, schemas: &Schemas, qindex: usize) -> SchemaIndex {
+        match self.in_place {
+            Some(sch) if qindex == 0 => sch,
+            Some(_) => SchemaIndex(schemas.size() + qindex - 1),
+            None => SchemaIndex(schemas.size() + qindex),
+        }
+    }
+
     pub(crate) fn enqueue_schema(&mut self, schemas: &Schemas, up: UrlPtr) -> SchemaIndex {
+        self.enqueue(schemas, up, false)
+    }
+
+    // enqueues `up`. `cold` tells whether it is needed only if validation
+    // reaches it, in which case it may be compiled lazily.
+    fn enqueue(&mut self, schemas: &Schemas, up: UrlPtr, cold: bool) -> SchemaIndex {
         if let Some(sch) = schemas.get_by_loc(&up) {
             // already got compiled
             return sch.idx;
         }
         if let Some(qindex) = self.schemas.iter().position(|e| *e == up) {
             // already queued for compilation
-            return SchemaIndex(schemas.size() + qindex);
+            self.cold[qindex] &= cold;
+            return self.index(schemas, qindex);
         }
 
         // new compilation request
         self.schemas.push(up);
-        SchemaIndex(schemas.size() + self.schemas.len() - 1)
+        self.ref_depths.push(self.ref_depth);
+        self.cold.push(cold);
+        self.index(schemas, self.schemas.len() - 1)
+    }
+
+    // same as enqueue_schema, but reuses subschema enqueued by this method
+    // earlier, if it is in the same resource, and equal to `up` in `doc`.
+    fn enqueue_dedup(
+        &mut self,
+        schemas: &Schemas,
+        up: UrlPtr,
+        doc: &Value,
+        root: &Root,
+    ) -> SchemaIndex {
+        if schemas.get_by_loc(&up).is_some() || self.schemas.contains(&up) {
+            return self.enqueue_schema(schemas, up);
+        }
+        let Ok(v) = up.lookup(doc) else {
+            return self.enqueue_schema(schemas, up);
+        };
+        let hash = hash_value(v);
+        let resource = &root.resource(&up.ptr).ptr;
+        for &i in self.shapes.get(&hash).into_iter().flatten() {
+            let other = &self.schemas[i];
+            if other.url == up.url
+                && root.resource(&other.ptr).ptr == *resource
+                && other.lookup(doc).is_ok_and(|other| equals(other, v))
+            {
+                return self.index(schemas, i);
+            }
+        }
+        let sch = self.enqueue_schema(schemas, up);
+        let qindex = self.schemas.len() - 1;
+        self.shapes.entry(hash).or_default().push(qindex);
+        sch
+    }
+
+    // same as enqueue_schema, but for target of reference.
+    fn enqueue_ref(&mut self, schemas: &Schemas, up: UrlPtr) -> SchemaIndex {
+        let len = self.schemas.len();
+        let sch = self.enqueue(schemas, up, self.lazy);
+        if self.schemas.len() > len {
+            self.ref_depths[len] = self.ref_depth + 1;
+        }
+        sch
     }
 }
@@ -18,6 +18,15 @@ pub struct Decoder {
     pub func: fn(s: &str) -> Result<Vec<u8>, Box<dyn Error>>,
 }
 
+impl Decoder {
+    /// Returns names of `contentEncoding`s supported by this library.
+    pub fn builtin_names() -> Vec<&'static str> {
+        let mut names = DECODERS.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+}
+
 pub(crate) static DECODERS: Lazy<HashMap<&'static str, Decoder>> = Lazy::new(|| {
     let mut m = HashMap::<&'static str, Decoder>::new();
     m.insert(
@@ -60,6 +69,15 @@ pub struct MediaType {
     pub func: fn(bytes: &[u8], deserialize: bool) -> Result<Option<Value>, Box<dyn Error>>,
 }
 
+impl MediaType {
+    /// Returns names of `contentMediaType`s supported by this library.
+    pub fn builtin_names() -> Vec<&'static str> {
+        let mut names = MEDIA_TYPES.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+}
+
 pub(crate) static MEDIA_TYPES: Lazy<HashMap<&'static str, MediaType>> = Lazy::new(|| {
     let mut m = HashMap::<&'static str, MediaType>::new();
     m.insert(
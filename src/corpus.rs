@@ -0,0 +1,386 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{Display, Formatter},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{
+    ser::{SerializeMap, SerializeSeq},
+    Serialize,
+};
+
+use serde_json::Value;
+use url::Url;
+
+use crate::{output::write_json_to_fmt, util::*, *};
+
+/**
+Consolidated report of compiling all schema files in a directory.
+
+It implements `serde::Serialize` and `Display` to print json.
+*/
+#[derive(Debug, Default)]
+pub struct CorpusReport {
+    /// one entry per schema file, ordered by path.
+    pub files: Vec<FileReport>,
+}
+
+/// Compile status of a schema file in [`CorpusReport`].
+#[derive(Debug)]
+pub struct FileReport {
+    /// path of the file, relative to the directory.
+    pub path: String,
+    /// draft used to compile the file. `None` if compilation failed.
+    pub draft: Option<Draft>,
+    /// compilation error if any.
+    pub error: Option<CompileError>,
+    /// reference which could not be loaded or resolved, if that caused the error.
+    pub unresolved_ref: Option<String>,
+    /// references to unknown `format`, `contentEncoding` or `contentMediaType`
+    /// found while compiling the file.
+    pub unknown_refs: Vec<UnknownRef>,
+    /// issues found by [`Compiler::lint`] in the file, whether or not it
+    /// compiled successfully.
+    pub lints: Vec<Lint>,
+}
+
+/// Url claimed by more than one resource, found by [`Compiler::add_resources_from_dir`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceConflict {
+    /// url claimed, either `file://` url or `$id`.
+    pub url: String,
+    /// paths of the files claiming it, relative to the directory. Has
+    /// single path, if it conflicts only with resource added earlier.
+    pub paths: Vec<String>,
+}
+
+impl CorpusReport {
+    /// Returns true if all files are compiled successfully.
+    pub fn is_ok(&self) -> bool {
+        self.files.iter().all(|f| f.error.is_none())
+    }
+
+    /// Returns number of successfully compiled files per draft.
+    pub fn drafts(&self) -> BTreeMap<Draft, usize> {
+        let mut drafts = BTreeMap::new();
+        for draft in self.files.iter().filter_map(|f| f.draft) {
+            *drafts.entry(draft).or_default() += 1;
+        }
+        drafts
+    }
+}
+
+impl Compiler {
+    /**
+    Compiles every `.json` file in directory `dir` and its subdirectories,
+    as root schema.
+
+    Failure to compile a file does not stop compiling other files.
+    It is reported in [`FileReport::error`] instead.
+
+    Note that [`FileReport::unknown_refs`] of a file include those found in
+    other files it references, unless they are already compiled. Whereas
+    [`FileReport::lints`] include only those found in the file itself.
+
+    # Errors
+
+    returns error if `dir` cannot be read.
+    */
+    pub fn compile_corpus(
+        &mut self,
+        dir: impl AsRef<Path>,
+        target: &mut Schemas,
+    ) -> Result<CorpusReport, io::Error> {
+        let dir = dir.as_ref();
+        let mut paths = vec![];
+        collect_files(dir, &mut paths)?;
+        paths.retain(|path| path.extension().filter(|&ext| ext == "json").is_some());
+        paths.sort();
+
+        let mut report = CorpusReport::default();
+        for path in paths {
+            let rel = path.strip_prefix(dir).unwrap_or(&path);
+            let mut file = FileReport {
+                path: rel.to_string_lossy().replace('\\', "/"),
+                draft: None,
+                error: None,
+                unresolved_ref: None,
+                unknown_refs: vec![],
+                lints: vec![],
+            };
+            let num_unknown_refs = self.unknown_refs().len();
+            match self.compile(&path.to_string_lossy(), target) {
+                Ok(sch_index) => {
+                    file.draft = Some(match target.get(sch_index).draft_version {
+                        4 => Draft::V4,
+                        6 => Draft::V6,
+                        7 => Draft::V7,
+                        2019 => Draft::V2019_09,
+                        _ => Draft::V2020_12,
+                    });
+                }
+                Err(e) => {
+                    file.unresolved_ref = match &e {
+                        CompileError::LoadUrlError { url, .. }
+                        | CompileError::UnsupportedUrlScheme { url }
+                        | CompileError::OfflineLoadError { url }
+                        | CompileError::AnchorNotFound { reference: url, .. }
+                        | CompileError::JsonPointerNotFound(url) => Some(url.clone()),
+                        _ => None,
+                    };
+                    // the file itself is not a reference
+                    let own_url = UrlFrag::absolute(&path.to_string_lossy())
+                        .ok()
+                        .map(|uf| uf.url.to_string());
+                    if file.unresolved_ref == own_url {
+                        file.unresolved_ref = None;
+                    }
+                    file.error = Some(e);
+                }
+            }
+            file.unknown_refs = self.unknown_refs()[num_unknown_refs..].to_vec();
+            // if file cannot be loaded, it is already reported as error
+            file.lints = self.lint(&path.to_string_lossy()).unwrap_or_default();
+            report.files.push(file);
+        }
+        Ok(report)
+    }
+
+    /// Adds every file in directory `dir` and its subdirectories, whose path
+    /// relative to `dir` matches `glob`, as schema resource.
+    ///
+    /// Each file is added under its `file://` url, and also under its `$id` if
+    /// any. `glob` uses `/` as path separator, and supports `*` matching
+    /// within a path segment, `**` matching across segments and `?` matching
+    /// single character. For example `**/*.json`.
+    ///
+    /// If a url is claimed by files with different content, the file whose
+    /// path comes first in sort order wins, and the clash is returned as
+    /// [`ResourceConflict`]. So is a file claiming url of resource added
+    /// earlier with different content, in which case the earlier one wins.
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use boon::*;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// let mut schemas = Schemas::new();
+    /// let mut compiler = Compiler::new();
+    /// let conflicts = compiler.add_resources_from_dir("schemas", "**/*.json")?;
+    /// assert!(conflicts.is_empty(), "{conflicts:?}");
+    /// compiler.compile("https://example.com/person.json", &mut schemas)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// returns [`CompileError`] if `dir` cannot be read, or if a matching
+    /// file cannot be loaded as json.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn add_resources_from_dir(
+        &mut self,
+        dir: impl AsRef<Path>,
+        glob: &str,
+    ) -> Result<Vec<ResourceConflict>, CompileError> {
+        let dir = dir.as_ref();
+        let to_err = |src: Box<dyn std::error::Error>| CompileError::LoadUrlError {
+            url: dir.display().to_string(),
+            src,
+        };
+        let glob = glob_regex(glob).map_err(|e| to_err(e.into()))?;
+        let mut paths = vec![];
+        collect_files(dir, &mut paths).map_err(|e| to_err(e.into()))?;
+        paths.sort();
+
+        let mut claims: HashMap<Url, Vec<String>> = HashMap::new();
+        let mut conflicts = vec![];
+        for path in paths {
+            let rel = path.strip_prefix(dir).unwrap_or(&path);
+            let rel = rel.to_string_lossy().replace('\\', "/");
+            if !glob.is_match(&rel) {
+                continue;
+            }
+            let url = UrlFrag::absolute(&path.to_string_lossy())?.url;
+            let doc = FileLoader
+                .load(url.as_str())
+                .map_err(|src| CompileError::LoadUrlError {
+                    url: url.to_string(),
+                    src,
+                })?;
+
+            let mut urls = vec![url.clone()];
+            if let Value::Object(obj) = &doc {
+                let draft = match obj.get("$schema") {
+                    Some(Value::String(sch)) => draft::Draft::from_url(sch),
+                    _ => None,
+                };
+                let draft = draft.unwrap_or(self.roots.default_draft);
+                if let Some(id) = draft.get_id(obj) {
+                    let id = UrlFrag::join(&url, id)?.url;
+                    if id != url {
+                        urls.push(id);
+                    }
+                }
+            }
+            for url in urls {
+                let claimers = claims.entry(url.clone()).or_default();
+                claimers.push(rel.clone());
+                match self.roots.loader.get_doc(&url) {
+                    Some(existing) => {
+                        if existing != &doc && !conflicts.contains(&url) {
+                            conflicts.push(url);
+                        }
+                    }
+                    None => self.roots.loader.add_doc(url, doc.clone()),
+                }
+            }
+        }
+
+        let mut conflicts = conflicts
+            .into_iter()
+            .map(|url| ResourceConflict {
+                paths: claims.remove(&url).unwrap_or_default(),
+                url: url.into(),
+            })
+            .collect::<Vec<_>>();
+        conflicts.sort_by(|a, b| a.url.cmp(&b.url));
+        Ok(conflicts)
+    }
+}
+
+fn collect_files(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<(), io::Error> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_files(&path, paths)?;
+        } else {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+// converts `glob` to anchored regex.
+fn glob_regex(glob: &str) -> Result<regex::Regex, regex::Error> {
+    let mut re = String::from("^");
+    let mut rest = glob;
+    while let Some(ch) = rest.chars().next() {
+        if let Some(tail) = rest.strip_prefix("**/") {
+            re.push_str("(?:.*/)?");
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("**") {
+            re.push_str(".*");
+            rest = tail;
+        } else {
+            match ch {
+                '*' => re.push_str("[^/]*"),
+                '?' => re.push_str("[^/]"),
+                _ => re.push_str(&regex::escape(&ch.to_string())),
+            }
+            rest = &rest[ch.len_utf8()..];
+        }
+    }
+    re.push('$');
+    regex::Regex::new(&re)
+}
+
+fn draft_name(draft: Draft) -> &'static str {
+    match draft {
+        Draft::V4 => "4",
+        Draft::V6 => "6",
+        Draft::V7 => "7",
+        Draft::V2019_09 => "2019-09",
+        _ => "2020-12",
+    }
+}
+
+impl Serialize for CorpusReport {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let drafts = self
+            .drafts()
+            .into_iter()
+            .map(|(draft, n)| (draft_name(draft), n))
+            .collect::<BTreeMap<_, _>>();
+        let failed = self.files.iter().filter(|f| f.error.is_some()).count();
+        let mut map = serializer.serialize_map(Some(4))?;
+        map.serialize_entry("valid", &self.is_ok())?;
+        map.serialize_entry("failed", &failed)?;
+        map.serialize_entry("drafts", &drafts)?;
+        map.serialize_entry("files", &self.files)?;
+        map.end()
+    }
+}
+
+impl Serialize for FileReport {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("path", &self.path)?;
+        if let Some(draft) = self.draft {
+            map.serialize_entry("draft", draft_name(draft))?;
+        }
+        if let Some(e) = &self.error {
+            map.serialize_entry("error", &format!("{e:#}"))?;
+        }
+        if let Some(url) = &self.unresolved_ref {
+            map.serialize_entry("unresolvedRef", url)?;
+        }
+        if !self.unknown_refs.is_empty() {
+            map.serialize_entry("unknownRefs", &UnknownRefs(&self.unknown_refs))?;
+        }
+        if !self.lints.is_empty() {
+            map.serialize_entry("lints", &Lints(&self.lints))?;
+        }
+        map.end()
+    }
+}
+
+struct UnknownRefs<'a>(&'a [UnknownRef]);
+
+impl Serialize for UnknownRefs<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for r in self.0 {
+            let mut m = BTreeMap::new();
+            m.insert("schemaUrl", r.schema_url.as_str());
+            m.insert("keyword", r.keyword);
+            m.insert("name", r.name.as_str());
+            seq.serialize_element(&m)?;
+        }
+        seq.end()
+    }
+}
+
+struct Lints<'a>(&'a [Lint]);
+
+impl Serialize for Lints<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for lint in self.0 {
+            let mut m = BTreeMap::new();
+            m.insert("url", lint.url.clone());
+            m.insert("message", lint.to_string());
+            seq.serialize_element(&m)?;
+        }
+        seq.end()
+    }
+}
+
+impl Display for CorpusReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_json_to_fmt(f, self)
+    }
+}
@@ -0,0 +1,150 @@
+use std::fmt::Display;
+
+use crate::{SchemaIndex, Schemas};
+
+/// Cycle of schema references, reported by [`Schemas::ref_cycles`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefCycle {
+    /// locations of schemas in the cycle. The last schema leads back to
+    /// the first.
+    pub schemas: Vec<String>,
+    /// keyword paths of the edges, such that `keywords[i]` leads from
+    /// `schemas[i]` to the next schema in the cycle.
+    pub keywords: Vec<String>,
+    /// whether every edge applies to the same instance value, as with
+    /// `$ref` or `allOf`, rather than to its properties or items. Such
+    /// cycle is never intentional: validation fails with
+    /// [`ErrorKind::RefCycle`](crate::ErrorKind::RefCycle) whenever it is
+    /// reached.
+    pub in_place: bool,
+}
+
+impl Display for RefCycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (sch, kw) in self.schemas.iter().zip(&self.keywords) {
+            write!(f, "{sch} -[{kw}]-> ")?;
+        }
+        if let Some(first) = self.schemas.first() {
+            write!(f, "{first}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Schemas {
+    /**
+    Finds cycles of references among all compiled schemas.
+
+    Recursive schemas, such as a tree whose children refer back to the
+    tree, are legitimate and show up here with `in_place` false. Cycles
+    which do not descend into the instance, such as two schemas whose
+    `$ref`s point to each other, have `in_place` true and are
+    always mistakes.
+
+    One cycle is reported per back edge found by depth-first traversal in
+    the order schemas were compiled. So every recursive structure is
+    reported at least once, but not every combination of paths through
+    it.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://example.com/tree.json", json!({
+        "properties": {
+            "children": {"items": {"$ref": "#"}}
+        }
+    }))?;
+    compiler.compile("http://example.com/tree.json", &mut schemas)?;
+
+    let cycles = schemas.ref_cycles();
+    assert_eq!(cycles.len(), 1);
+    assert_eq!(cycles[0].keywords, ["properties/children", "items", "$ref"]);
+    assert!(!cycles[0].in_place);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn ref_cycles(&self) -> Vec<RefCycle> {
+        let mut state = vec![State::New; self.list.len()];
+        let mut path = vec![];
+        let mut cycles = vec![];
+        for s in self.list.iter().filter(|s| !s.stale) {
+            if state[s.idx.0] == State::New {
+                self.find_cycles(s.idx, &mut state, &mut path, &mut cycles);
+            }
+        }
+        cycles
+    }
+
+    fn find_cycles(
+        &self,
+        sch: SchemaIndex,
+        state: &mut [State],
+        path: &mut Vec<(SchemaIndex, &'static str, String)>,
+        cycles: &mut Vec<RefCycle>,
+    ) {
+        state[sch.0] = State::OnPath;
+        for (kp, child) in self.get(sch).children(self) {
+            match state[child.0] {
+                State::New => {
+                    path.push((sch, kp.keyword, kp.to_string()));
+                    self.find_cycles(child, state, path, cycles);
+                    path.pop();
+                }
+                State::OnPath => {
+                    let start = path
+                        .iter()
+                        .position(|(s, ..)| *s == child)
+                        .unwrap_or(path.len());
+                    let edges = path[start..]
+                        .iter()
+                        .map(|(s, kw, label)| (*s, *kw, label.clone()))
+                        .chain([(sch, kp.keyword, kp.to_string())]);
+                    let mut cycle = RefCycle {
+                        schemas: vec![],
+                        keywords: vec![],
+                        in_place: true,
+                    };
+                    for (s, kw, label) in edges {
+                        cycle.schemas.push(self.get(s).loc.clone());
+                        cycle.keywords.push(label);
+                        cycle.in_place &= is_in_place(kw);
+                    }
+                    cycles.push(cycle);
+                }
+                State::Done => {}
+            }
+        }
+        state[sch.0] = State::Done;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    New,
+    OnPath,
+    Done,
+}
+
+// tells whether keyword applies subschema to the same instance value.
+pub(crate) fn is_in_place(kw: &str) -> bool {
+    matches!(
+        kw,
+        "$ref"
+            | "$recursiveRef"
+            | "$dynamicRef"
+            | "allOf"
+            | "anyOf"
+            | "oneOf"
+            | "not"
+            | "if"
+            | "then"
+            | "else"
+            | "dependentSchemas"
+            | "dependencies"
+    )
+}
@@ -0,0 +1,140 @@
+use std::borrow::Cow;
+
+use serde_json::{Map, Value};
+
+use crate::{util::*, InstanceToken};
+
+// keywords whose value can be `$data` reference, along with
+// the draft version they are supported from.
+pub(crate) const DATA_KEYWORDS: [(&str, usize); 12] = [
+    ("const", 6),
+    ("minimum", 4),
+    ("maximum", 4),
+    ("exclusiveMinimum", 6),
+    ("exclusiveMaximum", 6),
+    ("multipleOf", 4),
+    ("minLength", 4),
+    ("maxLength", 4),
+    ("minItems", 4),
+    ("maxItems", 4),
+    ("minProperties", 4),
+    ("maxProperties", 4),
+];
+
+/// `$data` reference, which is either json-pointer or relative json-pointer.
+#[derive(Debug, PartialEq)]
+pub(crate) struct DataRef {
+    up: Option<usize>, // None means json-pointer from root
+    tokens: Vec<String>,
+    key: bool, // relative json-pointer ending with `#`
+}
+
+impl DataRef {
+    /// returns approximate heap bytes owned.
+    pub(crate) fn heap_size(&self) -> usize {
+        self.tokens.capacity() * std::mem::size_of::<String>()
+            + self.tokens.iter().map(String::capacity).sum::<usize>()
+    }
+
+    pub(crate) fn parse(s: &str) -> Option<DataRef> {
+        let (up, ptr) = if s.is_empty() || s.starts_with('/') {
+            (None, s)
+        } else {
+            let i = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+            let (n, ptr) = s.split_at(i);
+            if n.is_empty() || (n.len() > 1 && n.starts_with('0')) {
+                return None;
+            }
+            (Some(n.parse().ok()?), ptr)
+        };
+        if up.is_some() && ptr == "#" {
+            return Some(DataRef {
+                up,
+                tokens: vec![],
+                key: true,
+            });
+        }
+        if !ptr.is_empty() && !ptr.starts_with('/') {
+            return None;
+        }
+        let mut tokens = vec![];
+        if let Some(ptr) = ptr.strip_prefix('/') {
+            for tok in ptr.split('/') {
+                tokens.push(JsonPointer::unescape(tok).ok()?.into_owned());
+            }
+        }
+        Some(DataRef {
+            up,
+            tokens,
+            key: false,
+        })
+    }
+
+    /// resolves against instance `root`, where `vloc` is the location of
+    /// instance being validated. returns None if it resolves to nothing.
+    pub(crate) fn resolve<'v>(
+        &self,
+        root: &'v Value,
+        vloc: &[InstanceToken<'v>],
+    ) -> Option<Cow<'v, Value>> {
+        let base = match self.up {
+            Some(n) => &vloc[..vloc.len().checked_sub(n)?],
+            None => &[],
+        };
+        if self.key {
+            return match base.last()? {
+                InstanceToken::Prop(p) => Some(Cow::Owned(Value::String(p.to_string()))),
+                InstanceToken::Item(i) => Some(Cow::Owned(Value::from(*i))),
+            };
+        }
+        let mut v = root;
+        for tok in base {
+            v = match (tok, v) {
+                (InstanceToken::Prop(p), Value::Object(obj)) => obj.get(p.as_ref())?,
+                (InstanceToken::Item(i), Value::Array(arr)) => arr.get(*i)?,
+                _ => return None,
+            };
+        }
+        for tok in &self.tokens {
+            v = match v {
+                Value::Object(obj) => obj.get(tok)?,
+                Value::Array(arr) => {
+                    if tok.len() > 1 && tok.starts_with('0') {
+                        return None;
+                    }
+                    arr.get(tok.parse::<usize>().ok()?)?
+                }
+                _ => return None,
+            };
+        }
+        Some(Cow::Borrowed(v))
+    }
+}
+
+// returns `$data` reference string, if `v` is `{"$data": "..."}`.
+pub(crate) fn data_ref(v: &Value) -> Option<&str> {
+    match v {
+        Value::Object(obj) if obj.len() == 1 => obj.get("$data")?.as_str(),
+        _ => None,
+    }
+}
+
+// returns copy of `v` with `$data` keywords removed, so that
+// it can be validated against metaschema.
+pub(crate) fn strip_data(v: &Value) -> Value {
+    match v {
+        Value::Object(obj) => {
+            let mut m = Map::new();
+            for (pname, pvalue) in obj {
+                let is_data =
+                    DATA_KEYWORDS.iter().any(|(kw, _)| kw == pname) && data_ref(pvalue).is_some();
+                if !is_data {
+                    m.insert(pname.clone(), strip_data(pvalue));
+                }
+            }
+            Value::Object(m)
+        }
+        Value::Array(arr) => Value::Array(arr.iter().map(strip_data).collect()),
+        _ => v.clone(),
+    }
+}
@@ -0,0 +1,187 @@
+use std::{cmp::min, collections::HashSet};
+
+use serde_json::Value;
+
+use crate::{util::*, *};
+
+impl Schemas {
+    /**
+    Fills missing properties and array items of `v` with `default` values
+    from schema identified by `sch_index`, and returns json-pointers of
+    the locations defaulted.
+
+    Defaults are taken from `properties`, `prefixItems` and array form of
+    `items`, of the schema and the subschemas it applies unconditionally
+    i.e. `$ref` and `allOf`. Defaults in `anyOf`, `oneOf`, `if`/`then`/`else`
+    etc. are not used, because which of them apply is not known before
+    validation. Missing array items are filled only at the end of the array.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let schema = json!({
+        "properties": {
+            "port": {"type": "integer", "default": 8080}
+        }
+    });
+    compiler.add_resource("config.json", schema)?;
+    let sch_index = compiler.compile("config.json", &mut schemas)?;
+
+    let mut config = json!({});
+    let defaulted = schemas.apply_defaults(&mut config, sch_index);
+    assert_eq!(config, json!({"port": 8080}));
+    assert_eq!(defaulted, ["/port"]);
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn apply_defaults(&self, v: &mut Value, sch_index: SchemaIndex) -> Vec<String> {
+        let mut defaulted = vec![];
+        self.fill_defaults(v, sch_index, &JsonPointer::from(""), &mut defaulted);
+        defaulted
+    }
+
+    /**
+    Same as [`Schemas::apply_defaults`] followed by [`Schemas::validate`]
+    of the resulting `v`.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn validate_defaults<'s, 'v>(
+        &'s self,
+        v: &'v mut Value,
+        sch_index: SchemaIndex,
+    ) -> Result<Vec<String>, ValidationError<'s, 'v>> {
+        let defaulted = self.apply_defaults(v, sch_index);
+        let v: &'v Value = v;
+        self.validate(v, sch_index).map(|_| defaulted)
+    }
+
+    fn fill_defaults(
+        &self,
+        v: &mut Value,
+        sch: SchemaIndex,
+        ptr: &JsonPointer,
+        defaulted: &mut Vec<String>,
+    ) {
+        let list = self.applied_schemas(sch);
+        match v {
+            Value::Object(obj) => {
+                for &sch in &list {
+                    let s = self.get(sch);
+                    let mut names = s.properties.keys().collect::<Vec<_>>();
+                    names.sort();
+                    for pname in names {
+                        if obj.contains_key(pname.as_ref()) {
+                            continue;
+                        }
+                        if let Some(default) = &self.get(s.properties[pname]).default {
+                            obj.insert(pname.to_string(), default.clone());
+                            defaulted.push(ptr.append(pname).to_string());
+                        }
+                    }
+                }
+                for (pname, pvalue) in obj.iter_mut() {
+                    for &sch in &list {
+                        for psch in prop_schemas(self.get(sch), pname) {
+                            self.fill_defaults(pvalue, psch, &ptr.append(pname), defaulted);
+                        }
+                    }
+                }
+            }
+            Value::Array(arr) => {
+                for &sch in &list {
+                    let prefix = prefix_items(self, self.get(sch));
+                    for &isch in &prefix[min(arr.len(), prefix.len())..] {
+                        let Some(default) = &self.get(isch).default else {
+                            break;
+                        };
+                        arr.push(default.clone());
+                        defaulted.push(ptr.append(&(arr.len() - 1).to_string()).to_string());
+                    }
+                }
+                for (i, item) in arr.iter_mut().enumerate() {
+                    for &sch in &list {
+                        if let Some(isch) = item_schema(self, self.get(sch), i) {
+                            self.fill_defaults(item, isch, &ptr.append(&i.to_string()), defaulted);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Schemas {
+    // returns schemas applying unconditionally to instance validated
+    // against `sch`, i.e. `sch` with $ref and allOf flattened.
+    pub(crate) fn applied_schemas(&self, sch: SchemaIndex) -> Vec<SchemaIndex> {
+        let mut list = vec![sch];
+        let mut seen = HashSet::from([sch]);
+        let mut i = 0;
+        while i < list.len() {
+            let s = self.get(list[i]);
+            for &sch in s.ref_.iter().chain(self.indexes(s.all_of)) {
+                if seen.insert(sch) {
+                    list.push(sch);
+                }
+            }
+            i += 1;
+        }
+        list
+    }
+}
+
+fn prefix_items<'s>(schemas: &'s Schemas, s: &Schema) -> &'s [SchemaIndex] {
+    match &s.items {
+        Some(Items::SchemaRefs(list)) => schemas.indexes(*list),
+        _ => schemas.indexes(s.prefix_items),
+    }
+}
+
+// returns schema which applies to array item at index `i`.
+pub(crate) fn item_schema(schemas: &Schemas, s: &Schema, i: usize) -> Option<SchemaIndex> {
+    let prefix = prefix_items(schemas, s);
+    if let Some(&sch) = prefix.get(i) {
+        return Some(sch);
+    }
+    match (&s.items, &s.additional_items) {
+        (Some(Items::SchemaRef(sch)), _) => Some(*sch),
+        (Some(Items::SchemaRefs(_)), Some(Additional::SchemaRef(sch))) => Some(*sch),
+        _ => s.items2020,
+    }
+}
+
+// returns schemas which apply to property `pname`.
+pub(crate) fn prop_schemas(s: &Schema, pname: &str) -> Vec<SchemaIndex> {
+    let mut v = s
+        .properties
+        .get(pname)
+        .into_iter()
+        .copied()
+        .collect::<Vec<_>>();
+    for (regex, sch) in &s.pattern_properties {
+        if regex.is_match(pname) {
+            v.push(*sch);
+        }
+    }
+    if v.is_empty() {
+        if let Some(Additional::SchemaRef(sch)) = &s.additional_properties {
+            v.push(*sch);
+        }
+    }
+    v
+}
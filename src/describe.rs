@@ -0,0 +1,305 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use serde_json::{Number, Value};
+
+use crate::{defaults::*, tooling::ptr_tokens, util::*, *};
+
+/// Effective constraints at an instance location, returned by [`Schemas::describe_at`].
+#[derive(Debug, Clone, Default)]
+pub struct Description {
+    /// locations of the schemas applicable.
+    pub schemas: Vec<String>,
+    /// first `title` found.
+    pub title: Option<String>,
+    /// first `description` found.
+    pub description: Option<String>,
+    /// allowed types. empty means any type, unless `unsatisfiable`.
+    pub types: Vec<Type>,
+    /// whether the schemas allow no type in common, so that no value is valid.
+    pub unsatisfiable: bool,
+    /// asserted formats.
+    pub formats: Vec<&'static str>,
+    /// regex patterns, all of which must match.
+    pub patterns: Vec<String>,
+    /// values allowed by `enum` or `const`, of the first schema using them.
+    pub values: Option<Vec<Value>>,
+    /// greatest of `minimum` values.
+    pub minimum: Option<Number>,
+    /// least of `maximum` values.
+    pub maximum: Option<Number>,
+    /// greatest of `exclusiveMinimum` values.
+    pub exclusive_minimum: Option<Number>,
+    /// least of `exclusiveMaximum` values.
+    pub exclusive_maximum: Option<Number>,
+    /// `multipleOf` values.
+    pub multiple_of: Vec<Number>,
+    /// greatest of `minLength` values.
+    pub min_length: Option<usize>,
+    /// least of `maxLength` values.
+    pub max_length: Option<usize>,
+    /// greatest of `minItems` values.
+    pub min_items: Option<usize>,
+    /// least of `maxItems` values.
+    pub max_items: Option<usize>,
+    /// greatest of `minProperties` values.
+    pub min_properties: Option<usize>,
+    /// least of `maxProperties` values.
+    pub max_properties: Option<usize>,
+    /// required properties.
+    pub required: Vec<String>,
+    /// whether any of the schemas is marked `deprecated`.
+    pub deprecated: bool,
+    /// constraints which apply only conditionally, one per branch of
+    /// `anyOf`, `oneOf` or `if`, which is not determined by the instance.
+    /// These are not combined into the other fields.
+    pub alternatives: Vec<Description>,
+}
+
+impl Schemas {
+    /**
+    Returns the effective constraints at location `instance_ptr` of
+    `instance`, validated against schema `sch_index`.
+
+    Constraints of all the applicable schemas are combined, with bounds
+    narrowed to the tightest. Applicable schemas are found following
+    `$ref`, `allOf` and conditionals as in [`tooling::completion_at`],
+    so that `instance_ptr` need not exist in `instance`.
+
+    Branch of `anyOf` or `oneOf` is combined only if it is the only branch
+    the instance is valid against, and `then` or `else` only if the
+    instance exists. Otherwise each branch is described separately in
+    [`Description::alternatives`].
+
+    Returns `None` if `instance_ptr` is not a valid json-pointer.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let schema = json!({
+        "properties": {
+            "port": {
+                "$ref": "#/$defs/port",
+                "title": "listen port",
+                "minimum": 1024
+            }
+        },
+        "$defs": {
+            "port": {"type": "integer", "minimum": 1, "maximum": 65535}
+        }
+    });
+    compiler.add_resource("config.json", schema)?;
+    let sch_index = compiler.compile("config.json", &mut schemas)?;
+
+    let d = schemas.describe_at(&json!({}), sch_index, "/port").unwrap();
+    assert_eq!(d.title.as_deref(), Some("listen port"));
+    assert_eq!(d.types, [Type::Integer]);
+    assert_eq!(d.minimum, Some(1024.into()));
+    assert_eq!(d.maximum, Some(65535.into()));
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn describe_at(
+        &self,
+        instance: &Value,
+        sch_index: SchemaIndex,
+        instance_ptr: &str,
+    ) -> Option<Description> {
+        let tokens = ptr_tokens(instance_ptr)?;
+        let mut seen = HashSet::new();
+        Some(self.describe_from(&[sch_index], Some(instance), &tokens, &mut seen))
+    }
+
+    // describes location `tokens` of `v`, where schemas `roots` apply.
+    // `seen` has branches already described, along with remaining tokens.
+    fn describe_from(
+        &self,
+        roots: &[SchemaIndex],
+        v: Option<&Value>,
+        tokens: &[String],
+        seen: &mut HashSet<(SchemaIndex, usize)>,
+    ) -> Description {
+        let mut list = vec![];
+        let mut branches = vec![];
+        for &sch in roots {
+            self.unconditional(sch, v, &mut list, &mut branches);
+        }
+        branches.retain(|sch| !list.contains(sch));
+
+        let mut d = match tokens.split_first() {
+            Some((tok, rest)) => {
+                let mut next = vec![];
+                for &sch in &list {
+                    let s = self.get(sch);
+                    match v {
+                        Some(Value::Array(_)) => {
+                            next.extend(tok.parse().ok().and_then(|i| item_schema(self, s, i)))
+                        }
+                        _ => next.extend(prop_schemas(s, tok)),
+                    }
+                }
+                let child = match v {
+                    Some(Value::Object(obj)) => obj.get(tok),
+                    Some(Value::Array(arr)) => tok.parse().ok().and_then(|i: usize| arr.get(i)),
+                    _ => None,
+                };
+                self.describe_from(&next, child, rest, seen)
+            }
+            None => self.combine(&list),
+        };
+        for sch in branches {
+            if !seen.insert((sch, tokens.len())) {
+                continue;
+            }
+            let alt = self.describe_from(&[sch], v, tokens, seen);
+            if !alt.schemas.is_empty() || !alt.alternatives.is_empty() {
+                d.alternatives.push(alt);
+            }
+        }
+        d
+    }
+
+    // adds schemas applicable to `v` unconditionally from `sch` into `list`,
+    // and branches which apply only conditionally into `branches`.
+    fn unconditional(
+        &self,
+        sch: SchemaIndex,
+        v: Option<&Value>,
+        list: &mut Vec<SchemaIndex>,
+        branches: &mut Vec<SchemaIndex>,
+    ) {
+        if list.contains(&sch) {
+            return;
+        }
+        list.push(sch);
+        let s = self.get(sch);
+        let is_valid = |sch: SchemaIndex| v.is_some_and(|v| self.validate(v, sch).is_ok());
+
+        let mut subschemas = vec![];
+        let all_of = self.indexes(s.all_of);
+        subschemas.extend(s.ref_.iter().chain(&s.recursive_ref).chain(all_of));
+        subschemas.extend(s.dynamic_ref.iter().map(|dref| dref.sch));
+        for group in [s.any_of, s.one_of] {
+            let group = self.indexes(group);
+            let matched = group.iter().copied().filter(|&sch| is_valid(sch));
+            match matched.collect::<Vec<_>>()[..] {
+                [sch] => subschemas.push(sch),
+                [] => branches.extend(group),
+                ref matched => branches.extend(matched),
+            }
+        }
+        if let Some(if_) = s.if_ {
+            match v {
+                Some(_) if is_valid(if_) => subschemas.extend(s.then),
+                Some(_) => subschemas.extend(s.else_),
+                None => branches.extend(s.then.iter().chain(&s.else_)),
+            }
+        }
+        if let Some(Value::Object(obj)) = v {
+            for (pname, sch) in &s.dependent_schemas {
+                if obj.contains_key(pname) {
+                    subschemas.push(*sch);
+                }
+            }
+            for (pname, dep) in &s.dependencies {
+                if let (true, Dependency::SchemaRef(sch)) = (obj.contains_key(pname), dep) {
+                    subschemas.push(*sch);
+                }
+            }
+        }
+        for sch in subschemas {
+            self.unconditional(sch, v, list, branches);
+        }
+    }
+
+    // combines constraints of schemas in `list`.
+    fn combine(&self, list: &[SchemaIndex]) -> Description {
+        let mut d = Description::default();
+        let mut types = None::<Types>;
+        for &sch in list {
+            let s = self.get(sch);
+            d.schemas.push(s.loc.clone());
+            d.title = d.title.or_else(|| s.title.clone());
+            d.description = d.description.or_else(|| s.description.clone());
+            if !s.types.is_empty() {
+                types = Some(match types {
+                    Some(t) => t.intersect(s.types),
+                    None => s.types,
+                });
+            }
+            if let Some(format) = s.asserted_format() {
+                if !d.formats.contains(&format.name) {
+                    d.formats.push(format.name);
+                }
+            }
+            if let Some(pattern) = &s.pattern {
+                d.patterns.push(pattern.as_str().to_owned());
+            }
+            if d.values.is_none() {
+                d.values = match (&s.constant, &s.enum_) {
+                    (Some(c), _) => Some(vec![c.clone()]),
+                    (_, Some(e)) => Some(e.values.clone()),
+                    _ => None,
+                };
+            }
+
+            narrow(&mut d.minimum, &s.minimum, Ordering::Greater);
+            narrow(&mut d.maximum, &s.maximum, Ordering::Less);
+            narrow(
+                &mut d.exclusive_minimum,
+                &s.exclusive_minimum,
+                Ordering::Greater,
+            );
+            narrow(
+                &mut d.exclusive_maximum,
+                &s.exclusive_maximum,
+                Ordering::Less,
+            );
+            d.multiple_of.extend(s.multiple_of.clone());
+
+            d.min_length = d.min_length.max(s.min_length);
+            d.max_length = min(d.max_length, s.max_length);
+            d.min_items = d.min_items.max(s.min_items);
+            d.max_items = min(d.max_items, s.max_items);
+            d.min_properties = d.min_properties.max(s.min_properties);
+            d.max_properties = min(d.max_properties, s.max_properties);
+            for pname in &s.required {
+                if !d.required.iter().any(|p| **p == **pname) {
+                    d.required.push(pname.to_string());
+                }
+            }
+            d.deprecated |= s.deprecated;
+        }
+        d.unsatisfiable = types.is_some_and(|t| t.is_empty());
+        d.types = types.map(|t| t.iter().collect()).unwrap_or_default();
+        d
+    }
+}
+
+// replaces `bound` with `n`, if `n` compares `tighter` than `bound`.
+fn narrow(bound: &mut Option<Number>, n: &Option<Number>, tighter: Ordering) {
+    let Some(n) = n else {
+        return;
+    };
+    match bound {
+        Some(b) if num_cmp(n, b) != Some(tighter) => {}
+        _ => *bound = Some(n.clone()),
+    }
+}
+
+// returns least of `a` and `b`, treating None as unbounded.
+fn min(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        _ => a.or(b),
+    }
+}
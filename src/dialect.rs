@@ -0,0 +1,303 @@
+use std::error::Error;
+
+use base64::Engine;
+use serde_json::{json, Value};
+
+use crate::*;
+
+/**
+Ready-made compiler configuration for popular ecosystems.
+
+see [`Compiler::use_dialect`]
+*/
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// Schema objects of OpenAPI 3.1, which are draft 2020-12 with
+    /// `discriminator`, `xml`, `externalDocs` and `example` keywords.
+    OpenApi31,
+    /// Schema objects of AsyncAPI 2.x, which are superset of draft-07.
+    AsyncApi,
+    /// Structural schemas of Kubernetes CustomResourceDefinitions, which
+    /// are OpenAPI 3.0 schemas with `x-kubernetes-*` extensions.
+    Kubernetes,
+    /// MongoDB `$jsonSchema`, which is a subset of draft-04.
+    MongoDb,
+}
+
+impl Dialect {
+    /// All dialects available.
+    pub const ALL: [Dialect; 4] = [
+        Dialect::OpenApi31,
+        Dialect::AsyncApi,
+        Dialect::Kubernetes,
+        Dialect::MongoDb,
+    ];
+
+    /// Returns name of the dialect, as accepted by [`Dialect::from_name`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::OpenApi31 => "openapi-3.1",
+            Self::AsyncApi => "asyncapi",
+            Self::Kubernetes => "kubernetes",
+            Self::MongoDb => "mongodb",
+        }
+    }
+
+    // tells whether `kw` is a vendor keyword of this dialect.
+    pub(crate) fn is_keyword(&self, kw: &str) -> bool {
+        match self {
+            Self::OpenApi31 => ["discriminator", "xml", "externalDocs", "example"].contains(&kw),
+            Self::AsyncApi => {
+                ["discriminator", "externalDocs", "example", "deprecated"].contains(&kw)
+            }
+            Self::Kubernetes => {
+                ["nullable", "example", "externalDocs"].contains(&kw)
+                    || kw.starts_with("x-kubernetes-")
+            }
+            Self::MongoDb => kw == "bsonType",
+        }
+    }
+
+    /// Returns dialect with given `name`, ignoring case.
+    pub fn from_name(name: &str) -> Option<Dialect> {
+        Self::ALL
+            .into_iter()
+            .find(|d| d.name().eq_ignore_ascii_case(name))
+    }
+}
+
+impl Compiler {
+    /**
+    Configures the compiler for given `dialect`.
+
+    This sets the default draft, registers the formats used by the
+    ecosystem, and enables or disables format assertions as the
+    ecosystem does. Vendor keywords such as `discriminator` or
+    `x-kubernetes-preserve-unknown-fields` are treated as annotations.
+
+    For `kubernetes`, `"nullable": true` adds `null` to `type` of the
+    schema, as in OpenAPI 3.0. Note that `null` is still rejected by
+    `enum` not listing it.
+
+    | dialect    | default draft | format assertions | extra formats |
+    |------------|---------------|-------------------|---------------|
+    | openapi-3.1| 2020-12       | disabled          | int32, int64, float, double, password |
+    | asyncapi   | draft-07      | enabled           | int32, int64, float, double, byte, binary, password |
+    | kubernetes | draft-04      | enabled           | int32, int64, float, double, byte, password |
+    | mongodb    | draft-04      | disabled          | |
+
+    For `mongodb`, keywords not supported by MongoDB, such as `$ref`,
+    `$schema`, `definitions`, `format` and `default`, fail compilation
+    with [`CompileError::UnsupportedKeyword`], as MongoDB rejects them.
+    Its `bsonType` keyword is supported, matching
+    [MongoDB Extended JSON](https://www.mongodb.com/docs/manual/reference/mongodb-extended-json/)
+    values such as `{"$oid": "..."}`. Plain json numbers are treated as
+    `int`, `long` or `double` depending on their value.
+
+    For `openapi-3.1`, the dialect metaschema
+    `https://spec.openapis.org/oas/3.1/dialect/base` is also made
+    available, so that it can be used as `$schema`.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.use_dialect(Dialect::from_name("kubernetes").unwrap());
+    let schema = json!({
+        "type": "object",
+        "x-kubernetes-preserve-unknown-fields": true,
+        "properties": {
+            "replicas": {"type": "integer", "format": "int32"}
+        }
+    });
+    compiler.add_resource("crd.json", schema)?;
+    let sch_index = compiler.compile("crd.json", &mut schemas)?;
+    let instance = json!({"replicas": 4294967296_u64});
+    assert!(schemas.validate(&instance, sch_index).is_err());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn use_dialect(&mut self, dialect: Dialect) {
+        let (draft, assert_format, formats): (_, _, &[&str]) = match dialect {
+            Dialect::OpenApi31 => (
+                Draft::V2020_12,
+                false,
+                &["int32", "int64", "float", "double", "password"],
+            ),
+            Dialect::AsyncApi => (
+                Draft::V7,
+                true,
+                &[
+                    "int32", "int64", "float", "double", "byte", "binary", "password",
+                ],
+            ),
+            Dialect::Kubernetes => (
+                Draft::V4,
+                true,
+                &["int32", "int64", "float", "double", "byte", "password"],
+            ),
+            Dialect::MongoDb => (Draft::V4, false, &[]),
+        };
+        self.dialect = Some(dialect);
+        self.set_default_draft(draft);
+        if assert_format {
+            self.enable_format_assertions();
+        }
+        for format in DIALECT_FORMATS {
+            if formats.contains(&format.name) {
+                self.register_format(format);
+            }
+        }
+        if dialect == Dialect::OpenApi31 {
+            for (url, doc) in openapi31_metaschemas() {
+                self.add_resource(url, doc)
+                    .unwrap_or_else(|_| panic!("{url} should be valid url"));
+            }
+        }
+    }
+}
+
+const DIALECT_FORMATS: [Format; 7] = [
+    Format {
+        name: "int32",
+        func: validate_int32,
+    },
+    Format {
+        name: "int64",
+        func: validate_int64,
+    },
+    Format {
+        name: "float",
+        func: validate_any,
+    },
+    Format {
+        name: "double",
+        func: validate_any,
+    },
+    Format {
+        name: "byte",
+        func: validate_byte,
+    },
+    Format {
+        name: "binary",
+        func: validate_any,
+    },
+    Format {
+        name: "password",
+        func: validate_any,
+    },
+];
+
+fn validate_int32(v: &Value) -> Result<(), Box<dyn Error>> {
+    let Value::Number(n) = v else {
+        return Ok(());
+    };
+    let in_range = match n.as_i64() {
+        Some(n) => i32::try_from(n).is_ok(),
+        None => n
+            .as_f64()
+            .is_some_and(|n| n >= i32::MIN as f64 && n <= i32::MAX as f64),
+    };
+    if !in_range {
+        Err("out of int32 range")?;
+    }
+    Ok(())
+}
+
+fn validate_int64(v: &Value) -> Result<(), Box<dyn Error>> {
+    let Value::Number(n) = v else {
+        return Ok(());
+    };
+    let in_range = n.is_i64()
+        || n.as_f64()
+            .is_some_and(|n| n >= i64::MIN as f64 && n < i64::MAX as f64);
+    if !in_range {
+        Err("out of int64 range")?;
+    }
+    Ok(())
+}
+
+fn validate_byte(v: &Value) -> Result<(), Box<dyn Error>> {
+    let Value::String(s) = v else {
+        return Ok(());
+    };
+    base64::engine::general_purpose::STANDARD.decode(s)?;
+    Ok(())
+}
+
+fn validate_any(_v: &Value) -> Result<(), Box<dyn Error>> {
+    Ok(())
+}
+
+fn openapi31_metaschemas() -> [(&'static str, Value); 2] {
+    [
+        (
+            "https://spec.openapis.org/oas/3.1/dialect/base",
+            json!({
+                "$id": "https://spec.openapis.org/oas/3.1/dialect/base",
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "$vocabulary": {
+                    "https://json-schema.org/draft/2020-12/vocab/core": true,
+                    "https://json-schema.org/draft/2020-12/vocab/applicator": true,
+                    "https://json-schema.org/draft/2020-12/vocab/unevaluated": true,
+                    "https://json-schema.org/draft/2020-12/vocab/validation": true,
+                    "https://json-schema.org/draft/2020-12/vocab/meta-data": true,
+                    "https://json-schema.org/draft/2020-12/vocab/format-annotation": true,
+                    "https://json-schema.org/draft/2020-12/vocab/content": true,
+                    "https://spec.openapis.org/oas/3.1/vocab/base": false
+                },
+                "$dynamicAnchor": "meta",
+                "allOf": [
+                    {"$ref": "https://json-schema.org/draft/2020-12/schema"},
+                    {"$ref": "https://spec.openapis.org/oas/3.1/meta/base"}
+                ]
+            }),
+        ),
+        (
+            "https://spec.openapis.org/oas/3.1/meta/base",
+            json!({
+                "$id": "https://spec.openapis.org/oas/3.1/meta/base",
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "$dynamicAnchor": "meta",
+                "type": ["object", "boolean"],
+                "properties": {
+                    "example": true,
+                    "discriminator": {
+                        "type": "object",
+                        "required": ["propertyName"],
+                        "properties": {
+                            "propertyName": {"type": "string"},
+                            "mapping": {
+                                "type": "object",
+                                "additionalProperties": {"type": "string"}
+                            }
+                        }
+                    },
+                    "externalDocs": {
+                        "type": "object",
+                        "required": ["url"],
+                        "properties": {
+                            "description": {"type": "string"},
+                            "url": {"type": "string"}
+                        }
+                    },
+                    "xml": {
+                        "type": "object",
+                        "properties": {
+                            "name": {"type": "string"},
+                            "namespace": {"type": "string"},
+                            "prefix": {"type": "string"},
+                            "attribute": {"type": "boolean"},
+                            "wrapped": {"type": "boolean"}
+                        }
+                    }
+                }
+            }),
+        ),
+    ]
+}
@@ -0,0 +1,108 @@
+use std::fmt::Write;
+
+use crate::{util::*, Schemas};
+
+impl Schemas {
+    /**
+    Returns the graph of compiled schemas in [Graphviz](https://graphviz.org)
+    DOT format.
+
+    Each schema is a node, grouped into a cluster per document. Edges are
+    labelled with the keyword through which the subschema is applied.
+    References such as `$ref` and `$dynamicRef` are drawn dashed, and
+    edges crossing documents are drawn red, to make unexpected
+    cross-document references stand out. Schemas with `$dynamicAnchor`
+    or `$recursiveAnchor` are drawn with double border.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://example.com/a.json", json!({
+        "properties": {"b": {"$ref": "b.json"}}
+    }))?;
+    compiler.add_resource("http://example.com/b.json", json!({"type": "string"}))?;
+    compiler.compile("http://example.com/a.json", &mut schemas)?;
+
+    let dot = schemas.to_dot();
+    assert!(dot.starts_with("digraph schemas {"));
+    assert!(dot.contains(r#"s1 -> s2 [label="$ref", style=dashed, color=red];"#));
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn to_dot(&self) -> String {
+        let mut docs: Vec<(&str, Vec<&crate::Schema>)> = vec![];
+        for s in self.list.iter().filter(|s| !s.stale) {
+            let (doc, _) = split(&s.loc);
+            match docs.iter_mut().find(|(url, _)| *url == doc) {
+                Some((_, list)) => list.push(s),
+                None => docs.push((doc, vec![s])),
+            }
+        }
+
+        let mut dot = String::new();
+        _ = writeln!(dot, "digraph schemas {{");
+        _ = writeln!(dot, "  node [shape=box];");
+        for (i, (doc, list)) in docs.iter().enumerate() {
+            _ = writeln!(dot, "  subgraph cluster_{i} {{");
+            _ = writeln!(dot, "    label={};", dot_str(doc));
+            for s in list {
+                let (_, frag) = split(&s.loc);
+                let mut label = format!("#{frag}");
+                if let Some(anchor) = &s.dynamic_anchor {
+                    _ = write!(label, "\n$dynamicAnchor: {anchor}");
+                }
+                if s.recursive_anchor {
+                    label.push_str("\n$recursiveAnchor");
+                }
+                let dynamic = s.dynamic_anchor.is_some() || s.recursive_anchor;
+                let peripheries = if dynamic { ", peripheries=2" } else { "" };
+                _ = writeln!(
+                    dot,
+                    "    s{} [label={}{peripheries}];",
+                    s.idx.0,
+                    dot_str(&label)
+                );
+            }
+            _ = writeln!(dot, "  }}");
+        }
+
+        for s in self.list.iter().filter(|s| !s.stale) {
+            let (doc, _) = split(&s.loc);
+            for (kp, child) in s.children(self) {
+                let mut attrs = format!("label={}", dot_str(&kp.to_string()));
+                if matches!(kp.keyword, "$ref" | "$recursiveRef" | "$dynamicRef") {
+                    attrs.push_str(", style=dashed");
+                }
+                if split(&self.get(child).loc).0 != doc {
+                    attrs.push_str(", color=red");
+                }
+                _ = writeln!(dot, "  s{} -> s{} [{attrs}];", s.idx.0, child.0);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+// quotes `s` as DOT string.
+fn dot_str(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(ch);
+            }
+            '\n' => quoted.push_str("\\n"),
+            _ => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -1,5 +1,5 @@
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet},
     str::FromStr,
 };
 
@@ -9,9 +9,9 @@ use url::Url;
 
 use crate::{compiler::*, root::Resource, util::*, SchemaIndex, Schemas};
 
-const POS_SELF: u8 = 1 << 0;
-const POS_PROP: u8 = 1 << 1;
-const POS_ITEM: u8 = 1 << 2;
+pub(crate) const POS_SELF: u8 = 1 << 0;
+pub(crate) const POS_PROP: u8 = 1 << 1;
+pub(crate) const POS_ITEM: u8 = 1 << 2;
 
 pub(crate) static DRAFT4: Lazy<Draft> = Lazy::new(|| Draft {
     version: 4,
@@ -33,6 +33,42 @@ pub(crate) static DRAFT4: Lazy<Draft> = Lazy::new(|| Draft {
         ("additionalItems", POS_SELF),
         ("dependencies", POS_PROP),
     ]),
+    keywords: HashSet::from([
+        "$schema",
+        "id",
+        "$ref",
+        "title",
+        "description",
+        "default",
+        "multipleOf",
+        "maximum",
+        "exclusiveMaximum",
+        "minimum",
+        "exclusiveMinimum",
+        "maxLength",
+        "minLength",
+        "pattern",
+        "additionalItems",
+        "items",
+        "maxItems",
+        "minItems",
+        "uniqueItems",
+        "maxProperties",
+        "minProperties",
+        "required",
+        "additionalProperties",
+        "definitions",
+        "properties",
+        "patternProperties",
+        "dependencies",
+        "enum",
+        "type",
+        "format",
+        "allOf",
+        "anyOf",
+        "oneOf",
+        "not",
+    ]),
     vocab_prefix: "",
     all_vocabs: vec![],
     default_vocabs: vec![],
@@ -41,11 +77,15 @@ pub(crate) static DRAFT4: Lazy<Draft> = Lazy::new(|| Draft {
 pub(crate) static DRAFT6: Lazy<Draft> = Lazy::new(|| {
     let mut subschemas = DRAFT4.subschemas.clone();
     subschemas.extend([("propertyNames", POS_SELF), ("contains", POS_SELF)]);
+    let mut keywords = DRAFT4.keywords.clone();
+    keywords.remove("id");
+    keywords.extend(["$id", "examples", "const", "contains", "propertyNames"]);
     Draft {
         version: 6,
         id: "$id",
         url: "http://json-schema.org/draft-06/schema",
         subschemas,
+        keywords,
         vocab_prefix: "",
         all_vocabs: vec![],
         default_vocabs: vec![],
@@ -55,11 +95,23 @@ pub(crate) static DRAFT6: Lazy<Draft> = Lazy::new(|| {
 pub(crate) static DRAFT7: Lazy<Draft> = Lazy::new(|| {
     let mut subschemas = DRAFT6.subschemas.clone();
     subschemas.extend([("if", POS_SELF), ("then", POS_SELF), ("else", POS_SELF)]);
+    let mut keywords = DRAFT6.keywords.clone();
+    keywords.extend([
+        "$comment",
+        "if",
+        "then",
+        "else",
+        "readOnly",
+        "writeOnly",
+        "contentMediaType",
+        "contentEncoding",
+    ]);
     Draft {
         version: 7,
         id: "$id",
         url: "http://json-schema.org/draft-07/schema",
         subschemas,
+        keywords,
         vocab_prefix: "",
         all_vocabs: vec![],
         default_vocabs: vec![],
@@ -75,11 +127,28 @@ pub(crate) static DRAFT2019: Lazy<Draft> = Lazy::new(|| {
         ("unevaluatedItems", POS_SELF),
         ("contentSchema", POS_SELF),
     ]);
+    let mut keywords = DRAFT7.keywords.clone();
+    keywords.extend([
+        "$anchor",
+        "$defs",
+        "$recursiveRef",
+        "$recursiveAnchor",
+        "$vocabulary",
+        "dependentSchemas",
+        "dependentRequired",
+        "unevaluatedProperties",
+        "unevaluatedItems",
+        "maxContains",
+        "minContains",
+        "deprecated",
+        "contentSchema",
+    ]);
     Draft {
         version: 2019,
         id: "$id",
         url: "https://json-schema.org/draft/2019-09/schema",
         subschemas,
+        keywords,
         vocab_prefix: "https://json-schema.org/draft/2019-09/vocab/",
         all_vocabs: vec![
             "core",
@@ -96,11 +165,17 @@ pub(crate) static DRAFT2019: Lazy<Draft> = Lazy::new(|| {
 pub(crate) static DRAFT2020: Lazy<Draft> = Lazy::new(|| {
     let mut subschemas = DRAFT2019.subschemas.clone();
     subschemas.extend([("prefixItems", POS_ITEM)]);
+    let mut keywords = DRAFT2019.keywords.clone();
+    for kw in ["$recursiveRef", "$recursiveAnchor", "additionalItems"] {
+        keywords.remove(kw);
+    }
+    keywords.extend(["$dynamicRef", "$dynamicAnchor", "prefixItems"]);
     Draft {
         version: 2020,
         id: "$id",
         url: "https://json-schema.org/draft/2020-12/schema",
         subschemas,
+        keywords,
         vocab_prefix: "https://json-schema.org/draft/2020-12/vocab/",
         all_vocabs: vec![
             "core",
@@ -119,18 +194,100 @@ pub(crate) static DRAFT2020: Lazy<Draft> = Lazy::new(|| {
 pub(crate) static STD_METASCHEMAS: Lazy<Schemas> =
     Lazy::new(|| load_std_metaschemas().expect("std metaschemas must be compilable"));
 
+// keywords defined by any of the supported drafts.
+pub(crate) static ALL_KEYWORDS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
+    [&DRAFT4, &DRAFT6, &DRAFT7, &DRAFT2019, &DRAFT2020]
+        .into_iter()
+        .flat_map(|d| d.keywords.iter().copied())
+        .collect()
+});
+
 pub(crate) fn latest() -> &'static Draft {
     crate::Draft::default().internal()
 }
 
+// detects draft of `doc` from its `$schema`, or else from keywords used.
+// returns newest draft consistent with them, or None if none of them
+// is specific to some drafts.
+pub(crate) fn detect(doc: &Value) -> Option<&'static Draft> {
+    if let Some(Value::String(sch)) = doc.get("$schema") {
+        if let Some(draft) = Draft::from_url(sch) {
+            return Some(draft);
+        }
+    }
+    let drafts = [&*DRAFT4, &*DRAFT6, &*DRAFT7, &*DRAFT2019, &*DRAFT2020];
+    let all = (1 << drafts.len()) - 1;
+    let mut candidates = all;
+    detect_in(doc, &drafts, &mut candidates);
+    if candidates == all {
+        return None;
+    }
+    let newest = usize::BITS - 1 - candidates.leading_zeros();
+    Some(drafts[newest as usize])
+}
+
+// narrows `candidates`, bitset of indexes into `drafts`, by keywords
+// used in schema `v` and its subschemas. keywords conflicting with
+// earlier ones are ignored.
+fn detect_in(v: &Value, drafts: &[&'static Draft; 5], candidates: &mut usize) {
+    let Value::Object(obj) = v else {
+        return;
+    };
+    let mut narrow = |allowed: usize| {
+        if *candidates & allowed != 0 {
+            *candidates &= allowed;
+        }
+    };
+    let only = |f: &dyn Fn(&Draft) -> bool| {
+        (0..drafts.len())
+            .filter(|&i| f(drafts[i]))
+            .fold(0, |bits, i| bits | (1 << i))
+    };
+    for (kw, v) in obj {
+        if ALL_KEYWORDS.contains(kw.as_str()) {
+            narrow(only(&|d| d.keywords.contains(kw.as_str())));
+        }
+        match (kw.as_str(), v) {
+            ("items", Value::Array(_)) => narrow(only(&|d| d.version < 2020)),
+            ("exclusiveMinimum" | "exclusiveMaximum", Value::Bool(_)) => {
+                narrow(only(&|d| d.version == 4))
+            }
+            ("exclusiveMinimum" | "exclusiveMaximum", Value::Number(_)) => {
+                narrow(only(&|d| d.version > 4))
+            }
+            _ => {}
+        }
+    }
+    for (kw, v) in obj {
+        let Some(pos) = DRAFT2020.subschemas.get(kw.as_str()) else {
+            continue;
+        };
+        match v {
+            Value::Object(m) if pos & POS_PROP != 0 => {
+                for v in m.values() {
+                    detect_in(v, drafts, candidates);
+                }
+            }
+            Value::Array(arr) if pos & POS_ITEM != 0 => {
+                for v in arr {
+                    detect_in(v, drafts, candidates);
+                }
+            }
+            _ if pos & POS_SELF != 0 => detect_in(v, drafts, candidates),
+            _ => {}
+        }
+    }
+}
+
 // --
 
 pub(crate) struct Draft {
     pub(crate) version: usize,
     pub(crate) url: &'static str,
-    id: &'static str,                         // property name used to represent id
-    subschemas: HashMap<&'static str, u8>,    // location of subschemas
-    pub(crate) vocab_prefix: &'static str,    // prefix used for vocabulary
+    pub(crate) id: &'static str, // property name used to represent id
+    pub(crate) subschemas: HashMap<&'static str, u8>, // location of subschemas
+    pub(crate) keywords: HashSet<&'static str>, // defined by the draft
+    pub(crate) vocab_prefix: &'static str, // prefix used for vocabulary
     pub(crate) all_vocabs: Vec<&'static str>, // names of supported vocabs
     pub(crate) default_vocabs: Vec<&'static str>, // names of default vocabs
 }
@@ -188,7 +345,7 @@ impl Draft {
             })
     }
 
-    fn get_id<'a>(&self, obj: &'a Map<String, Value>) -> Option<&'a str> {
+    pub(crate) fn get_id<'a>(&self, obj: &'a Map<String, Value>) -> Option<&'a str> {
         if self.version < 2019 && obj.contains_key("$ref") {
             return None; // All other properties in a "$ref" object MUST be ignored
         }
@@ -303,7 +460,7 @@ impl Draft {
         base: &Url,           // base of json
         sch_ptr: JsonPointer, // ptr of json
         url: &Url,
-        resources: &mut HashMap<JsonPointer, Resource>,
+        resources: &mut FastHashMap<JsonPointer, Resource>,
     ) -> Result<(), CompileError> {
         if resources.contains_key(&sch_ptr) {
             // resources are already collected
@@ -384,6 +541,44 @@ impl Draft {
         Ok(())
     }
 
+    // collects json-pointers of `sch` and all its subschemas into `ptrs`.
+    pub(crate) fn collect_subschemas(
+        &self,
+        sch: &Value,
+        sch_ptr: JsonPointer,
+        ptrs: &mut Vec<JsonPointer>,
+    ) {
+        let Value::Object(obj) = sch else {
+            ptrs.push(sch_ptr);
+            return;
+        };
+        for (&kw, &pos) in &self.subschemas {
+            let Some(v) = obj.get(kw) else {
+                continue;
+            };
+            if pos & POS_SELF != 0 {
+                self.collect_subschemas(v, sch_ptr.append(kw), ptrs);
+            }
+            if pos & POS_ITEM != 0 {
+                if let Value::Array(arr) = v {
+                    for (i, item) in arr.iter().enumerate() {
+                        let ptr = sch_ptr.append2(kw, &i.to_string());
+                        self.collect_subschemas(item, ptr, ptrs);
+                    }
+                }
+            }
+            if pos & POS_PROP != 0 {
+                if let Value::Object(obj) = v {
+                    for (pname, pvalue) in obj {
+                        let ptr = sch_ptr.append2(kw, pname);
+                        self.collect_subschemas(pvalue, ptr, ptrs);
+                    }
+                }
+            }
+        }
+        ptrs.push(sch_ptr);
+    }
+
     pub(crate) fn is_subschema(&self, ptr: &str) -> bool {
         if ptr.is_empty() {
             return true;
@@ -438,6 +633,8 @@ fn load_std_metaschemas() -> Result<Schemas, CompileError> {
 
 #[cfg(test)]
 mod tests {
+    use serde_json::json;
+
     use crate::{Compiler, Schemas};
 
     use super::*;
@@ -466,6 +663,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_detect() {
+        let tests = [
+            (
+                json!({"$schema": "https://json-schema.org/draft/2019-09/schema"}),
+                Some(2019),
+            ),
+            (
+                json!({"$schema": "http://example.com/meta.json", "id": "a.json"}),
+                Some(4),
+            ),
+            (json!({"type": "string"}), None),
+            (json!({"$defs": {"a": {"prefixItems": [true]}}}), Some(2020)),
+            (json!({"$recursiveRef": "#"}), Some(2019)),
+            (json!({"items": [{"const": 1}]}), Some(2019)),
+            (json!({"items": {"const": 1}}), Some(2020)),
+            (json!({"if": true, "definitions": {}}), Some(2020)),
+            (json!({"allOf": [{"exclusiveMaximum": false}]}), Some(4)),
+            (json!({"exclusiveMaximum": 5}), Some(2020)),
+            // names of properties are not keywords
+            (json!({"properties": {"id": {}, "prefixItems": {}}}), None),
+            (json!({"enum": [{"$recursiveRef": "#"}]}), None),
+            // conflicting keywords are ignored
+            (
+                json!({"additionalItems": false, "prefixItems": [true]}),
+                Some(2019),
+            ),
+        ];
+        for (doc, want) in tests {
+            let got = detect(&doc).map(|d| d.version);
+            assert_eq!(got, want, "for {doc}");
+        }
+    }
+
     #[test]
     fn test_collect_ids() {
         let url = Url::parse("http://a.com/schema.json").unwrap();
@@ -509,7 +740,7 @@ mod tests {
             m.insert("/definitions/s4", "http://e.com/def"); // id with fragments
             m
         };
-        let mut got = HashMap::new();
+        let mut got = FastHashMap::new();
         DRAFT4
             .collect_resources(&json, &url, "".into(), &url, &mut got)
             .unwrap();
@@ -545,20 +776,20 @@ mod tests {
             }"#,
         )
         .unwrap();
-        let mut resources = HashMap::new();
+        let mut resources = FastHashMap::new();
         DRAFT2020
             .collect_resources(&json, &url, "".into(), &url, &mut resources)
             .unwrap();
         assert!(resources.get("").unwrap().anchors.is_empty());
         assert_eq!(resources.get("/$defs/s2").unwrap().anchors, {
-            let mut want = HashMap::new();
+            let mut want = FastHashMap::new();
             want.insert("b1".into(), "/$defs/s2".into());
             want.insert("b2".into(), "/$defs/s2/items/0".into());
             want.insert("b3".into(), "/$defs/s2/items/2".into());
             want
         });
         assert_eq!(resources.get("/$defs/s2/items/1").unwrap().anchors, {
-            let mut want = HashMap::new();
+            let mut want = FastHashMap::new();
             want.insert("c1".into(), "/$defs/s2/items/1/items/0".into());
             want.insert("c2".into(), "/$defs/s2/items/1/items/1".into());
             want
@@ -6,7 +6,7 @@ use regex_syntax::ast::{self, *};
 // covert ecma regex to rust regex if possible
 // see https://262.ecma-international.org/11.0/#sec-regexp-regular-expression-objects
 pub(crate) fn convert(pattern: &str) -> Result<Cow<str>, Box<dyn std::error::Error>> {
-    let mut pattern = Cow::Borrowed(pattern);
+    let mut pattern = fix_empty_classes(pattern);
 
     let mut ast = loop {
         match Parser::new().parse(pattern.as_ref()) {
@@ -48,7 +48,58 @@ pub(crate) fn convert(pattern: &str) -> Result<Cow<str>, Box<dyn std::error::Err
     Ok(pattern)
 }
 
+// in ecma, `]` immediately after `[` or `[^` closes the class.
+// so `[]` never matches, and `[^]` matches any character.
+fn fix_empty_classes(pattern: &str) -> Cow<'_, str> {
+    if !pattern.contains("[]") && !pattern.contains("[^]") {
+        return Cow::Borrowed(pattern);
+    }
+    let mut out = String::with_capacity(pattern.len());
+    let mut in_class = false;
+    let mut chars = pattern.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => {
+                out.push(ch);
+                if let Some(ch) = chars.next() {
+                    out.push(ch);
+                }
+                continue;
+            }
+            '[' if !in_class => {
+                let rest = chars.clone().collect::<String>();
+                if rest.starts_with(']') {
+                    out.push_str(r"[^\x00-\x{10FFFF}]");
+                    chars.next();
+                    continue;
+                } else if rest.starts_with("^]") {
+                    out.push_str(r"[\x00-\x{10FFFF}]");
+                    chars.next();
+                    chars.next();
+                    continue;
+                }
+                in_class = true;
+            }
+            ']' => in_class = false,
+            _ => {}
+        }
+        out.push(ch);
+    }
+    Cow::Owned(out)
+}
+
 fn fix_error(e: &Error) -> Option<String> {
+    if let ErrorKind::UnsupportedBackreference = e.kind() {
+        // handle \0 not followed by decimal digit
+        let (start, end) = (e.span().start.offset, e.span().end.offset);
+        if &e.pattern()[start..end] == r"\0" {
+            return Some(format!(
+                r"{}\x00{}",
+                &e.pattern()[..start],
+                &e.pattern()[end..]
+            ));
+        }
+    }
     if let ErrorKind::EscapeUnrecognized = e.kind() {
         let (start, end) = (e.span().start.offset, e.span().end.offset);
         let s = &e.pattern()[start..end];
@@ -76,7 +127,10 @@ handles following translations:
 -  \w should match ascii letters only. so replace with [a-zA-Z0-9_]
 -  \W should match everything but ascii letters. so replace with [^a-zA-Z0-9_]
 -  \s and \S differences
+-  . should not match any line terminator. so replace with [^\n\r\u2028\u2029]
+-  \b and \B should use ascii word characters. so replace with (?-u:\b) and (?-u:\B)
 -  \a is not an ECMA 262 control escape
+-  \A, \z, \< etc are not ECMA 262 assertions
 */
 struct Translator<'a> {
     pat: &'a str,
@@ -104,9 +158,9 @@ impl<'a> Translator<'a> {
             }
             ClassPerlKind::Space => {
                 let with = &if perl.negated {
-                    "[^ \t\n\r\u{000b}\u{000c}\u{00a0}\u{feff}\u{2003}\u{2029}]"
+                    r"[^\t\n\v\f\r \x{a0}\x{1680}\x{2000}-\x{200a}\x{2028}\x{2029}\x{202f}\x{205f}\x{3000}\x{feff}]"
                 } else {
-                    "[ \t\n\r\u{000b}\u{000c}\u{00a0}\u{feff}\u{2003}\u{2029}]"
+                    r"[\t\n\v\f\r \x{a0}\x{1680}\x{2000}-\x{200a}\x{2028}\x{2029}\x{202f}\x{205f}\x{3000}\x{feff}]"
                 };
                 self.replace(&perl.span, with);
             }
@@ -137,6 +191,23 @@ impl<'a> Visitor for Translator<'a> {
             Ast::ClassPerl(perl) => {
                 self.replace_class_class(perl);
             }
+            Ast::Dot(span) => {
+                self.replace(span, r"[^\n\r\x{2028}\x{2029}]");
+            }
+            Ast::Assertion(assertion) => match assertion.kind {
+                AssertionKind::WordBoundary | AssertionKind::NotWordBoundary => {
+                    let start = assertion.span.start.offset;
+                    if !self.pat[..start].ends_with("(?-u:") {
+                        let with = match assertion.kind {
+                            AssertionKind::WordBoundary => r"(?-u:\b)",
+                            _ => r"(?-u:\B)",
+                        };
+                        self.replace(&assertion.span, with);
+                    }
+                }
+                AssertionKind::StartLine | AssertionKind::EndLine => {}
+                _ => return Err("not an ECMA 262 assertion"),
+            },
             Ast::Literal(ref literal) => {
                 if let Literal {
                     kind: LiteralKind::Special(SpecialLiteralKind::Bell),
@@ -152,6 +223,308 @@ impl<'a> Visitor for Translator<'a> {
     }
 }
 
+// validates `pattern` as per ECMA-262 regular expression syntax with `u` flag.
+// see https://262.ecma-international.org/11.0/#sec-patterns
+pub(crate) fn validate(pattern: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut p = PatternParser {
+        chars: pattern.chars().collect(),
+        pos: 0,
+        num_groups: 0,
+        group_names: vec![],
+        max_backref: 0,
+        named_backrefs: vec![],
+    };
+    p.disjunction()?;
+    if let Some(ch) = p.peek() {
+        debug_assert_eq!(ch, ')');
+        Err("unmatched ')'")?;
+    }
+    if p.max_backref > p.num_groups {
+        Err(format!("invalid backreference \\{}", p.max_backref))?;
+    }
+    if let Some(name) = p.named_backrefs.iter().find(|n| !p.group_names.contains(n)) {
+        Err(format!("invalid named backreference {name:?}"))?;
+    }
+    Ok(())
+}
+
+struct PatternParser {
+    chars: Vec<char>,
+    pos: usize,
+    num_groups: usize,
+    group_names: Vec<String>,
+    max_backref: usize,
+    named_backrefs: Vec<String>,
+}
+
+impl PatternParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).cloned()
+    }
+
+    fn next(&mut self) -> Result<char, &'static str> {
+        let ch = self.peek().ok_or("unexpected end of pattern")?;
+        self.pos += 1;
+        Ok(ch)
+    }
+
+    fn eat(&mut self, ch: char) -> bool {
+        if self.peek() == Some(ch) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn eat_str(&mut self, s: &str) -> bool {
+        let n = s.chars().count();
+        if self.pos + n <= self.chars.len()
+            && s.chars()
+                .eq(self.chars[self.pos..self.pos + n].iter().cloned())
+        {
+            self.pos += n;
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, ch: char) -> Result<(), String> {
+        if !self.eat(ch) {
+            return Err(format!("missing {ch:?}"));
+        }
+        Ok(())
+    }
+
+    fn disjunction(&mut self) -> Result<(), String> {
+        loop {
+            while let Some(ch) = self.peek() {
+                if ch == '|' || ch == ')' {
+                    break;
+                }
+                self.term()?;
+            }
+            if !self.eat('|') {
+                return Ok(());
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<(), String> {
+        // assertions --
+        if self.eat('^') || self.eat('$') || self.eat_str("\\b") || self.eat_str("\\B") {
+            return Ok(());
+        }
+        if self.eat_str("(?=")
+            || self.eat_str("(?!")
+            || self.eat_str("(?<=")
+            || self.eat_str("(?<!")
+        {
+            // lookarounds are not quantifiable with `u` flag
+            self.disjunction()?;
+            return self.expect(')');
+        }
+
+        // atom --
+        match self.next()? {
+            '(' => {
+                if self.eat('?') {
+                    if self.eat('<') {
+                        let name = self.group_name()?;
+                        if self.group_names.contains(&name) {
+                            return Err(format!("duplicate group name {name:?}"));
+                        }
+                        self.group_names.push(name);
+                        self.num_groups += 1;
+                    } else if !self.eat(':') {
+                        return Err("invalid group".into());
+                    }
+                } else {
+                    self.num_groups += 1;
+                }
+                self.disjunction()?;
+                self.expect(')')?;
+            }
+            '[' => self.class()?,
+            '\\' => self.atom_escape()?,
+            '*' | '+' | '?' | '{' => return Err("nothing to repeat".into()),
+            ch @ (']' | '}') => return Err(format!("lone {ch:?}")),
+            _ => {}
+        }
+        self.quantifier()
+    }
+
+    fn quantifier(&mut self) -> Result<(), String> {
+        if self.eat('*') || self.eat('+') || self.eat('?') {
+            self.eat('?'); // lazy
+            return Ok(());
+        }
+        if self.eat('{') {
+            let min = self.decimal().ok_or("incomplete quantifier")?;
+            let max = if self.eat(',') {
+                self.decimal()
+            } else {
+                Some(min)
+            };
+            self.expect('}').map_err(|_| "incomplete quantifier")?;
+            if matches!(max, Some(max) if max < min) {
+                return Err("numbers out of order in quantifier".into());
+            }
+            self.eat('?'); // lazy
+        }
+        Ok(())
+    }
+
+    fn decimal(&mut self) -> Option<usize> {
+        let start = self.pos;
+        while matches!(self.peek(), Some('0'..='9')) {
+            self.pos += 1;
+        }
+        let digits = self.chars[start..self.pos].iter().collect::<String>();
+        // too large numbers are treated as infinity
+        (!digits.is_empty()).then(|| digits.parse().unwrap_or(usize::MAX))
+    }
+
+    fn group_name(&mut self) -> Result<String, String> {
+        let mut name = String::new();
+        loop {
+            match self.next()? {
+                '>' if !name.is_empty() => return Ok(name),
+                ch @ ('$' | '_') => name.push(ch),
+                ch if ch.is_alphabetic() => name.push(ch),
+                ch @ ('\u{200C}' | '\u{200D}') if !name.is_empty() => name.push(ch),
+                ch if ch.is_alphanumeric() && !name.is_empty() => name.push(ch),
+                _ => return Err("invalid group name".into()),
+            }
+        }
+    }
+
+    fn atom_escape(&mut self) -> Result<(), String> {
+        match self.next()? {
+            '1'..='9' => {
+                self.pos -= 1;
+                let n = self.decimal().unwrap_or_default();
+                self.max_backref = self.max_backref.max(n);
+            }
+            'k' => {
+                self.expect('<')?;
+                let name = self.group_name()?;
+                self.named_backrefs.push(name);
+            }
+            'd' | 'D' | 's' | 'S' | 'w' | 'W' => {}
+            'p' | 'P' => self.property()?,
+            ch => _ = self.char_escape(ch)?,
+        }
+        Ok(())
+    }
+
+    // returns the code point of escaped character
+    fn char_escape(&mut self, ch: char) -> Result<u32, String> {
+        let cp = match ch {
+            'f' => 0x0C,
+            'n' => 0x0A,
+            'r' => 0x0D,
+            't' => 0x09,
+            'v' => 0x0B,
+            'c' => match self.next()? {
+                ch if ch.is_ascii_alphabetic() => ch as u32 % 32,
+                _ => return Err("invalid control escape".into()),
+            },
+            '0' => {
+                if matches!(self.peek(), Some('0'..='9')) {
+                    return Err("invalid decimal escape".into());
+                }
+                0
+            }
+            'x' => self.hex(2)?,
+            'u' => {
+                if self.eat('{') {
+                    let start = self.pos;
+                    while self.peek().filter(char::is_ascii_hexdigit).is_some() {
+                        self.pos += 1;
+                    }
+                    let digits = self.chars[start..self.pos].iter().collect::<String>();
+                    self.expect('}')?;
+                    match u32::from_str_radix(&digits, 16) {
+                        Ok(cp) if cp <= 0x10FFFF => cp,
+                        _ => return Err("invalid unicode escape".into()),
+                    }
+                } else {
+                    self.hex(4)?
+                }
+            }
+            '^' | '$' | '\\' | '.' | '*' | '+' | '?' | '(' | ')' | '[' | ']' | '{' | '}' | '|'
+            | '/' => ch as u32,
+            _ => return Err(format!("invalid escape \\{ch}")),
+        };
+        Ok(cp)
+    }
+
+    fn hex(&mut self, n: usize) -> Result<u32, String> {
+        let mut cp = 0;
+        for _ in 0..n {
+            let Some(d) = self.next()?.to_digit(16) else {
+                return Err("invalid hex escape".into());
+            };
+            cp = cp * 16 + d;
+        }
+        Ok(cp)
+    }
+
+    fn property(&mut self) -> Result<(), String> {
+        self.expect('{')?;
+        let start = self.pos;
+        while self.peek().filter(|&c| c != '}').is_some() {
+            self.pos += 1;
+        }
+        let prop = self.chars[start..self.pos].iter().collect::<String>();
+        self.expect('}')?;
+        let valid =
+            |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        match prop.split_once('=') {
+            Some((name, value)) if valid(name) && valid(value) => Ok(()),
+            None if valid(&prop) => Ok(()),
+            _ => Err(format!("invalid property name {prop:?}")),
+        }
+    }
+
+    fn class(&mut self) -> Result<(), String> {
+        self.eat('^');
+        loop {
+            if self.eat(']') {
+                return Ok(());
+            }
+            let from = self.class_atom()?;
+            if self.peek() == Some('-') && self.chars.get(self.pos + 1) != Some(&']') {
+                self.pos += 1;
+                let to = self.class_atom()?;
+                let (Some(from), Some(to)) = (from, to) else {
+                    return Err("invalid character class range".into());
+                };
+                if from > to {
+                    return Err("range out of order in character class".into());
+                }
+            }
+        }
+    }
+
+    // returns None for character class escapes
+    fn class_atom(&mut self) -> Result<Option<u32>, String> {
+        let ch = self
+            .next()
+            .map_err(|_| "unterminated character class".to_owned())?;
+        if ch != '\\' {
+            return Ok(Some(ch as u32));
+        }
+        match self.next()? {
+            'b' => Ok(Some(0x08)),
+            '-' => Ok(Some('-' as u32)),
+            'd' | 'D' | 's' | 'S' | 'w' | 'W' => Ok(None),
+            'p' | 'P' => self.property().map(|_| None),
+            ch => self.char_escape(ch).map(Some),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,6 +539,13 @@ mod tests {
             (r"ab[a-z\d]ef", r#"ab[a-z[0-9]]ef"#),   // \d inside classSet
             (r"ab\Def", r#"ab[^0-9]ef"#),            // \d
             (r"ab[a-z\D]ef", r#"ab[a-z[^0-9]]ef"#),  // \D inside classSet
+            (r"a.b", r"a[^\n\r\x{2028}\x{2029}]b"),  // .
+            (r"\bab\B", r"(?-u:\b)ab(?-u:\B)"),      // \b and \B
+            (r"a\0b", r"a\x00b"),                    // \0
+            (r"a[]b", r"a[^\x00-\x{10FFFF}]b"),      // empty class
+            (r"a[^]b", r"a[\x00-\x{10FFFF}]b"),      // any char
+            (r"[\]]", r"[\]]"),                      // escaped ]
+            (r"^ab$", r"^ab$"),                      // anchors
         ];
         for (input, want) in tests {
             match convert(input) {
@@ -181,12 +561,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validate() {
+        let valid = [
+            r"([abc])+\s+$",
+            r"^(?=.*\d)(?!x)\w+(?<=a)(?<!b)$",
+            r"(?<year>\d{4})-\k<year>",
+            r"(a)\1",
+            r"[\d-]",
+            r"\p{Letter}\P{Script=Greek}",
+            r"\u{1F600}\u0041\x41\cJ\0",
+            r"a{2,}?b{1,3}",
+        ];
+        for input in valid {
+            if let Err(e) = validate(input) {
+                panic!("validate({input:?}) failed: {e}");
+            }
+        }
+
+        let invalid = [
+            r"^(abc]",
+            r"abc)",
+            r"\a",
+            r"\c1",
+            r"a**",
+            r"{1}",
+            r"a{2,1}",
+            r"(?=a)*",
+            r"(a)\2",
+            r"\k<x>",
+            r"(?<x>a)(?<x>b)",
+            r"[z-a]",
+            r"[\d-z]",
+            r"\u{110000}",
+            r"a]",
+        ];
+        for input in invalid {
+            if validate(input).is_ok() {
+                panic!("validate({input:?}) must fail");
+            }
+        }
+    }
+
     #[test]
     fn test_ecma_compat_invalid() {
         // println!("{:#?}", Parser::new().parse(r#"a\a"#));
         let tests = [
             r"\c\n",     // \c{invalid_char}
             r"abc\adef", // \a is not valid
+            r"\Aabc\z",  // \A and \z are not valid
         ];
         for input in tests {
             if convert(input).is_ok() {
@@ -0,0 +1,299 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use crate::{util::*, *};
+
+impl Schemas {
+    /**
+    Tells whether schema `a` of this instance and schema `b` of `other`
+    accept the same instances, so that a registry can detect no-op
+    schema updates. `other` may be this instance too.
+
+    Schemas are compared keyword by keyword, ignoring `title`,
+    `description`, `examples`, `$comment` and unknown keywords. A schema
+    with only `$ref` is treated same as its target, so that moving a
+    subschema into `$defs` does not matter. Order of `required`, `enum`
+    and `type` does not matter, but order of subschemas in `allOf`,
+    `anyOf` and `oneOf` does. So this may return false for some
+    equivalent schemas, but never returns true for schemas which differ.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let a = compiler.compile_value(json!({
+        "title": "person",
+        "properties": {"name": {"$ref": "#/$defs/name"}},
+        "$defs": {"name": {"type": "string"}}
+    }), &mut schemas)?;
+    let b = compiler.compile_value(json!({
+        "properties": {"name": {"type": "string", "description": "full name"}}
+    }), &mut schemas)?;
+    assert!(schemas.equivalent(a, &schemas, b));
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `a` is not generated for this instance, or `b` for `other`.
+    */
+    pub fn equivalent(&self, a: SchemaIndex, other: &Schemas, b: SchemaIndex) -> bool {
+        assert!(self.contains(a), "Schemas::equivalent: a out of bounds");
+        assert!(other.contains(b), "Schemas::equivalent: b out of bounds");
+        Equiv {
+            s1: self,
+            s2: other,
+            assumed: HashSet::new(),
+        }
+        .eq(a, b)
+    }
+}
+
+struct Equiv<'a> {
+    s1: &'a Schemas,
+    s2: &'a Schemas,
+    // pairs being compared, or already found equal. these are assumed
+    // equal, so that recursive schemas terminate.
+    assumed: HashSet<(usize, usize)>,
+}
+
+impl<'a> Equiv<'a> {
+    fn eq(&mut self, a: SchemaIndex, b: SchemaIndex) -> bool {
+        let (a, b) = (deref(self.s1, a), deref(self.s2, b));
+        if !self.assumed.insert((a.idx.0, b.idx.0)) {
+            return true;
+        }
+        if a.lazy || b.lazy {
+            return false; // not compiled yet
+        }
+        self.same_values(a, b) && self.same_subschemas(a, b)
+    }
+
+    fn opt(&mut self, a: Option<SchemaIndex>, b: Option<SchemaIndex>) -> bool {
+        match (a, b) {
+            (Some(a), Some(b)) => self.eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        }
+    }
+
+    fn list(&mut self, a: IndexRange, b: IndexRange) -> bool {
+        let (a, b) = (self.s1.indexes(a), self.s2.indexes(b));
+        a.len() == b.len() && a.iter().zip(b).all(|(a, b)| self.eq(*a, *b))
+    }
+
+    fn additional(&mut self, a: &Option<Additional>, b: &Option<Additional>) -> bool {
+        match (a, b) {
+            (None, None) => true,
+            (Some(Additional::Bool(a)), Some(Additional::Bool(b))) => a == b,
+            (Some(Additional::SchemaRef(a)), Some(Additional::SchemaRef(b))) => self.eq(*a, *b),
+            _ => false,
+        }
+    }
+
+    // keywords without subschemas --
+    fn same_values(&self, a: &Schema, b: &Schema) -> bool {
+        let format = |s: &Schema| s.asserted_format().map(|f| f.name);
+        let num = |a: &Option<Number>, b: &Option<Number>| match (a, b) {
+            (Some(a), Some(b)) => num_cmp(a, b) == Some(Ordering::Equal),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        let value = |a: &Option<Value>, b: &Option<Value>| match (a, b) {
+            (Some(a), Some(b)) => equals(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        let enum_ = match (&a.enum_, &b.enum_) {
+            (Some(a), Some(b)) => {
+                a.types.0 == b.types.0
+                    && a.values.iter().all(|v| b.contains(v))
+                    && b.values.iter().all(|v| a.contains(v))
+            }
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        a.boolean == b.boolean
+            && a.types.0 == b.types.0
+            && enum_
+            && value(&a.constant, &b.constant)
+            && format(a) == format(b)
+            && a.format_name == b.format_name
+            && value(&a.default, &b.default)
+            && a.read_only == b.read_only
+            && a.write_only == b.write_only
+            && a.deprecated == b.deprecated
+            && sorted(&a.bson_types) == sorted(&b.bson_types)
+            && a.recursive_anchor == b.recursive_anchor
+            && a.dynamic_anchor == b.dynamic_anchor
+            // object
+            && a.min_properties == b.min_properties
+            && a.max_properties == b.max_properties
+            && sorted(&a.required) == sorted(&b.required)
+            && dependent_required(a) == dependent_required(b)
+            && dependencies(a) == dependencies(b)
+            // array
+            && a.min_items == b.min_items
+            && a.max_items == b.max_items
+            && a.unique_items == b.unique_items
+            && a.min_contains == b.min_contains
+            && a.max_contains == b.max_contains
+            // string
+            && a.min_length == b.min_length
+            && a.max_length == b.max_length
+            && a.pattern.as_ref().map(Regex::as_str) == b.pattern.as_ref().map(Regex::as_str)
+            && a.content_encoding.as_ref().map(|d| d.name) == b.content_encoding.as_ref().map(|d| d.name)
+            && a.content_media_type.as_ref().map(|m| m.name) == b.content_media_type.as_ref().map(|m| m.name)
+            // number
+            && num(&a.minimum, &b.minimum)
+            && num(&a.maximum, &b.maximum)
+            && num(&a.exclusive_minimum, &b.exclusive_minimum)
+            && num(&a.exclusive_maximum, &b.exclusive_maximum)
+            && num(&a.multiple_of, &b.multiple_of)
+            && a.data == b.data
+            // custom keywords
+            && a.keywords.len() == b.keywords.len()
+            && a.keywords.iter().zip(&b.keywords).all(|(k1, k2)| {
+                k1.name == k2.name && equals(&k1.value, &k2.value)
+            })
+    }
+
+    // keywords with subschemas --
+    fn same_subschemas(&mut self, a: &Schema, b: &Schema) -> bool {
+        let dynamic_ref = match (&a.dynamic_ref, &b.dynamic_ref) {
+            (Some(a), Some(b)) => a.anchor == b.anchor && self.eq(a.sch, b.sch),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        if !dynamic_ref
+            || !self.opt(a.ref_, b.ref_)
+            || !self.opt(a.recursive_ref, b.recursive_ref)
+            || !self.opt(a.not, b.not)
+            || !self.list(a.all_of, b.all_of)
+            || !self.list(a.any_of, b.any_of)
+            || !self.list(a.one_of, b.one_of)
+            || !self.opt(a.if_, b.if_)
+            || !self.opt(a.then, b.then)
+            || !self.opt(a.else_, b.else_)
+        {
+            return false;
+        }
+
+        // object --
+        if a.properties.len() != b.properties.len()
+            || a.pattern_properties.len() != b.pattern_properties.len()
+            || a.dependent_schemas.len() != b.dependent_schemas.len()
+        {
+            return false;
+        }
+        for (pname, sch) in &a.properties {
+            match b.properties.get(pname) {
+                Some(other) if self.eq(*sch, *other) => {}
+                _ => return false,
+            }
+        }
+        for ((r1, s1), (r2, s2)) in a.pattern_properties.iter().zip(&b.pattern_properties) {
+            if r1.as_str() != r2.as_str() || !self.eq(*s1, *s2) {
+                return false;
+            }
+        }
+        for (pname, sch) in &a.dependent_schemas {
+            match b.dependent_schemas.iter().find(|(p, _)| p == pname) {
+                Some((_, other)) if self.eq(*sch, *other) => {}
+                _ => return false,
+            }
+        }
+        let schema_deps = |s: &Schema| {
+            let deps = s.dependencies.iter();
+            deps.filter_map(|(pname, dep)| match dep {
+                Dependency::SchemaRef(sch) => Some((pname.clone(), *sch)),
+                Dependency::Props(_) => None,
+            })
+            .collect::<Vec<_>>()
+        };
+        let (deps1, deps2) = (schema_deps(a), schema_deps(b));
+        if deps1.len() != deps2.len() {
+            return false;
+        }
+        for (pname, sch) in deps1 {
+            match deps2.iter().find(|(p, _)| *p == pname) {
+                Some((_, other)) if self.eq(sch, *other) => {}
+                _ => return false,
+            }
+        }
+        if !self.opt(a.property_names, b.property_names)
+            || !self.additional(&a.additional_properties, &b.additional_properties)
+            || !self.opt(a.unevaluated_properties, b.unevaluated_properties)
+        {
+            return false;
+        }
+
+        // array --
+        let items = match (&a.items, &b.items) {
+            (None, None) => true,
+            (Some(Items::SchemaRef(a)), Some(Items::SchemaRef(b))) => self.eq(*a, *b),
+            (Some(Items::SchemaRefs(a)), Some(Items::SchemaRefs(b))) => self.list(*a, *b),
+            _ => false,
+        };
+        items
+            && self.additional(&a.additional_items, &b.additional_items)
+            && self.list(a.prefix_items, b.prefix_items)
+            && self.opt(a.items2020, b.items2020)
+            && self.opt(a.contains, b.contains)
+            && self.opt(a.unevaluated_items, b.unevaluated_items)
+            && self.opt(a.content_schema, b.content_schema)
+    }
+}
+
+// returns target of `$ref`, if schema has nothing else.
+fn deref(schemas: &Schemas, mut sch: SchemaIndex) -> &Schema {
+    let mut seen = HashSet::new();
+    loop {
+        let s = schemas.get(sch);
+        match s.ref_ {
+            Some(target) if is_ref_only(s, schemas) && seen.insert(sch) => sch = target,
+            _ => return s,
+        }
+    }
+}
+
+fn is_ref_only(s: &Schema, schemas: &Schemas) -> bool {
+    let empty = Schema {
+        draft_version: s.draft_version,
+        ..Default::default()
+    };
+    let equiv = Equiv {
+        s1: schemas,
+        s2: schemas,
+        assumed: HashSet::new(),
+    };
+    s.children(schemas).len() == 1 && !s.lazy && equiv.same_values(s, &empty)
+}
+
+fn dependencies(s: &Schema) -> Vec<(&String, Vec<String>)> {
+    let mut deps = s
+        .dependencies
+        .iter()
+        .filter_map(|(pname, dep)| match dep {
+            Dependency::Props(props) => Some((pname, sorted(props))),
+            Dependency::SchemaRef(_) => None,
+        })
+        .collect::<Vec<_>>();
+    deps.sort();
+    deps
+}
+
+fn dependent_required(s: &Schema) -> Vec<(&String, Vec<String>)> {
+    let mut deps = s
+        .dependent_required
+        .iter()
+        .map(|(pname, props)| (pname, sorted(props)))
+        .collect::<Vec<_>>();
+    deps.sort();
+    deps
+}
+
+fn sorted<T: Ord + Clone>(list: &[T]) -> Vec<T> {
+    let mut list = list.to_vec();
+    list.sort();
+    list.dedup();
+    list
+}
@@ -0,0 +1,166 @@
+use std::collections::HashSet;
+
+use crate::{util::*, Items, Schema, Type};
+
+/// Constraint which is lost in exported schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unrepresentable {
+    /// location of the schema containing the constraint.
+    pub schema_url: String,
+    /// keyword of the constraint.
+    pub keyword: &'static str,
+    /// why the constraint could not be represented.
+    pub reason: String,
+}
+
+// returns keywords of value constraints, which exporters cannot map to types.
+pub(crate) fn constraints(sch: &Schema) -> Vec<&'static str> {
+    let mut kws = vec![];
+    let mut check = |cond: bool, kw: &'static str| {
+        if cond {
+            kws.push(kw);
+        }
+    };
+    check(sch.recursive_ref.is_some(), "$recursiveRef");
+    check(sch.dynamic_ref.is_some(), "$dynamicRef");
+    check(sch.constant.is_some(), "const");
+    check(sch.not.is_some(), "not");
+    check(!sch.all_of.is_empty(), "allOf");
+    check(sch.if_.is_some(), "if");
+    check(sch.asserted_format().is_some(), "format");
+    check(sch.min_properties.is_some(), "minProperties");
+    check(sch.max_properties.is_some(), "maxProperties");
+    check(!sch.pattern_properties.is_empty(), "patternProperties");
+    check(sch.property_names.is_some(), "propertyNames");
+    check(!sch.dependent_required.is_empty(), "dependentRequired");
+    check(!sch.dependent_schemas.is_empty(), "dependentSchemas");
+    check(!sch.dependencies.is_empty(), "dependencies");
+    check(
+        sch.unevaluated_properties.is_some(),
+        "unevaluatedProperties",
+    );
+    check(sch.min_items.is_some(), "minItems");
+    check(sch.max_items.is_some(), "maxItems");
+    check(sch.unique_items, "uniqueItems");
+    check(sch.contains.is_some(), "contains");
+    check(matches!(sch.items, Some(Items::SchemaRefs(_))), "items");
+    check(!sch.prefix_items.is_empty(), "prefixItems");
+    check(sch.additional_items.is_some(), "additionalItems");
+    check(sch.unevaluated_items.is_some(), "unevaluatedItems");
+    check(sch.min_length.is_some(), "minLength");
+    check(sch.max_length.is_some(), "maxLength");
+    check(sch.pattern.is_some(), "pattern");
+    check(sch.content_encoding.is_some(), "contentEncoding");
+    check(sch.content_media_type.is_some(), "contentMediaType");
+    check(sch.minimum.is_some(), "minimum");
+    check(sch.maximum.is_some(), "maximum");
+    check(sch.exclusive_minimum.is_some(), "exclusiveMinimum");
+    check(sch.exclusive_maximum.is_some(), "exclusiveMaximum");
+    check(sch.multiple_of.is_some(), "multipleOf");
+    kws
+}
+
+pub(crate) fn is_object(sch: &Schema) -> bool {
+    sch.types.contains(Type::Object) || (sch.types.is_empty() && !sch.properties.is_empty())
+}
+
+pub(crate) fn is_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some(ch) if ch == '_' || ch.is_ascii_alphabetic())
+        && chars.all(|ch| ch == '_' || ch.is_ascii_alphanumeric())
+}
+
+pub(crate) fn field_name(s: &str) -> String {
+    let mut name = s
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+        .collect::<String>();
+    if !is_name(&name) {
+        name.insert(0, '_');
+    }
+    name
+}
+
+// returns field names for `pnames`, which are unique. names of properties
+// which need no change are preferred on collision, others get suffix.
+pub(crate) fn field_names(pnames: &[&str]) -> Vec<String> {
+    let mut used = pnames
+        .iter()
+        .filter(|p| is_name(p))
+        .map(|p| p.to_string())
+        .collect::<HashSet<_>>();
+    pnames
+        .iter()
+        .map(|p| {
+            if is_name(p) {
+                return p.to_string();
+            }
+            unique_field_name(&field_name(p), &mut used)
+        })
+        .collect()
+}
+
+// returns `base`, suffixed with number if it is in `used`.
+pub(crate) fn unique_field_name(base: &str, used: &mut HashSet<String>) -> String {
+    let mut name = base.to_owned();
+    let mut i = 2;
+    while !used.insert(name.clone()) {
+        name = format!("{base}_{i}");
+        i += 1;
+    }
+    name
+}
+
+// converts to PascalCase
+pub(crate) fn type_name(s: &str) -> String {
+    let mut name = String::new();
+    for word in s.split(|ch: char| !ch.is_ascii_alphanumeric()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            name.push(first.to_ascii_uppercase());
+            name.extend(chars);
+        }
+    }
+    if !is_name(&name) {
+        name.insert(0, '_');
+    }
+    name
+}
+
+// derives name for referenced schema from its location.
+pub(crate) fn ref_hint(sch: &Schema) -> String {
+    let (url, frag) = split(&sch.loc);
+    let frag = percent_encoding::percent_decode_str(frag).decode_utf8_lossy();
+    match frag.rsplit('/').next() {
+        Some(tok) if !tok.is_empty() => tok.to_owned(),
+        _ => {
+            let path = url.rsplit('/').next().unwrap_or_default();
+            path.split('.').next().unwrap_or_default().to_owned()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_name() {
+        assert_eq!(type_name("billing_address"), "BillingAddress");
+        assert_eq!(type_name("Person_tags"), "PersonTags");
+        assert_eq!(type_name("1st"), "_1st");
+    }
+
+    #[test]
+    fn test_field_name() {
+        assert_eq!(field_name("first-name"), "first_name");
+        assert_eq!(field_name("$id"), "_id");
+        assert_eq!(field_name("2fa"), "_2fa");
+    }
+
+    #[test]
+    fn test_field_names() {
+        let names = field_names(&["first-name", "first_name", "first.name", "$id"]);
+        assert_eq!(names, ["first_name_2", "first_name", "first_name_3", "_id"]);
+    }
+}
@@ -0,0 +1,235 @@
+use std::{collections::BTreeMap, error::Error};
+
+use serde_json::{json, Map, Value};
+
+use crate::{util::*, Compiler, OutputError, SchemaIndex, Schemas, ValidationError};
+
+/// Rule used by [`FixtureRecorder`] to anonymize recorded instances.
+#[derive(Debug, Clone)]
+pub enum Redaction {
+    /// Replaces value at given json-pointer with `with`.
+    ///
+    /// A token `*` in pointer matches any property or array item.
+    /// for example `/users/*/email`
+    Pointer { ptr: String, with: Value },
+    /// Replaces value of every property named `name` with `with`.
+    Property { name: String, with: Value },
+}
+
+/**
+Records anonymized instances that fail validation, into a corpus keyed
+by schema location.
+
+The corpus uses same layout as
+[JSON-Schema-Test-Suite](https://github.com/json-schema-org/JSON-Schema-Test-Suite),
+i.e a list of groups with `description`, `schema` and `tests`. Each group
+refers to the recorded schema location using `$ref`, and every test in it
+is expected to be invalid. Use [`replay_fixtures`] to check recorded corpus
+against updated schemas.
+*/
+#[derive(Debug, Default)]
+pub struct FixtureRecorder {
+    redactions: Vec<Redaction>,
+    groups: BTreeMap<String, Vec<(String, Value)>>, // loc => [(description, data)]
+}
+
+impl FixtureRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds redaction rule applied to instances before they are recorded.
+    pub fn add_redaction(&mut self, redaction: Redaction) {
+        self.redactions.push(redaction);
+    }
+
+    /**
+    Validates `v` with schema identified by `sch_index` and records
+    the anonymized `v` if validation fails.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for `schemas`.
+    */
+    pub fn validate<'s, 'v>(
+        &mut self,
+        schemas: &'s Schemas,
+        v: &'v Value,
+        sch_index: SchemaIndex,
+    ) -> Result<(), ValidationError<'s, 'v>> {
+        let result = schemas.validate(v, sch_index);
+        if let Err(e) = &result {
+            self.record(e, v);
+        }
+        result
+    }
+
+    fn record(&mut self, e: &ValidationError, v: &Value) {
+        let mut data = v.clone();
+        redact(&mut data, &self.redactions);
+
+        let fixtures = self.groups.entry(e.schema_url.to_owned()).or_default();
+        if fixtures.iter().any(|(_, d)| equals(d, &data)) {
+            return;
+        }
+        // keyword locations do not leak instance values.
+        let description = match e.basic_output().error {
+            OutputError::Leaf(_) => "invalid".to_owned(),
+            OutputError::Branch(units) => join_iter(
+                units.iter().map(|u| {
+                    format!(
+                        "{} at {}",
+                        u.keyword_location,
+                        quote(&u.instance_location.to_string())
+                    )
+                }),
+                ", ",
+            ),
+        };
+        fixtures.push((description, data));
+    }
+
+    /// Returns `true` if no instance is recorded.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Returns the number of recorded instances.
+    pub fn len(&self) -> usize {
+        self.groups.values().map(Vec::len).sum()
+    }
+
+    /// Returns the recorded corpus, which can be persisted as json file.
+    pub fn corpus(&self) -> Value {
+        let groups = self
+            .groups
+            .iter()
+            .map(|(loc, fixtures)| {
+                let tests = fixtures
+                    .iter()
+                    .map(|(description, data)| {
+                        json!({"description": description, "data": data, "valid": false})
+                    })
+                    .collect::<Vec<_>>();
+                json!({"description": loc, "schema": {"$ref": loc}, "tests": tests})
+            })
+            .collect();
+        Value::Array(groups)
+    }
+}
+
+pub(crate) fn redact(v: &mut Value, redactions: &[Redaction]) {
+    for r in redactions {
+        match r {
+            Redaction::Pointer { ptr, with } => {
+                let tokens = ptr.split('/').skip(1).collect::<Vec<_>>();
+                redact_ptr(v, &tokens, with);
+            }
+            Redaction::Property { name, with } => redact_prop(v, name, with),
+        }
+    }
+}
+
+fn redact_ptr(v: &mut Value, tokens: &[&str], with: &Value) {
+    let Some((tok, rest)) = tokens.split_first() else {
+        *v = with.clone();
+        return;
+    };
+    let Ok(tok) = JsonPointer::unescape(tok) else {
+        return;
+    };
+    match v {
+        Value::Object(obj) => {
+            if tok == "*" {
+                obj.values_mut().for_each(|v| redact_ptr(v, rest, with));
+            } else if let Some(v) = obj.get_mut(tok.as_ref()) {
+                redact_ptr(v, rest, with);
+            }
+        }
+        Value::Array(arr) => {
+            if tok == "*" {
+                arr.iter_mut().for_each(|v| redact_ptr(v, rest, with));
+            } else if let Some(v) = tok.parse::<usize>().ok().and_then(|i| arr.get_mut(i)) {
+                redact_ptr(v, rest, with);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn redact_prop(v: &mut Value, name: &str, with: &Value) {
+    match v {
+        Value::Object(obj) => {
+            for (pname, pvalue) in obj.iter_mut() {
+                if pname == name {
+                    *pvalue = with.clone();
+                } else {
+                    redact_prop(pvalue, name, with);
+                }
+            }
+        }
+        Value::Array(arr) => arr.iter_mut().for_each(|v| redact_prop(v, name, with)),
+        _ => {}
+    }
+}
+
+/// Recorded instance which no longer fails validation.
+#[derive(Debug)]
+pub struct FixtureRegression {
+    /// schema location under which the instance is recorded.
+    pub schema_url: String,
+    /// description of the recorded test.
+    pub description: String,
+    /// recorded instance.
+    pub data: Value,
+}
+
+/**
+Replays `corpus` recorded by [`FixtureRecorder`] against schemas
+loaded by `compiler`, and returns recorded instances which are now valid.
+
+# Errors
+
+returns error if `corpus` is not in expected format, or if any of the
+recorded schema locations fail to compile.
+*/
+pub fn replay_fixtures(
+    corpus: &Value,
+    compiler: &mut Compiler,
+    schemas: &mut Schemas,
+) -> Result<Vec<FixtureRegression>, Box<dyn Error>> {
+    let Value::Array(groups) = corpus else {
+        Err("fixtures corpus must be array")?
+    };
+    let mut regressions = vec![];
+    for group in groups {
+        let Some(Value::String(loc)) = group.pointer("/schema/$ref") else {
+            Err("fixtures group must have schema with $ref")?
+        };
+        let Some(Value::Array(tests)) = group.get("tests") else {
+            Err("fixtures group must have tests")?
+        };
+        let sch = compiler.compile(loc, schemas)?;
+        for test in tests {
+            let Value::Object(test) = test else {
+                Err("fixtures test must be object")?
+            };
+            let data = test.get("data").unwrap_or(&Value::Null);
+            if schemas.validate(data, sch).is_ok() {
+                regressions.push(FixtureRegression {
+                    schema_url: loc.clone(),
+                    description: description(test),
+                    data: data.clone(),
+                });
+            }
+        }
+    }
+    Ok(regressions)
+}
+
+fn description(test: &Map<String, Value>) -> String {
+    match test.get("description") {
+        Some(Value::String(s)) => s.clone(),
+        _ => String::new(),
+    }
+}
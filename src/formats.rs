@@ -1,7 +1,9 @@
 use std::{
     collections::HashMap,
     error::Error,
+    fmt::Display,
     net::{Ipv4Addr, Ipv6Addr},
+    sync::Arc,
 };
 
 use once_cell::sync::Lazy;
@@ -21,6 +23,149 @@ pub struct Format {
     pub func: fn(v: &Value) -> Result<(), Box<dyn Error>>,
 }
 
+/// Strictness options for `hostname` and `idn-hostname` formats.
+///
+/// These are also used to check domain part of `email` and `idn-email`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HostnameOptions {
+    /// Enforce maximum of 253 characters for hostname and 63 characters
+    /// for each label. Defaults to `true`.
+    pub check_lengths: bool,
+    /// Allow trailing dot as in fully qualified `example.com.`.
+    /// Defaults to `true`.
+    pub allow_trailing_dot: bool,
+    /// Allow underscore in labels as in `_sip._tcp.example.com`,
+    /// which is common in DNS but not allowed by RFC 1123.
+    /// Defaults to `false`.
+    pub allow_underscore: bool,
+}
+
+impl Default for HostnameOptions {
+    fn default() -> Self {
+        Self {
+            check_lengths: true,
+            allow_trailing_dot: true,
+            allow_underscore: false,
+        }
+    }
+}
+
+/// Strictness options for `email` and `idn-email` formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmailOptions {
+    /// Enforce RFC 5321 length limits, i.e. maximum of 64 characters
+    /// for local part and 254 characters for entire address.
+    /// Defaults to `true`.
+    pub check_lengths: bool,
+    /// Allow quoted local parts as in `"john doe"@example.com`.
+    /// Defaults to `true`.
+    pub allow_quoted_local: bool,
+    /// Allow ip address literals as domain as in `joe@[127.0.0.1]`.
+    /// Defaults to `true`.
+    pub allow_ip_literal: bool,
+}
+
+impl Default for EmailOptions {
+    fn default() -> Self {
+        Self {
+            check_lengths: true,
+            allow_quoted_local: true,
+            allow_ip_literal: true,
+        }
+    }
+}
+
+impl Format {
+    /// Returns names of formats supported by this library.
+    pub fn builtin_names() -> Vec<&'static str> {
+        let mut names = FORMATS.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+}
+
+pub(crate) type FormatFn = Arc<dyn Fn(&Value) -> Result<(), Box<dyn Error>> + Send + Sync>;
+
+/// format as used by compiled schema.
+#[derive(Clone)]
+pub(crate) struct CompiledFormat {
+    pub(crate) name: &'static str,
+    pub(crate) func: FormatFn,
+}
+
+impl From<Format> for CompiledFormat {
+    fn from(format: Format) -> Self {
+        Self {
+            name: format.name,
+            func: Arc::new(format.func),
+        }
+    }
+}
+
+// returns standard format `name` configured with given options.
+pub(crate) fn std_format(
+    name: &str,
+    email: EmailOptions,
+    hostname: HostnameOptions,
+) -> Option<CompiledFormat> {
+    let &Format { name, func } = FORMATS.get(name)?;
+    let func: FormatFn = match name {
+        "hostname" => Arc::new(move |v| with_str(v, |s| check_hostname(s, &hostname))),
+        "idn-hostname" => Arc::new(move |v| with_str(v, |s| check_idn_hostname(s, &hostname))),
+        "email" => Arc::new(move |v| with_str(v, |s| check_email(s, &email, &hostname))),
+        "idn-email" => Arc::new(move |v| with_str(v, |s| check_idn_email(s, &email, &hostname))),
+        _ => Arc::new(func),
+    };
+    Some(CompiledFormat { name, func })
+}
+
+fn with_str(
+    v: &Value,
+    f: impl Fn(&str) -> Result<(), Box<dyn Error>>,
+) -> Result<(), Box<dyn Error>> {
+    let Value::String(s) = v else {
+        return Ok(());
+    };
+    f(s)
+}
+
+// error of std formats, rendered only when displayed. most are never
+// displayed, such as those of failed anyOf/oneOf branches.
+#[derive(Debug)]
+enum FormatError {
+    Msg(&'static str),
+    Invalid(&'static str, Box<dyn Error>),
+    InvalidChar(char),
+    NonHexChar(char),
+    MonthsInYear(usize),
+    DaysInMonth(usize),
+    FebruaryDays(usize),
+    UnitOrder(char),
+    InvalidUnit(char),
+    ElementLen(usize, usize),
+}
+
+use FormatError::*;
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Msg(msg) => write!(f, "{msg}"),
+            Invalid(what, e) => write!(f, "invalid {what}: {e}"),
+            InvalidChar(ch) => write!(f, "invalid character {ch:?}"),
+            NonHexChar(ch) => write!(f, "non-hex character {ch:?}"),
+            MonthsInYear(m) => write!(f, "{m} months in year"),
+            DaysInMonth(d) => write!(f, "{d} days in month"),
+            FebruaryDays(d) => write!(f, "february has {d} days only"),
+            UnitOrder(unit) => write!(f, "unit {unit} out of order"),
+            InvalidUnit(unit) => write!(f, "invalid unit {unit}"),
+            ElementLen(i, len) => write!(f, "element {i} must be {len} characters long"),
+        }
+    }
+}
+
+impl Error for FormatError {}
+
 pub(crate) static FORMATS: Lazy<HashMap<&'static str, Format>> = Lazy::new(|| {
     let mut m = HashMap::<&'static str, Format>::new();
     let mut register = |name, func| m.insert(name, Format { name, func });
@@ -51,7 +196,7 @@ fn validate_regex(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
-    ecma::convert(s).map(|_| ())
+    ecma::validate(s)
 }
 
 fn validate_ipv4(v: &Value) -> Result<(), Box<dyn Error>> {
@@ -85,22 +230,22 @@ fn matches_char(s: &str, index: usize, ch: char) -> bool {
 fn check_date(s: &str) -> Result<(), Box<dyn Error>> {
     // yyyy-mm-dd
     if s.len() != 10 {
-        Err("must be 10 characters long")?;
+        Err(Msg("must be 10 characters long"))?;
     }
     if !matches_char(s, 4, '-') || !matches_char(s, 7, '-') {
-        Err("missing hyphen in correct place")?;
+        Err(Msg("missing hyphen in correct place"))?;
     }
 
     let mut ymd = s.splitn(3, '-').filter_map(|t| t.parse::<usize>().ok());
     let (Some(y), Some(m), Some(d)) = (ymd.next(), ymd.next(), ymd.next()) else {
-        Err("non-positive year/month/day")?
+        Err(Msg("non-positive year/month/day"))?
     };
 
     if !matches!(m, 1..=12) {
-        Err(format!("{m} months in year"))?;
+        Err(MonthsInYear(m))?;
     }
     if !matches!(d, 1..=31) {
-        Err(format!("{d} days in month"))?;
+        Err(DaysInMonth(d))?;
     }
 
     match m {
@@ -110,12 +255,12 @@ fn check_date(s: &str) -> Result<(), Box<dyn Error>> {
                 feb_days += 1; // leap year
             };
             if d > feb_days {
-                Err(format!("february has {feb_days} days only"))?;
+                Err(FebruaryDays(feb_days))?;
             }
         }
         4 | 6 | 9 | 11 => {
             if d > 30 {
-                Err("month has 30 days only")?;
+                Err(Msg("month has 30 days only"))?;
             }
         }
         _ => {}
@@ -133,24 +278,24 @@ fn validate_time(v: &Value) -> Result<(), Box<dyn Error>> {
 fn check_time(mut str: &str) -> Result<(), Box<dyn Error>> {
     // min: hh:mm:ssZ
     if str.len() < 9 {
-        Err("less than 9 characters long")?
+        Err(Msg("less than 9 characters long"))?
     }
     if !matches_char(str, 2, ':') || !matches_char(str, 5, ':') {
-        Err("missing colon in correct place")?
+        Err(Msg("missing colon in correct place"))?
     }
 
     // parse hh:mm:ss
     if !str.is_char_boundary(8) {
-        Err("contains non-ascii char")?
+        Err(Msg("contains non-ascii char"))?
     }
     let mut hms = (str[..8])
         .splitn(3, ':')
         .filter_map(|t| t.parse::<usize>().ok());
     let (Some(mut h), Some(mut m), Some(s)) = (hms.next(), hms.next(), hms.next()) else {
-        Err("non-positive hour/min/sec")?
+        Err(Msg("non-positive hour/min/sec"))?
     };
     if h > 23 || m > 59 || s > 60 {
-        Err("hour/min/sec out of range")?
+        Err(Msg("hour/min/sec out of range"))?
     }
     str = &str[8..];
 
@@ -158,7 +303,7 @@ fn check_time(mut str: &str) -> Result<(), Box<dyn Error>> {
     if let Some(rem) = str.strip_prefix('.') {
         let n_digits = rem.chars().take_while(char::is_ascii_digit).count();
         if n_digits == 0 {
-            Err("no digits in second fraction")?;
+            Err(Msg("no digits in second fraction"))?;
         }
         str = &rem[n_digits..];
     }
@@ -166,24 +311,24 @@ fn check_time(mut str: &str) -> Result<(), Box<dyn Error>> {
     if str != "z" && str != "Z" {
         // parse time-numoffset
         if str.len() != 6 {
-            Err("offset must be 6 characters long")?;
+            Err(Msg("offset must be 6 characters long"))?;
         }
         let sign: isize = match str.chars().next() {
             Some('+') => -1,
             Some('-') => 1,
-            _ => return Err("offset must begin with plus/minus")?,
+            _ => return Err(Msg("offset must begin with plus/minus"))?,
         };
         str = &str[1..];
         if !matches_char(str, 2, ':') {
-            Err("missing colon in offset at correct place")?
+            Err(Msg("missing colon in offset at correct place"))?
         }
 
         let mut zhm = str.splitn(2, ':').filter_map(|t| t.parse::<usize>().ok());
         let (Some(zh), Some(zm)) = (zhm.next(), zhm.next()) else {
-            Err("non-positive hour/min in offset")?
+            Err(Msg("non-positive hour/min in offset"))?
         };
         if zh > 23 || zm > 59 {
-            Err("hour/min in offset out of range")?
+            Err(Msg("hour/min in offset out of range"))?
         }
 
         // apply timezone
@@ -198,7 +343,7 @@ fn check_time(mut str: &str) -> Result<(), Box<dyn Error>> {
 
     // check leap second
     if !(s < 60 || (h == 23 && m == 59)) {
-        Err("invalid leap second")?
+        Err(Msg("invalid leap second"))?
     }
     Ok(())
 }
@@ -213,16 +358,16 @@ fn validate_date_time(v: &Value) -> Result<(), Box<dyn Error>> {
 fn check_date_time(s: &str) -> Result<(), Box<dyn Error>> {
     // min: yyyy-mm-ddThh:mm:ssZ
     if s.len() < 20 {
-        Err("less than 20 characters long")?;
+        Err(Msg("less than 20 characters long"))?;
     }
     if !s.is_char_boundary(10) || !s[10..].starts_with(|c| matches!(c, 't' | 'T')) {
-        Err("11th character must be t or T")?;
+        Err(Msg("11th character must be t or T"))?;
     }
     if let Err(e) = check_date(&s[..10]) {
-        Err(format!("invalid date element: {e}"))?;
+        Err(Invalid("date element", e))?;
     }
     if let Err(e) = check_time(&s[11..]) {
-        Err(format!("invalid time element: {e}"))?;
+        Err(Invalid("time element", e))?;
     }
     Ok(())
 }
@@ -235,58 +380,85 @@ fn validate_duration(v: &Value) -> Result<(), Box<dyn Error>> {
 }
 
 // see https://datatracker.ietf.org/doc/html/rfc3339#appendix-A
+//
+// in addition, as per ISO 8601, the smallest component may have
+// decimal fraction, using either comma or full stop.
 fn check_duration(s: &str) -> Result<(), Box<dyn Error>> {
     // must start with 'P'
     let Some(s) = s.strip_prefix('P') else {
-        Err("must start with P")?
+        Err(Msg("must start with P"))?
     };
     if s.is_empty() {
-        Err("nothing after P")?
+        Err(Msg("nothing after P"))?
     }
 
     // dur-week
     if let Some(s) = s.strip_suffix('W') {
-        if s.is_empty() {
-            Err("no number in week")?
-        }
-        if !s.chars().all(|c| c.is_ascii_digit()) {
-            Err("invalid week")?
+        let (_, rem) = parse_duration_number(s)?;
+        if !rem.is_empty() {
+            if rem.contains(|c| "YMDTHS".contains(c)) {
+                Err(Msg("weeks cannot be combined with other units"))?
+            }
+            Err(Msg("invalid week"))?
         }
         return Ok(());
     }
 
     static UNITS: [&str; 2] = ["YMD", "HMS"];
+    let mut fraction = false;
     for (i, s) in s.split('T').enumerate() {
         let mut s = s;
         if i != 0 && s.is_empty() {
-            Err("no time elements")?
+            Err(Msg("no time elements"))?
         }
         let Some(mut units) = UNITS.get(i).cloned() else {
-            Err("more than one T")?
+            Err(Msg("more than one T"))?
         };
         while !s.is_empty() {
-            let digit_count = s.chars().take_while(char::is_ascii_digit).count();
-            if digit_count == 0 {
-                Err("missing number")?
+            if fraction {
+                Err(Msg("only the smallest component can have fraction"))?
             }
-            s = &s[digit_count..];
+            let (frac, rem) = parse_duration_number(s)?;
+            fraction = frac;
+            s = rem;
             let Some(unit) = s.chars().next() else {
-                Err("missing unit")?
+                Err(Msg("missing unit"))?
             };
             let Some(j) = units.find(unit) else {
                 if UNITS[i].contains(unit) {
-                    Err(format!("unit {unit} out of order"))?
+                    Err(UnitOrder(unit))?
+                }
+                if unit == 'W' {
+                    Err(Msg("weeks cannot be combined with other units"))?
                 }
-                Err(format!("invalid unit {unit}"))?
+                Err(InvalidUnit(unit))?
             };
             units = &units[j + 1..];
-            s = &s[1..];
+            s = &s[unit.len_utf8()..];
         }
     }
 
     Ok(())
 }
 
+// parses `1*DIGIT [ ( "." / "," ) 1*DIGIT ]` at the start of `s`.
+// returns whether fraction is present, along with remaining string.
+fn parse_duration_number(s: &str) -> Result<(bool, &str), Box<dyn Error>> {
+    let digit_count = s.chars().take_while(char::is_ascii_digit).count();
+    if digit_count == 0 {
+        Err(Msg("missing number"))?
+    }
+    let s = &s[digit_count..];
+    let Some(frac) = s.strip_prefix(['.', ',']) else {
+        return Ok((false, s));
+    };
+    let digit_count = frac.chars().take_while(char::is_ascii_digit).count();
+    if digit_count == 0 {
+        Err(Msg("no digits in fraction"))?
+    }
+    Ok((true, &frac[digit_count..]))
+}
+
 // see https://datatracker.ietf.org/doc/html/rfc3339#appendix-A
 fn validate_period(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
@@ -294,27 +466,27 @@ fn validate_period(v: &Value) -> Result<(), Box<dyn Error>> {
     };
 
     let Some(slash) = s.find('/') else {
-        Err("missing slash")?
+        Err(Msg("missing slash"))?
     };
 
     let (start, end) = (&s[..slash], &s[slash + 1..]);
     if start.starts_with('P') {
         if let Err(e) = check_duration(start) {
-            Err(format!("invalid start duration: {e}"))?
+            Err(Invalid("start duration", e))?
         }
         if let Err(e) = check_date_time(end) {
-            Err(format!("invalid end date-time: {e}"))?
+            Err(Invalid("end date-time", e))?
         }
     } else {
         if let Err(e) = check_date_time(start) {
-            Err(format!("invalid start date-time: {e}"))?
+            Err(Invalid("start date-time", e))?
         }
         if end.starts_with('P') {
             if let Err(e) = check_duration(end) {
-                Err(format!("invalid end duration: {e}"))?;
+                Err(Invalid("end duration", e))?;
             }
         } else if let Err(e) = check_date_time(end) {
-            Err(format!("invalid end date-time: {e}"))?;
+            Err(Invalid("end date-time", e))?;
         }
     }
     Ok(())
@@ -324,40 +496,46 @@ fn validate_hostname(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
-    check_hostname(s)
+    check_hostname(s, &HostnameOptions::default())
 }
 
 // see https://en.wikipedia.org/wiki/Hostname#Restrictions_on_valid_host_names
-fn check_hostname(mut s: &str) -> Result<(), Box<dyn Error>> {
+fn check_hostname(mut s: &str, opts: &HostnameOptions) -> Result<(), Box<dyn Error>> {
+    if let Some(prefix) = s.strip_suffix('.') {
+        if !opts.allow_trailing_dot {
+            Err(Msg("trailing dot not allowed"))?
+        }
+        s = prefix;
+    }
+
     // entire hostname (including the delimiting dots but not a trailing dot) has a maximum of 253 ASCII characters
-    s = s.strip_suffix('.').unwrap_or(s);
-    if s.len() > 253 {
-        Err("more than 253 characters long")?
+    if opts.check_lengths && s.len() > 253 {
+        Err(Msg("more than 253 characters long"))?
     }
 
     // Hostnames are composed of series of labels concatenated with dots, as are all domain names
     for label in s.split('.') {
         // Each label must be from 1 to 63 characters long
-        if !matches!(label.len(), 1..=63) {
-            Err("label must be 1 to 63 characters long")?;
+        if label.is_empty() || (opts.check_lengths && label.len() > 63) {
+            Err(Msg("label must be 1 to 63 characters long"))?;
         }
 
         // labels must not start or end with a hyphen
         if label.starts_with('-') {
-            Err("label starts with hyphen")?;
+            Err(Msg("label starts with hyphen"))?;
         }
 
         if label.ends_with('-') {
-            Err("label ends with hyphen")?;
+            Err(Msg("label ends with hyphen"))?;
         }
 
         // labels may contain only the ASCII letters 'a' through 'z' (in a case-insensitive manner),
         // the digits '0' through '9', and the hyphen ('-')
-        if let Some(ch) = label
-            .chars()
-            .find(|c| !matches!(c, 'a'..='z' | 'A'..='Z' | '0'..='9' | '-'))
-        {
-            Err(format!("invalid character {ch:?}"))?;
+        if let Some(ch) = label.chars().find(|c| {
+            !(matches!(c, 'a'..='z' | 'A'..='Z' | '0'..='9' | '-')
+                || opts.allow_underscore && *c == '_')
+        }) {
+            Err(InvalidChar(ch))?;
         }
     }
 
@@ -368,10 +546,10 @@ fn validate_idn_hostname(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
-    check_idn_hostname(s)
+    check_idn_hostname(s, &HostnameOptions::default())
 }
 
-fn check_idn_hostname(s: &str) -> Result<(), Box<dyn Error>> {
+fn check_idn_hostname(s: &str, opts: &HostnameOptions) -> Result<(), Box<dyn Error>> {
     let s = idna::domain_to_ascii_strict(s)?;
     let unicode = idna::domain_to_unicode(&s).0;
 
@@ -390,7 +568,7 @@ fn check_idn_hostname(s: &str) -> Result<(), Box<dyn Error>> {
             '\u{303B}', //  VERTICAL IDEOGRAPHIC ITERATION MARK
         ];
         if unicode.contains(DISALLOWED) {
-            Err("contains disallowed character")?;
+            Err(Msg("contains disallowed character"))?;
         }
     }
 
@@ -405,7 +583,9 @@ fn check_idn_hostname(s: &str) -> Result<(), Box<dyn Error>> {
             .map(|c| if c == '-' { 1 } else { 0 })
             .sum();
         if count == 2 {
-            Err("unicode string must not contain '--' in 3rd and 4th position")?;
+            Err(Msg(
+                "unicode string must not contain '--' in 3rd and 4th position",
+            ))?;
         }
     }
 
@@ -418,7 +598,7 @@ fn check_idn_hostname(s: &str) -> Result<(), Box<dyn Error>> {
             let prefix = &s[..i];
             let suffix = &s[i + middle_dot.len_utf8()..];
             if !prefix.ends_with('l') || !suffix.ends_with('l') {
-                Err("MIDDLE DOT is allowed between 'l' characters only")?;
+                Err(Msg("MIDDLE DOT is allowed between 'l' characters only"))?;
             }
             s = suffix;
         }
@@ -433,7 +613,7 @@ fn check_idn_hostname(s: &str) -> Result<(), Box<dyn Error>> {
         while let Some(i) = s.find(keralia) {
             let suffix = &s[i + keralia.len_utf8()..];
             if !suffix.starts_with(|c| greek.contains(&c)) {
-                Err("Greek KERAIA must be followed by Greek character")?;
+                Err(Msg("Greek KERAIA must be followed by Greek character"))?;
             }
             s = suffix;
         }
@@ -455,9 +635,9 @@ fn check_idn_hostname(s: &str) -> Result<(), Box<dyn Error>> {
                 let suffix = &s[i + ch.len_utf8()..];
                 if !prefix.ends_with(|c| hebrew.contains(&c)) {
                     if i == 0 {
-                        Err("Hebrew GERESH must be preceded by Hebrew character")?;
+                        Err(Msg("Hebrew GERESH must be preceded by Hebrew character"))?;
                     } else {
-                        Err("Hebrew GERESHYIM must be preceded by Hebrew character")?;
+                        Err(Msg("Hebrew GERESHYIM must be preceded by Hebrew character"))?;
                     }
                 }
                 s = suffix;
@@ -479,7 +659,9 @@ fn check_idn_hostname(s: &str) -> Result<(), Box<dyn Error>> {
             {
                 // ok
             } else {
-                Err("KATAKANA MIDDLE DOT must be with Hiragana, Katakana, or Han")?;
+                Err(Msg(
+                    "KATAKANA MIDDLE DOT must be with Hiragana, Katakana, or Han",
+                ))?;
             }
         }
     }
@@ -493,7 +675,9 @@ fn check_idn_hostname(s: &str) -> Result<(), Box<dyn Error>> {
         if unicode.contains(|c| arabic_indic_digits.contains(&c))
             && unicode.contains(|c| extended_arabic_indic_digits.contains(&c))
         {
-            Err("ARABIC-INDIC DIGITS and Extended Arabic-Indic Digits cannot be mixed")?;
+            Err(Msg(
+                "ARABIC-INDIC DIGITS and Extended Arabic-Indic Digits cannot be mixed",
+            ))?;
         }
     }
 
@@ -569,59 +753,68 @@ fn check_idn_hostname(s: &str) -> Result<(), Box<dyn Error>> {
             let prefix = &s[..i];
             let suffix = &s[i + zero_width_jointer.len_utf8()..];
             if !prefix.ends_with(VIRAMA) {
-                Err("ZERO WIDTH JOINER must be preceded by Virama")?;
+                Err(Msg("ZERO WIDTH JOINER must be preceded by Virama"))?;
             }
             s = suffix;
         }
     }
 
-    check_hostname(&s)
+    check_hostname(&s, opts)
 }
 
 fn validate_email(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
-    check_email(s)
+    check_email(s, &EmailOptions::default(), &HostnameOptions::default())
 }
 
 // see https://en.wikipedia.org/wiki/Email_address
-fn check_email(s: &str) -> Result<(), Box<dyn Error>> {
+fn check_email(
+    s: &str,
+    opts: &EmailOptions,
+    hostname_opts: &HostnameOptions,
+) -> Result<(), Box<dyn Error>> {
     // entire email address to be no more than 254 characters long
-    if s.len() > 254 {
-        Err("more than 254 characters long")?
+    if opts.check_lengths && s.len() > 254 {
+        Err(Msg("more than 254 characters long"))?
     }
 
     // email address is generally recognized as having two parts joined with an at-sign
     let Some(at) = s.rfind('@') else {
-        Err("missing @")?
+        Err(Msg("missing @"))?
     };
     let (local, domain) = (&s[..at], &s[at + 1..]);
 
     // local part may be up to 64 characters long
-    if local.len() > 64 {
-        Err("local part more than 64 characters long")?
+    if opts.check_lengths && local.len() > 64 {
+        Err(Msg("local part more than 64 characters long"))?
     }
 
     if local.len() > 1 && local.starts_with('"') && local.ends_with('"') {
         // quoted
+        if !opts.allow_quoted_local {
+            Err(Msg("quoted local part not allowed"))?
+        }
         let local = &local[1..local.len() - 1];
         if local.contains(|c| matches!(c, '\\' | '"')) {
-            Err("backslash and quote not allowed within quoted local part")?
+            Err(Msg(
+                "backslash and quote not allowed within quoted local part",
+            ))?
         }
     } else {
         // unquoted
 
         if local.starts_with('.') {
-            Err("starts with dot")?
+            Err(Msg("starts with dot"))?
         }
         if local.ends_with('.') {
-            Err("ends with dot")?
+            Err(Msg("ends with dot"))?
         }
 
         // consecutive dots not allowed
         if local.contains("..") {
-            Err("consecutive dots")?
+            Err(Msg("consecutive dots"))?
         }
 
         // check allowd chars
@@ -629,28 +822,31 @@ fn check_email(s: &str) -> Result<(), Box<dyn Error>> {
             .chars()
             .find(|c| !(c.is_ascii_alphanumeric() || ".!#$%&'*+-/=?^_`{|}~".contains(*c)))
         {
-            Err(format!("invalid character {ch:?}"))?
+            Err(InvalidChar(ch))?
         }
     }
 
     // domain if enclosed in brackets, must match an IP address
     if domain.starts_with('[') && domain.ends_with(']') {
+        if !opts.allow_ip_literal {
+            Err(Msg("ip address literal not allowed"))?
+        }
         let s = &domain[1..domain.len() - 1];
         if let Some(s) = s.strip_prefix("IPv6:") {
             if let Err(e) = s.parse::<Ipv6Addr>() {
-                Err(format!("invalid ipv6 address: {e}"))?
+                Err(Invalid("ipv6 address", e.into()))?
             }
             return Ok(());
         }
         if let Err(e) = s.parse::<Ipv4Addr>() {
-            Err(format!("invalid ipv4 address: {e}"))?
+            Err(Invalid("ipv4 address", e.into()))?
         }
         return Ok(());
     }
 
     // domain must match the requirements for a hostname
-    if let Err(e) = check_hostname(domain) {
-        Err(format!("invalid domain: {e}"))?
+    if let Err(e) = check_hostname(domain, hostname_opts) {
+        Err(Invalid("domain", e))?
     }
 
     Ok(())
@@ -660,18 +856,25 @@ fn validate_idn_email(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
+    check_idn_email(s, &EmailOptions::default(), &HostnameOptions::default())
+}
 
+fn check_idn_email(
+    s: &str,
+    opts: &EmailOptions,
+    hostname_opts: &HostnameOptions,
+) -> Result<(), Box<dyn Error>> {
     let Some(at) = s.rfind('@') else {
-        Err("missing @")?
+        Err(Msg("missing @"))?
     };
     let (local, domain) = (&s[..at], &s[at + 1..]);
 
     let local = idna::domain_to_ascii_strict(local)?;
     let domain = idna::domain_to_ascii_strict(domain)?;
-    if let Err(e) = check_idn_hostname(&domain) {
-        Err(format!("invalid domain: {e}"))?
+    if let Err(e) = check_idn_hostname(&domain, hostname_opts) {
+        Err(Invalid("domain", e))?
     }
-    check_email(&format!("{local}@{domain}"))
+    check_email(&format!("{local}@{domain}"), opts, hostname_opts)
 }
 
 fn validate_json_pointer(v: &Value) -> Result<(), Box<dyn Error>> {
@@ -687,17 +890,17 @@ fn check_json_pointer(s: &str) -> Result<(), Box<dyn Error>> {
         return Ok(());
     }
     if !s.starts_with('/') {
-        Err("not starting with slash")?;
+        Err(Msg("not starting with slash"))?;
     }
     for token in s.split('/').skip(1) {
         let mut chars = token.chars();
         while let Some(ch) = chars.next() {
             if ch == '~' {
                 if !matches!(chars.next(), Some('0' | '1')) {
-                    Err("~ must be followed by 0 or 1")?;
+                    Err(Msg("~ must be followed by 0 or 1"))?;
                 }
             } else if !matches!(ch, '\x00'..='\x2E' | '\x30'..='\x7D' | '\x7F'..='\u{10FFFF}') {
-                Err("contains disallowed character")?;
+                Err(Msg("contains disallowed character"))?;
             }
         }
     }
@@ -713,10 +916,10 @@ fn validate_relative_json_pointer(v: &Value) -> Result<(), Box<dyn Error>> {
     // start with non-negative-integer
     let num_digits = s.chars().take_while(char::is_ascii_digit).count();
     if num_digits == 0 {
-        Err("must start with non-negative integer")?;
+        Err(Msg("must start with non-negative integer"))?;
     }
     if num_digits > 1 && s.starts_with('0') {
-        Err("starts with zero")?;
+        Err(Msg("starts with zero"))?;
     }
     let s = &s[num_digits..];
 
@@ -725,7 +928,7 @@ fn validate_relative_json_pointer(v: &Value) -> Result<(), Box<dyn Error>> {
         return Ok(());
     }
     if let Err(e) = check_json_pointer(s) {
-        Err(format!("invalid json-pointer element: {e}"))?;
+        Err(Invalid("json-pointer element", e))?;
     }
     Ok(())
 }
@@ -740,22 +943,18 @@ fn validate_uuid(v: &Value) -> Result<(), Box<dyn Error>> {
     let mut i = 0;
     for group in s.split('-') {
         if i >= HEX_GROUPS.len() {
-            Err("more than 5 elements")?;
+            Err(Msg("more than 5 elements"))?;
         }
         if group.len() != HEX_GROUPS[i] {
-            Err(format!(
-                "element {} must be {} characters long",
-                i + 1,
-                HEX_GROUPS[i]
-            ))?;
+            Err(ElementLen(i + 1, HEX_GROUPS[i]))?;
         }
         if let Some(ch) = group.chars().find(|c| !c.is_ascii_hexdigit()) {
-            Err(format!("non-hex character {ch:?}"))?;
+            Err(NonHexChar(ch))?;
         }
         i += 1;
     }
     if i != HEX_GROUPS.len() {
-        Err("must have 5 elements")?;
+        Err(Msg("must have 5 elements"))?;
     }
     Ok(())
 }
@@ -765,7 +964,7 @@ fn validate_uri(v: &Value) -> Result<(), Box<dyn Error>> {
         return Ok(());
     };
     if fluent_uri::Uri::parse(s)?.is_relative() {
-        Err("relative url")?;
+        Err(Msg("relative url"))?;
     };
     Ok(())
 }
@@ -774,18 +973,17 @@ fn validate_iri(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
-    match Url::parse(s) {
-        Ok(_) => Ok(()),
-        Err(url::ParseError::RelativeUrlWithoutBase) => Err("relative url")?,
-        Err(e) => Err(e)?,
+    if parse_iri_reference(s)? {
+        Err(Msg("relative url"))?;
     }
+    Ok(())
 }
 
 static TEMP_URL: Lazy<Url> = Lazy::new(|| Url::parse("http://temp.com").unwrap());
 
 fn parse_uri_reference(s: &str) -> Result<Url, Box<dyn Error>> {
     if s.contains('\\') {
-        Err("contains \\\\")?;
+        Err(Msg("contains \\\\"))?;
     }
     Ok(TEMP_URL.join(s)?)
 }
@@ -802,10 +1000,71 @@ fn validate_iri_reference(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
     };
-    parse_uri_reference(s)?;
+    parse_iri_reference(s)?;
     Ok(())
 }
 
+// see https://www.rfc-editor.org/rfc/rfc3987#section-2.2
+//
+// IRI grammar is same as URI grammar, except that `ucschar` is allowed
+// wherever `unreserved` is allowed and `iprivate` is allowed in query.
+// so we percent-encode such characters and parse result as uri-reference.
+//
+// returns whether the iri-reference is relative.
+fn parse_iri_reference(s: &str) -> Result<bool, Box<dyn Error>> {
+    if s.is_ascii() {
+        return Ok(fluent_uri::Uri::parse(s)?.is_relative());
+    }
+
+    let frag_start = s.find('#').unwrap_or(s.len());
+    let query_start = s[..frag_start].find('?').unwrap_or(frag_start);
+
+    let mut uri = String::with_capacity(s.len() * 3);
+    for (i, ch) in s.char_indices() {
+        if ch.is_ascii() {
+            uri.push(ch);
+            continue;
+        }
+        let in_query = i > query_start && i < frag_start;
+        if !(is_ucschar(ch) || in_query && is_iprivate(ch)) {
+            Err(InvalidChar(ch))?;
+        }
+        let mut buf = [0; 4];
+        for b in ch.encode_utf8(&mut buf).bytes() {
+            uri.push_str(&format!("%{b:02X}"));
+        }
+    }
+    Ok(fluent_uri::Uri::parse(&uri)?.is_relative())
+}
+
+fn is_ucschar(ch: char) -> bool {
+    matches!(ch,
+        '\u{A0}'..='\u{D7FF}'
+        | '\u{F900}'..='\u{FDCF}'
+        | '\u{FDF0}'..='\u{FFEF}'
+        | '\u{10000}'..='\u{1FFFD}'
+        | '\u{20000}'..='\u{2FFFD}'
+        | '\u{30000}'..='\u{3FFFD}'
+        | '\u{40000}'..='\u{4FFFD}'
+        | '\u{50000}'..='\u{5FFFD}'
+        | '\u{60000}'..='\u{6FFFD}'
+        | '\u{70000}'..='\u{7FFFD}'
+        | '\u{80000}'..='\u{8FFFD}'
+        | '\u{90000}'..='\u{9FFFD}'
+        | '\u{A0000}'..='\u{AFFFD}'
+        | '\u{B0000}'..='\u{BFFFD}'
+        | '\u{C0000}'..='\u{CFFFD}'
+        | '\u{D0000}'..='\u{DFFFD}'
+        | '\u{E1000}'..='\u{EFFFD}'
+    )
+}
+
+fn is_iprivate(ch: char) -> bool {
+    matches!(ch,
+        '\u{E000}'..='\u{F8FF}' | '\u{F0000}'..='\u{FFFFD}' | '\u{100000}'..='\u{10FFFD}'
+    )
+}
+
 fn validate_uri_template(v: &Value) -> Result<(), Box<dyn Error>> {
     let Value::String(s) = v else {
         return Ok(());
@@ -826,12 +1085,12 @@ fn validate_uri_template(v: &Value) -> Result<(), Box<dyn Error>> {
             .map(|c| c == '{')
         {
             if got != want {
-                Err("nested curly braces")?;
+                Err(Msg("nested curly braces"))?;
             }
             want = !want;
         }
         if !want {
-            Err("no matching closing brace")?
+            Err(Msg("no matching closing brace"))?
         }
     }
     Ok(())
@@ -0,0 +1,75 @@
+use std::{ops::Deref, sync::Arc};
+
+use crate::Schemas;
+
+/**
+Immutable collection of compiled schemas.
+
+Once all the schemas are compiled into [`Schemas`], use
+[`Schemas::freeze`] to get this for validation.
+
+It dereferences to [`Schemas`] for validation, but never gives out
+`&mut Schemas`, so no more schemas can be compiled into it. This
+guarantees that [`SchemaIndex`](crate::SchemaIndex)es handed out
+remain valid, and that it can be shared across threads without locks.
+Cloning is cheap, since the schemas are reference counted.
+
+```
+# use std::error::Error;
+# use boon::*;
+# use serde_json::json;
+# fn main() -> Result<(), Box<dyn Error>> {
+let mut schemas = Schemas::new();
+let mut compiler = Compiler::new();
+compiler.add_resource("schema.json", json!({"type": "string"}))?;
+let sch_index = compiler.compile("schema.json", &mut schemas)?;
+let schemas: CompiledSchemas = schemas.freeze();
+
+let handle = {
+    let schemas = schemas.clone();
+    std::thread::spawn(move || schemas.validate(&json!("x"), sch_index).is_ok())
+};
+assert!(handle.join().unwrap());
+# Ok(())
+# }
+```
+
+Compiling into it does not compile:
+
+```compile_fail
+# use boon::*;
+let mut schemas = Schemas::new().freeze();
+let mut compiler = Compiler::new();
+compiler.compile("schema.json", &mut schemas);
+```
+*/
+#[derive(Default, Clone)]
+pub struct CompiledSchemas(Arc<Schemas>);
+
+impl CompiledSchemas {
+    /// Returns the underlying [`Schemas`], if this is the only handle to it.
+    pub fn try_unfreeze(self) -> Result<Schemas, Self> {
+        Arc::try_unwrap(self.0).map_err(Self)
+    }
+}
+
+impl Deref for CompiledSchemas {
+    type Target = Schemas;
+
+    fn deref(&self) -> &Schemas {
+        &self.0
+    }
+}
+
+impl From<Schemas> for CompiledSchemas {
+    fn from(schemas: Schemas) -> Self {
+        Self(Arc::new(schemas))
+    }
+}
+
+impl Schemas {
+    /// Converts into [`CompiledSchemas`], preventing any further compilation.
+    pub fn freeze(self) -> CompiledSchemas {
+        self.into()
+    }
+}
@@ -0,0 +1,492 @@
+use regex_syntax::hir::{Class, Hir, HirKind};
+use serde_json::{Map, Number, Value};
+
+use crate::{ecma, *};
+
+// attempts made, before giving up.
+const ATTEMPTS: usize = 32;
+
+// depth beyond which optional properties and items are not generated.
+const MAX_DEPTH: usize = 8;
+
+// depth beyond which generation gives up, such as for recursive schemas
+// requiring themselves.
+const GIVE_UP_DEPTH: usize = 32;
+
+// values generated in an attempt, before giving up.
+const MAX_VALUES: usize = 10_000;
+
+// longest string or array generated, or object properties. larger minimums
+// are given up.
+const MAX_LEN: usize = 4096;
+
+/**
+Generates an instance valid against schema identified by `sch_index`.
+
+The instance honors `type`, `enum`, `const`, `required`, numeric and
+length bounds, `pattern` where feasible, and `format` of strings. `examples`
+and `default` are used when valid. Different `seed` values generate
+different instances, while the same seed always generates the same
+instance.
+
+Candidates are validated before being returned, so that a constraint not
+understood by the generator, such as `if`/`then`/`else`, results in a
+retry rather than an invalid instance. Returns `None`, if no valid
+instance is found, or if the instance would be too deep or too large,
+for example because a recursive schema requires itself or because of
+huge `minItems`.
+
+```
+# use std::error::Error;
+# use boon::*;
+# use serde_json::json;
+# fn main() -> Result<(), Box<dyn Error>> {
+let mut schemas = Schemas::new();
+let mut compiler = Compiler::new();
+let schema = json!({
+    "type": "object",
+    "required": ["id", "email"],
+    "properties": {
+        "id": {"type": "integer", "minimum": 1},
+        "email": {"type": "string", "format": "email"},
+        "sku": {"type": "string", "pattern": "^[A-Z]{3}-[0-9]{4}$"}
+    }
+});
+compiler.add_resource("item.json", schema)?;
+let sch_index = compiler.compile("item.json", &mut schemas)?;
+
+let instance = generate_instance(&schemas, sch_index, 42).unwrap();
+assert!(schemas.validate(&instance, sch_index).is_ok());
+# Ok(())
+# }
+```
+
+# Panics
+
+Panics if `sch_index` is not generated for `schemas`.
+*/
+pub fn generate_instance(schemas: &Schemas, sch_index: SchemaIndex, seed: u64) -> Option<Value> {
+    let mut generator = Generator {
+        schemas,
+        rng: Rng(seed ^ 0x9E37_79B9_7F4A_7C15),
+        budget: 0,
+    };
+    for attempt in 0..ATTEMPTS {
+        generator.budget = MAX_VALUES;
+        // first attempt prefers examples and defaults
+        let Some(v) = generator.generate(sch_index, 0, attempt == 0) else {
+            continue;
+        };
+        if schemas.validate(&v, sch_index).is_ok() {
+            return Some(v);
+        }
+    }
+    None
+}
+
+// xorshift64*
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        if self.0 == 0 {
+            self.0 = 0x2545_F491_4F6C_DD1D;
+        }
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    // returns random number in range [0, n).
+    fn below(&mut self, n: usize) -> usize {
+        if n == 0 {
+            return 0;
+        }
+        (self.next() % n as u64) as usize
+    }
+
+    fn bool(&mut self) -> bool {
+        self.next() & 1 == 1
+    }
+}
+
+struct Generator<'s> {
+    schemas: &'s Schemas,
+    rng: Rng,
+    budget: usize, // values left to generate in this attempt
+}
+
+impl Generator<'_> {
+    // returns None, if instance is too deep or too large.
+    fn generate(&mut self, sch: SchemaIndex, depth: usize, prefer_examples: bool) -> Option<Value> {
+        if depth > GIVE_UP_DEPTH || self.budget == 0 {
+            return None;
+        }
+        self.budget -= 1;
+        // schemas to be satisfied, with $ref, allOf and a branch of anyOf/oneOf flattened
+        let mut list = vec![];
+        self.collect(sch, &mut list);
+        let schemas = list
+            .iter()
+            .map(|&sch| self.schemas.get(sch))
+            .collect::<Vec<_>>();
+
+        if schemas.iter().any(|s| s.boolean == Some(false)) {
+            return Some(Value::Null);
+        }
+        if let Some(c) = schemas.iter().find_map(|s| s.constant.as_ref()) {
+            return Some(c.clone());
+        }
+        if let Some(e) = schemas.iter().find_map(|s| s.enum_.as_ref()) {
+            return Some(e.values[self.rng.below(e.values.len())].clone());
+        }
+        if prefer_examples {
+            let candidates = schemas
+                .iter()
+                .flat_map(|s| s.examples.iter().chain(&s.default))
+                .find(|v| {
+                    list.iter()
+                        .all(|&sch| self.schemas.validate(v, sch).is_ok())
+                });
+            if let Some(v) = candidates {
+                return Some(v.clone());
+            }
+        }
+
+        let v = match self.pick_type(&schemas)? {
+            Type::Null => Value::Null,
+            Type::Boolean => Value::Bool(self.rng.bool()),
+            Type::Integer => self.gen_number(&schemas, true),
+            Type::Number => self.gen_number(&schemas, false),
+            Type::String => Value::String(self.gen_string(&schemas)?),
+            Type::Array => self.gen_array(&schemas, depth, prefer_examples)?,
+            Type::Object => self.gen_object(&schemas, depth, prefer_examples)?,
+        };
+        Some(v)
+    }
+
+    fn collect(&mut self, sch: SchemaIndex, list: &mut Vec<SchemaIndex>) {
+        if list.contains(&sch) {
+            return;
+        }
+        list.push(sch);
+        let s = self.schemas.get(sch);
+        let mut next = vec![];
+        let all_of = self.schemas.indexes(s.all_of);
+        next.extend(s.ref_.iter().chain(&s.recursive_ref).chain(all_of));
+        next.extend(s.dynamic_ref.iter().map(|dref| dref.sch));
+        for group in [s.any_of, s.one_of] {
+            let group = self.schemas.indexes(group);
+            if !group.is_empty() {
+                next.push(group[self.rng.below(group.len())]);
+            }
+        }
+        for sch in next {
+            self.collect(sch, list);
+        }
+    }
+
+    // returns None, if no type is allowed by all `schemas`.
+    fn pick_type(&mut self, schemas: &[&Schema]) -> Option<Type> {
+        let mut types = None::<Types>;
+        for s in schemas {
+            if !s.types.is_empty() {
+                types = Some(match types {
+                    Some(t) => t.intersect(s.types),
+                    None => s.types,
+                });
+            }
+        }
+        if let Some(types) = types {
+            let mut types = types.iter().collect::<Vec<_>>();
+            if types.len() > 1 {
+                types.retain(|t| *t != Type::Null);
+            }
+            if types.is_empty() {
+                return None;
+            }
+            return Some(types[self.rng.below(types.len())]);
+        }
+
+        // infer from keywords used
+        let has = |f: fn(&Schema) -> bool| schemas.iter().any(|s| f(s));
+        let t = if has(|s| !s.properties.is_empty() || !s.required.is_empty()) {
+            Type::Object
+        } else if has(|s| s.items.is_some() || s.items2020.is_some() || !s.prefix_items.is_empty())
+        {
+            Type::Array
+        } else if has(|s| s.pattern.is_some() || s.format_name.is_some() || s.min_length.is_some())
+        {
+            Type::String
+        } else if has(|s| s.minimum.is_some() || s.maximum.is_some() || s.multiple_of.is_some()) {
+            Type::Number
+        } else {
+            [Type::String, Type::Integer, Type::Boolean][self.rng.below(3)]
+        };
+        Some(t)
+    }
+
+    fn gen_number(&mut self, schemas: &[&Schema], integer: bool) -> Value {
+        let (mut lo, mut hi) = (f64::MIN, f64::MAX);
+        let mut multiple_of = None;
+        for s in schemas {
+            let f = |n: &Option<Number>| n.as_ref().and_then(Number::as_f64);
+            if let Some(n) = f(&s.minimum) {
+                lo = lo.max(n);
+            }
+            if let Some(n) = f(&s.exclusive_minimum) {
+                lo = lo.max(if integer { n.floor() + 1.0 } else { n + 1e-6 });
+            }
+            if let Some(n) = f(&s.maximum) {
+                hi = hi.min(n);
+            }
+            if let Some(n) = f(&s.exclusive_maximum) {
+                hi = hi.min(if integer { n.ceil() - 1.0 } else { n - 1e-6 });
+            }
+            multiple_of = multiple_of.or(f(&s.multiple_of));
+        }
+        if integer {
+            (lo, hi) = (lo.ceil(), hi.floor());
+        }
+        let mut n = match (lo > f64::MIN, hi < f64::MAX) {
+            (true, true) => lo + ((hi - lo) * (self.rng.below(101) as f64 / 100.0)),
+            (true, false) => lo + self.rng.below(100) as f64,
+            (false, true) => hi - self.rng.below(100) as f64,
+            (false, false) => self.rng.below(100) as f64,
+        };
+        if let Some(m) = multiple_of.filter(|m| *m > 0.0) {
+            n = (n / m).ceil() * m;
+            if n > hi {
+                n -= m;
+            }
+        }
+        if integer {
+            n = n.round();
+        }
+        match integer || n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+            true => Value::from(n as i64),
+            false => Number::from_f64(n).map_or(Value::Null, Value::Number),
+        }
+    }
+
+    fn gen_string(&mut self, schemas: &[&Schema]) -> Option<String> {
+        let pattern = schemas.iter().find_map(|s| s.pattern.as_ref());
+        if let Some(s) = pattern.and_then(|re| self.gen_regex(re.as_str())) {
+            return Some(s);
+        }
+        if let Some(s) = schemas
+            .iter()
+            .find_map(|s| s.format_name.as_deref().and_then(sample_format))
+        {
+            return Some(s.to_owned());
+        }
+        let min = schemas
+            .iter()
+            .filter_map(|s| s.min_length)
+            .max()
+            .unwrap_or(0);
+        if min > MAX_LEN {
+            return None;
+        }
+        let max = schemas
+            .iter()
+            .filter_map(|s| s.max_length)
+            .min()
+            .unwrap_or(min.max(8));
+        let len = if max > min {
+            min + self.rng.below(max - min + 1)
+        } else {
+            min
+        };
+        let len = len.min(MAX_LEN);
+        let s = (0..len)
+            .map(|_| (b'a' + self.rng.below(26) as u8) as char)
+            .collect();
+        Some(s)
+    }
+
+    fn gen_regex(&mut self, pattern: &str) -> Option<String> {
+        let pattern = ecma::convert(pattern).ok()?;
+        let hir = regex_syntax::Parser::new().parse(&pattern).ok()?;
+        let mut s = String::new();
+        self.gen_hir(&hir, &mut s).then_some(s)
+    }
+
+    // returns false, if `hir` is not supported.
+    fn gen_hir(&mut self, hir: &Hir, s: &mut String) -> bool {
+        match hir.kind() {
+            HirKind::Empty | HirKind::Look(_) => true,
+            HirKind::Literal(lit) => match std::str::from_utf8(&lit.0) {
+                Ok(lit) => {
+                    s.push_str(lit);
+                    true
+                }
+                Err(_) => false,
+            },
+            HirKind::Class(Class::Unicode(class)) => {
+                let ranges = class.ranges();
+                if ranges.is_empty() {
+                    return false;
+                }
+                // prefer printable ascii
+                let ascii = ranges
+                    .iter()
+                    .filter(|r| r.start() <= '~' && r.end() >= ' ')
+                    .collect::<Vec<_>>();
+                let (start, end) = match ascii.is_empty() {
+                    true => (ranges[0].start(), ranges[0].end()),
+                    false => {
+                        let r = ascii[self.rng.below(ascii.len())];
+                        (r.start().max(' '), r.end().min('~'))
+                    }
+                };
+                let n = (end as u32 - start as u32) as usize + 1;
+                let c = char::from_u32(start as u32 + self.rng.below(n) as u32);
+                s.extend(c.or(Some(start)));
+                true
+            }
+            HirKind::Class(Class::Bytes(_)) => false,
+            HirKind::Repetition(rep) => {
+                if rep.min as usize > MAX_LEN {
+                    return false;
+                }
+                let max = rep.max.unwrap_or(rep.min + 3).min(rep.min + 3);
+                let n = rep.min as usize + self.rng.below((max - rep.min) as usize + 1);
+                (0..n).all(|_| self.gen_hir(&rep.sub, s))
+            }
+            HirKind::Capture(cap) => self.gen_hir(&cap.sub, s),
+            HirKind::Concat(list) => list.iter().all(|hir| self.gen_hir(hir, s)),
+            HirKind::Alternation(list) => {
+                let i = self.rng.below(list.len());
+                self.gen_hir(&list[i], s)
+            }
+        }
+    }
+
+    fn gen_array(
+        &mut self,
+        schemas: &[&Schema],
+        depth: usize,
+        prefer_examples: bool,
+    ) -> Option<Value> {
+        let min = schemas
+            .iter()
+            .filter_map(|s| s.min_items)
+            .max()
+            .unwrap_or(0);
+        if min > MAX_LEN {
+            return None;
+        }
+        let max = schemas.iter().filter_map(|s| s.max_items).min();
+        let mut len = match depth < MAX_DEPTH {
+            true => min.max(1 + self.rng.below(2)),
+            false => min,
+        };
+        if let Some(max) = max {
+            len = len.min(max);
+        }
+        let mut arr = vec![];
+        for i in 0..len {
+            let item = schemas
+                .iter()
+                .find_map(|s| defaults::item_schema(self.schemas, s, i));
+            let item = match item {
+                Some(sch) => self.generate(sch, depth + 1, prefer_examples)?,
+                None => Value::from(i),
+            };
+            arr.push(item);
+        }
+        Some(Value::Array(arr))
+    }
+
+    fn gen_object(
+        &mut self,
+        schemas: &[&Schema],
+        depth: usize,
+        prefer_examples: bool,
+    ) -> Option<Value> {
+        let mut names = vec![];
+        for s in schemas {
+            for pname in &s.required {
+                if !names.contains(pname) {
+                    names.push(pname.clone());
+                }
+            }
+        }
+        let min = schemas
+            .iter()
+            .filter_map(|s| s.min_properties)
+            .max()
+            .unwrap_or(0);
+        if min > MAX_LEN {
+            return None;
+        }
+        let max = schemas.iter().filter_map(|s| s.max_properties).min();
+        if depth < MAX_DEPTH {
+            let mut optional = schemas
+                .iter()
+                .flat_map(|s| s.properties.keys())
+                .filter(|pname| !names.contains(pname))
+                .cloned()
+                .collect::<Vec<_>>();
+            optional.sort();
+            optional.dedup();
+            for pname in optional {
+                if names.len() < min || self.rng.bool() {
+                    names.push(pname);
+                }
+            }
+        }
+        if let Some(max) = max {
+            names.truncate(max);
+        }
+        let mut i = 0;
+        while names.len() < min {
+            let pname = Name::from(format!("key{i}"));
+            if !names.contains(&pname) {
+                names.push(pname);
+            }
+            i += 1;
+        }
+
+        let mut obj = Map::new();
+        for pname in names {
+            let psch = schemas
+                .iter()
+                .flat_map(|s| defaults::prop_schemas(s, &pname))
+                .next();
+            let pvalue = match psch {
+                Some(sch) => self.generate(sch, depth + 1, prefer_examples)?,
+                None => Value::Null,
+            };
+            obj.insert(pname.to_string(), pvalue);
+        }
+        Some(Value::Object(obj))
+    }
+}
+
+fn sample_format(format: &str) -> Option<&'static str> {
+    let s = match format {
+        "date-time" => "2024-01-01T00:00:00Z",
+        "date" => "2024-01-01",
+        "time" => "00:00:00Z",
+        "duration" => "P1D",
+        "period" => "2024-01-01T00:00:00Z/P1D",
+        "email" | "idn-email" => "user@example.com",
+        "hostname" | "idn-hostname" => "example.com",
+        "ipv4" => "192.0.2.1",
+        "ipv6" => "2001:db8::1",
+        "uri" | "iri" | "url" => "https://example.com/",
+        "uri-reference" | "iri-reference" => "/path",
+        "uri-template" => "https://example.com/{id}",
+        "uuid" => "123e4567-e89b-12d3-a456-426614174000",
+        "json-pointer" => "/path",
+        "relative-json-pointer" => "0",
+        "regex" => ".*",
+        "semver" => "1.0.0",
+        "byte" => "aGVsbG8=",
+        _ => return None,
+    };
+    Some(s)
+}
@@ -0,0 +1,297 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde_json::Value;
+
+use crate::{export::*, util::*, Additional, Items, Schema, SchemaIndex, Schemas, Type};
+
+/// Result of [`export_graphql`].
+#[derive(Debug, Clone)]
+pub struct GraphQLExport {
+    /// GraphQL schema definition language.
+    pub sdl: String,
+    /// constraints not representable in GraphQL.
+    pub unrepresentable: Vec<Unrepresentable>,
+}
+
+/**
+Exports compiled object schemas as GraphQL types.
+
+`types` maps GraphQL type name to the schema it is derived from. Nested
+object schemas and referenced schemas are exported as separate types,
+named after their property or location. Constraints which cannot be
+expressed in GraphQL (for example `pattern`, `minimum` or `if`) are
+dropped and reported in [`GraphQLExport::unrepresentable`]. Values which
+cannot be typed are exported using `JSON` custom scalar.
+
+Note that only output types are generated.
+
+# Panics
+
+Panics if any of the schema index is not generated for `schemas`.
+*/
+pub fn export_graphql(schemas: &Schemas, types: &[(&str, SchemaIndex)]) -> GraphQLExport {
+    let mut exp = Exporter {
+        schemas,
+        names: HashMap::new(),
+        used: HashSet::new(),
+        objects: HashSet::new(),
+        queue: VecDeque::new(),
+        active: HashSet::new(),
+        reported: HashSet::new(),
+        defs: vec![],
+        json: false,
+        unrepresentable: vec![],
+    };
+    for &(name, idx) in types {
+        let sch = schemas.get(idx);
+        if !is_object(sch) {
+            exp.report(sch, "type", "root schema must be object");
+            continue;
+        }
+        exp.object_name(idx, name);
+    }
+    while let Some(idx) = exp.queue.pop_front() {
+        exp.object_type(idx);
+    }
+    let mut defs = vec![];
+    if exp.json {
+        defs.push("scalar JSON".to_owned());
+    }
+    defs.append(&mut exp.defs);
+    GraphQLExport {
+        sdl: join_iter(defs, "\n\n"),
+        unrepresentable: exp.unrepresentable,
+    }
+}
+
+struct Exporter<'a> {
+    schemas: &'a Schemas,
+    names: HashMap<SchemaIndex, String>, // object/enum/union types
+    used: HashSet<String>,
+    objects: HashSet<String>,
+    queue: VecDeque<SchemaIndex>,
+    active: HashSet<SchemaIndex>, // being exported by type_ref
+    reported: HashSet<SchemaIndex>,
+    defs: Vec<String>,
+    json: bool,
+    unrepresentable: Vec<Unrepresentable>,
+}
+
+impl<'a> Exporter<'a> {
+    fn report(&mut self, sch: &Schema, keyword: &'static str, reason: impl Into<String>) {
+        self.unrepresentable.push(Unrepresentable {
+            schema_url: sch.loc.clone(),
+            keyword,
+            reason: reason.into(),
+        });
+    }
+
+    fn json(&mut self) -> (String, bool) {
+        self.json = true;
+        ("JSON".to_owned(), true)
+    }
+
+    fn unique_name(&mut self, hint: &str) -> String {
+        let base = type_name(hint);
+        let mut name = base.clone();
+        let mut i = 2;
+        while self.used.contains(&name) || name == "JSON" {
+            name = format!("{base}{i}");
+            i += 1;
+        }
+        self.used.insert(name.clone());
+        name
+    }
+
+    fn object_name(&mut self, idx: SchemaIndex, hint: &str) -> String {
+        if let Some(name) = self.names.get(&idx) {
+            return name.clone();
+        }
+        let name = self.unique_name(hint);
+        self.names.insert(idx, name.clone());
+        self.objects.insert(name.clone());
+        self.queue.push_back(idx);
+        name
+    }
+
+    fn object_type(&mut self, idx: SchemaIndex) {
+        let sch = self.schemas.get(idx);
+        let name = self.names[&idx].clone();
+        let mut props = sch.properties.iter().collect::<Vec<_>>();
+        props.sort_by(|a, b| a.0.cmp(b.0));
+        let fnames = field_names(&props.iter().map(|p| p.0.as_ref()).collect::<Vec<_>>());
+        let mut fields = vec![];
+        for ((pname, &pidx), fname) in props.into_iter().zip(fnames) {
+            if *fname != **pname {
+                self.report(
+                    self.schemas.get(pidx),
+                    "properties",
+                    format!("property {} renamed to {fname}", quote(pname)),
+                );
+            }
+            let (ty, nullable) = self.type_ref(pidx, &format!("{name}_{fname}"));
+            let bang = if sch.required.contains(pname) && !nullable {
+                "!"
+            } else {
+                ""
+            };
+            fields.push(format!("  {fname}: {ty}{bang}"));
+        }
+        let def = format!("type {name} {{\n{}\n}}", join_iter(fields, "\n"));
+        self.defs.push(def);
+    }
+
+    // returns type without non-null marker, and whether it is nullable.
+    fn type_ref(&mut self, idx: SchemaIndex, hint: &str) -> (String, bool) {
+        if !self.active.insert(idx) {
+            let sch = self.schemas.get(idx);
+            self.report(sch, "$ref", "cyclic reference");
+            return self.json();
+        }
+        let ty = self.schema_type(idx, hint);
+        self.active.remove(&idx);
+        ty
+    }
+
+    fn schema_type(&mut self, idx: SchemaIndex, hint: &str) -> (String, bool) {
+        let sch = self.schemas.get(idx);
+        self.check_constraints(sch);
+        if let Some(b) = sch.boolean {
+            if !b {
+                self.report(sch, "", "false schema");
+            }
+            return self.json();
+        }
+        if let Some(ref_) = sch.ref_ {
+            if sch.types.is_empty() {
+                return self.type_ref(ref_, &ref_hint(self.schemas.get(ref_)));
+            }
+            self.report(sch, "$ref", "$ref with sibling type");
+        }
+
+        let mut types = sch.types;
+        if types.is_empty() {
+            if let Some(e) = &sch.enum_ {
+                types = e.types;
+            } else if !sch.properties.is_empty() {
+                types.add(Type::Object);
+            }
+        }
+        let nullable = types.is_empty() || types.contains(Type::Null);
+        let mut non_null = types
+            .iter()
+            .filter(|&t| t != Type::Null)
+            .collect::<Vec<_>>();
+        if non_null == [Type::Number, Type::Integer] {
+            non_null = vec![Type::Number];
+        }
+        let ty = match non_null.as_slice() {
+            [] => {
+                if !types.is_empty() {
+                    self.report(sch, "type", "null type");
+                    return self.json();
+                }
+                return self.union_type(idx, hint);
+            }
+            [Type::Boolean] => "Boolean".to_owned(),
+            [Type::Integer] => "Int".to_owned(),
+            [Type::Number] => "Float".to_owned(),
+            [Type::String] => self.enum_type(idx, hint),
+            [Type::Array] => {
+                let items = match &sch.items {
+                    Some(Items::SchemaRef(items)) => Some(*items),
+                    _ => sch.items2020,
+                };
+                match items {
+                    Some(items) => {
+                        let (ty, nullable) = self.type_ref(items, &format!("{hint}_item"));
+                        let bang = if nullable { "" } else { "!" };
+                        format!("[{ty}{bang}]")
+                    }
+                    None => {
+                        self.json = true;
+                        "[JSON]".to_owned()
+                    }
+                }
+            }
+            [Type::Object] => {
+                if sch.properties.is_empty() {
+                    if let Some(Additional::SchemaRef(_)) = sch.additional_properties {
+                        self.report(sch, "additionalProperties", "map types");
+                    }
+                    return (self.json().0, nullable);
+                }
+                self.object_name(idx, hint)
+            }
+            _ => {
+                self.report(sch, "type", "multiple types");
+                return self.json();
+            }
+        };
+        (ty, nullable)
+    }
+
+    fn enum_type(&mut self, idx: SchemaIndex, hint: &str) -> String {
+        let sch = self.schemas.get(idx);
+        let Some(e) = &sch.enum_ else {
+            return "String".to_owned();
+        };
+        let valid = e.values.iter().all(|v| match v {
+            Value::String(s) => is_name(s) && !matches!(s.as_str(), "true" | "false" | "null"),
+            _ => false,
+        });
+        if !valid {
+            self.report(sch, "enum", "enum values are not valid GraphQL names");
+            return "String".to_owned();
+        }
+        if let Some(name) = self.names.get(&idx) {
+            return name.clone();
+        }
+        let name = self.unique_name(hint);
+        self.names.insert(idx, name.clone());
+        let values = e.values.iter().filter_map(|v| v.as_str());
+        let values = join_iter(values.map(|v| format!("  {v}")), "\n");
+        self.defs.push(format!("enum {name} {{\n{values}\n}}"));
+        name
+    }
+
+    fn union_type(&mut self, idx: SchemaIndex, hint: &str) -> (String, bool) {
+        let sch = self.schemas.get(idx);
+        let (kw, members) = if !sch.one_of.is_empty() {
+            ("oneOf", self.schemas.indexes(sch.one_of))
+        } else if !sch.any_of.is_empty() {
+            ("anyOf", self.schemas.indexes(sch.any_of))
+        } else {
+            return self.json();
+        };
+        if let Some(name) = self.names.get(&idx) {
+            return (name.clone(), false);
+        }
+        let mut types = vec![];
+        for (i, &m) in members.iter().enumerate() {
+            let (ty, _) = self.type_ref(m, &format!("{hint}_{i}"));
+            if !self.objects.contains(&ty) {
+                self.report(sch, kw, "union members must be object types");
+                return self.json();
+            }
+            types.push(ty);
+        }
+        if kw == "anyOf" {
+            self.report(sch, kw, "anyOf exported as union");
+        }
+        let name = self.unique_name(hint);
+        self.names.insert(idx, name.clone());
+        self.defs
+            .push(format!("union {name} = {}", join_iter(types, " | ")));
+        (name, false)
+    }
+
+    fn check_constraints(&mut self, sch: &Schema) {
+        if !self.reported.insert(sch.idx) {
+            return;
+        }
+        for kw in constraints(sch) {
+            self.report(sch, kw, "no GraphQL equivalent");
+        }
+    }
+}
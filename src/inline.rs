@@ -0,0 +1,149 @@
+use crate::{util::*, Schema, Schemas};
+
+// inlines `$ref` of schemas from `start`, whose target is a leaf schema
+// in the same document, by copying the assertions of target.
+pub(crate) fn inline_refs(schemas: &mut Schemas, start: usize) {
+    // targets are usually enqueued after the schema referring them, so
+    // that reverse order inlines chains of refs too.
+    for i in (start..schemas.list.len()).rev() {
+        let s = &schemas.list[i];
+        let Some(target) = s.ref_ else {
+            continue;
+        };
+        let t = schemas.get(target);
+        if !is_leaf(t, schemas) || has_assertions(s) || split(&t.loc).0 != split(&s.loc).0 {
+            continue;
+        }
+        let t = Assertions::of(t);
+        let s = &mut schemas.list[i];
+        t.copy_to(s);
+        s.ref_ = None;
+    }
+}
+
+// tells whether `s` has only assertions copied by `Assertions`, which
+// depend neither on other schemas nor on dynamic scope. annotations
+// are not copied, so schemas with them are not leaf.
+fn is_leaf(s: &Schema, schemas: &Schemas) -> bool {
+    !s.lazy
+        && s.boolean != Some(false)
+        && s.ref_.is_none()
+        && s.recursive_ref.is_none()
+        && s.dynamic_ref.is_none()
+        && s.children(schemas).is_empty()
+        && s.title.is_none()
+        && s.description.is_none()
+        && s.default.is_none()
+        && s.examples.is_empty()
+        && s.unknown_keywords.is_empty()
+        && s.keywords.is_empty()
+        && !s.deprecated
+        && !s.read_only
+        && !s.write_only
+        && s.bson_types.is_empty()
+        && s.dependent_required.is_empty()
+        && s.dependencies.is_empty()
+        && s.content_encoding.is_none()
+        && s.content_media_type.is_none()
+        && s.data.is_empty()
+}
+
+fn has_assertions(s: &Schema) -> bool {
+    s.boolean.is_some()
+        || !s.types.is_empty()
+        || s.enum_.is_some()
+        || s.constant.is_some()
+        || s.format_name.is_some()
+        || s.min_properties.is_some()
+        || s.max_properties.is_some()
+        || !s.required.is_empty()
+        || s.min_items.is_some()
+        || s.max_items.is_some()
+        || s.unique_items
+        || s.min_length.is_some()
+        || s.max_length.is_some()
+        || s.pattern.is_some()
+        || s.minimum.is_some()
+        || s.maximum.is_some()
+        || s.exclusive_minimum.is_some()
+        || s.exclusive_maximum.is_some()
+        || s.multiple_of.is_some()
+}
+
+// Assertions --
+
+struct Assertions {
+    types: crate::Types,
+    enum_: Option<crate::Enum>,
+    constant: Option<serde_json::Value>,
+    format: Option<crate::formats::CompiledFormat>,
+    format_assert: bool,
+    format_name: Option<String>,
+    min_properties: Option<usize>,
+    max_properties: Option<usize>,
+    required: Vec<crate::Name>,
+    required_bits: Option<FastHashMap<crate::Name, usize>>,
+    min_items: Option<usize>,
+    max_items: Option<usize>,
+    unique_items: bool,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    pattern: Option<crate::regexp::Regex>,
+    minimum: Option<serde_json::Number>,
+    maximum: Option<serde_json::Number>,
+    exclusive_minimum: Option<serde_json::Number>,
+    exclusive_maximum: Option<serde_json::Number>,
+    multiple_of: Option<serde_json::Number>,
+}
+
+impl Assertions {
+    fn of(s: &Schema) -> Self {
+        Self {
+            types: s.types,
+            enum_: s.enum_.clone(),
+            constant: s.constant.clone(),
+            format: s.format.clone(),
+            format_assert: s.format_assert,
+            format_name: s.format_name.clone(),
+            min_properties: s.min_properties,
+            max_properties: s.max_properties,
+            required: s.required.clone(),
+            required_bits: s.required_bits.clone(),
+            min_items: s.min_items,
+            max_items: s.max_items,
+            unique_items: s.unique_items,
+            min_length: s.min_length,
+            max_length: s.max_length,
+            pattern: s.pattern.clone(),
+            minimum: s.minimum.clone(),
+            maximum: s.maximum.clone(),
+            exclusive_minimum: s.exclusive_minimum.clone(),
+            exclusive_maximum: s.exclusive_maximum.clone(),
+            multiple_of: s.multiple_of.clone(),
+        }
+    }
+
+    fn copy_to(self, s: &mut Schema) {
+        s.types = self.types;
+        s.enum_ = self.enum_;
+        s.constant = self.constant;
+        s.format = self.format;
+        s.format_assert = self.format_assert;
+        s.format_name = self.format_name;
+        s.min_properties = self.min_properties;
+        s.max_properties = self.max_properties;
+        s.required = self.required;
+        s.required_bits = self.required_bits;
+        s.min_items = self.min_items;
+        s.max_items = self.max_items;
+        s.unique_items = self.unique_items;
+        s.min_length = self.min_length;
+        s.max_length = self.max_length;
+        s.pattern = self.pattern;
+        s.minimum = self.minimum;
+        s.maximum = self.maximum;
+        s.exclusive_minimum = self.exclusive_minimum;
+        s.exclusive_maximum = self.exclusive_maximum;
+        s.multiple_of = self.multiple_of;
+    }
+}
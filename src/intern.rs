@@ -0,0 +1,44 @@
+use std::{mem::size_of, sync::Arc};
+
+use crate::util::FastHashSet;
+
+/// string shared by all schemas using it, see [`Interner`].
+pub(crate) type Name = Arc<str>;
+
+/// set of interned strings, such as property names, which tend to repeat
+/// across thousands of schemas.
+#[derive(Debug, Default)]
+pub(crate) struct Interner(FastHashSet<Name>);
+
+impl Interner {
+    pub(crate) fn get(&self, s: &str) -> Option<&Name> {
+        self.0.get(s)
+    }
+
+    pub(crate) fn intern(&mut self, s: &str) -> Name {
+        if let Some(name) = self.0.get(s) {
+            return Arc::clone(name);
+        }
+        let name = Name::from(s);
+        self.0.insert(Arc::clone(&name));
+        name
+    }
+
+    pub(crate) fn extend(&mut self, other: Interner) {
+        self.0.extend(other.0);
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Name> {
+        self.0.iter()
+    }
+
+    // approximate heap used, including the strings.
+    pub(crate) fn heap_size(&self) -> usize {
+        let strings = self.0.iter().map(|s| s.len() + 2 * size_of::<usize>());
+        self.0.capacity() * size_of::<Name>() + strings.sum::<usize>()
+    }
+}
@@ -0,0 +1,386 @@
+use std::{error::Error, str::Chars};
+
+use serde_json::{Map, Number, Value};
+
+use crate::{compiler::CompileError, util::*, Compiler};
+
+impl Compiler {
+    /**
+    Adds schema resource `loc`, parsing `text` as [JSON5](https://json5.org).
+
+    JSON5 allows comments, trailing commas, unquoted keys, single-quoted
+    strings and hexadecimal numbers, which is convenient for hand-written
+    schemas. Files with `.json5` extension are parsed as JSON5 too, when
+    loaded from file system.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource_json5("schema.json", "{
+        // port to listen on
+        type: 'integer',
+        maximum: 0xFFFF,
+    }")?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+    assert!(schemas.validate(&json!(80), sch_index).is_ok());
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    returns [`CompileError`] if url parsing failed, or `text` is not
+    valid JSON5.
+    */
+    pub fn add_resource_json5(&mut self, loc: &str, text: &str) -> Result<(), CompileError> {
+        let uf = UrlFrag::absolute(loc)?;
+        let json = parse(text).map_err(|src| CompileError::LoadUrlError {
+            url: uf.url.to_string(),
+            src,
+        })?;
+        self.roots.loader.add_doc(uf.url, json);
+        Ok(())
+    }
+}
+
+/// parses JSON5 `text` into json value.
+pub(crate) fn parse(text: &str) -> Result<Value, Box<dyn Error>> {
+    let mut p = Parser {
+        chars: text.chars(),
+        line: 1,
+        col: 0,
+        depth: 0,
+    };
+    let v = p.value()?;
+    p.skip_ws()?;
+    if p.peek().is_some() {
+        p.err("unexpected character after value")?;
+    }
+    Ok(v)
+}
+
+// nesting of arrays and objects allowed, same as serde_json.
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'a> {
+    chars: Chars<'a>,
+    line: usize,
+    col: usize,
+    depth: usize, // arrays and objects being parsed
+}
+
+impl Parser<'_> {
+    fn err<T>(&self, msg: &str) -> Result<T, Box<dyn Error>> {
+        Err(format!(
+            "{msg} at line {} column {}",
+            self.line,
+            self.col + 1
+        ))?
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.clone().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.chars.next()?;
+        if ch == '\n' {
+            self.line += 1;
+            self.col = 0;
+        } else {
+            self.col += 1;
+        }
+        Some(ch)
+    }
+
+    fn expect(&mut self, want: char) -> Result<(), Box<dyn Error>> {
+        match self.next() {
+            Some(ch) if ch == want => Ok(()),
+            _ => self.err(&format!("expected {want:?}")),
+        }
+    }
+
+    // skips whitespace and comments.
+    fn skip_ws(&mut self) -> Result<(), Box<dyn Error>> {
+        loop {
+            match self.peek() {
+                Some(ch) if ch.is_whitespace() || ch == '\u{FEFF}' => {
+                    self.next();
+                }
+                Some('/') => {
+                    self.next();
+                    match self.next() {
+                        Some('/') => while self.next().is_some_and(|ch| ch != '\n') {},
+                        Some('*') => loop {
+                            match self.next() {
+                                Some('*') if self.peek() == Some('/') => {
+                                    self.next();
+                                    break;
+                                }
+                                Some(_) => {}
+                                None => return self.err("unterminated comment"),
+                            }
+                        },
+                        _ => return self.err("invalid comment"),
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, Box<dyn Error>> {
+        self.skip_ws()?;
+        match self.peek() {
+            Some('{') => self.nested(Self::object),
+            Some('[') => self.nested(Self::array),
+            Some(quote @ ('"' | '\'')) => {
+                self.next();
+                Ok(Value::String(self.string(quote)?))
+            }
+            Some(ch) if ch.is_ascii_digit() || "+-.".contains(ch) => self.number(),
+            Some(ch) if is_ident_start(ch) => match self.ident()?.as_str() {
+                "null" => Ok(Value::Null),
+                "true" => Ok(Value::Bool(true)),
+                "false" => Ok(Value::Bool(false)),
+                "Infinity" | "NaN" => self.err("Infinity and NaN are not supported in json"),
+                _ => self.err("unexpected identifier"),
+            },
+            Some(_) => self.err("unexpected character"),
+            None => self.err("unexpected end of input"),
+        }
+    }
+
+    fn nested(
+        &mut self,
+        f: fn(&mut Self) -> Result<Value, Box<dyn Error>>,
+    ) -> Result<Value, Box<dyn Error>> {
+        if self.depth == MAX_DEPTH {
+            return self.err("recursion limit exceeded");
+        }
+        self.depth += 1;
+        let v = f(self);
+        self.depth -= 1;
+        v
+    }
+
+    fn object(&mut self) -> Result<Value, Box<dyn Error>> {
+        self.expect('{')?;
+        let mut obj = Map::new();
+        loop {
+            self.skip_ws()?;
+            let key = match self.peek() {
+                Some('}') => {
+                    self.next();
+                    return Ok(Value::Object(obj));
+                }
+                Some(quote @ ('"' | '\'')) => {
+                    self.next();
+                    self.string(quote)?
+                }
+                Some(ch) if is_ident_start(ch) => self.ident()?,
+                _ => return self.err("expected property name"),
+            };
+            self.skip_ws()?;
+            self.expect(':')?;
+            let value = self.value()?;
+            obj.insert(key, value);
+            self.skip_ws()?;
+            match self.next() {
+                Some(',') => {}
+                Some('}') => return Ok(Value::Object(obj)),
+                _ => return self.err("expected ',' or '}'"),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, Box<dyn Error>> {
+        self.expect('[')?;
+        let mut arr = vec![];
+        loop {
+            self.skip_ws()?;
+            if self.peek() == Some(']') {
+                self.next();
+                return Ok(Value::Array(arr));
+            }
+            arr.push(self.value()?);
+            self.skip_ws()?;
+            match self.next() {
+                Some(',') => {}
+                Some(']') => return Ok(Value::Array(arr)),
+                _ => return self.err("expected ',' or ']'"),
+            }
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, Box<dyn Error>> {
+        let mut s = String::new();
+        while let Some(ch) = self.peek() {
+            if ch == '\\' {
+                self.next();
+                self.expect('u')?;
+                s.push(self.hex_char(4)?);
+            } else if is_ident_start(ch) || ch.is_alphanumeric() {
+                self.next();
+                s.push(ch);
+            } else {
+                break;
+            }
+        }
+        Ok(s)
+    }
+
+    fn hex_char(&mut self, n: usize) -> Result<char, Box<dyn Error>> {
+        let mut code = 0;
+        for _ in 0..n {
+            match self.next().and_then(|ch| ch.to_digit(16)) {
+                Some(d) => code = code * 16 + d,
+                None => return self.err("invalid hex escape"),
+            }
+        }
+        // surrogate pair
+        if (0xD800..0xDC00).contains(&code) && self.chars.as_str().starts_with("\\u") {
+            let mut ahead = self.chars.clone();
+            ahead.nth(1);
+            let low = ahead
+                .as_str()
+                .get(..4)
+                .and_then(|h| u32::from_str_radix(h, 16).ok());
+            if let Some(low @ 0xDC00..=0xDFFF) = low {
+                for _ in 0..6 {
+                    self.next();
+                }
+                code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+            }
+        }
+        match char::from_u32(code) {
+            Some(ch) => Ok(ch),
+            None => self.err("invalid unicode escape"),
+        }
+    }
+
+    fn string(&mut self, quote: char) -> Result<String, Box<dyn Error>> {
+        let mut s = String::new();
+        loop {
+            if let Some('\n' | '\r') = self.peek() {
+                return self.err("unescaped line break in string");
+            }
+            let Some(ch) = self.next() else {
+                return self.err("unterminated string");
+            };
+            match ch {
+                _ if ch == quote => return Ok(s),
+                '\\' => {
+                    let Some(ch) = self.next() else {
+                        return self.err("unterminated string");
+                    };
+                    match ch {
+                        'b' => s.push('\u{8}'),
+                        'f' => s.push('\u{C}'),
+                        'n' => s.push('\n'),
+                        'r' => s.push('\r'),
+                        't' => s.push('\t'),
+                        'v' => s.push('\u{B}'),
+                        '0' if !self.peek().is_some_and(|ch| ch.is_ascii_digit()) => s.push('\0'),
+                        'x' => s.push(self.hex_char(2)?),
+                        'u' => s.push(self.hex_char(4)?),
+                        // line continuation
+                        '\n' | '\u{2028}' | '\u{2029}' => {}
+                        '\r' => {
+                            if self.peek() == Some('\n') {
+                                self.next();
+                            }
+                        }
+                        _ if ch.is_ascii_digit() => return self.err("invalid escape"),
+                        _ => s.push(ch),
+                    }
+                }
+                _ => s.push(ch),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, Box<dyn Error>> {
+        let mut s = String::new();
+        if let Some(sign @ ('+' | '-')) = self.peek() {
+            self.next();
+            if sign == '-' {
+                s.push(sign);
+            }
+        }
+        if self.peek().is_some_and(is_ident_start) {
+            return match self.ident()?.as_str() {
+                "Infinity" | "NaN" => self.err("Infinity and NaN are not supported in json"),
+                _ => self.err("invalid number"),
+            };
+        }
+
+        // hexadecimal
+        if self.chars.as_str().starts_with("0x") || self.chars.as_str().starts_with("0X") {
+            self.next();
+            self.next();
+            let mut hex = String::new();
+            while let Some(ch) = self.peek().filter(char::is_ascii_hexdigit) {
+                self.next();
+                hex.push(ch);
+            }
+            let Ok(n) = u64::from_str_radix(&hex, 16) else {
+                return self.err("invalid hexadecimal number");
+            };
+            return Ok(match s.is_empty() {
+                true => Value::from(n),
+                false => match i64::try_from(n) {
+                    Ok(n) => Value::from(-n),
+                    Err(_) => Value::from(-(n as f64)),
+                },
+            });
+        }
+
+        let digits = |p: &mut Self, s: &mut String| {
+            let mut n = 0;
+            while let Some(ch) = p.peek().filter(char::is_ascii_digit) {
+                p.next();
+                s.push(ch);
+                n += 1;
+            }
+            n
+        };
+        let int = digits(self, &mut s);
+        if int == 0 {
+            s.push('0');
+        }
+        if self.peek() == Some('.') {
+            self.next();
+            s.push('.');
+            if digits(self, &mut s) == 0 {
+                if int == 0 {
+                    return self.err("invalid number");
+                }
+                s.push('0');
+            }
+        }
+        if let Some(e @ ('e' | 'E')) = self.peek() {
+            self.next();
+            s.push(e);
+            if let Some(sign @ ('+' | '-')) = self.peek() {
+                self.next();
+                s.push(sign);
+            }
+            if digits(self, &mut s) == 0 {
+                return self.err("invalid number");
+            }
+        }
+        match serde_json::from_str::<Number>(&s) {
+            Ok(n) => Ok(Value::Number(n)),
+            Err(_) => self.err("invalid number"),
+        }
+    }
+}
+
+fn is_ident_start(ch: char) -> bool {
+    ch == '$' || ch == '_' || ch.is_alphabetic()
+}
@@ -0,0 +1,167 @@
+use std::{collections::HashSet, error::Error, sync::Arc};
+
+use serde_json::Value;
+
+use crate::Compiler;
+
+/**
+Phase in which a custom keyword is evaluated, relative to built-in
+keywords. see [Evaluation Order](crate#evaluation-order).
+
+Phases are ordered, so that `Phase::Assertion < Phase::BeforeUnevaluated`.
+*/
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Phase {
+    /// after `type`, `const`, `enum` and `format`, before `$ref` and
+    /// other applicators. No properties or items are evaluated yet.
+    Assertion,
+    /// after all keywords except `unevaluatedProperties` and
+    /// `unevaluatedItems`, so that [`Annotations`] have properties and
+    /// items evaluated by applicators such as `properties`,
+    /// `additionalProperties`, `items`, `allOf` and `$ref`.
+    BeforeUnevaluated,
+    /// after `unevaluatedProperties` and `unevaluatedItems`.
+    Last,
+}
+
+/**
+Custom keyword compiled from its value in a schema.
+see [`Compiler::register_keyword`].
+*/
+pub trait Keyword: Send + Sync {
+    /**
+    Validates instance `v`.
+
+    `annotations` tells which properties or items of `v` are evaluated
+    by the schema containing this keyword, in the phases before that of
+    this keyword.
+    */
+    fn validate(&self, v: &Value, annotations: &Annotations) -> Result<(), Box<dyn Error>>;
+}
+
+/**
+Properties and items of an instance evaluated by a schema and its
+in-place subschemas, such as those of `allOf` and `$ref`, that
+validated successfully.
+
+These are the annotations used by `unevaluatedProperties` and
+`unevaluatedItems`.
+*/
+pub struct Annotations<'a> {
+    // None means not tracked, i.e. nothing evaluated yet
+    props: Option<&'a HashSet<&'a str>>, // unevaluated
+    items: Option<&'a HashSet<usize>>,   // unevaluated
+}
+
+impl<'a> Annotations<'a> {
+    pub(crate) fn none() -> Self {
+        Self {
+            props: None,
+            items: None,
+        }
+    }
+
+    pub(crate) fn new(
+        uneval_props: &'a HashSet<&'a str>,
+        uneval_items: &'a HashSet<usize>,
+    ) -> Self {
+        Self {
+            props: Some(uneval_props),
+            items: Some(uneval_items),
+        }
+    }
+
+    /// Tells whether property `name` of object instance is evaluated.
+    pub fn is_property_evaluated(&self, name: &str) -> bool {
+        self.props.is_some_and(|props| !props.contains(name))
+    }
+
+    /// Tells whether item at `index` of array instance is evaluated.
+    pub fn is_item_evaluated(&self, index: usize) -> bool {
+        self.items.is_some_and(|items| !items.contains(&index))
+    }
+}
+
+pub(crate) type KeywordCompiler =
+    Arc<dyn Fn(&Value) -> Result<Box<dyn Keyword>, Box<dyn Error>> + Send + Sync>;
+
+#[derive(Clone)]
+pub(crate) struct KeywordDef {
+    pub(crate) phase: Phase,
+    pub(crate) compile: KeywordCompiler,
+}
+
+#[derive(Clone)]
+pub(crate) struct CompiledKeyword {
+    pub(crate) name: &'static str,
+    pub(crate) value: Value,
+    pub(crate) phase: Phase,
+    pub(crate) keyword: Arc<dyn Keyword>,
+}
+
+impl Compiler {
+    /**
+    Registers custom keyword `name`, evaluated in `phase`.
+
+    When a schema has keyword `name`, its value is passed to `compile`,
+    which returns [`Keyword`] used to validate instances. If `compile`
+    fails, compilation fails with [`CompileError::InvalidKeyword`](crate::CompileError::InvalidKeyword).
+    Failed validation is reported as [`ErrorKind::Keyword`](crate::ErrorKind::Keyword).
+
+    Built-in keywords of the draft in use cannot be overridden. Custom
+    keywords are allowed in strict mode.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::{json, Value};
+    # fn main() -> Result<(), Box<dyn Error>> {
+    // fails if object has properties, not evaluated by other keywords
+    struct NoExtraProperties;
+
+    impl Keyword for NoExtraProperties {
+        fn validate(&self, v: &Value, annotations: &Annotations) -> Result<(), Box<dyn Error>> {
+            let Value::Object(obj) = v else {
+                return Ok(());
+            };
+            match obj.keys().find(|p| !annotations.is_property_evaluated(p)) {
+                Some(p) => Err(format!("property {p:?} is not evaluated"))?,
+                None => Ok(()),
+            }
+        }
+    }
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.register_keyword("x-closed", Phase::BeforeUnevaluated, |v| match v {
+        Value::Bool(true) => Ok(Box::new(NoExtraProperties)),
+        _ => Err("x-closed must be true")?,
+    });
+    let schema = json!({
+        "x-closed": true,
+        "properties": {"name": true},
+        "allOf": [{"properties": {"age": true}}]
+    });
+    let sch_index = compiler.compile_value(schema, &mut schemas)?;
+    assert!(schemas.validate(&json!({"name": 1, "age": 2}), sch_index).is_ok());
+    assert!(schemas.validate(&json!({"name": 1, "x": 2}), sch_index).is_err());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn register_keyword<F>(&mut self, name: &'static str, phase: Phase, compile: F)
+    where
+        F: Fn(&Value) -> Result<Box<dyn Keyword>, Box<dyn Error>> + Send + Sync + 'static,
+    {
+        let compile = Arc::new(compile);
+        self.keywords.insert(name, KeywordDef { phase, compile });
+    }
+
+    /// Returns names of custom keywords registered.
+    pub fn custom_keywords(&self) -> Vec<&'static str> {
+        let mut names = self.keywords.keys().copied().collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+}
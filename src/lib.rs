@@ -73,6 +73,36 @@ for example:
 - after line 1: `S` refers to `file:///tmp/customer.json`
 - after line 3: `S` refers to `file://tmp/address.json`
 
+# Evaluation Order
+
+Keywords of a schema are evaluated in following order:
+
+1. `type`, `const`, `enum`. validation stops at first failure.
+2. `format`
+3. custom keywords of [`Phase::Assertion`]
+4. `$ref`. for draft < 2019-09, sibling keywords are ignored.
+5. type specific keywords. for objects, `properties` and
+   `patternProperties` are evaluated before `additionalProperties`,
+   which applies only to properties not matched by them.
+6. `$recursiveRef`, `$dynamicRef`
+7. `not`, `allOf`, `anyOf`, `oneOf`, `if`/`then`/`else`
+8. custom keywords of [`Phase::BeforeUnevaluated`]
+9. `unevaluatedProperties`, `unevaluatedItems`
+10. custom keywords of [`Phase::Last`]
+
+`unevaluated*` keywords are evaluated after the built-in keywords above,
+so that they see annotations from all of them, including the ones from
+subschemas which are successfully evaluated. [`ValidationError::causes`]
+are in the same order.
+
+Custom keywords declare their phase when registered, see
+[`Compiler::register_keyword`]. Keywords of same phase are evaluated in
+the order of their names. Those of [`Phase::BeforeUnevaluated`] and
+later see the same annotations as `unevaluated*`, through [`Annotations`].
+
+Note that the result of validation by built-in keywords does not depend
+on this order. It only affects the order of errors reported.
+
 
 # Output Formats
 
@@ -102,33 +132,132 @@ println!("{output:#}"); // prints indented json
 
 */
 
+mod asyncapi;
+mod batch;
+mod bson;
+mod build;
+mod builder;
+mod bundle;
+mod canonical;
+mod catalog;
+mod coerce;
 mod compiler;
 mod content;
+mod corpus;
+mod cycles;
+mod data;
+mod defaults;
+mod describe;
+mod dialect;
+mod dot;
 mod draft;
 mod ecma;
+mod equiv;
+mod export;
+mod fixtures;
 mod formats;
+mod frozen;
+mod generate;
+mod graphql;
+mod inline;
+mod intern;
+#[cfg(feature = "json5")]
+mod json5;
+mod keyword;
+mod lint;
 mod loader;
+mod metadata;
+mod metrics;
+mod migrate;
+mod negative;
+mod options;
 mod output;
+mod overlay;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod plugin;
+mod pretty;
+mod proto;
+mod regexp;
+mod rename;
 mod root;
 mod roots;
+mod sanitize;
+mod span;
+mod stream;
+mod support;
+mod table;
+pub mod tooling;
+mod trace;
 mod util;
 mod validator;
+mod walk;
+#[cfg(feature = "yaml")]
+mod yaml;
 
 pub use {
-    compiler::{CompileError, Compiler, Draft},
+    asyncapi::AsyncApiPayload,
+    build::build_schemas,
+    builder::CompilerBuilder,
+    bundle::Dereferenced,
+    compiler::{
+        CompileError, CompileLimit, CompileLimits, CompileWarning, Compiler, Draft, UnknownRef,
+    },
     content::{Decoder, MediaType},
-    formats::Format,
-    loader::{FileLoader, SchemeUrlLoader, UrlLoader},
+    corpus::{CorpusReport, FileReport, ResourceConflict},
+    cycles::RefCycle,
+    describe::Description,
+    dialect::Dialect,
+    export::Unrepresentable,
+    fixtures::{replay_fixtures, FixtureRecorder, FixtureRegression, Redaction},
+    formats::{EmailOptions, Format, HostnameOptions},
+    frozen::CompiledSchemas,
+    generate::generate_instance,
+    graphql::{export_graphql, GraphQLExport},
+    keyword::{Annotations, Keyword, Phase},
+    lint::{Lint, LintKind},
+    loader::{
+        metaschema, metaschemas, DataUrlLoader, FileLoader, LoadAttempt, LoadAttemptsError,
+        LoadPolicy, Metaschema, SchemeUrlLoader, UrlContent, UrlLoader,
+    },
+    metadata::Metadata,
+    metrics::{SchemaMetrics, SchemasStats},
+    migrate::{migrate, MigrationNote, MigrationNoteKind},
+    negative::{near_misses, NearMiss},
+    options::{AccessContext, LengthUnit, ValidationOptions},
     output::{
         AbsoluteKeywordLocation, FlagOutput, KeywordPath, OutputError, OutputUnit, SchemaToken,
     },
+    overlay::{Provenance, SchemaPatch},
+    plugin::{PluginFormat, PluginKeyword, PluginV1, PluginV2, PLUGIN_ABI_VERSION, PLUGIN_SYMBOL},
+    pretty::PrettyFormat,
+    proto::{export_proto, ProtoExport},
+    regexp::{RegexEngine, RegexLimits},
+    rename::{rename, RenameTarget},
+    sanitize::RemoveAdditional,
+    span::Span,
+    stream::{JsonEvent, StreamError, StreamValidator},
+    table::{derive_table, Column, ColumnType, Table},
+    trace::{KeywordOutcome, TraceEvent},
     validator::{InstanceLocation, InstanceToken},
+    walk::Subschema,
 };
 
-use std::{borrow::Cow, collections::HashMap, error::Error, fmt::Display};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::Display,
+    sync::Arc,
+    time::Duration,
+};
 
-use ahash::AHashMap;
-use regex::Regex;
+use data::DataRef;
+use formats::CompiledFormat;
+use intern::{Interner, Name};
+use keyword::CompiledKeyword;
+use overlay::Overlay;
+use regexp::{Regex, RegexSet};
 use serde_json::{Number, Value};
 use util::*;
 
@@ -140,7 +269,11 @@ pub struct SchemaIndex(usize);
 #[derive(Default)]
 pub struct Schemas {
     list: Vec<Schema>,
-    map: HashMap<UrlPtr, usize>, // loc => schema-index
+    indexes: Vec<SchemaIndex>,            // subschema lists, see IndexRange
+    names: Interner,                      // property names
+    map: HashMap<UrlPtr, usize>,          // loc => schema-index
+    docs: HashMap<url::Url, Arc<Value>>,  // documents of compiled schemas
+    overlays: HashMap<url::Url, Overlay>, // overlays among docs
 }
 
 impl Schemas {
@@ -148,31 +281,399 @@ impl Schemas {
         Self::default()
     }
 
-    fn insert(&mut self, locs: Vec<UrlPtr>, compiled: Vec<Schema>) {
-        for (up, sch) in locs.into_iter().zip(compiled.into_iter()) {
+    // inserts `compiled` schemas. if `in_place` is given, the first one
+    // replaces that lazy schema, instead of being appended.
+    fn insert(
+        &mut self,
+        locs: Vec<UrlPtr>,
+        indexes: Vec<SchemaIndex>,
+        names: Interner,
+        compiled: Vec<Schema>,
+        in_place: Option<SchemaIndex>,
+    ) {
+        let start = self.list.len();
+        self.indexes.extend(indexes);
+        self.names.extend(names);
+        let mut compiled = locs.into_iter().zip(compiled);
+        if let Some(idx) = in_place {
+            if let Some((_, mut sch)) = compiled.next() {
+                // evaluates_xxx of its parents are computed already,
+                // assuming that it evaluates everything.
+                sch.evaluates_props = true;
+                sch.evaluates_items = true;
+                self.list[idx.0] = sch;
+            }
+        }
+        for (up, sch) in compiled {
             let i = self.list.len();
             self.list.push(sch);
             self.map.insert(up, i);
         }
+        self.propagate_evaluates(start);
+    }
+
+    // propagates `evaluates_props` and `evaluates_items` of schemas from
+    // `start` to the schemas applying them in-place, so that validator
+    // tracks evaluated properties and items only where it matters.
+    fn propagate_evaluates(&mut self, start: usize) {
+        loop {
+            let mut changed = false;
+            for i in start..self.list.len() {
+                let s = &self.list[i];
+                if s.evaluates_props && s.evaluates_items {
+                    continue;
+                }
+                let (mut props, mut items) = (s.evaluates_props, s.evaluates_items);
+                for (kp, child) in s.children(self) {
+                    if cycles::is_in_place(kp.keyword) {
+                        let child = self.get(child);
+                        props |= child.evaluates_props;
+                        items |= child.evaluates_items;
+                    }
+                }
+                if (props, items) != (s.evaluates_props, s.evaluates_items) {
+                    let s = &mut self.list[i];
+                    (s.evaluates_props, s.evaluates_items) = (props, items);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
     }
 
     fn get(&self, idx: SchemaIndex) -> &Schema {
         &self.list[idx.0] // todo: return bug
     }
 
+    fn indexes(&self, range: IndexRange) -> &[SchemaIndex] {
+        &self.indexes[range.start as usize..][..range.len as usize]
+    }
+
     fn get_by_loc(&self, up: &UrlPtr) -> Option<&Schema> {
         self.map.get(up).and_then(|&i| self.list.get(i))
     }
 
+    /**
+    Returns location of schema identified by `sch_index`.
+
+    The location is absolute url of the document containing the schema,
+    with json-pointer to the schema as fragment. for example
+    `http://example.com/user.json#/properties/name`
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn location(&self, sch_index: SchemaIndex) -> &str {
+        &self.get(sch_index).loc
+    }
+
+    /**
+    Returns index of compiled schema at `loc`.
+
+    `loc` may use `$id` of a resource, and anchor or json-pointer
+    fragment, like the ones used in `$ref`. Relative `loc` is
+    resolved against the current directory, as in [`Compiler::compile`].
+    Returns `None` if no schema is compiled for `loc`.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let schema = json!({
+        "properties": {"name": {"$ref": "http://example.com/name"}},
+        "$defs": {"name": {"$id": "http://example.com/name", "type": "string"}}
+    });
+    compiler.add_resource("http://example.com/user.json", schema)?;
+    let sch_index = compiler.compile("http://example.com/user.json", &mut schemas)?;
+
+    let name = schemas.index_of("http://example.com/name").unwrap();
+    assert_eq!(schemas.location(name), "http://example.com/user.json#/$defs/name");
+    assert_eq!(schemas.index_of("http://example.com/user.json#"), Some(sch_index));
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn index_of(&self, loc: &str) -> Option<SchemaIndex> {
+        let uf = UrlFrag::absolute(loc).ok()?;
+        let is_resource = |s: &Schema| s.id.as_ref() == Some(&uf.url);
+        match uf.frag {
+            Fragment::JsonPointer(ptr) => {
+                let up = UrlPtr {
+                    url: uf.url.clone(),
+                    ptr,
+                };
+                if let Some(&i) = self.map.get(&up) {
+                    return Some(SchemaIndex(i));
+                }
+                // relative to resource with matching id
+                let res = self.list.iter().find(|s| is_resource(s))?;
+                let (res_up, _) = self.map.iter().find(|(_, &i)| i == res.idx.0)?;
+                let up = UrlPtr {
+                    url: res_up.url.clone(),
+                    ptr: res_up.ptr.concat(&up.ptr),
+                };
+                self.map.get(&up).map(|&i| SchemaIndex(i))
+            }
+            Fragment::Anchor(anchor) => self
+                .list
+                .iter()
+                .find(|s| s.anchors.contains(&anchor.0) && is_resource(self.get(s.resource)))
+                .map(|s| s.idx),
+        }
+    }
+
+    /**
+    Returns json of schema identified by `sch_index`, as it was before
+    compilation.
+
+    The documents are shared with the [`Compiler`] which loaded them, so
+    this does not copy any json.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn source(&self, sch_index: SchemaIndex) -> Option<&Value> {
+        let uf = UrlFrag::absolute(&self.get(sch_index).loc).ok()?;
+        let Fragment::JsonPointer(ptr) = uf.frag else {
+            return None;
+        };
+        let doc = self.docs.get(&uf.url)?;
+        ptr.lookup(doc, &uf.url).ok()
+    }
+
     /// Returns true if `sch_index` is generated for this instance.
     pub fn contains(&self, sch_index: SchemaIndex) -> bool {
         self.list.get(sch_index.0).is_some()
     }
 
+    /**
+    Returns origin of location `loc` in schema added using
+    [`Compiler::add_overlay`], and compiled into this instance.
+
+    Unlike [`Compiler::provenance`], fragment of `loc` must be
+    json-pointer, as in locations reported by [`ValidationError`].
+    returns `None` if `loc` is not in such schema.
+    */
+    pub fn provenance(&self, loc: &str) -> Option<Provenance> {
+        let uf = UrlFrag::absolute(loc).ok()?;
+        let overlay = self.overlays.get(&uf.url)?;
+        let Fragment::JsonPointer(ptr) = uf.frag else {
+            return None;
+        };
+        Some(overlay.provenance(ptr.as_str()))
+    }
+
+    // sets provenance of `e` and its causes, from their keyword location.
+    fn set_provenance(&self, e: &mut ValidationError) {
+        if self.overlays.is_empty() {
+            return;
+        }
+        e.provenance = self.provenance(&e.absolute_keyword_location().to_string());
+        for cause in &mut e.causes {
+            self.set_provenance(cause);
+        }
+    }
+
+    /**
+    Flags schemas compiled from document `loc` as stale, along with
+    the schemas referring to them directly or indirectly.
+
+    This is meant to be used along with [`Compiler::replace_resource`]
+    and [`Compiler::remove_resource`]. Compiling any of the stale schemas
+    again, compiles it afresh with new [`SchemaIndex`]. Stale schemas are
+    retained, so that their indexes can still be used for validation, but
+    they are no longer reported by [`Schemas::iter`] and
+    [`Schemas::index_of`].
+
+    Returns the schemas flagged, in ascending order.
+    */
+    pub fn invalidate(&mut self, loc: &str) -> Vec<SchemaIndex> {
+        let Ok(uf) = UrlFrag::absolute(loc) else {
+            return vec![];
+        };
+        let mut parents = vec![vec![]; self.list.len()];
+        for sch in &self.list {
+            for (_, child) in sch.children(self) {
+                parents[child.0].push(sch.idx);
+            }
+        }
+
+        let mut stack = self
+            .map
+            .iter()
+            .filter(|(up, _)| up.url == uf.url)
+            .map(|(_, &i)| SchemaIndex(i))
+            .collect::<Vec<_>>();
+        let mut stale = HashSet::new();
+        while let Some(idx) = stack.pop() {
+            if stale.insert(idx) {
+                stack.extend(parents[idx.0].iter().copied());
+            }
+        }
+
+        self.map.retain(|_, i| !stale.contains(&SchemaIndex(*i)));
+        self.docs.remove(&uf.url);
+        self.overlays.remove(&uf.url);
+        let mut stale = stale.into_iter().collect::<Vec<_>>();
+        stale.sort();
+        for idx in &stale {
+            self.list[idx.0].stale = true;
+        }
+        stale
+    }
+
+    /// Returns true if `sch_index` is flagged stale by [`Schemas::invalidate`].
+    pub fn is_stale(&self, sch_index: SchemaIndex) -> bool {
+        self.list.get(sch_index.0).is_some_and(|s| s.stale)
+    }
+
     pub fn size(&self) -> usize {
         self.list.len()
     }
 
+    /**
+    Returns an iterator over all the compiled schemas, yielding index and
+    location of each schema in the order they are compiled.
+
+    This includes subschemas and the schemas pulled in through `$ref`,
+    which is useful for coverage and for debugging which references got
+    resolved.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let schema = json!({"items": {"$ref": "#/$defs/item"}, "$defs": {"item": true}});
+    compiler.add_resource("http://example.com/list.json", schema)?;
+    compiler.compile("http://example.com/list.json", &mut schemas)?;
+
+    let locs = schemas.iter().map(|(_, loc)| loc).collect::<Vec<_>>();
+    assert_eq!(locs, [
+        "http://example.com/list.json#",
+        "http://example.com/list.json#/items",
+        "http://example.com/list.json#/$defs/item",
+    ]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn iter(&self) -> impl Iterator<Item = (SchemaIndex, &str)> + '_ {
+        self.list
+            .iter()
+            .filter(|s| !s.stale)
+            .map(|s| (s.idx, s.loc.as_str()))
+    }
+
+    /**
+    Returns keywords of schema identified by `sch_index`, which take part
+    in validation.
+
+    Annotation keywords such as `title` are not included.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn keywords(&self, sch_index: SchemaIndex) -> Vec<&'static str> {
+        let s = self.get(sch_index);
+        let mut kws = vec![];
+        let mut add = |kw| {
+            if !kws.contains(&kw) {
+                kws.push(kw);
+            }
+        };
+        if !s.types.is_empty() {
+            add("type");
+        }
+        if s.enum_.is_some() {
+            add("enum");
+        }
+        if !s.required.is_empty() {
+            add("required");
+        }
+        if !s.bson_types.is_empty() {
+            add("bsonType");
+        }
+        if s.min_contains.is_some() {
+            add("minContains");
+        }
+        if s.max_contains.is_some() {
+            add("maxContains");
+        }
+        if s.dependencies
+            .iter()
+            .any(|(_, dep)| matches!(dep, Dependency::Props(_)))
+        {
+            add("dependencies");
+        }
+        for kw in export::constraints(s) {
+            add(kw);
+        }
+        for (kp, _) in s.children(self) {
+            add(kp.keyword);
+        }
+        for k in &s.keywords {
+            add(k.name);
+        }
+        kws
+    }
+
+    /**
+    Returns keywords of schema identified by `sch_index`, which are not
+    defined by its draft, along with their values.
+
+    Such keywords, for example `x-*` extensions or vendor keywords of the
+    [`Dialect`] in use, are treated as annotations. Keywords are returned
+    in the order they appear in the schema. Values are borrowed from
+    [`Schemas::source`], so they are not copied during compilation.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", json!({"type": "string", "x-widget": "textarea"}))?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+    assert_eq!(
+        schemas.unknown_keywords(sch_index),
+        [("x-widget", &json!("textarea"))]
+    );
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn unknown_keywords(&self, sch_index: SchemaIndex) -> Vec<(&str, &Value)> {
+        let s = self.get(sch_index);
+        let Some(Value::Object(obj)) = self.source(sch_index) else {
+            return vec![];
+        };
+        s.unknown_keywords
+            .iter()
+            .filter_map(|kw| obj.get_key_value(kw.as_str()))
+            .map(|(kw, v)| (kw.as_str(), v))
+            .collect()
+    }
+
     /**
     Validates `v` with schema identified by `sch_index`
 
@@ -186,10 +687,82 @@ impl Schemas {
         v: &'v Value,
         sch_index: SchemaIndex,
     ) -> Result<(), ValidationError<'s, 'v>> {
+        self.validate_with(v, sch_index, &ValidationOptions::default())
+    }
+
+    /**
+    Validates `v` with schema identified by `sch_index`, using
+    given `options`.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn validate_with<'s, 'v>(
+        &'s self,
+        v: &'v Value,
+        sch_index: SchemaIndex,
+        options: &ValidationOptions,
+    ) -> Result<(), ValidationError<'s, 'v>> {
+        let Some(sch) = self.list.get(sch_index.0) else {
+            panic!("Schemas::validate: schema index out of bounds");
+        };
+        validator::validate(v, sch, self, options, None, None)
+    }
+
+    /**
+    Same as [`Schemas::validate_with`], but also collects warnings, such
+    as usage of schemas marked `deprecated: true`.
+
+    Warnings are collected only from the subschemas which matched, so
+    for example a deprecated property in a failed `anyOf` branch is not
+    reported. Warnings are collected even if validation fails.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let schema = json!({
+        "properties": {
+            "fax": {"type": "string", "deprecated": true}
+        }
+    });
+    compiler.add_resource("contact.json", schema)?;
+    let sch_index = compiler.compile("contact.json", &mut schemas)?;
+
+    let instance = json!({"fax": "555-0100"});
+    let options = ValidationOptions::new();
+    let (result, warnings) = schemas.validate_with_warnings(&instance, sch_index, &options);
+    assert!(result.is_ok());
+    assert_eq!(warnings[0].instance_location.to_string(), "/fax");
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn validate_with_warnings<'s, 'v>(
+        &'s self,
+        v: &'v Value,
+        sch_index: SchemaIndex,
+        options: &ValidationOptions,
+    ) -> (
+        Result<(), ValidationError<'s, 'v>>,
+        Vec<ValidationWarning<'s, 'v>>,
+    ) {
         let Some(sch) = self.list.get(sch_index.0) else {
             panic!("Schemas::validate: schema index out of bounds");
         };
-        validator::validate(v, sch, self)
+        let mut warnings = vec![];
+        let result = validator::validate(v, sch, self, options, Some(&mut warnings), None);
+        (result, warnings)
     }
 }
 
@@ -199,10 +772,16 @@ struct Schema {
     idx: SchemaIndex,
     loc: String,
     resource: SchemaIndex,
+    id: Option<url::Url>, // set for resource
+    anchors: Vec<String>,
     dynamic_anchors: HashMap<String, SchemaIndex>,
+    stale: bool, // see Schemas::invalidate
+    lazy: bool,  // not compiled yet, see Compiler::enable_lazy_compilation
     all_props_evaluated: bool,
     all_items_evaluated: bool,
     num_items_evaluated: usize,
+    evaluates_props: bool, // by schema or its in-place subschemas
+    evaluates_items: bool, // by schema or its in-place subschemas
 
     // type agnostic --
     boolean: Option<bool>, // boolean schema
@@ -215,20 +794,36 @@ struct Schema {
     enum_: Option<Enum>,
     constant: Option<Value>,
     not: Option<SchemaIndex>,
-    all_of: Vec<SchemaIndex>,
-    any_of: Vec<SchemaIndex>,
-    one_of: Vec<SchemaIndex>,
+    all_of: IndexRange,
+    any_of: IndexRange,
+    one_of: IndexRange,
     if_: Option<SchemaIndex>,
     then: Option<SchemaIndex>,
     else_: Option<SchemaIndex>,
-    format: Option<Format>,
+    format: Option<CompiledFormat>, // compiled even if not asserted
+    format_assert: bool,
+    format_name: Option<String>,
+    default: Option<Value>,
+    examples: Vec<Value>,
+    title: Option<String>,
+    description: Option<String>,
+    read_only: bool,
+    write_only: bool,
+    deprecated: bool,
+    bson_types: Vec<String>,
+    unknown_keywords: Vec<String>, // not defined by draft, values in source
+    keywords: Vec<CompiledKeyword>, // custom, ordered by phase
 
     // object --
     min_properties: Option<usize>,
     max_properties: Option<usize>,
-    required: Vec<String>,
-    properties: AHashMap<String, SchemaIndex>,
+    required: Vec<Name>,
+    /// bit of each distinct name in `required`, for large `required`
+    required_bits: Option<FastHashMap<Name, usize>>,
+    properties: FastHashMap<Name, SchemaIndex>,
+    property_order: Vec<Name>, // declaration order, with serde_json `preserve_order`
     pattern_properties: Vec<(Regex, SchemaIndex)>,
+    pattern_properties_set: Option<RegexSet>, // for many patternProperties
     property_names: Option<SchemaIndex>,
     additional_properties: Option<Additional>,
     dependent_required: Vec<(String, Vec<String>)>,
@@ -245,7 +840,7 @@ struct Schema {
     contains: Option<SchemaIndex>,
     items: Option<Items>,
     additional_items: Option<Additional>,
-    prefix_items: Vec<SchemaIndex>,
+    prefix_items: IndexRange,
     items2020: Option<SchemaIndex>,
     unevaluated_items: Option<SchemaIndex>,
 
@@ -263,20 +858,84 @@ struct Schema {
     exclusive_minimum: Option<Number>,
     exclusive_maximum: Option<Number>,
     multiple_of: Option<Number>,
+
+    // $data --
+    data: Vec<(&'static str, DataRef)>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Enum {
     /// types that occur in enum
     types: Types,
     /// values in enum
     values: Vec<Value>,
+    /// indexes of values by hash, for large enum
+    index: Option<FastHashMap<u64, Vec<usize>>>,
+}
+
+impl Enum {
+    fn new(values: Vec<Value>) -> Self {
+        let mut types = Types::default();
+        for item in &values {
+            types.add(Type::of(item));
+        }
+        let index = (values.len() > 20).then(|| {
+            let mut index = FastHashMap::<u64, Vec<usize>>::with_capacity(values.len());
+            for (i, item) in values.iter().enumerate() {
+                index.entry(hash_value(item)).or_default().push(i);
+            }
+            index
+        });
+        Self {
+            types,
+            values,
+            index,
+        }
+    }
+
+    fn contains(&self, v: &Value) -> bool {
+        if !self.types.contains(Type::of(v)) {
+            return false;
+        }
+        match &self.index {
+            Some(index) => index
+                .get(&hash_value(v))
+                .is_some_and(|list| list.iter().any(|&i| equals(&self.values[i], v))),
+            None => self.values.iter().any(|e| equals(e, v)),
+        }
+    }
 }
 
 #[derive(Debug)]
 enum Items {
     SchemaRef(SchemaIndex),
-    SchemaRefs(Vec<SchemaIndex>),
+    SchemaRefs(IndexRange),
+}
+
+/// Range of [`Schemas::indexes`], listing subschemas of keyword such as
+/// `allOf`. Lists of all schemas are stored contiguously, rather than in
+/// a `Vec` per keyword.
+#[derive(Debug, Default, Clone, Copy)]
+struct IndexRange {
+    start: u32,
+    len: u32,
+}
+
+impl IndexRange {
+    fn new(start: usize, len: usize) -> Self {
+        Self {
+            start: start as u32,
+            len: len as u32,
+        }
+    }
+
+    fn is_empty(self) -> bool {
+        self.len == 0
+    }
+
+    fn len(self) -> usize {
+        self.len as usize
+    }
 }
 
 #[derive(Debug)]
@@ -297,12 +956,123 @@ struct DynamicRef {
 }
 
 impl Schema {
+    // returns format, if asserted by schema.
+    fn asserted_format(&self) -> Option<&CompiledFormat> {
+        self.format.as_ref().filter(|_| self.format_assert)
+    }
+
     fn new(loc: String) -> Self {
         Self {
             loc,
             ..Default::default()
         }
     }
+
+    // returns immediate subschemas along with their keyword path, in the order of keywords.
+    fn children<'a>(&'a self, schemas: &'a Schemas) -> Vec<(KeywordPath<'a>, SchemaIndex)> {
+        let mut v = vec![];
+        let mut add = |keyword, token, sch| {
+            v.push((KeywordPath { keyword, token }, sch));
+        };
+        let items = |list: IndexRange| {
+            schemas
+                .indexes(list)
+                .iter()
+                .enumerate()
+                .map(|(i, sch)| (Some(SchemaToken::Item(i)), *sch))
+                .collect::<Vec<_>>()
+        };
+
+        if let Some(sch) = self.ref_ {
+            add("$ref", None, sch);
+        }
+        if let Some(sch) = self.recursive_ref {
+            add("$recursiveRef", None, sch);
+        }
+        if let Some(dref) = &self.dynamic_ref {
+            add("$dynamicRef", None, dref.sch);
+        }
+        if let Some(sch) = self.not {
+            add("not", None, sch);
+        }
+        for (kw, list) in [
+            ("allOf", self.all_of),
+            ("anyOf", self.any_of),
+            ("oneOf", self.one_of),
+        ] {
+            for (token, sch) in items(list) {
+                add(kw, token, sch);
+            }
+        }
+        for (kw, sch) in [("if", self.if_), ("then", self.then), ("else", self.else_)] {
+            if let Some(sch) = sch {
+                add(kw, None, sch);
+            }
+        }
+
+        // object --
+        for pname in &self.property_order {
+            if let Some(&sch) = self.properties.get(pname) {
+                add("properties", Some(SchemaToken::Prop(pname)), sch);
+            }
+        }
+        for (regex, sch) in &self.pattern_properties {
+            add(
+                "patternProperties",
+                Some(SchemaToken::Prop(regex.as_str())),
+                *sch,
+            );
+        }
+        if let Some(sch) = self.property_names {
+            add("propertyNames", None, sch);
+        }
+        if let Some(Additional::SchemaRef(sch)) = self.additional_properties {
+            add("additionalProperties", None, sch);
+        }
+        for (pname, sch) in &self.dependent_schemas {
+            add("dependentSchemas", Some(SchemaToken::Prop(pname)), *sch);
+        }
+        for (pname, dep) in &self.dependencies {
+            if let Dependency::SchemaRef(sch) = dep {
+                add("dependencies", Some(SchemaToken::Prop(pname)), *sch);
+            }
+        }
+        if let Some(sch) = self.unevaluated_properties {
+            add("unevaluatedProperties", None, sch);
+        }
+
+        // array --
+        match &self.items {
+            Some(Items::SchemaRef(sch)) => add("items", None, *sch),
+            Some(Items::SchemaRefs(list)) => {
+                for (token, sch) in items(*list) {
+                    add("items", token, sch);
+                }
+            }
+            None => {}
+        }
+        if let Some(Additional::SchemaRef(sch)) = self.additional_items {
+            add("additionalItems", None, sch);
+        }
+        for (token, sch) in items(self.prefix_items) {
+            add("prefixItems", token, sch);
+        }
+        if let Some(sch) = self.items2020 {
+            add("items", None, sch);
+        }
+        if let Some(sch) = self.contains {
+            add("contains", None, sch);
+        }
+        if let Some(sch) = self.unevaluated_items {
+            add("unevaluatedItems", None, sch);
+        }
+
+        // string --
+        if let Some(sch) = self.content_schema {
+            add("contentSchema", None, sch);
+        }
+        v
+    }
 }
 
 /// JSON data types for JSONSchema
@@ -374,6 +1144,17 @@ impl Types {
         self.0 |= t as u8;
     }
 
+    // returns types allowed by both. integer is allowed, if one allows
+    // number and the other integer.
+    pub(crate) fn intersect(self, other: Types) -> Types {
+        let mut types = Types(self.0 & other.0);
+        let has = |a: Types, b: Types| a.contains(Type::Number) && b.contains(Type::Integer);
+        if !types.contains(Type::Number) && (has(self, other) || has(other, self)) {
+            types.add(Type::Integer);
+        }
+        types
+    }
+
     /// Returns `true` if this set contains given type.
     pub fn contains(&self, t: Type) -> bool {
         self.0 & t as u8 != 0
@@ -415,11 +1196,61 @@ pub struct ValidationError<'s, 'v> {
     pub kind: ErrorKind<'s, 'v>,
     /// Holds nested errors
     pub causes: Vec<ValidationError<'s, 'v>>,
+    /**
+    Origin of the location, if it is in schema added by
+    [`Compiler::add_overlay`].
+
+    When validating an instance, this is origin of the keyword location
+    in the schema. When validating a schema against its metaschema, as
+    in [`CompileError::ValidationError`], this is origin of the instance
+    location in the schema.
+    */
+    pub provenance: Option<Provenance>,
 }
 
 impl<'s, 'v> Error for ValidationError<'s, 'v> {}
 
+/// Warning reported by [`Schemas::validate_with_warnings`].
+#[derive(Debug)]
+pub struct ValidationWarning<'s, 'v> {
+    /// The absolute, dereferenced schema location.
+    pub schema_url: &'s str,
+    /// The location of the JSON value within the instance being validated
+    pub instance_location: InstanceLocation<'v>,
+    /// kind of warning
+    pub kind: WarningKind,
+}
+
+impl Display for ValidationWarning<'_, '_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "at {}: {} (see {})",
+            quote(&self.instance_location.to_string()),
+            self.kind,
+            self.schema_url
+        )
+    }
+}
+
+/// A list specifying categories of validation warnings.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarningKind {
+    /// value is validated by schema marked `deprecated: true`.
+    Deprecated,
+}
+
+impl Display for WarningKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Deprecated => write!(f, "deprecated"),
+        }
+    }
+}
+
 /// A list specifying general categories of validation errors.
+#[non_exhaustive]
 #[derive(Debug)]
 pub enum ErrorKind<'s, 'v> {
     Group,
@@ -439,11 +1270,40 @@ pub enum ErrorKind<'s, 'v> {
         kw_loc1: String,
         kw_loc2: String,
     },
+    /// schemas nested deeper than [`ValidationOptions::set_max_depth`].
+    /// This aborts the validation.
+    MaxDepth {
+        want: usize,
+    },
+    /// more schemas evaluated than [`ValidationOptions::set_max_evaluations`].
+    /// This aborts the validation.
+    MaxEvaluations {
+        want: usize,
+    },
+    /// validation took longer than [`ValidationOptions::set_time_limit`].
+    /// This aborts the validation.
+    TimeLimit {
+        want: Duration,
+    },
+    /// schema is compiled lazily, and not yet compiled.
+    /// see [`Compiler::validate_lazily`]. This aborts the validation.
+    NotCompiled {
+        url: &'s str,
+    },
     FalseSchema,
+    /// `readOnly` value in [`AccessContext::Write`].
+    ReadOnly,
+    /// `writeOnly` value in [`AccessContext::Read`].
+    WriteOnly,
     Type {
         got: Type,
         want: Types,
     },
+    /// `bsonType` of MongoDB `$jsonSchema` failed.
+    BsonType {
+        got: &'static str,
+        want: Vec<&'s str>,
+    },
     Enum {
         want: &'s Vec<Value>,
     },
@@ -516,6 +1376,13 @@ pub enum ErrorKind<'s, 'v> {
         got: Cow<'v, str>,
         want: &'s str,
     },
+    /// matching `got` against regex `want` of keyword `kw` exceeded
+    /// [`RegexLimits::backtrack_limit`], so the result is unknown.
+    RegexLimit {
+        kw: &'static str,
+        got: Cow<'v, str>,
+        want: &'s str,
+    },
     ContentEncoding {
         want: &'static str,
         err: Box<dyn Error>,
@@ -545,6 +1412,22 @@ pub enum ErrorKind<'s, 'v> {
         got: Cow<'v, Number>,
         want: &'s Number,
     },
+    /// keyword `kw` with value resolved from instance using `$data` failed.
+    Data {
+        kw: &'static str,
+        got: Cow<'v, Value>,
+        want: Box<Value>,
+    },
+    /// `$data` of keyword `kw` resolved to a value not valid for `kw`.
+    InvalidData {
+        kw: &'static str,
+        got: Cow<'v, Value>,
+    },
+    /// custom `keyword` failed. see [`Compiler::register_keyword`].
+    Keyword {
+        keyword: &'static str,
+        err: Box<dyn Error>,
+    },
     Not,
     /// none of the subschemas matched
     AllOf,
@@ -575,12 +1458,26 @@ impl<'s, 'v> Display for ErrorKind<'s, 'v> {
                 quote(&kw_loc1.to_string()),
                 quote(&kw_loc2.to_string())
             ),
+            Self::MaxDepth { want } => {
+                write!(f, "maximum depth of {want} nested schemas exceeded")
+            }
+            Self::MaxEvaluations { want } => {
+                write!(f, "maximum of {want} schema evaluations exceeded")
+            }
+            Self::TimeLimit { want } => write!(f, "time limit of {want:?} exceeded"),
+            Self::NotCompiled { url } => write!(f, "schema {url} is not compiled yet"),
             Self::FalseSchema => write!(f, "false schema"),
+            Self::ReadOnly => write!(f, "readOnly value not allowed in write context"),
+            Self::WriteOnly => write!(f, "writeOnly value not allowed in read context"),
             Self::Type { got, want } => {
                 // todo: why join not working for Type struct ??
                 let want = join_iter(want.iter(), " or ");
                 write!(f, "want {want}, but got {got}",)
             }
+            Self::BsonType { got, want } => {
+                let want = join_iter(want, " or ");
+                write!(f, "want bsonType {want}, but got {got}")
+            }
             Self::Enum { want } => {
                 if want.iter().all(Type::primitive) {
                     if want.len() == 1 {
@@ -677,6 +1574,14 @@ impl<'s, 'v> Display for ErrorKind<'s, 'v> {
             Self::Pattern { got, want } => {
                 write!(f, "{} does not match pattern {}", quote(got), quote(want))
             }
+            Self::RegexLimit { got, want, .. } => {
+                write!(
+                    f,
+                    "matching {} against pattern {} exceeded backtrack limit",
+                    quote(got),
+                    quote(want)
+                )
+            }
             Self::ContentEncoding { want, err } => {
                 write!(f, "value is not {} encoded: {err}", quote(want))
             }
@@ -688,6 +1593,23 @@ impl<'s, 'v> Display for ErrorKind<'s, 'v> {
             Self::ExclusiveMinimum { got, want } => write!(f, "must be > {want} but got {got}"),
             Self::ExclusiveMaximum { got, want } => write!(f, "must be < {want} but got {got}"),
             Self::MultipleOf { got, want } => write!(f, "{got} is not multipleOf {want}"),
+            Self::Data { kw, got, want } => match *kw {
+                "const" => {
+                    write!(f, "value must be ")?;
+                    display(f, want)
+                }
+                "minimum" => write!(f, "must be >={want}, but got {got}"),
+                "maximum" => write!(f, "must be <={want}, but got {got}"),
+                "exclusiveMinimum" => write!(f, "must be > {want} but got {got}"),
+                "exclusiveMaximum" => write!(f, "must be < {want} but got {got}"),
+                "multipleOf" => write!(f, "{got} is not multipleOf {want}"),
+                _ => write!(f, "{kw} failed"),
+            },
+            Self::InvalidData { kw, got } => {
+                write!(f, "$data resolved to invalid {kw} ")?;
+                display(f, got)
+            }
+            Self::Keyword { keyword, err } => write!(f, "{keyword} failed: {err}"),
             Self::Not => write!(f, "not failed"),
             Self::AllOf => write!(f, "allOf failed",),
             Self::AnyOf => write!(f, "anyOf failed"),
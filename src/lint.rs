@@ -0,0 +1,346 @@
+use std::{collections::HashSet, fmt::Display};
+
+use serde_json::{Map, Value};
+
+use crate::{compiler::CompileError, draft::Draft, util::*, Compiler, Type};
+
+/// Issue found by [`Compiler::lint`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lint {
+    /// absolute location of the offending keyword or subschema.
+    pub url: String,
+    /// kind of issue.
+    pub kind: LintKind,
+}
+
+/// Kind of [`Lint`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum LintKind {
+    /// `keyword` applies only to instance types which are not allowed
+    /// by `type`, so it has no effect. For example `minimum` with
+    /// `"type": "string"`.
+    InapplicableKeyword { keyword: String, types: Vec<Type> },
+    /// lower bound `min` is greater than upper bound `max`, so no
+    /// instance can satisfy both.
+    ContradictoryBounds { min: String, max: String },
+    /// `items` is an array in draft 2020-12, where `prefixItems` should be
+    /// used instead.
+    ItemsArray,
+    /// definition in `$defs` or `definitions` is not referenced within the
+    /// document.
+    UnreachableDef { name: String },
+    /// `keyword` is boolean in draft-06 or later, or is number in draft-04.
+    ExclusiveBoundMisuse { keyword: String },
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let url = &self.url;
+        match &self.kind {
+            LintKind::InapplicableKeyword { keyword, types } => write!(
+                f,
+                "{} at {url} has no effect, as type is {}",
+                quote(keyword),
+                join_iter(types, " or ")
+            ),
+            LintKind::ContradictoryBounds { min, max } => {
+                write!(f, "{} contradicts {} at {url}", quote(min), quote(max))
+            }
+            LintKind::ItemsArray => {
+                write!(f, "items array at {url} is not allowed, use prefixItems")
+            }
+            LintKind::UnreachableDef { name } => {
+                write!(f, "definition {} at {url} is never referenced", quote(name))
+            }
+            LintKind::ExclusiveBoundMisuse { keyword } => {
+                write!(
+                    f,
+                    "{} at {url} has wrong type for the draft",
+                    quote(keyword)
+                )
+            }
+        }
+    }
+}
+
+impl Compiler {
+    /**
+    Analyzes schema document `loc` and reports issues which are not
+    errors, but likely mistakes.
+
+    The document need not be valid against its metaschema, so this can be
+    used to explain compile failures caused by mistakes common when
+    migrating across drafts, such as `items` array in draft 2020-12.
+    Issues are reported in the order of their location.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", json!({
+        "properties": {
+            "age": {"type": "string", "minimum": 0},
+            "tags": {"minItems": 3, "maxItems": 1}
+        }
+    }))?;
+    let lints = compiler.lint("schema.json")?;
+    assert!(matches!(lints[0].kind, LintKind::InapplicableKeyword { .. }));
+    assert!(matches!(lints[1].kind, LintKind::ContradictoryBounds { .. }));
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    returns [`CompileError`] if `loc` cannot be loaded, or its draft
+    cannot be determined.
+    */
+    pub fn lint(&mut self, loc: &str) -> Result<Vec<Lint>, CompileError> {
+        let uf = UrlFrag::absolute(loc)?;
+        let doc = self.roots.loader.load(&uf.url)?;
+        let draft = match self.roots.get(&uf.url) {
+            Some(root) => root.draft,
+            None => {
+                let up = UrlPtr {
+                    url: uf.url.clone(),
+                    ptr: "".into(),
+                };
+                let default_draft = self.roots.default_draft;
+                self.roots
+                    .loader
+                    .get_draft(&up, doc, default_draft, HashSet::new())?
+            }
+        };
+
+        let mut ptrs = vec![];
+        draft.collect_subschemas(doc, "".into(), &mut ptrs);
+        ptrs.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        let refs = collect_refs(doc);
+        let mut lints = vec![];
+        for ptr in ptrs {
+            if let Ok(Value::Object(obj)) = ptr.lookup(doc, &uf.url) {
+                lint_schema(
+                    obj,
+                    &UrlPtr {
+                        url: uf.url.clone(),
+                        ptr,
+                    },
+                    draft,
+                    &refs,
+                    &mut lints,
+                );
+            }
+        }
+        Ok(lints)
+    }
+}
+
+// fragments of all references in document.
+struct Refs {
+    ptrs: Vec<String>,
+    anchors: HashSet<String>,
+}
+
+fn collect_refs(doc: &Value) -> Refs {
+    fn collect(v: &Value, refs: &mut Refs) {
+        match v {
+            Value::Object(obj) => {
+                for (kw, v) in obj {
+                    match (kw.as_str(), v) {
+                        ("$ref" | "$dynamicRef" | "$recursiveRef", Value::String(r)) => {
+                            if let Ok((_, frag)) = Fragment::split(r) {
+                                match frag {
+                                    Fragment::JsonPointer(ptr) => refs.ptrs.push(ptr.0),
+                                    Fragment::Anchor(anchor) => {
+                                        refs.anchors.insert(anchor.0.to_string());
+                                    }
+                                }
+                            }
+                        }
+                        _ => collect(v, refs),
+                    }
+                }
+            }
+            Value::Array(arr) => arr.iter().for_each(|v| collect(v, refs)),
+            _ => {}
+        }
+    }
+    let mut refs = Refs {
+        ptrs: vec![],
+        anchors: HashSet::new(),
+    };
+    collect(doc, &mut refs);
+    refs
+}
+
+fn lint_schema(
+    obj: &Map<String, Value>,
+    up: &UrlPtr,
+    draft: &Draft,
+    refs: &Refs,
+    lints: &mut Vec<Lint>,
+) {
+    let mut add = |url: String, kind| lints.push(Lint { url, kind });
+
+    // inapplicable keywords
+    let types = match obj.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(arr)) => arr.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    };
+    let types = types
+        .into_iter()
+        .filter_map(Type::from_str)
+        .collect::<Vec<_>>();
+    if !types.is_empty() {
+        for kw in obj.keys() {
+            let Some(t) = keyword_type(kw) else {
+                continue;
+            };
+            let applies = types
+                .iter()
+                .any(|&typ| typ == t || (t == Type::Number && typ == Type::Integer));
+            if !applies {
+                add(
+                    up.format(kw),
+                    LintKind::InapplicableKeyword {
+                        keyword: kw.clone(),
+                        types: types.clone(),
+                    },
+                );
+            }
+        }
+    }
+
+    // contradictory bounds
+    let num = |kw| obj.get(kw).and_then(Value::as_f64);
+    let flag = |kw| draft.version == 4 && obj.get(kw) == Some(&Value::Bool(true));
+    let mut bounds = vec![
+        ("minLength", "maxLength", false),
+        ("minItems", "maxItems", false),
+        ("minProperties", "maxProperties", false),
+        ("minContains", "maxContains", false),
+    ];
+    if draft.version == 4 {
+        let exclusive = flag("exclusiveMinimum") || flag("exclusiveMaximum");
+        bounds.push(("minimum", "maximum", exclusive));
+    } else {
+        bounds.extend([
+            ("minimum", "maximum", false),
+            ("exclusiveMinimum", "maximum", true),
+            ("minimum", "exclusiveMaximum", true),
+            ("exclusiveMinimum", "exclusiveMaximum", true),
+        ]);
+    }
+    for (min, max, exclusive) in bounds {
+        let (Some(lo), Some(hi)) = (num(min), num(max)) else {
+            continue;
+        };
+        if lo > hi || (exclusive && lo == hi) {
+            add(
+                up.format(min),
+                LintKind::ContradictoryBounds {
+                    min: min.to_owned(),
+                    max: max.to_owned(),
+                },
+            );
+        }
+    }
+
+    // items array
+    if draft.version >= 2020 && matches!(obj.get("items"), Some(Value::Array(_))) {
+        add(up.format("items"), LintKind::ItemsArray);
+    }
+
+    // exclusive bounds
+    for kw in ["exclusiveMinimum", "exclusiveMaximum"] {
+        let misused = match obj.get(kw) {
+            Some(Value::Bool(_)) => draft.version >= 6,
+            Some(Value::Number(_)) => draft.version == 4,
+            _ => false,
+        };
+        if misused {
+            add(
+                up.format(kw),
+                LintKind::ExclusiveBoundMisuse {
+                    keyword: kw.to_owned(),
+                },
+            );
+        }
+    }
+
+    // unreachable defs
+    for kw in ["$defs", "definitions"] {
+        let Some(Value::Object(defs)) = obj.get(kw) else {
+            continue;
+        };
+        for (name, def) in defs {
+            let ptr = up.ptr.append2(kw, name);
+            if !is_referenced(ptr.as_str(), def, draft, refs) {
+                let def_up = UrlPtr {
+                    url: up.url.clone(),
+                    ptr,
+                };
+                add(
+                    def_up.to_string(),
+                    LintKind::UnreachableDef { name: name.clone() },
+                );
+            }
+        }
+    }
+}
+
+// tells whether definition `def` at `ptr` is referenced.
+fn is_referenced(ptr: &str, def: &Value, draft: &Draft, refs: &Refs) -> bool {
+    if let Value::Object(obj) = def {
+        // addressable by id, possibly from other documents
+        if draft.get_id(obj).is_some() {
+            return true;
+        }
+        let anchors = ["$anchor", "$dynamicAnchor", draft.id]
+            .into_iter()
+            .filter_map(|kw| obj.get(kw).and_then(Value::as_str))
+            .map(|anchor| anchor.trim_start_matches('#'));
+        for anchor in anchors {
+            if refs.anchors.contains(anchor) {
+                return true;
+            }
+        }
+    }
+    // pointers in embedded resources are relative to them, so match suffix
+    refs.ptrs.iter().any(|r| {
+        !r.is_empty()
+            && (ptr.ends_with(r.as_str())
+                || r.strip_prefix(ptr)
+                    .is_some_and(|rest| rest.starts_with('/')))
+    })
+}
+
+// returns the instance type `kw` applies to.
+pub(crate) fn keyword_type(kw: &str) -> Option<Type> {
+    let t = match kw {
+        "minimum" | "maximum" | "exclusiveMinimum" | "exclusiveMaximum" | "multipleOf" => {
+            Type::Number
+        }
+        "minLength" | "maxLength" | "pattern" | "contentEncoding" | "contentMediaType"
+        | "contentSchema" => Type::String,
+        "items" | "prefixItems" | "additionalItems" | "minItems" | "maxItems" | "uniqueItems"
+        | "contains" | "minContains" | "maxContains" | "unevaluatedItems" => Type::Array,
+        "properties"
+        | "patternProperties"
+        | "additionalProperties"
+        | "required"
+        | "minProperties"
+        | "maxProperties"
+        | "propertyNames"
+        | "dependentRequired"
+        | "dependentSchemas"
+        | "dependencies"
+        | "unevaluatedProperties" => Type::Object,
+        _ => return None,
+    };
+    Some(t)
+}
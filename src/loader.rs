@@ -2,19 +2,26 @@ use std::{
     cell::RefCell,
     collections::{HashMap, HashSet},
     error::Error,
+    fmt::Display,
+    sync::Arc,
+    time::{Duration, Instant},
 };
 
 #[cfg(not(target_arch = "wasm32"))]
-use std::fs::File;
+use std::fs;
 
 use appendlist::AppendList;
+use base64::Engine;
 use once_cell::sync::Lazy;
+use percent_encoding::percent_decode_str;
 use serde_json::Value;
 use url::Url;
 
 use crate::{
     compiler::CompileError,
+    content::{MediaType, MEDIA_TYPES},
     draft::{latest, Draft},
+    span::SourceMap,
     util::split,
     UrlPtr,
 };
@@ -23,20 +30,299 @@ use crate::{
 pub trait UrlLoader {
     /// Loads json from given absolute `url`.
     fn load(&self, url: &str) -> Result<Value, Box<dyn Error>>;
+
+    /**
+    Loads content of given absolute `url`, which is either json or raw
+    bytes with media type.
+
+    The compiler calls this rather than [`UrlLoader::load`]. Override it
+    to return [`UrlContent::Bytes`] with media type reported by server,
+    so that the document is parsed accordingly. Default implementation
+    returns json loaded by [`UrlLoader::load`].
+    */
+    fn load_content(&self, url: &str) -> Result<UrlContent, Box<dyn Error>> {
+        self.load(url).map(UrlContent::Json)
+    }
+}
+
+/// Content returned by [`UrlLoader::load_content`].
+#[derive(Debug)]
+pub enum UrlContent {
+    /// already parsed json.
+    Json(Value),
+    /**
+    Raw bytes, which are parsed based on `media_type`.
+
+    Media types with `+json` suffix, and the ones registered using
+    [`Compiler::register_content_media_type`](crate::Compiler::register_content_media_type)
+    with `json_compatible` are supported. With `yaml` and `json5` features,
+    yaml and json5 media types are supported too. Bytes with no or unknown
+    media type are parsed as json.
+    */
+    Bytes {
+        bytes: Vec<u8>,
+        media_type: Option<String>,
+    },
+}
+
+impl UrlContent {
+    // parses bytes, using custom `media_types` in addition to builtin ones.
+    pub(crate) fn parse(
+        self,
+        media_types: &HashMap<&'static str, MediaType>,
+    ) -> Result<Value, Box<dyn Error>> {
+        let (bytes, media_type) = match self {
+            UrlContent::Json(v) => return Ok(v),
+            UrlContent::Bytes { bytes, media_type } => (bytes, media_type),
+        };
+        match find_media_type(media_types, media_type.as_deref()) {
+            (media_type, Some(mt)) => {
+                if !mt.json_compatible {
+                    Err(format!("media type {media_type} is not json compatible"))?;
+                }
+                let v = (mt.func)(&bytes, true)?;
+                Ok(v.ok_or(format!("media type {media_type} returned no json"))?)
+            }
+            _ => Ok(serde_json::from_slice(&bytes)?),
+        }
+    }
+
+    // returns the text, if it is parsed as json.
+    pub(crate) fn json_text(&self, media_types: &HashMap<&'static str, MediaType>) -> Option<&str> {
+        let UrlContent::Bytes { bytes, media_type } = self else {
+            return None;
+        };
+        match find_media_type(media_types, media_type.as_deref()) {
+            (_, Some(_)) => None,
+            _ => std::str::from_utf8(bytes).ok(),
+        }
+    }
+}
+
+// returns normalized media type, along with its parser if it is not json.
+fn find_media_type<'a>(
+    media_types: &'a HashMap<&'static str, MediaType>,
+    media_type: Option<&str>,
+) -> (String, Option<&'a MediaType>) {
+    let media_type = media_type.unwrap_or_default();
+    let media_type = media_type.split(';').next().unwrap_or_default();
+    let media_type = media_type.trim().to_ascii_lowercase();
+    if media_type == "application/json" || media_type.ends_with("+json") {
+        return (media_type, None);
+    }
+    let found = media_types
+        .get(media_type.as_str())
+        .or_else(|| DOC_MEDIA_TYPES.get(media_type.as_str()));
+    (media_type, found)
+}
+
+// media types supported for schema documents, in addition to json.
+static DOC_MEDIA_TYPES: Lazy<HashMap<&'static str, MediaType>> = Lazy::new(|| {
+    #[allow(unused_mut)]
+    let mut m = MEDIA_TYPES.clone();
+    #[cfg(feature = "json5")]
+    m.insert(
+        "application/json5",
+        MediaType {
+            name: "application/json5",
+            json_compatible: true,
+            func: parse_json5,
+        },
+    );
+    #[cfg(feature = "yaml")]
+    for name in ["application/yaml", "application/x-yaml", "text/yaml"] {
+        m.insert(
+            name,
+            MediaType {
+                name,
+                json_compatible: true,
+                func: parse_yaml,
+            },
+        );
+    }
+    m
+});
+
+#[cfg(feature = "json5")]
+fn parse_json5(bytes: &[u8], _deserialize: bool) -> Result<Option<Value>, Box<dyn Error>> {
+    Ok(Some(crate::json5::parse(std::str::from_utf8(bytes)?)?))
+}
+
+#[cfg(feature = "yaml")]
+fn parse_yaml(bytes: &[u8], _deserialize: bool) -> Result<Option<Value>, Box<dyn Error>> {
+    Ok(Some(crate::yaml::parse(std::str::from_utf8(bytes)?)?))
 }
 
 // --
 
+/**
+Retry policy for loading urls of a scheme.
+
+Only transient failures are retried. An error is permanent, if it or any
+of its sources is [`std::io::Error`] of kind `NotFound`, `PermissionDenied`,
+`InvalidInput`, `InvalidData` or `Unsupported`, or is url parse error or
+[`CompileError::UnsupportedUrlScheme`]. So [`UrlLoader`] should report
+http `404` as `NotFound`, to avoid retrying it.
+
+See [`Compiler::set_load_policy`](crate::Compiler::set_load_policy).
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadPolicy {
+    /// number of retries after first attempt fails.
+    pub retries: usize,
+    /// delay before first retry, doubled for each subsequent retry.
+    pub backoff: Duration,
+    /**
+    time since first attempt, after which no more retries are made.
+    `None` means no limit.
+
+    This is not a timeout of an attempt. Attempt in progress is not
+    interrupted, so [`UrlLoader`] should use its own timeout per request.
+    */
+    pub retry_budget: Option<Duration>,
+}
+
+impl Default for LoadPolicy {
+    fn default() -> Self {
+        Self {
+            retries: 2,
+            backoff: Duration::from_millis(100),
+            retry_budget: None,
+        }
+    }
+}
+
+/// Failed attempt to load url, in [`LoadAttemptsError`].
+#[derive(Debug)]
+pub struct LoadAttempt {
+    /// time since first attempt, when this attempt failed.
+    pub elapsed: Duration,
+    /// error returned by [`UrlLoader`].
+    pub error: Box<dyn Error>,
+}
+
+/// Error in [`CompileError::LoadUrlError`], when all attempts of [`LoadPolicy`] fail.
+#[derive(Debug)]
+pub struct LoadAttemptsError {
+    /// attempts made, in order.
+    pub attempts: Vec<LoadAttempt>,
+    /// whether retries stopped because [`LoadPolicy::retry_budget`] would
+    /// be exceeded.
+    pub budget_exhausted: bool,
+}
+
+impl Error for LoadAttemptsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.attempts.last().map(|a| a.error.as_ref())
+    }
+}
+
+impl Display for LoadAttemptsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let n = self.attempts.len();
+        if self.budget_exhausted {
+            write!(f, "retry budget exhausted after {n} attempts")?;
+        } else {
+            write!(f, "failed after {n} attempts")?;
+        }
+        if let Some(last) = self.attempts.last() {
+            write!(f, ": {}", last.error)?;
+        }
+        Ok(())
+    }
+}
+
+// tells whether load failure `err` will not go away on retry.
+// see LoadPolicy.
+fn is_permanent(err: &(dyn Error + 'static)) -> bool {
+    use std::io::ErrorKind::*;
+    let mut err = Some(err);
+    while let Some(e) = err {
+        if let Some(e) = e.downcast_ref::<std::io::Error>() {
+            if matches!(
+                e.kind(),
+                NotFound | PermissionDenied | InvalidInput | InvalidData | Unsupported
+            ) {
+                return true;
+            }
+        }
+        if e.is::<url::ParseError>()
+            || matches!(
+                e.downcast_ref::<CompileError>(),
+                Some(CompileError::UnsupportedUrlScheme { .. })
+            )
+        {
+            return true;
+        }
+        err = e.source();
+    }
+    false
+}
+
+// --
+
+/**
+Loads json embedded in `data:` url, as per [RFC 2397](https://www.rfc-editor.org/rfc/rfc2397).
+
+For example `data:application/schema+json;base64,eyJ0eXBlIjoic3RyaW5nIn0=`.
+The data is parsed based on media type, as explained in [`UrlContent::Bytes`].
+
+It is registered by default. Since it does no io, such urls are loaded
+even in offline mode.
+*/
+pub struct DataUrlLoader;
+
+impl UrlLoader for DataUrlLoader {
+    fn load(&self, url: &str) -> Result<Value, Box<dyn Error>> {
+        self.load_content(url)?.parse(&HashMap::new())
+    }
+
+    fn load_content(&self, url: &str) -> Result<UrlContent, Box<dyn Error>> {
+        let Some(url) = url.strip_prefix("data:") else {
+            Err("not a data url")?
+        };
+        let (url, _) = split(url); // ignore fragment
+        let Some((media_type, data)) = url.split_once(',') else {
+            Err("missing comma in data url")?
+        };
+        let data = percent_decode_str(data).collect::<Vec<_>>();
+        let (media_type, bytes) = match media_type.strip_suffix(";base64") {
+            Some(media_type) => (
+                media_type,
+                base64::engine::general_purpose::STANDARD.decode(data)?,
+            ),
+            None => (media_type, data),
+        };
+        Ok(UrlContent::Bytes {
+            bytes,
+            media_type: Some(media_type.to_owned()).filter(|mt| !mt.is_empty()),
+        })
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 pub struct FileLoader;
 
 #[cfg(not(target_arch = "wasm32"))]
 impl UrlLoader for FileLoader {
     fn load(&self, url: &str) -> Result<Value, Box<dyn Error>> {
+        self.load_content(url)?.parse(&HashMap::new())
+    }
+
+    /// media type is derived from file extension, `.yaml` and `.yml`
+    /// being yaml, `.json5` being json5, and others json.
+    fn load_content(&self, url: &str) -> Result<UrlContent, Box<dyn Error>> {
         let url = Url::parse(url)?;
         let path = url.to_file_path().map_err(|_| "invalid file path")?;
-        let file = File::open(path)?;
-        Ok(serde_json::from_reader(file)?)
+        let media_type = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml" | "yml") => "application/yaml",
+            Some("json5") => "application/json5",
+            _ => "application/json",
+        };
+        Ok(UrlContent::Bytes {
+            bytes: fs::read(path)?,
+            media_type: Some(media_type.to_owned()),
+        })
     }
 }
 
@@ -69,14 +355,31 @@ impl UrlLoader for SchemeUrlLoader {
         };
         loader.load(url.as_str())
     }
+
+    fn load_content(&self, url: &str) -> Result<UrlContent, Box<dyn Error>> {
+        let url = Url::parse(url)?;
+        let Some(loader) = self.loaders.get(url.scheme()) else {
+            return Err(CompileError::UnsupportedUrlScheme {
+                url: url.as_str().to_owned(),
+            }
+            .into());
+        };
+        loader.load_content(url.as_str())
+    }
 }
 
 // --
 
 pub(crate) struct DefaultUrlLoader {
     doc_map: RefCell<HashMap<Url, usize>>,
-    doc_list: AppendList<Value>,
+    doc_list: AppendList<Arc<Value>>,
     loader: Box<dyn UrlLoader>,
+    pub(crate) offline: bool, // only docs added, and standard metaschemas
+    pub(crate) url_prefixes: Vec<(String, String)>, // from => to, longest first
+    pub(crate) url_aliases: HashMap<String, String>,
+    pub(crate) policies: HashMap<String, LoadPolicy>, // scheme => policy
+    pub(crate) media_types: HashMap<&'static str, MediaType>, // custom
+    pub(crate) sources: RefCell<HashMap<Url, SourceMap>>,
 }
 
 impl DefaultUrlLoader {
@@ -84,10 +387,17 @@ impl DefaultUrlLoader {
         let mut loader = SchemeUrlLoader::new();
         #[cfg(not(target_arch = "wasm32"))]
         loader.register("file", Box::new(FileLoader));
+        loader.register("data", Box::new(DataUrlLoader));
         Self {
             doc_map: Default::default(),
             doc_list: AppendList::new(),
             loader: Box::new(loader),
+            offline: false,
+            url_prefixes: vec![],
+            url_aliases: HashMap::new(),
+            policies: HashMap::new(),
+            media_types: HashMap::new(),
+            sources: Default::default(),
         }
     }
 
@@ -96,18 +406,46 @@ impl DefaultUrlLoader {
             .borrow()
             .get(url)
             .and_then(|i| self.doc_list.get(*i))
+            .map(Arc::as_ref)
+    }
+
+    // returns doc, sharing it with the loader.
+    pub(crate) fn get_shared_doc(&self, url: &Url) -> Option<Arc<Value>> {
+        self.doc_map
+            .borrow()
+            .get(url)
+            .and_then(|i| self.doc_list.get(*i))
+            .cloned()
     }
 
     pub fn add_doc(&self, url: Url, json: Value) {
         if self.get_doc(&url).is_some() {
             return;
         }
-        self.doc_list.push(json);
+        self.doc_list.push(Arc::new(json));
         self.doc_map
             .borrow_mut()
             .insert(url, self.doc_list.len() - 1);
     }
 
+    // removes doc, returning true if it was present.
+    pub(crate) fn remove_doc(&mut self, url: &Url) -> bool {
+        let doc_map = self.doc_map.get_mut();
+        if doc_map.remove(url).is_none() {
+            return false;
+        }
+        self.sources.get_mut().remove(url);
+        // compact, so that removed doc is dropped
+        let doc_list = std::mem::take(&mut self.doc_list);
+        for i in doc_map.values_mut() {
+            if let Some(doc) = doc_list.get(*i) {
+                self.doc_list.push(Arc::clone(doc));
+                *i = self.doc_list.len() - 1;
+            }
+        }
+        true
+    }
+
     pub fn use_loader(&mut self, loader: Box<dyn UrlLoader>) {
         self.loader = loader;
     }
@@ -117,19 +455,39 @@ impl DefaultUrlLoader {
             return Ok(doc);
         }
 
-        // check in STD_METAFILES
+        // embedded metaschemas are used, without consulting loader
         let doc = if let Some(content) = load_std_meta(url.as_str()) {
             serde_json::from_str::<Value>(content).map_err(|e| CompileError::LoadUrlError {
                 url: url.to_string(),
                 src: e.into(),
             })?
+        } else if self.offline && url.scheme() != "data" {
+            return Err(CompileError::OfflineLoadError {
+                url: url.as_str().to_owned(),
+            });
         } else {
-            self.loader
-                .load(url.as_str())
-                .map_err(|src| CompileError::LoadUrlError {
-                    url: url.as_str().to_owned(),
-                    src,
-                })?
+            let mapped = self.url_aliases.get(url.as_str()).cloned();
+            let mapped = mapped
+                .or_else(|| {
+                    self.url_prefixes.iter().find_map(|(from, to)| {
+                        let rest = url.as_str().strip_prefix(from.as_str())?;
+                        Some(format!("{to}{rest}"))
+                    })
+                })
+                .unwrap_or_else(|| url.as_str().to_owned());
+            let load_err = |src| CompileError::LoadUrlError {
+                url: url.as_str().to_owned(),
+                src,
+            };
+            let content = self.load_with_policy(&mapped).map_err(load_err)?;
+            let source = content
+                .json_text(&self.media_types)
+                .and_then(SourceMap::from_json);
+            let doc = content.parse(&self.media_types).map_err(load_err)?;
+            if let Some(source) = source {
+                self.sources.borrow_mut().insert(url.clone(), source);
+            }
+            doc
         };
         self.add_doc(url.clone(), doc);
         return self
@@ -137,6 +495,39 @@ impl DefaultUrlLoader {
             .ok_or(CompileError::Bug("doc must exist".into()));
     }
 
+    fn load_with_policy(&self, url: &str) -> Result<UrlContent, Box<dyn Error>> {
+        let scheme = url.split_once(':').map_or("", |(scheme, _)| scheme);
+        let Some(policy) = self.policies.get(scheme) else {
+            return self.loader.load_content(url);
+        };
+        let start = Instant::now();
+        let mut backoff = policy.backoff;
+        let mut attempts = vec![];
+        loop {
+            let error = match self.loader.load_content(url) {
+                Ok(content) => return Ok(content),
+                Err(e) => e,
+            };
+            let elapsed = start.elapsed();
+            let permanent = is_permanent(error.as_ref());
+            attempts.push(LoadAttempt { elapsed, error });
+            if permanent || attempts.len() > policy.retries {
+                return Err(LoadAttemptsError {
+                    attempts,
+                    budget_exhausted: false,
+                })?;
+            }
+            if policy.retry_budget.is_some_and(|t| elapsed + backoff >= t) {
+                return Err(LoadAttemptsError {
+                    attempts,
+                    budget_exhausted: true,
+                })?;
+            }
+            std::thread::sleep(backoff);
+            backoff *= 2;
+        }
+    }
+
     pub(crate) fn get_draft(
         &self,
         up: &UrlPtr,
@@ -197,47 +588,129 @@ impl DefaultUrlLoader {
     }
 }
 
-pub(crate) static STD_METAFILES: Lazy<HashMap<String, &str>> = Lazy::new(|| {
-    let mut files = HashMap::new();
-    macro_rules! add {
-        ($path:expr) => {
-            files.insert(
-                $path["metaschemas/".len()..].to_owned(),
-                include_str!($path),
-            );
-        };
-    }
-    add!("metaschemas/draft-04/schema");
-    add!("metaschemas/draft-06/schema");
-    add!("metaschemas/draft-07/schema");
-    add!("metaschemas/draft/2019-09/schema");
-    add!("metaschemas/draft/2019-09/meta/core");
-    add!("metaschemas/draft/2019-09/meta/applicator");
-    add!("metaschemas/draft/2019-09/meta/validation");
-    add!("metaschemas/draft/2019-09/meta/meta-data");
-    add!("metaschemas/draft/2019-09/meta/format");
-    add!("metaschemas/draft/2019-09/meta/content");
-    add!("metaschemas/draft/2020-12/schema");
-    add!("metaschemas/draft/2020-12/meta/core");
-    add!("metaschemas/draft/2020-12/meta/applicator");
-    add!("metaschemas/draft/2020-12/meta/unevaluated");
-    add!("metaschemas/draft/2020-12/meta/validation");
-    add!("metaschemas/draft/2020-12/meta/meta-data");
-    add!("metaschemas/draft/2020-12/meta/content");
-    add!("metaschemas/draft/2020-12/meta/format-annotation");
-    add!("metaschemas/draft/2020-12/meta/format-assertion");
-    files
+/// Standard metaschema embedded in this crate, see [`metaschemas`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Metaschema {
+    /// canonical url, without fragment. for example
+    /// `https://json-schema.org/draft/2020-12/meta/core`.
+    pub url: &'static str,
+    /// draft the metaschema belongs to.
+    pub draft: crate::Draft,
+    /// `false` for the metaschema of draft, and `true` for
+    /// the metaschemas of its vocabularies.
+    pub vocabulary: bool,
+    /// json text, as published.
+    pub content: &'static str,
+}
+
+impl Metaschema {
+    /// Returns parsed json of the metaschema.
+    pub fn json(&self) -> Value {
+        serde_json::from_str(self.content).expect("std metaschema must be valid json")
+    }
+}
+
+static STD_METASCHEMAS: &[Metaschema] = {
+    use crate::Draft::*;
+    macro_rules! meta {
+        ($draft:expr, $vocab:expr, $host:literal, $path:literal) => {
+            Metaschema {
+                url: concat!($host, "json-schema.org/", $path),
+                draft: $draft,
+                vocabulary: $vocab,
+                content: include_str!(concat!("metaschemas/", $path)),
+            }
+        };
+    }
+    &[
+        meta!(V4, false, "http://", "draft-04/schema"),
+        meta!(V6, false, "http://", "draft-06/schema"),
+        meta!(V7, false, "http://", "draft-07/schema"),
+        meta!(V2019_09, false, "https://", "draft/2019-09/schema"),
+        meta!(V2019_09, true, "https://", "draft/2019-09/meta/core"),
+        meta!(V2019_09, true, "https://", "draft/2019-09/meta/applicator"),
+        meta!(V2019_09, true, "https://", "draft/2019-09/meta/validation"),
+        meta!(V2019_09, true, "https://", "draft/2019-09/meta/meta-data"),
+        meta!(V2019_09, true, "https://", "draft/2019-09/meta/format"),
+        meta!(V2019_09, true, "https://", "draft/2019-09/meta/content"),
+        meta!(V2020_12, false, "https://", "draft/2020-12/schema"),
+        meta!(V2020_12, true, "https://", "draft/2020-12/meta/core"),
+        meta!(V2020_12, true, "https://", "draft/2020-12/meta/applicator"),
+        meta!(V2020_12, true, "https://", "draft/2020-12/meta/unevaluated"),
+        meta!(V2020_12, true, "https://", "draft/2020-12/meta/validation"),
+        meta!(V2020_12, true, "https://", "draft/2020-12/meta/meta-data"),
+        meta!(V2020_12, true, "https://", "draft/2020-12/meta/content"),
+        meta!(
+            V2020_12,
+            true,
+            "https://",
+            "draft/2020-12/meta/format-annotation"
+        ),
+        meta!(
+            V2020_12,
+            true,
+            "https://",
+            "draft/2020-12/meta/format-assertion"
+        ),
+    ]
+};
+
+// path relative to `json-schema.org/` => metaschema
+static STD_METAFILES: Lazy<HashMap<&str, &Metaschema>> = Lazy::new(|| {
+    STD_METASCHEMAS
+        .iter()
+        .map(|m| (m.url.split_once("json-schema.org/").unwrap().1, m))
+        .collect()
 });
 
-fn load_std_meta(url: &str) -> Option<&'static str> {
+/**
+Returns the standard metaschemas of all supported drafts, along with
+the metaschemas of their vocabularies.
+
+These are embedded in this crate, and are always used instead of
+loading them: neither [`UrlLoader`] nor [`Compiler::enable_offline`]
+affects them. So `$schema` is resolved without network access.
+
+```
+# use boon::*;
+let meta = metaschemas()
+    .find(|m| m.url == "https://json-schema.org/draft/2020-12/meta/core")
+    .unwrap();
+assert_eq!(meta.draft, Draft::V2020_12);
+assert!(meta.vocabulary);
+assert_eq!(meta.json()["$id"], meta.url);
+```
+
+[`Compiler::enable_offline`]: crate::Compiler::enable_offline
+*/
+pub fn metaschemas() -> impl Iterator<Item = Metaschema> {
+    STD_METASCHEMAS.iter().copied()
+}
+
+/**
+Returns the standard metaschema embedded for `url`, if any.
+
+`url` accepts both `http` and `https` and ignores any fragment.
+`https://json-schema.org/schema` refers to latest draft.
+
+```
+# use boon::*;
+let meta = metaschema("http://json-schema.org/draft-07/schema#").unwrap();
+assert_eq!(meta.draft, Draft::V7);
+assert!(metaschema("https://example.com/schema").is_none());
+```
+*/
+pub fn metaschema(url: &str) -> Option<Metaschema> {
+    let (url, _) = split(url);
     let meta = url
         .strip_prefix("http://json-schema.org/")
-        .or_else(|| url.strip_prefix("https://json-schema.org/"));
-    if let Some(meta) = meta {
-        if meta == "schema" {
-            return load_std_meta(latest().url);
-        }
-        return STD_METAFILES.get(meta).cloned();
+        .or_else(|| url.strip_prefix("https://json-schema.org/"))?;
+    if meta == "schema" {
+        return metaschema(latest().url);
     }
-    None
+    STD_METAFILES.get(meta).map(|m| **m)
+}
+
+pub(crate) fn load_std_meta(url: &str) -> Option<&'static str> {
+    metaschema(url).map(|m| m.content)
 }
@@ -0,0 +1,118 @@
+use serde_json::{Map, Value};
+
+use crate::*;
+
+/// Metadata annotations of a schema, returned by [`Schemas::metadata`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metadata {
+    /// first `title` found.
+    pub title: Option<String>,
+    /// first `description` found.
+    pub description: Option<String>,
+    /// `examples` of all the schemas.
+    pub examples: Vec<Value>,
+    /// first `default` found.
+    pub default: Option<Value>,
+    /// whether any of the schemas is marked `deprecated`.
+    pub deprecated: bool,
+    /// whether any of the schemas is marked `readOnly`.
+    pub read_only: bool,
+    /// whether any of the schemas is marked `writeOnly`.
+    pub write_only: bool,
+    /// keywords not defined by the draft, such as `x-*` extensions. value
+    /// found first is used. see [`Schemas::unknown_keywords`].
+    pub unknown_keywords: Map<String, Value>,
+}
+
+impl Metadata {
+    fn collect(schemas: &Schemas, list: Vec<SchemaIndex>) -> Self {
+        let mut m = Metadata::default();
+        for sch in list {
+            let s = schemas.get(sch);
+            m.title = m.title.or_else(|| s.title.clone());
+            m.description = m.description.or_else(|| s.description.clone());
+            for example in &s.examples {
+                if !m.examples.contains(example) {
+                    m.examples.push(example.clone());
+                }
+            }
+            m.default = m.default.or_else(|| s.default.clone());
+            m.deprecated |= s.deprecated;
+            m.read_only |= s.read_only;
+            m.write_only |= s.write_only;
+            for (kw, v) in schemas.unknown_keywords(sch) {
+                if !m.unknown_keywords.contains_key(kw) {
+                    m.unknown_keywords.insert(kw.to_owned(), v.clone());
+                }
+            }
+        }
+        m
+    }
+}
+
+impl Schemas {
+    /**
+    Returns metadata annotations of schema identified by `sch_index`.
+
+    Annotations are collected from the schema and the subschemas it
+    applies unconditionally i.e. `$ref` and `allOf`, in that order. So
+    annotations of the schema take precedence over those of the schema it
+    refers to.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let schema = json!({
+        "$ref": "#/$defs/port",
+        "title": "http port",
+        "$defs": {
+            "port": {"title": "port", "description": "tcp port", "examples": [8080]}
+        }
+    });
+    compiler.add_resource("port.json", schema)?;
+    let sch_index = compiler.compile("port.json", &mut schemas)?;
+
+    let m = schemas.metadata(sch_index);
+    assert_eq!(m.title.as_deref(), Some("http port"));
+    assert_eq!(m.description.as_deref(), Some("tcp port"));
+    assert_eq!(m.examples, [json!(8080)]);
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn metadata(&self, sch_index: SchemaIndex) -> Metadata {
+        Metadata::collect(self, self.applied_schemas(sch_index))
+    }
+
+    /**
+    Returns metadata annotations applicable at location `instance_ptr` of
+    `instance`, validated against schema `sch_index`.
+
+    Applicable schemas are found as in [`Schemas::describe_at`], so that
+    `instance_ptr` need not exist in `instance`. Returns `None` if
+    `instance_ptr` is not a valid json-pointer.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn metadata_at(
+        &self,
+        instance: &Value,
+        sch_index: SchemaIndex,
+        instance_ptr: &str,
+    ) -> Option<Metadata> {
+        let list = self.schemas_at(instance, sch_index, instance_ptr)?;
+        Some(Metadata::collect(self, list))
+    }
+}
@@ -0,0 +1,281 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Display, Formatter},
+    mem::size_of,
+};
+
+use serde::{ser::SerializeMap, Serialize};
+use serde_json::Value;
+
+use crate::{output::write_json_to_fmt, *};
+
+/**
+Complexity metrics of a schema, computed over all the subschemas
+reachable from it, including referenced ones.
+
+It implements `serde::Serialize` and `Display` to print json.
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaMetrics {
+    /// number of subschemas, including the schema itself.
+    pub subschemas: usize,
+    /// length of longest chain of nested subschemas, ignoring cycles.
+    pub max_depth: usize,
+    /// max number of subschemas in single `allOf`, `anyOf` or `oneOf`.
+    pub max_branching: usize,
+    /// number of regexes used by `pattern` and `patternProperties`.
+    pub regexes: usize,
+    /// whether any subschema refers to itself, directly or indirectly.
+    pub recursive: bool,
+}
+
+/**
+Size statistics of [`Schemas`], as returned by [`Schemas::stats`].
+
+It implements `serde::Serialize` and `Display` to print json.
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemasStats {
+    /// number of compiled schemas, including subschemas.
+    pub schemas: usize,
+    /// number of distinct regexes. regexes with same pattern are shared.
+    pub regexes: usize,
+    /// number of strings owned by compiled schemas, such as locations,
+    /// property names and anchors.
+    pub strings: usize,
+    /// number of distinct values among `strings`.
+    pub distinct_strings: usize,
+    /// number of schema documents retained.
+    pub documents: usize,
+    /// approximate heap usage in bytes.
+    pub heap_bytes: usize,
+}
+
+impl Schemas {
+    /**
+    Computes size statistics of all schemas compiled so far.
+
+    `heap_bytes` is an estimate, summing up the sizes of compiled schemas,
+    the strings and json values they own, and the documents retained. It
+    does not include memory used internally by regex engines, and
+    allocator overhead. Use it to enforce quotas, when compiling untrusted
+    schemas, rather than for exact accounting.
+    */
+    pub fn stats(&self) -> SchemasStats {
+        let mut st = SchemasStats {
+            schemas: self.list.len(),
+            documents: self.docs.len(),
+            ..Default::default()
+        };
+        let mut strings = HashSet::new();
+        let mut regexes = HashSet::new();
+        let mut heap = self.list.capacity() * size_of::<Schema>();
+        heap += self.indexes.capacity() * size_of::<SchemaIndex>();
+        heap += self.map.capacity() * (size_of::<UrlPtr>() + size_of::<usize>());
+        for up in self.map.keys() {
+            heap += up.url.as_str().len() + up.ptr.as_str().len();
+        }
+        heap += self.docs.capacity() * (size_of::<url::Url>() + size_of::<Arc<Value>>());
+        for (url, doc) in &self.docs {
+            heap += url.as_str().len() + size_of::<Value>() + value_heap(doc);
+        }
+
+        // property names are interned, and shared by schemas
+        heap += self.names.heap_size();
+        st.strings += self.names.len();
+        strings.extend(self.names.iter().map(|s| s.to_string()));
+
+        let mut add_str = |s: &str, heap: &mut usize| {
+            st.strings += 1;
+            strings.insert(s.to_owned());
+            *heap += s.len();
+        };
+        for sch in &self.list {
+            add_str(&sch.loc, &mut heap);
+            if let Some(id) = &sch.id {
+                heap += id.as_str().len();
+            }
+            for s in sch.anchors.iter().chain(&sch.bson_types) {
+                add_str(s, &mut heap);
+            }
+            for s in [
+                &sch.dynamic_anchor,
+                &sch.format_name,
+                &sch.title,
+                &sch.description,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                add_str(s, &mut heap);
+            }
+            if let Some(DynamicRef {
+                anchor: Some(s), ..
+            }) = &sch.dynamic_ref
+            {
+                add_str(s, &mut heap);
+            }
+            heap +=
+                sch.dynamic_anchors.capacity() * (size_of::<String>() + size_of::<SchemaIndex>());
+            for s in sch.dynamic_anchors.keys() {
+                add_str(s, &mut heap);
+            }
+            heap += sch.properties.capacity() * (size_of::<Name>() + size_of::<SchemaIndex>());
+            for (s, props) in &sch.dependent_required {
+                add_str(s, &mut heap);
+                for s in props {
+                    add_str(s, &mut heap);
+                }
+            }
+            for (s, _) in &sch.dependent_schemas {
+                add_str(s, &mut heap);
+            }
+            for (s, dep) in &sch.dependencies {
+                add_str(s, &mut heap);
+                if let Dependency::Props(props) = dep {
+                    for s in props {
+                        add_str(s, &mut heap);
+                    }
+                }
+            }
+
+            let values = sch.enum_.iter().flat_map(|e| &e.values);
+            let values = values.chain(&sch.constant).chain(&sch.default);
+            for v in values.chain(&sch.examples) {
+                heap += size_of::<Value>() + value_heap(v);
+            }
+            for re in sch
+                .pattern
+                .iter()
+                .chain(sch.pattern_properties.iter().map(|(re, _)| re))
+            {
+                if regexes.insert(re.as_str()) {
+                    heap += re.as_str().len();
+                }
+            }
+
+            // vectors
+            heap += sch.pattern_properties.capacity() * size_of::<(Regex, SchemaIndex)>();
+            heap += (sch.anchors.capacity() + sch.bson_types.capacity()) * size_of::<String>();
+            heap += (sch.required.capacity() + sch.property_order.capacity()) * size_of::<Name>();
+            if let Some(bits) = &sch.required_bits {
+                heap += bits.capacity() * (size_of::<Name>() + size_of::<usize>());
+            }
+            heap += sch.dependent_required.capacity() * size_of::<(String, Vec<String>)>();
+            heap += sch.dependent_schemas.capacity() * size_of::<(String, SchemaIndex)>();
+            heap += sch.dependencies.capacity() * size_of::<(String, Dependency)>();
+            heap += sch.examples.capacity() * size_of::<Value>();
+            heap += sch.data.capacity() * size_of::<(&str, DataRef)>();
+            heap += sch.data.iter().map(|(_, d)| d.heap_size()).sum::<usize>();
+        }
+        st.regexes = regexes.len();
+        st.distinct_strings = strings.len();
+        st.heap_bytes = heap;
+        st
+    }
+
+    /**
+    Computes complexity metrics of schema identified by `sch_index`.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    */
+    pub fn metrics(&self, sch_index: SchemaIndex) -> SchemaMetrics {
+        let mut m = SchemaMetrics::default();
+        let mut depths = HashMap::new();
+        m.max_depth = self.visit(sch_index, &mut m, &mut depths);
+        m
+    }
+
+    // returns depth of `idx`. depths of schemas on stack are `None`.
+    fn visit(
+        &self,
+        idx: SchemaIndex,
+        m: &mut SchemaMetrics,
+        depths: &mut HashMap<SchemaIndex, Option<usize>>,
+    ) -> usize {
+        match depths.get(&idx) {
+            Some(Some(depth)) => return *depth,
+            Some(None) => {
+                m.recursive = true;
+                return 0;
+            }
+            None => {}
+        }
+        depths.insert(idx, None);
+
+        let sch = self.get(idx);
+        m.subschemas += 1;
+        m.regexes += sch.pattern.iter().count() + sch.pattern_properties.len();
+        m.max_branching = [sch.all_of, sch.any_of, sch.one_of]
+            .into_iter()
+            .map(IndexRange::len)
+            .fold(m.max_branching, usize::max);
+        let mut depth = 0;
+        for (_, child) in sch.children(self) {
+            depth = depth.max(self.visit(child, m, depths));
+        }
+
+        depths.insert(idx, Some(depth + 1));
+        depth + 1
+    }
+}
+
+impl Serialize for SchemaMetrics {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(5))?;
+        map.serialize_entry("subschemas", &self.subschemas)?;
+        map.serialize_entry("maxDepth", &self.max_depth)?;
+        map.serialize_entry("maxBranching", &self.max_branching)?;
+        map.serialize_entry("regexes", &self.regexes)?;
+        map.serialize_entry("recursive", &self.recursive)?;
+        map.end()
+    }
+}
+
+impl Display for SchemaMetrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_json_to_fmt(f, self)
+    }
+}
+
+impl Serialize for SchemasStats {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(6))?;
+        map.serialize_entry("schemas", &self.schemas)?;
+        map.serialize_entry("regexes", &self.regexes)?;
+        map.serialize_entry("strings", &self.strings)?;
+        map.serialize_entry("distinctStrings", &self.distinct_strings)?;
+        map.serialize_entry("documents", &self.documents)?;
+        map.serialize_entry("heapBytes", &self.heap_bytes)?;
+        map.end()
+    }
+}
+
+impl Display for SchemasStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_json_to_fmt(f, self)
+    }
+}
+
+// returns approximate heap bytes owned by `v`, excluding `v` itself.
+fn value_heap(v: &Value) -> usize {
+    match v {
+        Value::String(s) => s.capacity(),
+        Value::Array(arr) => {
+            arr.capacity() * size_of::<Value>() + arr.iter().map(value_heap).sum::<usize>()
+        }
+        Value::Object(obj) => obj
+            .iter()
+            .map(|(k, v)| size_of::<String>() + k.capacity() + size_of::<Value>() + value_heap(v))
+            .sum(),
+        _ => 0,
+    }
+}
@@ -0,0 +1,493 @@
+use std::fmt::Display;
+
+use percent_encoding::percent_decode_str;
+use serde_json::{Map, Value};
+use url::Url;
+
+use crate::{draft::*, util::*};
+
+/// Construct which [`migrate`] could not rewrite faithfully, so that it
+/// needs human review.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationNote {
+    /// json-pointer to the schema with the construct, in migrated document.
+    pub ptr: String,
+    /// kind of construct.
+    pub kind: MigrationNoteKind,
+}
+
+/// Kind of [`MigrationNote`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationNoteKind {
+    /// sibling `keywords` of `$ref` are ignored before draft 2019-09, but
+    /// apply in 2020-12.
+    RefSiblings { keywords: Vec<String> },
+    /// `format` is asserted before draft 2019-09, but is only annotation in
+    /// 2020-12, unless format assertions are enabled.
+    FormatAssertion { format: String },
+    /// `$ref` into other document, through keywords which are renamed when
+    /// that document is migrated.
+    ExternalRef { ref_: String },
+    /// `keyword` is left as is, since some of its entries are already in
+    /// `replacement`.
+    Conflict {
+        keyword: &'static str,
+        replacement: &'static str,
+    },
+    /// `$schema` refers to custom metaschema, which is left as is.
+    CustomMetaschema { url: String },
+    /// `keyword` of draft 2019-09 has no equivalent in 2020-12, and is
+    /// left as is.
+    Unsupported { keyword: &'static str },
+}
+
+impl Display for MigrationNote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ptr = quote(&self.ptr);
+        match &self.kind {
+            MigrationNoteKind::RefSiblings { keywords } => write!(
+                f,
+                "{} at {ptr} were ignored beside $ref, but now apply",
+                join_iter(keywords.iter().map(quote), ", ")
+            ),
+            MigrationNoteKind::FormatAssertion { format } => {
+                write!(f, "format {} at {ptr} is no longer asserted", quote(format))
+            }
+            MigrationNoteKind::ExternalRef { ref_ } => write!(
+                f,
+                "$ref {} at {ptr} may need update, after migrating the document it refers",
+                quote(ref_)
+            ),
+            MigrationNoteKind::Conflict {
+                keyword,
+                replacement,
+            } => write!(
+                f,
+                "{} at {ptr} is not migrated, as it overlaps with {}",
+                quote(keyword),
+                quote(replacement)
+            ),
+            MigrationNoteKind::CustomMetaschema { url } => {
+                write!(
+                    f,
+                    "custom metaschema {} at {ptr} is not migrated",
+                    quote(url)
+                )
+            }
+            MigrationNoteKind::Unsupported { keyword } => {
+                write!(f, "{} at {ptr} is not supported in 2020-12", quote(keyword))
+            }
+        }
+    }
+}
+
+/**
+Rewrites schema document `doc` to draft 2020-12, returning notes on
+constructs which need human review.
+
+`draft` is used if `doc` has no `$schema`. Embedded resources with their
+own `$schema` are migrated from their draft. The following are rewritten:
+- `$schema` of standard metaschema
+- `id` to `$id`, and `$id` with fragment to `$anchor`
+- boolean `exclusiveMinimum` and `exclusiveMaximum` of draft-04 to number
+- `definitions` to `$defs`
+- `items` array to `prefixItems`, and `additionalItems` to `items`
+- `dependencies` to `dependentRequired` and `dependentSchemas`
+- `$ref` pointing to the locations moved by above, within the document
+
+```
+# use boon::*;
+# use serde_json::json;
+let mut doc = json!({
+    "$schema": "http://json-schema.org/draft-07/schema#",
+    "items": [{"$ref": "#/definitions/name"}],
+    "additionalItems": false,
+    "definitions": {"name": {"type": "string", "format": "email"}}
+});
+let notes = migrate(&mut doc, Draft::V7);
+assert_eq!(doc, json!({
+    "$schema": "https://json-schema.org/draft/2020-12/schema",
+    "prefixItems": [{"$ref": "#/$defs/name"}],
+    "items": false,
+    "$defs": {"name": {"type": "string", "format": "email"}}
+}));
+assert_eq!(notes[0].ptr, "/$defs/name");
+```
+*/
+pub fn migrate(doc: &mut Value, draft: crate::Draft) -> Vec<MigrationNote> {
+    let version = draft_of(doc).unwrap_or(draft.internal().version);
+    if version >= 2020 {
+        return vec![];
+    }
+    let old = doc.clone();
+    rewrite_refs(doc, &old, &old, None, version);
+    let mut notes = vec![];
+    migrate_schema(doc, &"".into(), None, version, &mut notes);
+    if let Value::Object(obj) = doc {
+        obj.entry("$schema").or_insert_with(|| DRAFT2020.url.into());
+    }
+    notes
+}
+
+fn draft_of(v: &Value) -> Option<usize> {
+    let Some(Value::String(sch)) = v.get("$schema") else {
+        return None;
+    };
+    Draft::from_url(sch).map(|d| d.version)
+}
+
+fn id_of(obj: &Map<String, Value>, version: usize) -> Option<&str> {
+    let kw = if version == 4 { "id" } else { "$id" };
+    let Some(Value::String(id)) = obj.get(kw) else {
+        return None;
+    };
+    Some(split(id).0).filter(|id| !id.is_empty())
+}
+
+// resolves `id` against `base`, ignoring fragment.
+fn resolve(base: Option<&Url>, id: &str) -> Option<Url> {
+    let mut url = match base {
+        Some(base) => base.join(id).ok()?,
+        None => Url::parse(id).ok()?,
+    };
+    url.set_fragment(None);
+    Some(url)
+}
+
+// tells whether `$ref` with `url` refers to resource `res_url`.
+fn is_local(url: &str, res_url: Option<&Url>) -> bool {
+    url.is_empty()
+        || res_url.is_some_and(|res_url| resolve(Some(res_url), url).as_ref() == Some(res_url))
+}
+
+// tells whether entries of `kw` cannot be merged into `target`.
+fn conflicts(obj: &Map<String, Value>, kw: &str, target: &str) -> bool {
+    match (obj.get(kw), obj.get(target)) {
+        (_, None) => false,
+        (Some(Value::Object(m1)), Some(Value::Object(m2))) => m1.keys().any(|k| m2.contains_key(k)),
+        _ => true,
+    }
+}
+
+fn dependencies_conflict(obj: &Map<String, Value>) -> Option<&'static str> {
+    ["dependentRequired", "dependentSchemas"]
+        .into_iter()
+        .find(|target| conflicts(obj, "dependencies", target))
+}
+
+// returns the keyword `kw` of schema `obj` is renamed to. `entry` is the
+// name of entry in `kw`, if known.
+fn renamed(obj: &Map<String, Value>, kw: &str, entry: Option<&str>) -> Option<&'static str> {
+    let items_array = matches!(obj.get("items"), Some(Value::Array(_)));
+    match kw {
+        "definitions" if !conflicts(obj, kw, "$defs") => Some("$defs"),
+        "items" if items_array => Some("prefixItems"),
+        "additionalItems" if items_array => Some("items"),
+        "dependencies" if dependencies_conflict(obj).is_none() => {
+            match obj.get(kw).and_then(|deps| deps.get(entry?)) {
+                Some(Value::Array(_)) => Some("dependentRequired"),
+                _ => Some("dependentSchemas"),
+            }
+        }
+        _ => None,
+    }
+}
+
+// translates json-pointer `ptr` in resource `res`, to the location after
+// migration. returns None if location is not changed.
+fn translate(res: &Value, ptr: &str) -> Option<String> {
+    let decode = |tok: &str| -> Option<String> {
+        let tok = percent_decode_str(tok).decode_utf8().ok()?;
+        Some(JsonPointer::unescape(&tok).ok()?.into_owned())
+    };
+    let mut cur = Some(res); // None, once not a schema, nor schemas
+    let mut in_schema = true;
+    let mut changed = false;
+    let mut new = String::new();
+    let mut toks = ptr.split('/').skip(1).peekable();
+    while let Some(tok) = toks.next() {
+        let key = decode(tok)?;
+        let mut new_tok = tok;
+        let next = match cur {
+            Some(Value::Object(obj)) if in_schema => {
+                let entry = toks.peek().and_then(|t| decode(t));
+                if let Some(kw) = renamed(obj, &key, entry.as_deref()) {
+                    new_tok = kw;
+                    changed = true;
+                }
+                let v = obj.get(&key);
+                match (DRAFT2020.subschemas.get(key.as_str()), v) {
+                    (Some(pos), Some(Value::Object(_))) if pos & POS_PROP != 0 => {
+                        in_schema = false;
+                        v
+                    }
+                    (Some(pos), Some(Value::Array(_))) if pos & POS_ITEM != 0 => {
+                        in_schema = false;
+                        v
+                    }
+                    (Some(pos), _) if pos & POS_SELF != 0 => v,
+                    _ => None,
+                }
+            }
+            Some(Value::Object(obj)) => {
+                in_schema = true;
+                obj.get(&key)
+            }
+            Some(Value::Array(arr)) => {
+                in_schema = true;
+                key.parse().ok().and_then(|i: usize| arr.get(i))
+            }
+            _ => None,
+        };
+        cur = next;
+        new.push('/');
+        new.push_str(new_tok);
+    }
+    changed.then_some(new)
+}
+
+// rewrites `$ref` in schema `v` and its subschemas, which point to
+// locations moved by migration. `old` is copy of `v`, and `res` is its
+// enclosing resource with `res_url`.
+fn rewrite_refs<'a>(
+    v: &mut Value,
+    old: &'a Value,
+    mut res: &'a Value,
+    mut res_url: Option<Url>,
+    version: usize,
+) {
+    let (Value::Object(obj), Value::Object(old_obj)) = (v, old) else {
+        return;
+    };
+    let version = draft_of(old).unwrap_or(version);
+    if version >= 2020 {
+        return;
+    }
+    if let Some(id) = id_of(old_obj, version) {
+        res = old;
+        res_url = resolve(res_url.as_ref(), id).or(res_url);
+    }
+    if let Some(Value::String(ref_)) = obj.get_mut("$ref") {
+        let (url, frag) = split(ref_);
+        if is_local(url, res_url.as_ref()) {
+            if let Some(frag) = translate(res, frag) {
+                *ref_ = format!("{url}#{frag}");
+            }
+        }
+    }
+    for (kw, old_v) in old_obj {
+        let (Some(pos), Some(v)) = (DRAFT2020.subschemas.get(kw.as_str()), obj.get_mut(kw)) else {
+            continue;
+        };
+        match (v, old_v) {
+            (Value::Object(m), Value::Object(old_m)) if pos & POS_PROP != 0 => {
+                for (k, v) in m {
+                    if let Some(old_v) = old_m.get(k) {
+                        rewrite_refs(v, old_v, res, res_url.clone(), version);
+                    }
+                }
+            }
+            (Value::Array(arr), Value::Array(old_arr)) if pos & POS_ITEM != 0 => {
+                for (v, old_v) in arr.iter_mut().zip(old_arr) {
+                    rewrite_refs(v, old_v, res, res_url.clone(), version);
+                }
+            }
+            (v, old_v) if pos & POS_SELF != 0 => {
+                rewrite_refs(v, old_v, res, res_url.clone(), version)
+            }
+            _ => {}
+        }
+    }
+}
+
+// keywords beside `$ref`, which were ignored and are harmless to apply.
+const REF_SIBLINGS: [&str; 10] = [
+    "$schema",
+    "$id",
+    "id",
+    "$comment",
+    "definitions",
+    "$defs",
+    "title",
+    "description",
+    "default",
+    "examples",
+];
+
+fn migrate_schema(
+    v: &mut Value,
+    ptr: &JsonPointer,
+    mut res_url: Option<Url>,
+    version: usize,
+    notes: &mut Vec<MigrationNote>,
+) {
+    let Value::Object(obj) = v else {
+        return;
+    };
+    let note = |kind| MigrationNote {
+        ptr: ptr.to_string(),
+        kind,
+    };
+    let version = match obj.get("$schema") {
+        Some(Value::String(sch)) => match Draft::from_url(sch) {
+            Some(draft) if draft.version >= 2020 => return,
+            Some(draft) => {
+                obj.insert("$schema".into(), DRAFT2020.url.into());
+                draft.version
+            }
+            None => {
+                let url = sch.clone();
+                notes.push(note(MigrationNoteKind::CustomMetaschema { url }));
+                version
+            }
+        },
+        _ => version,
+    };
+
+    // $id --
+    if let Some(id) = id_of(obj, version) {
+        res_url = resolve(res_url.as_ref(), id).or(res_url);
+    }
+    let id_kw = if version == 4 { "id" } else { "$id" };
+    if let Some(Value::String(id)) = obj.get(id_kw) {
+        let (url, frag) = split(id);
+        if version == 4 || !frag.is_empty() {
+            let (url, frag) = (url.to_owned(), frag.to_owned());
+            obj.remove(id_kw);
+            if !url.is_empty() {
+                obj.insert("$id".into(), url.into());
+            }
+            if !frag.is_empty() && !frag.starts_with('/') {
+                obj.insert("$anchor".into(), frag.into());
+            }
+        }
+    }
+
+    // $ref --
+    if let Some(Value::String(ref_)) = obj.get("$ref") {
+        let (url, frag) = split(ref_);
+        let moved = ["definitions", "items", "additionalItems", "dependencies"];
+        if !is_local(url, res_url.as_ref()) && frag.split('/').any(|tok| moved.contains(&tok)) {
+            let ref_ = ref_.clone();
+            notes.push(note(MigrationNoteKind::ExternalRef { ref_ }));
+        }
+        if version < 2019 {
+            let keywords = obj
+                .keys()
+                .filter(|kw| *kw != "$ref" && !REF_SIBLINGS.contains(&kw.as_str()))
+                .cloned()
+                .collect::<Vec<_>>();
+            if !keywords.is_empty() {
+                notes.push(note(MigrationNoteKind::RefSiblings { keywords }));
+            }
+        }
+    }
+
+    // exclusiveMinimum, exclusiveMaximum --
+    if version == 4 {
+        for (kw, bound) in [
+            ("exclusiveMinimum", "minimum"),
+            ("exclusiveMaximum", "maximum"),
+        ] {
+            if let Some(Value::Bool(exclusive)) = obj.get(kw) {
+                let exclusive = *exclusive;
+                obj.remove(kw);
+                if !exclusive {
+                    continue;
+                }
+                if let Some(n) = obj.remove(bound) {
+                    obj.insert(kw.into(), n);
+                }
+            }
+        }
+    }
+
+    // definitions --
+    if renamed(obj, "definitions", None).is_some() {
+        if let Some(defs) = obj.remove("definitions") {
+            match (obj.get_mut("$defs"), defs) {
+                (Some(Value::Object(m)), Value::Object(defs)) => m.extend(defs),
+                (_, defs) => {
+                    obj.insert("$defs".into(), defs);
+                }
+            }
+        }
+    } else if obj.contains_key("definitions") {
+        notes.push(note(MigrationNoteKind::Conflict {
+            keyword: "definitions",
+            replacement: "$defs",
+        }));
+    }
+
+    // items, additionalItems --
+    if matches!(obj.get("items"), Some(Value::Array(_))) {
+        if let Some(items) = obj.remove("items") {
+            obj.insert("prefixItems".into(), items);
+        }
+        if let Some(additional) = obj.remove("additionalItems") {
+            obj.insert("items".into(), additional);
+        }
+    } else {
+        obj.remove("additionalItems"); // has no effect
+    }
+
+    // dependencies --
+    if let Some(replacement) = dependencies_conflict(obj) {
+        notes.push(note(MigrationNoteKind::Conflict {
+            keyword: "dependencies",
+            replacement,
+        }));
+    } else if let Some(Value::Object(deps)) = obj.get("dependencies") {
+        let deps = deps.clone();
+        obj.remove("dependencies");
+        for (name, dep) in deps {
+            let kw = match dep {
+                Value::Array(_) => "dependentRequired",
+                _ => "dependentSchemas",
+            };
+            if let Value::Object(m) = obj.entry(kw).or_insert_with(|| Map::new().into()) {
+                m.insert(name, dep);
+            }
+        }
+    }
+
+    // format --
+    if let Some(Value::String(format)) = obj.get("format") {
+        if version < 2019 {
+            let format = format.clone();
+            notes.push(note(MigrationNoteKind::FormatAssertion { format }));
+        }
+    }
+
+    // $recursiveRef, $recursiveAnchor --
+    for keyword in ["$recursiveRef", "$recursiveAnchor"] {
+        if obj.contains_key(keyword) {
+            notes.push(note(MigrationNoteKind::Unsupported { keyword }));
+        }
+    }
+
+    for (kw, v) in obj.iter_mut() {
+        let Some(pos) = DRAFT2020.subschemas.get(kw.as_str()) else {
+            continue;
+        };
+        match v {
+            Value::Object(m) if pos & POS_PROP != 0 => {
+                for (name, v) in m {
+                    let ptr = ptr.append2(kw, name);
+                    migrate_schema(v, &ptr, res_url.clone(), version, notes);
+                }
+            }
+            Value::Array(arr) if pos & POS_ITEM != 0 => {
+                for (i, v) in arr.iter_mut().enumerate() {
+                    let ptr = ptr.append2(kw, &i.to_string());
+                    migrate_schema(v, &ptr, res_url.clone(), version, notes);
+                }
+            }
+            _ if pos & POS_SELF != 0 => {
+                migrate_schema(v, &ptr.append(kw), res_url.clone(), version, notes);
+            }
+            _ => {}
+        }
+    }
+}
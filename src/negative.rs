@@ -0,0 +1,318 @@
+use std::collections::HashSet;
+
+use serde_json::{json, Map, Value};
+
+use crate::{util::*, Additional, Items, SchemaIndex, Schemas, Type, ValidationError};
+
+// largest `maxLength`, `maxItems` or `maxProperties` for which an instance
+// exceeding it is generated. larger bounds are skipped.
+const MAX_LEN: usize = 4096;
+
+/// Invalid instance generated by [`near_misses`].
+#[derive(Debug, Clone)]
+pub struct NearMiss {
+    /// generated invalid instance.
+    pub instance: Value,
+    /// json-pointer to the value which is changed.
+    pub instance_location: String,
+    /// location of the schema containing violated constraint.
+    pub schema_url: String,
+    /// keyword of violated constraint.
+    pub keyword: &'static str,
+}
+
+/**
+Generates "near-miss" invalid instances, by changing the `valid` instance
+such that one constraint of schema identified by `sch_index` is violated
+at a time.
+
+Only the changes which are rejected by the schema are returned. Note that
+a change targeting one constraint may incidentally violate other
+constraints as well.
+
+# Errors
+
+returns [`ValidationError`] if `valid` is not valid.
+
+# Panics
+
+Panics if `sch_index` is not generated for `schemas`.
+*/
+pub fn near_misses<'s, 'v>(
+    schemas: &'s Schemas,
+    sch_index: SchemaIndex,
+    valid: &'v Value,
+) -> Result<Vec<NearMiss>, ValidationError<'s, 'v>> {
+    schemas.validate(valid, sch_index)?;
+    let mut gen = Generator {
+        schemas,
+        root: valid,
+        visited: HashSet::new(),
+        seen: vec![],
+        misses: vec![],
+    };
+    gen.walk(sch_index, valid, &JsonPointer::from(""));
+    let misses = gen
+        .misses
+        .into_iter()
+        .filter(|m| schemas.validate(&m.instance, sch_index).is_err())
+        .collect();
+    Ok(misses)
+}
+
+struct Generator<'a> {
+    schemas: &'a Schemas,
+    root: &'a Value,
+    visited: HashSet<(SchemaIndex, JsonPointer)>,
+    seen: Vec<Value>,
+    misses: Vec<NearMiss>,
+}
+
+impl<'a> Generator<'a> {
+    fn add(&mut self, sch: SchemaIndex, ptr: &JsonPointer, keyword: &'static str, v: Value) {
+        let mut instance = self.root.clone();
+        if let Some(target) = instance.pointer_mut(ptr.as_str()) {
+            *target = v;
+        }
+        if self.seen.iter().any(|s| equals(s, &instance)) {
+            return;
+        }
+        self.seen.push(instance.clone());
+        self.misses.push(NearMiss {
+            instance,
+            instance_location: ptr.to_string(),
+            schema_url: self.schemas.get(sch).loc.clone(),
+            keyword,
+        });
+    }
+
+    fn walk(&mut self, idx: SchemaIndex, v: &Value, ptr: &JsonPointer) {
+        if !self.visited.insert((idx, ptr.clone())) {
+            return;
+        }
+        let sch = self.schemas.get(idx);
+        if sch.boolean.is_some() {
+            return;
+        }
+
+        // type agnostic --
+        if !sch.types.is_empty() {
+            let candidates = [
+                json!(null),
+                json!(true),
+                json!(0.5),
+                json!(""),
+                json!([]),
+                json!({}),
+            ];
+            if let Some(other) = candidates
+                .into_iter()
+                .find(|c| !sch.types.contains(Type::of(c)))
+            {
+                self.add(idx, ptr, "type", other);
+            }
+        }
+        if let Some(e) = &sch.enum_ {
+            if let Some(other) = not_in(&e.values, v) {
+                self.add(idx, ptr, "enum", other);
+            }
+        }
+        if let Some(c) = &sch.constant {
+            if let Some(other) = not_in(std::slice::from_ref(c), v) {
+                self.add(idx, ptr, "const", other);
+            }
+        }
+
+        match v {
+            Value::String(s) => self.string(idx, s, ptr),
+            Value::Number(_) => self.number(idx, v, ptr),
+            Value::Array(arr) => self.array(idx, arr, ptr),
+            Value::Object(obj) => self.object(idx, obj, ptr),
+            _ => {}
+        }
+
+        // applicators --
+        if let Some(ref_) = sch.ref_ {
+            self.walk(ref_, v, ptr);
+        }
+        for &sub in self.schemas.indexes(sch.all_of) {
+            self.walk(sub, v, ptr);
+        }
+    }
+
+    fn string(&mut self, idx: SchemaIndex, s: &str, ptr: &JsonPointer) {
+        let sch = self.schemas.get(idx);
+        if let Some(min) = sch.min_length.filter(|&n| n > 0) {
+            let short = s.chars().take(min - 1).collect::<String>();
+            self.add(idx, ptr, "minLength", Value::String(short));
+        }
+        if let Some(max) = sch.max_length.filter(|&n| n < MAX_LEN) {
+            let len = s.chars().count();
+            let long = s.to_owned() + &"x".repeat((max + 1).saturating_sub(len));
+            self.add(idx, ptr, "maxLength", Value::String(long));
+        }
+        if let Some(re) = &sch.pattern {
+            let candidates = [
+                String::new(),
+                format!("{s}\u{0}"),
+                "\u{0}".to_owned(),
+                "!".into(),
+            ];
+            if let Some(c) = candidates.into_iter().find(|c| !re.is_match(c)) {
+                self.add(idx, ptr, "pattern", Value::String(c));
+            }
+        }
+        if let Some(format) = sch.asserted_format() {
+            let candidates = [
+                String::new(),
+                format!("{s}\u{0}"),
+                "\u{0}".to_owned(),
+                "!".into(),
+            ];
+            if let Some(c) = candidates
+                .into_iter()
+                .find(|c| (format.func)(&Value::String(c.clone())).is_err())
+            {
+                self.add(idx, ptr, "format", Value::String(c));
+            }
+        }
+    }
+
+    fn number(&mut self, idx: SchemaIndex, v: &Value, ptr: &JsonPointer) {
+        let sch = self.schemas.get(idx);
+        let int = sch.types.contains(Type::Integer) && !sch.types.contains(Type::Number);
+        let num = |f: f64| -> Value {
+            if int || f.fract() == 0.0 && f.abs() < (i64::MAX as f64) {
+                json!(f as i64)
+            } else {
+                json!(f)
+            }
+        };
+        let get = |n: &Option<serde_json::Number>| n.as_ref().and_then(|n| n.as_f64());
+        if let Some(min) = get(&sch.minimum) {
+            self.add(idx, ptr, "minimum", num(min.ceil() - 1.0));
+        }
+        if let Some(min) = get(&sch.exclusive_minimum) {
+            self.add(idx, ptr, "exclusiveMinimum", num(min.floor()));
+        }
+        if let Some(max) = get(&sch.maximum) {
+            self.add(idx, ptr, "maximum", num(max.floor() + 1.0));
+        }
+        if let Some(max) = get(&sch.exclusive_maximum) {
+            self.add(idx, ptr, "exclusiveMaximum", num(max.ceil()));
+        }
+        if let Some(m) = get(&sch.multiple_of) {
+            let f = v.as_f64().unwrap_or_default();
+            let off = if int && m > 1.0 { 1.0 } else { m / 2.0 };
+            self.add(idx, ptr, "multipleOf", num(f + off));
+        }
+        if int {
+            let f = v.as_f64().unwrap_or_default();
+            self.add(idx, ptr, "type", json!(f + 0.5));
+        }
+    }
+
+    fn array(&mut self, idx: SchemaIndex, arr: &[Value], ptr: &JsonPointer) {
+        let sch = self.schemas.get(idx);
+        if let Some(min) = sch.min_items.filter(|&n| n > 0) {
+            self.add(idx, ptr, "minItems", Value::Array(arr[..min - 1].to_vec()));
+        }
+        if let (Some(max), Some(first)) = (sch.max_items.filter(|&n| n < MAX_LEN), arr.first()) {
+            let mut long = arr.to_vec();
+            long.resize(long.len().max(max + 1), first.clone());
+            self.add(idx, ptr, "maxItems", Value::Array(long));
+        }
+        if let (true, Some(first)) = (sch.unique_items, arr.first()) {
+            let mut dup = arr.to_vec();
+            dup.push(first.clone());
+            self.add(idx, ptr, "uniqueItems", Value::Array(dup));
+        }
+
+        let items = match &sch.items {
+            Some(Items::SchemaRef(items)) => Some(*items),
+            _ => sch.items2020,
+        };
+        let prefix = match &sch.items {
+            Some(Items::SchemaRefs(list)) => self.schemas.indexes(*list),
+            _ => self.schemas.indexes(sch.prefix_items),
+        };
+        for (i, item) in arr.iter().enumerate() {
+            let item_sch = prefix.get(i).cloned().or(items);
+            if let Some(item_sch) = item_sch {
+                self.walk(item_sch, item, &ptr.append(&i.to_string()));
+            }
+        }
+    }
+
+    fn object(&mut self, idx: SchemaIndex, obj: &Map<String, Value>, ptr: &JsonPointer) {
+        let sch = self.schemas.get(idx);
+        for pname in &sch.required {
+            if obj.contains_key(pname.as_ref()) {
+                let mut obj = obj.clone();
+                obj.remove(pname.as_ref());
+                self.add(idx, ptr, "required", Value::Object(obj));
+            }
+        }
+        if let Some(min) = sch.min_properties.filter(|&n| n > 0) {
+            let obj = obj.clone().into_iter().take(min - 1).collect();
+            self.add(idx, ptr, "minProperties", Value::Object(obj));
+        }
+        let extra = (0..)
+            .map(|i| format!("x{i}"))
+            .find(|pname| {
+                !obj.contains_key(pname)
+                    && !sch.properties.contains_key(pname.as_str())
+                    && !sch
+                        .pattern_properties
+                        .iter()
+                        .any(|(re, _)| re.is_match(pname))
+            })
+            .unwrap_or_default();
+        if let Some(max) = sch.max_properties.filter(|&n| n < MAX_LEN) {
+            let mut long = obj.clone();
+            for i in 0..(max + 1).saturating_sub(obj.len()) {
+                long.insert(format!("{extra}_{i}"), Value::Null);
+            }
+            self.add(idx, ptr, "maxProperties", Value::Object(long));
+        }
+        if let Some(Additional::Bool(false)) = sch.additional_properties {
+            let mut obj = obj.clone();
+            obj.insert(extra, Value::Null);
+            self.add(idx, ptr, "additionalProperties", Value::Object(obj));
+        }
+
+        let mut props = sch.properties.iter().collect::<Vec<_>>();
+        props.sort_by(|a, b| a.0.cmp(b.0));
+        for (pname, &psch) in props {
+            if let Some(pvalue) = obj.get(pname.as_ref()) {
+                self.walk(psch, pvalue, &ptr.append(pname));
+            }
+        }
+    }
+}
+
+// returns value not in `values`, preferably of same type as `v`.
+fn not_in(values: &[Value], v: &Value) -> Option<Value> {
+    let candidates = match v {
+        Value::String(s) => vec![json!(format!("{s}~")), json!("")],
+        Value::Number(n) => vec![json!(n.as_f64().unwrap_or_default() + 1.0), json!(-1)],
+        Value::Bool(b) => vec![json!(!b)],
+        _ => vec![],
+    };
+    candidates
+        .into_iter()
+        .chain([json!(null), json!(""), json!(0), json!(false), json!({})])
+        .find(|c| !values.iter().any(|v| equals(v, c)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_not_in() {
+        let values = [json!("a"), json!("a~")];
+        assert_eq!(not_in(&values, &json!("a")), Some(json!("")));
+        assert_eq!(not_in(&[json!(true)], &json!(true)), Some(json!(false)));
+    }
+}
@@ -0,0 +1,364 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::Debug,
+    sync::Arc,
+    time::Duration,
+};
+
+use serde_json::Value;
+
+use crate::{formats::*, keyword::Annotations};
+
+/**
+Options used by [`Schemas::validate_with`](crate::Schemas::validate_with).
+
+These allow tuning validation of already compiled schemas per call,
+for example when tenants of a service require different strictness.
+
+```
+# use std::error::Error;
+# use boon::*;
+# use serde_json::json;
+# fn main() -> Result<(), Box<dyn Error>> {
+let mut schemas = Schemas::new();
+let mut compiler = Compiler::new();
+compiler.enable_format_assertions();
+compiler.add_resource("schema.json", json!({"format": "email"}))?;
+let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+let mut options = ValidationOptions::new();
+options.set_email_options(EmailOptions {
+    allow_quoted_local: false,
+    ..Default::default()
+});
+let instance = json!(r#""john doe"@example.com"#);
+assert!(schemas.validate(&instance, sch_index).is_ok());
+assert!(schemas.validate_with(&instance, sch_index, &options).is_err());
+# Ok(())
+# }
+```
+
+# Note
+
+Format overrides apply only to formats which are asserted by the compiled
+schema, see [`Compiler::enable_format_assertions`](crate::Compiler::enable_format_assertions),
+unless enforced by [`Self::assert_format`].
+*/
+#[derive(Default, Clone)]
+pub struct ValidationOptions {
+    formats: HashMap<String, Option<FormatFn>>, // None means skip
+    asserted_formats: HashSet<String>,
+    keywords: HashMap<String, Option<KeywordFn>>, // None means skip
+    length_unit: LengthUnit,
+    access_context: Option<AccessContext>,
+    max_depth: Option<usize>,
+    max_evaluations: Option<usize>,
+    time_limit: Option<Duration>,
+}
+
+/// Whether instance is sent to server or received from it.
+/// see [`ValidationOptions::set_access_context`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessContext {
+    /// instance is read from server, e.g. response payload.
+    Read,
+    /// instance is written to server, e.g. request payload.
+    Write,
+}
+
+/// Unit used to measure string length for `minLength` and `maxLength`.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LengthUnit {
+    /// unicode code points, as required by JSON Schema specification.
+    #[default]
+    CodePoints,
+    /// bytes in UTF-8 encoding.
+    Bytes,
+    /// extended grapheme clusters, i.e. user-perceived characters.
+    #[cfg(feature = "unicode-segmentation")]
+    Graphemes,
+}
+
+impl LengthUnit {
+    pub(crate) fn len(self, s: &str) -> usize {
+        match self {
+            Self::CodePoints => s.chars().count(),
+            Self::Bytes => s.len(),
+            #[cfg(feature = "unicode-segmentation")]
+            Self::Graphemes => {
+                use unicode_segmentation::UnicodeSegmentation;
+                s.graphemes(true).count()
+            }
+        }
+    }
+}
+
+impl ValidationOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Overrides validation function of `format`.
+    pub fn override_format<F>(&mut self, name: &str, func: F)
+    where
+        F: Fn(&Value) -> Result<(), Box<dyn Error>> + Send + Sync + 'static,
+    {
+        self.formats.insert(name.to_owned(), Some(Arc::new(func)));
+    }
+
+    /// Skips assertion of `format`.
+    pub fn skip_format(&mut self, name: &str) {
+        self.asserted_formats.remove(name);
+        self.formats.insert(name.to_owned(), None);
+    }
+
+    /**
+    Asserts `format`, even where the compiled schema treats it as
+    annotation only, using its override if any.
+
+    Only formats known to the compiler, i.e. built-in or registered via
+    [`Compiler::register_format`](crate::Compiler::register_format), can be asserted.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", json!({"format": "email"}))?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+    let mut options = ValidationOptions::new();
+    options.assert_format("email");
+    let instance = json!("not-an-email");
+    assert!(schemas.validate(&instance, sch_index).is_ok());
+    assert!(schemas.validate_with(&instance, sch_index, &options).is_err());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn assert_format(&mut self, name: &str) {
+        if let Some(None) = self.formats.get(name) {
+            self.formats.remove(name);
+        }
+        self.asserted_formats.insert(name.to_owned());
+    }
+
+    /**
+    Replaces validation of custom keyword `name`, registered via
+    [`Compiler::register_keyword`](crate::Compiler::register_keyword).
+
+    `func` is called with the value of the keyword in schema, the instance
+    and [`Annotations`], in the same phase as the keyword.
+    */
+    pub fn override_keyword<F>(&mut self, name: &str, func: F)
+    where
+        F: Fn(&Value, &Value, &Annotations) -> Result<(), Box<dyn Error>> + Send + Sync + 'static,
+    {
+        self.keywords.insert(name.to_owned(), Some(Arc::new(func)));
+    }
+
+    /// Skips validation of custom keyword `name`.
+    pub fn disable_keyword(&mut self, name: &str) {
+        self.keywords.insert(name.to_owned(), None);
+    }
+
+    /// Validates custom keyword `name` as compiled, undoing
+    /// [`Self::disable_keyword`] and [`Self::override_keyword`].
+    pub fn enable_keyword(&mut self, name: &str) {
+        self.keywords.remove(name);
+    }
+
+    /**
+    Overrides strictness of `email` and `idn-email` formats.
+
+    This replaces any override of these formats. Note that
+    hostname options used for domain part are defaults.
+    */
+    pub fn set_email_options(&mut self, options: EmailOptions) {
+        for name in ["email", "idn-email"] {
+            let f = std_format(name, options, HostnameOptions::default());
+            self.formats.insert(name.to_owned(), f.map(|f| f.func));
+        }
+    }
+
+    /**
+    Overrides strictness of `hostname` and `idn-hostname` formats.
+
+    This replaces any override of these formats.
+    */
+    pub fn set_hostname_options(&mut self, options: HostnameOptions) {
+        for name in ["hostname", "idn-hostname"] {
+            let f = std_format(name, EmailOptions::default(), options);
+            self.formats.insert(name.to_owned(), f.map(|f| f.func));
+        }
+    }
+
+    /**
+    Overrides the unit used to measure string length for `minLength`
+    and `maxLength`.
+
+    Note that units other than [`LengthUnit::CodePoints`] do not
+    conform to JSON Schema specification.
+    */
+    pub fn set_length_unit(&mut self, unit: LengthUnit) {
+        self.length_unit = unit;
+    }
+
+    /**
+    Enforces `readOnly` and `writeOnly` for given access `context`.
+
+    With [`AccessContext::Write`], values whose schema has `readOnly: true`
+    fail validation with [`ErrorKind::ReadOnly`](crate::ErrorKind::ReadOnly),
+    and with [`AccessContext::Read`], values whose schema has
+    `writeOnly: true` fail with [`ErrorKind::WriteOnly`](crate::ErrorKind::WriteOnly).
+    By default, these keywords are annotations only.
+
+    To treat them as warnings instead, check whether all leaf errors are
+    of these kinds. Note that, like any other error, they can change
+    which of the subschemas of `anyOf`, `oneOf` etc. match.
+    */
+    pub fn set_access_context(&mut self, context: AccessContext) {
+        self.access_context = Some(context);
+    }
+
+    /**
+    Limits the number of schemas nested in evaluation path to `depth`.
+
+    Recursive schemas with deeply nested instances can otherwise
+    overflow the stack. Once exceeded, validation is aborted with
+    [`ErrorKind::MaxDepth`](crate::ErrorKind::MaxDepth) as the only cause,
+    regardless of `anyOf`, `not` etc. enclosing it.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("list.json", json!({"items": {"$ref": "#"}}))?;
+    let sch_index = compiler.compile("list.json", &mut schemas)?;
+
+    let mut options = ValidationOptions::new();
+    options.set_max_depth(10);
+    let instance = json!([[[[[[[[[[]]]]]]]]]]);
+    let err = schemas.validate_with(&instance, sch_index, &options).unwrap_err();
+    assert!(matches!(err.causes[0].kind, ErrorKind::MaxDepth { want: 10 }));
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn set_max_depth(&mut self, depth: usize) {
+        self.max_depth = Some(depth);
+    }
+
+    /**
+    Limits the number of schemas evaluated by single validation call to
+    `max`, counting every subschema each time it is applied.
+
+    Along with [`Self::set_time_limit`], this bounds worst-case latency
+    when validating untrusted instances against untrusted schemas. Once
+    exceeded, validation is aborted with
+    [`ErrorKind::MaxEvaluations`](crate::ErrorKind::MaxEvaluations) as the
+    only cause. Unlike time limit, the outcome does not depend on machine
+    load.
+    */
+    pub fn set_max_evaluations(&mut self, max: usize) {
+        self.max_evaluations = Some(max);
+    }
+
+    /**
+    Limits the time taken by single validation call to `limit`.
+
+    Once exceeded, validation is aborted with
+    [`ErrorKind::TimeLimit`](crate::ErrorKind::TimeLimit) as the only
+    cause. The clock is checked periodically rather than on every
+    schema, so the limit may be overrun slightly.
+
+    ```
+    # use std::error::Error;
+    # use std::time::Duration;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", json!({"items": {"type": "integer"}}))?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+    let mut options = ValidationOptions::new();
+    options.set_time_limit(Duration::from_secs(1));
+    options.set_max_evaluations(100);
+    let instance = json!(vec![1; 1000]);
+    let err = schemas.validate_with(&instance, sch_index, &options).unwrap_err();
+    assert!(matches!(err.causes[0].kind, ErrorKind::MaxEvaluations { want: 100 }));
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn set_time_limit(&mut self, limit: Duration) {
+        self.time_limit = Some(limit);
+    }
+
+    pub(crate) fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    pub(crate) fn max_evaluations(&self) -> Option<usize> {
+        self.max_evaluations
+    }
+
+    pub(crate) fn time_limit(&self) -> Option<Duration> {
+        self.time_limit
+    }
+
+    pub(crate) fn access_context(&self) -> Option<AccessContext> {
+        self.access_context
+    }
+
+    pub(crate) fn length_unit(&self) -> LengthUnit {
+        self.length_unit
+    }
+
+    // returns None if format is not overridden.
+    pub(crate) fn format(&self, name: &str) -> Option<Option<&FormatFn>> {
+        self.formats.get(name).map(Option::as_ref)
+    }
+
+    pub(crate) fn is_format_asserted(&self, name: &str) -> bool {
+        self.asserted_formats.contains(name)
+    }
+
+    // returns None if keyword is not overridden.
+    pub(crate) fn keyword(&self, name: &str) -> Option<Option<&KeywordFn>> {
+        self.keywords.get(name).map(Option::as_ref)
+    }
+}
+
+pub(crate) type KeywordFn =
+    Arc<dyn Fn(&Value, &Value, &Annotations) -> Result<(), Box<dyn Error>> + Send + Sync>;
+
+impl Debug for ValidationOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut names = self.formats.keys().collect::<Vec<_>>();
+        names.sort();
+        let mut asserted = self.asserted_formats.iter().collect::<Vec<_>>();
+        asserted.sort();
+        let mut keywords = self.keywords.keys().collect::<Vec<_>>();
+        keywords.sort();
+        f.debug_struct("ValidationOptions")
+            .field("formats", &names)
+            .field("asserted_formats", &asserted)
+            .field("keywords", &keywords)
+            .field("length_unit", &self.length_unit)
+            .field("access_context", &self.access_context)
+            .field("max_depth", &self.max_depth)
+            .field("max_evaluations", &self.max_evaluations)
+            .field("time_limit", &self.time_limit)
+            .finish()
+    }
+}
@@ -8,10 +8,10 @@ use serde::{
     Serialize,
 };
 
-use crate::{util::*, ErrorKind, InstanceLocation, ValidationError};
+use crate::{util::*, ErrorKind, InstanceLocation, Provenance, ValidationError};
 
 impl<'s, 'v> ValidationError<'s, 'v> {
-    fn absolute_keyword_location(&self) -> AbsoluteKeywordLocation<'s> {
+    pub(crate) fn absolute_keyword_location(&self) -> AbsoluteKeywordLocation<'s> {
         if let ErrorKind::Reference { url, .. } = &self.kind {
             AbsoluteKeywordLocation {
                 schema_url: url,
@@ -58,6 +58,7 @@ impl<'s, 'v> ValidationError<'s, 'v> {
                         keyword_location: kw_loc.get(e),
                         absolute_keyword_location,
                         instance_location: &e.instance_location,
+                        provenance: e.provenance.as_ref(),
                         error: OutputError::Leaf(&e.kind),
                     });
                 }
@@ -81,6 +82,7 @@ impl<'s, 'v> ValidationError<'s, 'v> {
             keyword_location: String::new(),
             absolute_keyword_location: None,
             instance_location: &self.instance_location,
+            provenance: self.provenance.as_ref(),
             error,
         }
     }
@@ -110,6 +112,7 @@ impl<'s, 'v> ValidationError<'s, 'v> {
                         keyword_location: kw_loc.get(e),
                         absolute_keyword_location,
                         instance_location: &e.instance_location,
+                        provenance: e.provenance.as_ref(),
                         error: OutputError::Leaf(&e.kind),
                     });
                 }
@@ -162,6 +165,9 @@ impl<'s, 'v> Display for ValidationError<'s, 'v> {
                         write!(f, "at {}", quote(&e.instance_location.to_string()))?;
                         if f.alternate() {
                             write!(f, " [{}]", sloc)?;
+                            if let Some(p) = &e.provenance {
+                                write!(f, " ({p})")?;
+                            }
                             // write!(f, " [{}]", kw_loc.get(e))?;
                             // write!(f, " [{}]", e.absolute_keyword_location())?;
                         }
@@ -420,6 +426,8 @@ pub struct OutputUnit<'e, 's, 'v> {
     pub absolute_keyword_location: Option<AbsoluteKeywordLocation<'s>>,
     /// The location of the JSON value within the instance being validated
     pub instance_location: &'e InstanceLocation<'v>,
+    /// see [`ValidationError::provenance`].
+    pub provenance: Option<&'e Provenance>,
     pub error: OutputError<'e, 's, 'v>,
 }
 
@@ -428,7 +436,9 @@ impl<'e, 's, 'v> Serialize for OutputUnit<'e, 's, 'v> {
     where
         S: serde::Serializer,
     {
-        let n = 4 + self.absolute_keyword_location.as_ref().map_or(0, |_| 1);
+        let n = 4
+            + self.absolute_keyword_location.as_ref().map_or(0, |_| 1)
+            + self.provenance.map_or(0, |_| 1);
         let mut map = serializer.serialize_map(Some(n))?;
         map.serialize_entry("valid", &self.valid)?;
         map.serialize_entry("keywordLocation", &self.keyword_location.to_string())?;
@@ -436,6 +446,9 @@ impl<'e, 's, 'v> Serialize for OutputUnit<'e, 's, 'v> {
             map.serialize_entry("absoluteKeywordLocation", &s.to_string())?;
         }
         map.serialize_entry("instanceLocation", &self.instance_location.to_string())?;
+        if let Some(p) = self.provenance {
+            map.serialize_entry("provenance", &p.to_string())?;
+        }
         let pname = match self.error {
             OutputError::Leaf(_) => "error",
             OutputError::Branch(_) => "errors",
@@ -505,8 +518,15 @@ impl<'s, 'v> ErrorKind<'s, 'v> {
             PropertyName { .. } => kw("propertyNames"),
             Reference { kw: kword, .. } => kw(kword),
             RefCycle { .. } => None,
+            MaxDepth { .. } => None,
+            MaxEvaluations { .. } => None,
+            TimeLimit { .. } => None,
+            NotCompiled { .. } => None,
             FalseSchema => None,
+            ReadOnly => kw("readOnly"),
+            WriteOnly => kw("writeOnly"),
             Type { .. } => kw("type"),
+            BsonType { .. } => kw("bsonType"),
             Enum { .. } => kw("enum"),
             Const { .. } => kw("const"),
             Format { .. } => kw("format"),
@@ -526,6 +546,12 @@ impl<'s, 'v> ErrorKind<'s, 'v> {
             MinLength { .. } => kw("minLength"),
             MaxLength { .. } => kw("maxLength"),
             Pattern { .. } => kw("pattern"),
+            RegexLimit {
+                kw: "patternProperties",
+                want,
+                ..
+            } => kw_prop("patternProperties", want),
+            RegexLimit { kw: kword, .. } => kw(kword),
             ContentEncoding { .. } => kw("contentEncoding"),
             ContentMediaType { .. } => kw("contentMediaType"),
             Minimum { .. } => kw("minimum"),
@@ -533,6 +559,8 @@ impl<'s, 'v> ErrorKind<'s, 'v> {
             ExclusiveMinimum { .. } => kw("exclusiveMinimum"),
             ExclusiveMaximum { .. } => kw("exclusiveMaximum"),
             MultipleOf { .. } => kw("multipleOf"),
+            Data { kw: kword, .. } | InvalidData { kw: kword, .. } => kw(kword),
+            Keyword { keyword, .. } => kw(keyword),
             Not => kw("not"),
             AllOf => kw("allOf"),
             AnyOf => kw("anyOf"),
@@ -608,7 +636,10 @@ impl<'s> Display for SchemaToken<'s> {
 
 // helpers --
 
-fn write_json_to_fmt<T>(f: &mut std::fmt::Formatter, value: &T) -> Result<(), std::fmt::Error>
+pub(crate) fn write_json_to_fmt<T>(
+    f: &mut std::fmt::Formatter,
+    value: &T,
+) -> Result<(), std::fmt::Error>
 where
     T: ?Sized + Serialize,
 {
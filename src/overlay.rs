@@ -0,0 +1,244 @@
+use std::{error::Error, fmt::Display};
+
+use serde_json::{Map, Value};
+
+use crate::util::*;
+
+/// Patch applied to base schema by [`Compiler::add_overlay`](crate::Compiler::add_overlay).
+#[derive(Debug, Clone)]
+pub enum SchemaPatch {
+    /// [JSON Merge Patch](https://www.rfc-editor.org/rfc/rfc7396) document.
+    Merge(Value),
+    /// [JSON Patch](https://www.rfc-editor.org/rfc/rfc6902) document.
+    Json(Value),
+}
+
+/// Origin of a location in schema added by [`Compiler::add_overlay`](crate::Compiler::add_overlay).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Provenance {
+    /// taken from base schema as is. `loc` is the location in base schema.
+    Base { loc: String },
+    /// added, changed or removed by patch. `ptr` is json-pointer of the
+    /// patched value.
+    Patch { ptr: String },
+}
+
+impl Display for Provenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Base { loc } => write!(f, "from base {loc}"),
+            Self::Patch { ptr } => write!(f, "from patch at {}", quote(ptr)),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Overlay {
+    pub(crate) base: UrlPtr,
+    pub(crate) patched: Vec<String>, // json-pointers of patched values
+}
+
+impl Overlay {
+    pub(crate) fn provenance(&self, ptr: &str) -> Provenance {
+        let patched = self.patched.iter().find(|p| {
+            ptr.strip_prefix(p.as_str())
+                .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+                .is_some()
+        });
+        match patched {
+            Some(p) => Provenance::Patch { ptr: p.clone() },
+            None => Provenance::Base {
+                loc: UrlPtr {
+                    url: self.base.url.clone(),
+                    ptr: self.base.ptr.concat(&JsonPointer::from(ptr)),
+                }
+                .to_string(),
+            },
+        }
+    }
+}
+
+impl SchemaPatch {
+    // applies patch to `doc` and returns json-pointers of patched values.
+    pub(crate) fn apply(&self, doc: &mut Value) -> Result<Vec<String>, Box<dyn Error>> {
+        let mut patched = vec![];
+        match self {
+            Self::Merge(patch) => merge_patch(doc, patch, &JsonPointer::from(""), &mut patched),
+            Self::Json(Value::Array(ops)) => {
+                for (i, op) in ops.iter().enumerate() {
+                    json_patch(doc, op, &mut patched).map_err(|e| format!("operation {i}: {e}"))?;
+                }
+            }
+            Self::Json(_) => Err("json patch must be an array")?,
+        }
+        Ok(patched)
+    }
+}
+
+fn merge_patch(doc: &mut Value, patch: &Value, ptr: &JsonPointer, patched: &mut Vec<String>) {
+    let Value::Object(patch) = patch else {
+        *doc = patch.clone();
+        patched.push(ptr.to_string());
+        return;
+    };
+    if !doc.is_object() {
+        *doc = Value::Object(Map::new());
+        patched.push(ptr.to_string());
+    }
+    let Value::Object(obj) = doc else {
+        return;
+    };
+    for (pname, pvalue) in patch {
+        let ptr = ptr.append(pname);
+        if pvalue.is_null() {
+            obj.remove(pname);
+            patched.push(ptr.to_string());
+        } else {
+            let v = obj.entry(pname.as_str()).or_insert(Value::Null);
+            merge_patch(v, pvalue, &ptr, patched);
+        }
+    }
+}
+
+fn json_patch(
+    doc: &mut Value,
+    op: &Value,
+    patched: &mut Vec<String>,
+) -> Result<(), Box<dyn Error>> {
+    let str = |name: &str| match op.get(name) {
+        Some(Value::String(s)) => Ok(s.as_str()),
+        _ => Err(format!("{name} must be string")),
+    };
+    let value = || op.get("value").ok_or("value missing");
+    let path = str("path")?;
+    match str("op")? {
+        "add" => add(doc, path, value()?.clone(), patched),
+        "remove" => remove(doc, path, patched).map(|_| ()),
+        "replace" => {
+            *get_mut(doc, path)? = value()?.clone();
+            patched.push(path.to_owned());
+            Ok(())
+        }
+        "move" => {
+            let from = str("from")?;
+            if path.starts_with(from) && path[from.len()..].starts_with('/') {
+                Err(format!("cannot move {from:?} into its child"))?;
+            }
+            let v = remove(doc, from, patched)?;
+            add(doc, path, v, patched)
+        }
+        "copy" => {
+            let v = get_mut(doc, str("from")?)?.clone();
+            add(doc, path, v, patched)
+        }
+        "test" => {
+            if !equals(get_mut(doc, path)?, value()?) {
+                Err(format!("test failed at {path:?}"))?;
+            }
+            Ok(())
+        }
+        op => Err(format!("unknown op {op:?}"))?,
+    }
+}
+
+fn tokens(path: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let Some(path) = path.strip_prefix('/') else {
+        if path.is_empty() {
+            return Ok(vec![]);
+        }
+        Err(format!("invalid json-pointer {path:?}"))?
+    };
+    let mut tokens = vec![];
+    for tok in path.split('/') {
+        let tok =
+            JsonPointer::unescape(tok).map_err(|_| format!("invalid json-pointer {path:?}"))?;
+        tokens.push(tok.into_owned());
+    }
+    Ok(tokens)
+}
+
+fn index(tok: &str, len: usize) -> Result<usize, Box<dyn Error>> {
+    match tok.parse::<usize>() {
+        Ok(i) if i < len && (tok == "0" || !tok.starts_with('0')) => Ok(i),
+        _ => Err(format!("invalid array index {tok:?}"))?,
+    }
+}
+
+fn get_mut<'a>(mut v: &'a mut Value, path: &str) -> Result<&'a mut Value, Box<dyn Error>> {
+    for tok in tokens(path)? {
+        v = match v {
+            Value::Object(obj) => obj.get_mut(&tok),
+            Value::Array(arr) => {
+                let i = index(&tok, arr.len())?;
+                arr.get_mut(i)
+            }
+            _ => None,
+        }
+        .ok_or_else(|| format!("{path:?} not found"))?;
+    }
+    Ok(v)
+}
+
+// returns parent of `path` along with last token.
+fn parent_mut<'a>(
+    doc: &'a mut Value,
+    path: &str,
+) -> Result<(&'a mut Value, String), Box<dyn Error>> {
+    let Some((parent, tok)) = path.rsplit_once('/') else {
+        Err(format!("invalid json-pointer {path:?}"))?
+    };
+    let tok = JsonPointer::unescape(tok).map_err(|_| format!("invalid json-pointer {path:?}"))?;
+    Ok((get_mut(doc, parent)?, tok.into_owned()))
+}
+
+fn add(
+    doc: &mut Value,
+    path: &str,
+    v: Value,
+    patched: &mut Vec<String>,
+) -> Result<(), Box<dyn Error>> {
+    if path.is_empty() {
+        *doc = v;
+        patched.push(String::new());
+        return Ok(());
+    }
+    let (parent, tok) = parent_mut(doc, path)?;
+    match parent {
+        Value::Object(obj) => {
+            obj.insert(tok, v);
+            patched.push(path.to_owned());
+        }
+        Value::Array(arr) => {
+            let i = if tok == "-" {
+                arr.len()
+            } else {
+                index(&tok, arr.len() + 1)?
+            };
+            arr.insert(i, v);
+            // indexes of following items are shifted
+            patched.push(path.rsplit_once('/').map_or("", |(p, _)| p).to_owned());
+        }
+        _ => Err(format!("parent of {path:?} is not object or array"))?,
+    }
+    Ok(())
+}
+
+fn remove(doc: &mut Value, path: &str, patched: &mut Vec<String>) -> Result<Value, Box<dyn Error>> {
+    let (parent, tok) = parent_mut(doc, path)?;
+    match parent {
+        Value::Object(obj) => {
+            let v = obj
+                .remove(&tok)
+                .ok_or_else(|| format!("{path:?} not found"))?;
+            patched.push(path.to_owned());
+            Ok(v)
+        }
+        Value::Array(arr) => {
+            let i = index(&tok, arr.len())?;
+            // indexes of following items are shifted
+            patched.push(path.rsplit_once('/').map_or("", |(p, _)| p).to_owned());
+            Ok(arr.remove(i))
+        }
+        _ => Err(format!("{path:?} not found"))?,
+    }
+}
@@ -0,0 +1,268 @@
+use std::{error::Error, ffi::CStr, os::raw::c_char, slice};
+
+use serde_json::Value;
+
+use crate::{Annotations, Compiler, Keyword, Phase};
+
+/// Latest ABI version, that of [`PluginV2`].
+pub const PLUGIN_ABI_VERSION: u32 = 2;
+
+/**
+Symbol exported by plugin library, of type
+`unsafe extern "C" fn() -> *const PluginV1`.
+
+For version 2 of plugin ABI, the pointer returned is that of
+[`PluginV2::v1`].
+*/
+pub const PLUGIN_SYMBOL: &str = "boon_plugin_v1";
+
+/// Custom `format` provided by plugin.
+#[repr(C)]
+pub struct PluginFormat {
+    /// nul-terminated utf-8 name of the format.
+    pub name: *const c_char,
+
+    /**
+    Validates json text of `json_len` bytes at `json`.
+
+    Returns `true` if valid. Otherwise writes utf-8 error message of
+    at most `err_cap` bytes to `err`, sets its length at `err_len` and
+    returns `false`.
+    */
+    pub validate: unsafe extern "C" fn(
+        json: *const u8,
+        json_len: usize,
+        err: *mut u8,
+        err_cap: usize,
+        err_len: *mut usize,
+    ) -> bool,
+}
+
+/**
+Custom keyword provided by plugin. see [`Compiler::register_keyword`].
+
+Unlike keywords registered from rust, [`Annotations`] are not available
+to plugin keywords.
+*/
+#[repr(C)]
+pub struct PluginKeyword {
+    /// nul-terminated utf-8 name of the keyword.
+    pub name: *const c_char,
+
+    /// `0`, `1` or `2` for [`Phase::Assertion`], [`Phase::BeforeUnevaluated`]
+    /// or [`Phase::Last`] respectively.
+    pub phase: u32,
+
+    /**
+    Validates json text of `json_len` bytes at `json`, against keyword
+    value given as json text of `value_len` bytes at `value`.
+
+    Returns `true` if valid. Otherwise writes utf-8 error message of
+    at most `err_cap` bytes to `err`, sets its length at `err_len` and
+    returns `false`.
+    */
+    pub validate: unsafe extern "C" fn(
+        value: *const u8,
+        value_len: usize,
+        json: *const u8,
+        json_len: usize,
+        err: *mut u8,
+        err_cap: usize,
+        err_len: *mut usize,
+    ) -> bool,
+}
+
+/**
+Extensions provided by plugin, as per version 1 of plugin ABI.
+
+Only `format`s can be provided. Version 2 plugins embed this as
+[`PluginV2::v1`] with `abi_version` set to `2`.
+*/
+#[repr(C)]
+pub struct PluginV1 {
+    /// `1`, or `2` if this is [`PluginV2::v1`].
+    pub abi_version: u32,
+    pub formats: *const PluginFormat,
+    pub num_formats: usize,
+}
+
+/// Extensions provided by plugin, as per version 2 of plugin ABI.
+#[repr(C)]
+pub struct PluginV2 {
+    /// `abi_version` must be [`PLUGIN_ABI_VERSION`].
+    pub v1: PluginV1,
+    pub keywords: *const PluginKeyword,
+    pub num_keywords: usize,
+}
+
+impl Compiler {
+    /**
+    Registers extensions provided by `plugin`.
+
+    Note that format and keyword names are leaked, since they are
+    identified by `&'static str`.
+
+    # Safety
+
+    `plugin` must be valid as documented in [`PluginV1`]. If its
+    `abi_version` is `2`, it must be [`PluginV2::v1`]. Its
+    functions must remain loaded as long as the schemas compiled
+    using this compiler are used.
+
+    # Errors
+
+    returns error if ABI version is not supported, any name is not valid
+    utf-8 or any keyword phase is not valid.
+    */
+    pub unsafe fn register_plugin(&mut self, plugin: &PluginV1) -> Result<(), Box<dyn Error>> {
+        self.register_plugin_with(plugin, ())
+    }
+
+    /**
+    Loads plugin from dynamic library at `path` and registers
+    extensions provided by it. The library is kept loaded as long
+    as any of its extensions are in use.
+
+    # Safety
+
+    Loading a library runs its initialization routines, and the library
+    must export [`PLUGIN_SYMBOL`] as documented.
+
+    # Errors
+
+    returns error if library could not be loaded or it is not a valid plugin.
+    */
+    #[cfg(feature = "plugins")]
+    pub unsafe fn load_plugin<P>(&mut self, path: P) -> Result<(), Box<dyn Error>>
+    where
+        P: AsRef<std::ffi::OsStr>,
+    {
+        type Entry = unsafe extern "C" fn() -> *const PluginV1;
+
+        let lib = std::sync::Arc::new(libloading::Library::new(path)?);
+        let entry = lib.get::<Entry>(PLUGIN_SYMBOL.as_bytes())?;
+        let Some(plugin) = entry().as_ref() else {
+            Err("plugin entry returned null")?
+        };
+        self.register_plugin_with(plugin, lib.clone())
+    }
+
+    // `lib` is kept alive as long as registered formats and keywords.
+    unsafe fn register_plugin_with<L>(
+        &mut self,
+        plugin: &PluginV1,
+        lib: L,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        L: Clone + Send + Sync + 'static,
+    {
+        if !(1..=PLUGIN_ABI_VERSION).contains(&plugin.abi_version) {
+            Err(format!(
+                "plugin abi version {} is not supported, want {PLUGIN_ABI_VERSION}",
+                plugin.abi_version
+            ))?
+        }
+        let formats = match plugin.num_formats {
+            0 => &[],
+            n => slice::from_raw_parts(plugin.formats, n),
+        };
+        let keywords = match plugin.abi_version {
+            1 => &[],
+            _ => {
+                let plugin = &*(plugin as *const PluginV1 as *const PluginV2);
+                match plugin.num_keywords {
+                    0 => &[],
+                    n => slice::from_raw_parts(plugin.keywords, n),
+                }
+            }
+        };
+
+        // validate all, before registering any
+        let mut phases = Vec::with_capacity(keywords.len());
+        for keyword in keywords {
+            CStr::from_ptr(keyword.name).to_str()?;
+            let phase = match keyword.phase {
+                0 => Phase::Assertion,
+                1 => Phase::BeforeUnevaluated,
+                2 => Phase::Last,
+                p => Err(format!("plugin keyword phase {p} is not valid"))?,
+            };
+            phases.push(phase);
+        }
+
+        for format in formats {
+            let name = CStr::from_ptr(format.name).to_str()?;
+            let name: &'static str = Box::leak(name.to_owned().into_boxed_str());
+            let validate = format.validate;
+            let lib = lib.clone();
+            self.register_format_fn(name, move |v| {
+                let _ = &lib;
+                let json = v.to_string();
+                // SAFETY: guaranteed by caller of register_plugin
+                call_validate(|err, err_cap, err_len| unsafe {
+                    validate(json.as_ptr(), json.len(), err, err_cap, err_len)
+                })
+            });
+        }
+        for (keyword, phase) in keywords.iter().zip(phases) {
+            let name = CStr::from_ptr(keyword.name).to_str()?;
+            let name: &'static str = Box::leak(name.to_owned().into_boxed_str());
+            let validate = keyword.validate;
+            let lib = lib.clone();
+            self.register_keyword(name, phase, move |value| {
+                Ok(Box::new(PluginKeywordFn {
+                    value: value.to_string(),
+                    validate,
+                    _lib: lib.clone(),
+                }))
+            });
+        }
+        Ok(())
+    }
+}
+
+struct PluginKeywordFn<L> {
+    value: String,
+    validate: unsafe extern "C" fn(
+        *const u8,
+        usize,
+        *const u8,
+        usize,
+        *mut u8,
+        usize,
+        *mut usize,
+    ) -> bool,
+    _lib: L,
+}
+
+impl<L: Send + Sync> Keyword for PluginKeywordFn<L> {
+    fn validate(&self, v: &Value, _annotations: &Annotations) -> Result<(), Box<dyn Error>> {
+        let (value, json) = (&self.value, v.to_string());
+        // SAFETY: guaranteed by caller of register_plugin
+        call_validate(|err, err_cap, err_len| unsafe {
+            (self.validate)(
+                value.as_ptr(),
+                value.len(),
+                json.as_ptr(),
+                json.len(),
+                err,
+                err_cap,
+                err_len,
+            )
+        })
+    }
+}
+
+// calls plugin's `validate` with buffer for error message.
+fn call_validate<F>(validate: F) -> Result<(), Box<dyn Error>>
+where
+    F: FnOnce(*mut u8, usize, *mut usize) -> bool,
+{
+    let mut err = vec![0u8; 256];
+    let mut err_len = 0;
+    if !validate(err.as_mut_ptr(), err.len(), &mut err_len) {
+        err.truncate(err_len.min(err.len()));
+        Err(String::from_utf8_lossy(&err).into_owned())?
+    }
+    Ok(())
+}
@@ -0,0 +1,228 @@
+use serde_json::Value;
+
+use crate::{defaults::*, *};
+
+/// Output syntax of [`Schemas::pretty_print`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrettyFormat {
+    /// standard json, without comments.
+    #[default]
+    Json,
+    /// json5, with `//` comments and unquoted keys where possible.
+    Json5,
+    /// yaml block style, with `#` comments.
+    Yaml,
+}
+
+impl Schemas {
+    /**
+    Formats `v` with object keys ordered as declared in `properties` of
+    schema identified by `sch_index`.
+
+    Properties declared are written first, in declaration order, followed
+    by the remaining ones in the order of `v`. With [`PrettyFormat::Json5`]
+    and [`PrettyFormat::Yaml`], `description` of each property schema is
+    written as comment above it. This is useful to generate human-friendly
+    canonical config files.
+
+    Property schemas are taken from the schema and the subschemas it
+    applies unconditionally i.e. `$ref` and `allOf`.
+
+    Note that `serde_json` sorts keys of json objects unless its
+    `preserve_order` feature is enabled, so enable it to honor declaration
+    order.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let schema = json!({
+        "properties": {
+            "port": {"description": "port to listen on"},
+            "host": {"type": "string"}
+        }
+    });
+    compiler.add_resource("config.json", schema)?;
+    let sch_index = compiler.compile("config.json", &mut schemas)?;
+
+    let config = json!({"port": 80, "host": "localhost"});
+    let yaml = schemas.pretty_print(&config, sch_index, PrettyFormat::Yaml);
+    assert!(yaml.contains("# port to listen on\nport: 80\n"));
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn pretty_print(&self, v: &Value, sch_index: SchemaIndex, format: PrettyFormat) -> String {
+        let lines = self.pretty_lines(v, Some(sch_index), format);
+        let mut s = lines.join("\n");
+        s.push('\n');
+        s
+    }
+
+    fn pretty_lines(
+        &self,
+        v: &Value,
+        sch: Option<SchemaIndex>,
+        format: PrettyFormat,
+    ) -> Vec<String> {
+        let list = sch.map(|sch| self.applied_schemas(sch)).unwrap_or_default();
+        match v {
+            Value::Object(obj) if !obj.is_empty() => {
+                // order keys
+                let mut keys = vec![];
+                for &sch in &list {
+                    for pname in &self.get(sch).property_order {
+                        let pname = pname.as_ref();
+                        if obj.contains_key(pname) && !keys.contains(&pname) {
+                            keys.push(pname);
+                        }
+                    }
+                }
+                for pname in obj.keys() {
+                    if !keys.contains(&pname.as_str()) {
+                        keys.push(pname);
+                    }
+                }
+
+                let mut lines = vec![];
+                if format != PrettyFormat::Yaml {
+                    lines.push("{".to_owned());
+                }
+                for (i, pname) in keys.iter().enumerate() {
+                    let pvalue = &obj[*pname];
+                    let psch = list
+                        .iter()
+                        .flat_map(|&sch| prop_schemas(self.get(sch), pname))
+                        .next();
+                    let description = psch.and_then(|psch| {
+                        self.applied_schemas(psch)
+                            .into_iter()
+                            .find_map(|sch| self.get(sch).description.as_deref())
+                    });
+                    let plines = self.pretty_lines(pvalue, psch, format);
+                    let (key, indent) = match format {
+                        PrettyFormat::Json => (json_string(pname), "  "),
+                        PrettyFormat::Json5 => (json5_key(pname), "  "),
+                        PrettyFormat::Yaml => (yaml_scalar(pname), ""),
+                    };
+                    if let (Some(description), PrettyFormat::Json5 | PrettyFormat::Yaml) =
+                        (description, format)
+                    {
+                        let marker = if format == PrettyFormat::Yaml {
+                            "#"
+                        } else {
+                            "//"
+                        };
+                        for line in description.lines() {
+                            lines.push(format!("{indent}{marker} {line}").trim_end().to_owned());
+                        }
+                    }
+                    let mut entry = if format == PrettyFormat::Yaml && is_block(pvalue) {
+                        let mut entry = vec![format!("{key}:")];
+                        entry.extend(plines.into_iter().map(|line| format!("  {line}")));
+                        entry
+                    } else {
+                        let mut plines = plines.into_iter();
+                        let first = plines.next().unwrap_or_default();
+                        let mut entry = vec![format!("{indent}{key}: {first}")];
+                        entry.extend(plines.map(|line| format!("{indent}{line}")));
+                        entry
+                    };
+                    if format != PrettyFormat::Yaml && i + 1 < keys.len() {
+                        if let Some(last) = entry.last_mut() {
+                            last.push(',');
+                        }
+                    }
+                    lines.extend(entry);
+                }
+                if format != PrettyFormat::Yaml {
+                    lines.push("}".to_owned());
+                }
+                lines
+            }
+            Value::Array(arr) if !arr.is_empty() => {
+                let mut lines = vec![];
+                if format != PrettyFormat::Yaml {
+                    lines.push("[".to_owned());
+                }
+                for (i, item) in arr.iter().enumerate() {
+                    let isch = list
+                        .iter()
+                        .find_map(|&sch| item_schema(self, self.get(sch), i));
+                    let ilines = self.pretty_lines(item, isch, format);
+                    if format == PrettyFormat::Yaml {
+                        for (j, line) in ilines.into_iter().enumerate() {
+                            let prefix = if j == 0 { "- " } else { "  " };
+                            lines.push(format!("{prefix}{line}"));
+                        }
+                        continue;
+                    }
+                    let n = ilines.len();
+                    for (j, line) in ilines.into_iter().enumerate() {
+                        let comma = if j + 1 == n && i + 1 < arr.len() {
+                            ","
+                        } else {
+                            ""
+                        };
+                        lines.push(format!("  {line}{comma}"));
+                    }
+                }
+                if format != PrettyFormat::Yaml {
+                    lines.push("]".to_owned());
+                }
+                lines
+            }
+            Value::Object(_) => vec!["{}".to_owned()],
+            Value::Array(_) => vec!["[]".to_owned()],
+            Value::String(s) if format == PrettyFormat::Yaml => vec![yaml_scalar(s)],
+            _ => vec![v.to_string()],
+        }
+    }
+}
+
+// tells whether `v` is written as yaml block, on lines following its key.
+fn is_block(v: &Value) -> bool {
+    match v {
+        Value::Object(obj) => !obj.is_empty(),
+        Value::Array(arr) => !arr.is_empty(),
+        _ => false,
+    }
+}
+
+fn json_string(s: &str) -> String {
+    Value::String(s.to_owned()).to_string()
+}
+
+fn json5_key(s: &str) -> String {
+    let mut chars = s.chars();
+    let ident = matches!(chars.next(), Some(ch) if ch == '_' || ch == '$' || ch.is_ascii_alphabetic())
+        && chars.all(|ch| ch == '_' || ch == '$' || ch.is_ascii_alphanumeric());
+    match ident {
+        true => s.to_owned(),
+        false => json_string(s),
+    }
+}
+
+// returns `s` as plain yaml scalar if unambiguous, otherwise double-quoted.
+fn yaml_scalar(s: &str) -> String {
+    const RESERVED: [&str; 11] = [
+        "true", "false", "yes", "no", "on", "off", "y", "n", "null", "~", "",
+    ];
+    let mut chars = s.chars();
+    let plain = matches!(chars.next(), Some(ch) if ch.is_ascii_alphabetic() || ch == '_')
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || "_-./".contains(ch))
+        && !RESERVED.contains(&s.to_ascii_lowercase().as_str());
+    match plain {
+        true => s.to_owned(),
+        false => json_string(s),
+    }
+}
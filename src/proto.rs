@@ -0,0 +1,408 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde_json::Value;
+
+use crate::{export::*, util::*, Additional, Items, Schema, SchemaIndex, Schemas, Type};
+
+/// Result of [`export_proto`].
+#[derive(Debug, Clone)]
+pub struct ProtoExport {
+    /// `proto3` file contents.
+    pub proto: String,
+    /// constraints not representable in protobuf.
+    pub unrepresentable: Vec<Unrepresentable>,
+}
+
+/**
+Exports compiled object schemas as `proto3` messages.
+
+`messages` maps message name to the schema it is derived from. Nested
+object schemas and referenced schemas are exported as separate messages,
+named after their property or location. Constraints which cannot be
+expressed in protobuf are dropped and reported in
+[`ProtoExport::unrepresentable`]. Values which cannot be typed are
+exported using `google.protobuf.Value`.
+
+Properties which are not required or nullable are exported as `optional`
+fields. `oneOf` of object schemas is exported as `oneof` field.
+
+Note that field numbers are assigned sequentially in the order of
+property names, so adding a property may renumber existing fields.
+
+# Panics
+
+Panics if any of the schema index is not generated for `schemas`.
+*/
+pub fn export_proto(schemas: &Schemas, messages: &[(&str, SchemaIndex)]) -> ProtoExport {
+    let mut exp = Exporter {
+        schemas,
+        names: HashMap::new(),
+        used: HashSet::new(),
+        messages: HashSet::new(),
+        queue: VecDeque::new(),
+        active: HashSet::new(),
+        reported: HashSet::new(),
+        defs: vec![],
+        wkt: false,
+        unrepresentable: vec![],
+    };
+    for &(name, idx) in messages {
+        let sch = schemas.get(idx);
+        if !is_object(sch) || sch.properties.is_empty() {
+            exp.report(sch, "type", "root schema must be object with properties");
+            continue;
+        }
+        exp.message_name(idx, name);
+    }
+    while let Some(idx) = exp.queue.pop_front() {
+        exp.message(idx);
+    }
+    let mut proto = vec![r#"syntax = "proto3";"#.to_owned()];
+    if exp.wkt {
+        proto.push(r#"import "google/protobuf/struct.proto";"#.to_owned());
+    }
+    proto.append(&mut exp.defs);
+    ProtoExport {
+        proto: join_iter(proto, "\n\n"),
+        unrepresentable: exp.unrepresentable,
+    }
+}
+
+enum ProtoType {
+    Single { ty: String, nullable: bool },
+    Repeated(String),
+    Map(String),
+    OneOf(Vec<String>),
+}
+
+struct Exporter<'a> {
+    schemas: &'a Schemas,
+    names: HashMap<SchemaIndex, String>, // message/enum types
+    used: HashSet<String>,
+    messages: HashSet<String>,
+    queue: VecDeque<SchemaIndex>,
+    active: HashSet<SchemaIndex>, // being exported by type_ref
+    reported: HashSet<SchemaIndex>,
+    defs: Vec<String>,
+    wkt: bool, // whether google/protobuf/struct.proto is used
+    unrepresentable: Vec<Unrepresentable>,
+}
+
+impl<'a> Exporter<'a> {
+    fn report(&mut self, sch: &Schema, keyword: &'static str, reason: impl Into<String>) {
+        self.unrepresentable.push(Unrepresentable {
+            schema_url: sch.loc.clone(),
+            keyword,
+            reason: reason.into(),
+        });
+    }
+
+    fn value(&mut self) -> ProtoType {
+        self.wkt = true;
+        ProtoType::Single {
+            ty: "google.protobuf.Value".to_owned(),
+            nullable: true,
+        }
+    }
+
+    fn unique_name(&mut self, hint: &str) -> String {
+        let base = type_name(hint);
+        let mut name = base.clone();
+        let mut i = 2;
+        while self.used.contains(&name) {
+            name = format!("{base}{i}");
+            i += 1;
+        }
+        self.used.insert(name.clone());
+        name
+    }
+
+    fn message_name(&mut self, idx: SchemaIndex, hint: &str) -> String {
+        if let Some(name) = self.names.get(&idx) {
+            return name.clone();
+        }
+        let name = self.unique_name(hint);
+        self.names.insert(idx, name.clone());
+        self.messages.insert(name.clone());
+        self.queue.push_back(idx);
+        name
+    }
+
+    fn message(&mut self, idx: SchemaIndex) {
+        let sch = self.schemas.get(idx);
+        let name = self.names[&idx].clone();
+        let mut props = sch.properties.iter().collect::<Vec<_>>();
+        props.sort_by(|a, b| a.0.cmp(b.0));
+        let fnames = field_names(&props.iter().map(|p| p.0.as_ref()).collect::<Vec<_>>());
+        let mut used = fnames.iter().cloned().collect::<HashSet<_>>();
+        let mut fields = vec![];
+        let mut num = 1;
+        for ((pname, &pidx), fname) in props.into_iter().zip(fnames) {
+            let opts = if *fname != **pname {
+                format!(" [json_name = {}]", Value::String(pname.to_string()))
+            } else {
+                String::new()
+            };
+            match self.type_ref(pidx, &format!("{name}_{fname}")) {
+                ProtoType::Single { ty, nullable } => {
+                    let label = if sch.required.contains(pname) && !nullable {
+                        ""
+                    } else {
+                        "optional "
+                    };
+                    fields.push(format!("  {label}{ty} {fname} = {num}{opts};"));
+                    num += 1;
+                }
+                ProtoType::Repeated(ty) => {
+                    fields.push(format!("  repeated {ty} {fname} = {num}{opts};"));
+                    num += 1;
+                }
+                ProtoType::Map(ty) => {
+                    fields.push(format!("  map<string, {ty}> {fname} = {num}{opts};"));
+                    num += 1;
+                }
+                ProtoType::OneOf(types) => {
+                    fields.push(format!("  oneof {fname} {{"));
+                    for ty in types {
+                        let member = snake_case(&ty);
+                        let member = unique_field_name(&format!("{fname}_{member}"), &mut used);
+                        fields.push(format!("    {ty} {member} = {num};"));
+                        num += 1;
+                    }
+                    fields.push("  }".to_owned());
+                }
+            }
+        }
+        let def = format!("message {name} {{\n{}\n}}", join_iter(fields, "\n"));
+        self.defs.push(def);
+    }
+
+    // returns type usable as repeated or map value.
+    fn element_type(&mut self, idx: SchemaIndex, hint: &str, kw: &'static str) -> String {
+        match self.type_ref(idx, hint) {
+            ProtoType::Single { ty, nullable } => {
+                if nullable && ty != "google.protobuf.Value" {
+                    self.report(self.schemas.get(idx), "type", "nullable element");
+                }
+                ty
+            }
+            _ => {
+                self.report(self.schemas.get(idx), kw, "nested collections");
+                self.wkt = true;
+                "google.protobuf.Value".to_owned()
+            }
+        }
+    }
+
+    fn type_ref(&mut self, idx: SchemaIndex, hint: &str) -> ProtoType {
+        if !self.active.insert(idx) {
+            let sch = self.schemas.get(idx);
+            self.report(sch, "$ref", "cyclic reference");
+            return self.value();
+        }
+        let ty = self.schema_type(idx, hint);
+        self.active.remove(&idx);
+        ty
+    }
+
+    fn schema_type(&mut self, idx: SchemaIndex, hint: &str) -> ProtoType {
+        let sch = self.schemas.get(idx);
+        self.check_constraints(sch);
+        if let Some(b) = sch.boolean {
+            if !b {
+                self.report(sch, "", "false schema");
+            }
+            return self.value();
+        }
+        if let Some(ref_) = sch.ref_ {
+            if sch.types.is_empty() {
+                return self.type_ref(ref_, &ref_hint(self.schemas.get(ref_)));
+            }
+            self.report(sch, "$ref", "$ref with sibling type");
+        }
+
+        let mut types = sch.types;
+        if types.is_empty() {
+            if let Some(e) = &sch.enum_ {
+                types = e.types;
+            } else if !sch.properties.is_empty() {
+                types.add(Type::Object);
+            }
+        }
+        let nullable = types.is_empty() || types.contains(Type::Null);
+        let mut non_null = types
+            .iter()
+            .filter(|&t| t != Type::Null)
+            .collect::<Vec<_>>();
+        if non_null == [Type::Number, Type::Integer] {
+            non_null = vec![Type::Number];
+        }
+        let ty = match non_null.as_slice() {
+            [] => {
+                if !types.is_empty() {
+                    self.report(sch, "type", "null type");
+                    return self.value();
+                }
+                return self.oneof_type(idx, hint);
+            }
+            [Type::Boolean] => "bool".to_owned(),
+            [Type::Integer] => "int64".to_owned(),
+            [Type::Number] => "double".to_owned(),
+            [Type::String] => self.enum_type(idx, hint),
+            [Type::Array] => {
+                if nullable {
+                    self.report(sch, "type", "nullable repeated field");
+                }
+                let items = match &sch.items {
+                    Some(Items::SchemaRef(items)) => Some(*items),
+                    _ => sch.items2020,
+                };
+                let ty = match items {
+                    Some(items) => self.element_type(items, &format!("{hint}_item"), "items"),
+                    None => {
+                        self.wkt = true;
+                        "google.protobuf.Value".to_owned()
+                    }
+                };
+                return ProtoType::Repeated(ty);
+            }
+            [Type::Object] => {
+                if sch.properties.is_empty() {
+                    if let Some(Additional::SchemaRef(additional)) = sch.additional_properties {
+                        if nullable {
+                            self.report(sch, "type", "nullable map field");
+                        }
+                        let ty = self.element_type(
+                            additional,
+                            &format!("{hint}_value"),
+                            "additionalProperties",
+                        );
+                        return ProtoType::Map(ty);
+                    }
+                    self.wkt = true;
+                    "google.protobuf.Struct".to_owned()
+                } else {
+                    if !matches!(
+                        sch.additional_properties,
+                        None | Some(Additional::Bool(false))
+                    ) {
+                        self.report(sch, "additionalProperties", "messages with extra fields");
+                    }
+                    self.message_name(idx, hint)
+                }
+            }
+            _ => {
+                self.report(sch, "type", "multiple types");
+                return self.value();
+            }
+        };
+        ProtoType::Single { ty, nullable }
+    }
+
+    fn enum_type(&mut self, idx: SchemaIndex, hint: &str) -> String {
+        let sch = self.schemas.get(idx);
+        let Some(e) = &sch.enum_ else {
+            return "string".to_owned();
+        };
+        if !e
+            .values
+            .iter()
+            .all(|v| matches!(v, Value::String(s) if !s.is_empty()))
+        {
+            self.report(sch, "enum", "enum values must be non-empty strings");
+            return "string".to_owned();
+        }
+        if let Some(name) = self.names.get(&idx) {
+            return name.clone();
+        }
+        let name = self.unique_name(hint);
+        self.names.insert(idx, name.clone());
+
+        // enum values are scoped to package, so prefix them with enum name
+        let prefix = snake_case(&name).to_ascii_uppercase();
+        let mut values = vec![format!("  {prefix}_UNSPECIFIED = 0;")];
+        let mut seen = HashSet::from(["UNSPECIFIED".to_owned()]);
+        for (i, v) in e.values.iter().filter_map(|v| v.as_str()).enumerate() {
+            let value = field_name(&snake_case(v)).to_ascii_uppercase();
+            if !seen.insert(value.clone()) {
+                self.report(
+                    sch,
+                    "enum",
+                    format!("enum value {} collides, skipped", quote(v)),
+                );
+                continue;
+            }
+            values.push(format!("  {prefix}_{value} = {};", i + 1));
+        }
+        let values = join_iter(values, "\n");
+        self.defs.push(format!("enum {name} {{\n{values}\n}}"));
+        name
+    }
+
+    fn oneof_type(&mut self, idx: SchemaIndex, hint: &str) -> ProtoType {
+        let sch = self.schemas.get(idx);
+        let (kw, members) = if !sch.one_of.is_empty() {
+            ("oneOf", self.schemas.indexes(sch.one_of))
+        } else if !sch.any_of.is_empty() {
+            ("anyOf", self.schemas.indexes(sch.any_of))
+        } else {
+            return self.value();
+        };
+        let mut types = vec![];
+        for (i, &m) in members.iter().enumerate() {
+            match self.type_ref(m, &format!("{hint}_{i}")) {
+                ProtoType::Single { ty, .. } if self.messages.contains(&ty) => types.push(ty),
+                _ => {
+                    self.report(sch, kw, "oneof members must be messages");
+                    return self.value();
+                }
+            }
+        }
+        if kw == "anyOf" {
+            self.report(sch, kw, "anyOf exported as oneof");
+        }
+        ProtoType::OneOf(types)
+    }
+
+    fn check_constraints(&mut self, sch: &Schema) {
+        if !self.reported.insert(sch.idx) {
+            return;
+        }
+        for kw in constraints(sch) {
+            self.report(sch, kw, "no protobuf equivalent");
+        }
+    }
+}
+
+// converts camelCase or kebab-case to snake_case
+fn snake_case(s: &str) -> String {
+    let mut name = String::new();
+    let mut prev = None;
+    for ch in s.chars() {
+        if ch.is_ascii_uppercase() {
+            if matches!(prev, Some(p) if char::is_ascii_lowercase(&p) || char::is_ascii_digit(&p)) {
+                name.push('_');
+            }
+            name.push(ch.to_ascii_lowercase());
+        } else if ch.is_ascii_alphanumeric() {
+            name.push(ch);
+        } else if !name.ends_with('_') {
+            name.push('_');
+        }
+        prev = Some(ch);
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snake_case() {
+        assert_eq!(snake_case("PersonColor"), "person_color");
+        assert_eq!(snake_case("dark-red"), "dark_red");
+        assert_eq!(snake_case("RED"), "red");
+        assert_eq!(snake_case("isActive"), "is_active");
+    }
+}
@@ -0,0 +1,166 @@
+use std::{error::Error, sync::Arc};
+
+use crate::ecma;
+
+/// Regex engine used for `pattern` and `patternProperties`.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RegexEngine {
+    /// [regex](https://docs.rs/regex) crate, which guarantees linear time
+    /// matching, but does not support lookaround and backreferences.
+    #[default]
+    Regex,
+    /// [fancy-regex](https://docs.rs/fancy-regex) crate, which supports
+    /// lookaround and backreferences using backtracking.
+    #[cfg(feature = "fancy-regex")]
+    FancyRegex,
+}
+
+/**
+Limits on regexes compiled for `pattern` and `patternProperties`.
+
+These protect against pathological patterns in third-party schemas.
+Patterns exceeding the limits fail to compile with
+[`CompileError::InvalidRegex`](crate::CompileError::InvalidRegex).
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegexLimits {
+    /// Approximate limit in bytes on the size of compiled regex.
+    /// Defaults to 10 MiB. see [`regex::RegexBuilder::size_limit`].
+    pub size_limit: usize,
+    /// Approximate limit in bytes on the cache used by lazy DFA, per
+    /// regex. Defaults to 2 MiB. see [`regex::RegexBuilder::dfa_size_limit`].
+    pub dfa_size_limit: usize,
+    /// Limit on nesting of groups and repetitions in pattern.
+    /// Defaults to 250. see [`regex::RegexBuilder::nest_limit`].
+    pub nest_limit: u32,
+    /// Limit on backtracking steps per match with `RegexEngine::FancyRegex`,
+    /// available with feature `fancy-regex`. Exceeding it during validation
+    /// fails with [`ErrorKind::RegexLimit`](crate::ErrorKind::RegexLimit).
+    /// Defaults to 1,000,000.
+    pub backtrack_limit: usize,
+}
+
+impl Default for RegexLimits {
+    fn default() -> Self {
+        Self {
+            size_limit: 10 * (1 << 20),
+            dfa_size_limit: 2 * (1 << 20),
+            nest_limit: 250,
+            backtrack_limit: 1_000_000,
+        }
+    }
+}
+
+impl RegexEngine {
+    pub(crate) fn compile(
+        &self,
+        pattern: &str,
+        limits: &RegexLimits,
+    ) -> Result<Regex, Box<dyn Error>> {
+        match self {
+            Self::Regex => {
+                let converted = ecma::convert(pattern)?;
+                let re = regex::RegexBuilder::new(converted.as_ref())
+                    .size_limit(limits.size_limit)
+                    .dfa_size_limit(limits.dfa_size_limit)
+                    .nest_limit(limits.nest_limit)
+                    .build()?;
+                Ok(Regex::new(pattern, Engine::Regex(re)))
+            }
+            #[cfg(feature = "fancy-regex")]
+            Self::FancyRegex => {
+                // patterns without lookaround and backreferences are
+                // converted for ecma compatibility.
+                let converted = match ecma::convert(pattern) {
+                    Ok(converted) => converted,
+                    Err(_) => pattern.into(),
+                };
+                let re = fancy_regex::RegexBuilder::new(converted.as_ref())
+                    .backtrack_limit(limits.backtrack_limit)
+                    .delegate_size_limit(limits.size_limit)
+                    .delegate_dfa_size_limit(limits.dfa_size_limit)
+                    .build()?;
+                Ok(Regex::new(pattern, Engine::FancyRegex(re)))
+            }
+        }
+    }
+}
+
+/// compiled regex, which is cheap to clone.
+#[derive(Debug, Clone)]
+pub(crate) struct Regex {
+    pattern: Arc<str>, // before ecma translation
+    re: Arc<Engine>,
+}
+
+#[derive(Debug, Clone)]
+enum Engine {
+    Regex(regex::Regex),
+    #[cfg(feature = "fancy-regex")]
+    FancyRegex(fancy_regex::Regex),
+}
+
+impl Regex {
+    fn new(pattern: &str, re: Engine) -> Self {
+        Self {
+            pattern: pattern.into(),
+            re: Arc::new(re),
+        }
+    }
+
+    /// backtrack limit exceeded is treated as mismatch.
+    /// use [`Self::try_match`] when validating.
+    pub(crate) fn is_match(&self, haystack: &str) -> bool {
+        self.try_match(haystack).unwrap_or(false)
+    }
+
+    /// returns error if backtrack limit is exceeded.
+    pub(crate) fn try_match(&self, haystack: &str) -> Result<bool, Box<dyn Error>> {
+        match self.re.as_ref() {
+            Engine::Regex(re) => Ok(re.is_match(haystack)),
+            #[cfg(feature = "fancy-regex")]
+            Engine::FancyRegex(re) => Ok(re.is_match(haystack)?),
+        }
+    }
+
+    /// returns pattern as specified in schema.
+    pub(crate) fn as_str(&self) -> &str {
+        &self.pattern
+    }
+}
+
+/// `patternProperties` regexes combined, to match property name against
+/// all of them in single pass.
+#[derive(Debug)]
+pub(crate) struct RegexSet(regex::RegexSet);
+
+impl RegexSet {
+    /// returns `None` if some regex is not compiled with [`RegexEngine::Regex`],
+    /// or if the combined regex exceeds `limits`.
+    pub(crate) fn new<'a>(
+        regexes: impl IntoIterator<Item = &'a Regex>,
+        limits: &RegexLimits,
+    ) -> Option<Self> {
+        let mut patterns = vec![];
+        for regex in regexes {
+            match regex.re.as_ref() {
+                Engine::Regex(re) => patterns.push(re.as_str()),
+                #[cfg(feature = "fancy-regex")]
+                Engine::FancyRegex(_) => return None,
+            }
+        }
+        let set = regex::RegexSetBuilder::new(patterns)
+            .size_limit(limits.size_limit)
+            .dfa_size_limit(limits.dfa_size_limit)
+            .nest_limit(limits.nest_limit)
+            .build()
+            .ok()?;
+        Some(Self(set))
+    }
+
+    /// returns indexes of regexes matching `haystack`, in ascending order.
+    pub(crate) fn matches(&self, haystack: &str) -> regex::SetMatchesIntoIter {
+        self.0.matches(haystack).into_iter()
+    }
+}
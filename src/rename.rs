@@ -0,0 +1,326 @@
+use std::error::Error;
+
+use serde_json::{Map, Value};
+use url::Url;
+
+use crate::util::*;
+
+/// What to rename using [`rename`].
+#[derive(Debug, Clone, Copy)]
+pub enum RenameTarget<'a> {
+    /// `$anchor` with given `name`, in resource identified by `url`.
+    Anchor { url: &'a str, name: &'a str },
+    /// `$id` of the resource identified by `url`.
+    Id { url: &'a str },
+    /// entry `name` in `$defs` or `definitions`, of resource identified by `url`.
+    Def { url: &'a str, name: &'a str },
+}
+
+/**
+Renames an `$anchor`, `$id` or `$defs` entry across given schema
+documents, rewriting all references to it.
+
+`docs` are pairs of document url and its content. `new_name` is used as
+is for `$id`, so it may be relative to the parent resource. References
+which no longer resolve to their original target are rewritten, keeping
+them relative if they were relative. This includes references affected
+by the change in base url, when `$id` is renamed.
+
+Returns the number of references rewritten.
+
+# Errors
+
+returns error if `target` is not found, or `new_name` is already in use.
+*/
+pub fn rename(
+    docs: &mut [(String, Value)],
+    target: RenameTarget,
+    new_name: &str,
+) -> Result<usize, Box<dyn Error>> {
+    let old = Index::new(docs)?;
+    let url = match target {
+        RenameTarget::Anchor { url, .. }
+        | RenameTarget::Id { url }
+        | RenameTarget::Def { url, .. } => UrlFrag::absolute(url)?.url,
+    };
+    let Some(res) = old.resources.iter().position(|r| r.url == url) else {
+        Err(format!("resource {url} not found"))?
+    };
+    let (doc, rptr) = (old.resources[res].doc, old.resources[res].ptr.clone());
+
+    // apply rename --
+    let mut moved = None; // (doc, old_prefix, new_prefix)
+    let mut anchor = None; // old name
+    match target {
+        RenameTarget::Anchor { name, .. } => {
+            let find = |name: &str| old.anchors.iter().find(|a| a.res == res && a.name == name);
+            if find(new_name).is_some() {
+                Err(format!("anchor {new_name:?} already exists in {url}"))?;
+            }
+            let Some(a) = find(name) else {
+                Err(format!("anchor {name:?} not found in {url}"))?
+            };
+            let obj = object_mut(docs, a.doc, &a.ptr)?;
+            if a.legacy {
+                obj.insert("$id".to_owned(), Value::String(format!("#{new_name}")));
+            } else {
+                obj.insert("$anchor".to_owned(), Value::String(new_name.to_owned()));
+            }
+            anchor = Some(name.to_owned());
+        }
+        RenameTarget::Id { .. } => {
+            let obj = object_mut(docs, doc, &rptr)?;
+            obj.insert("$id".to_owned(), Value::String(new_name.to_owned()));
+        }
+        RenameTarget::Def { name, .. } => {
+            let obj = object_mut(docs, doc, &rptr)?;
+            let kw = ["$defs", "definitions"].into_iter().find(
+                |kw| matches!(obj.get(*kw), Some(Value::Object(defs)) if defs.contains_key(name)),
+            );
+            let Some((kw, Some(Value::Object(defs)))) = kw.map(|kw| (kw, obj.get_mut(kw))) else {
+                Err(format!("definition {name:?} not found in {url}"))?
+            };
+            if defs.contains_key(new_name) {
+                Err(format!("definition {new_name:?} already exists in {url}"))?;
+            }
+            if let Some(v) = defs.remove(name) {
+                defs.insert(new_name.to_owned(), v);
+            }
+            let ptr = JsonPointer::from(rptr.as_str());
+            moved = Some((
+                doc,
+                ptr.append2(kw, name).to_string(),
+                ptr.append2(kw, new_name).to_string(),
+            ));
+        }
+    }
+
+    // maps location before rename to location after rename
+    let move_ptr = |d: usize, ptr: &str| -> String {
+        if let Some((doc, from, to)) = &moved {
+            if d == *doc {
+                if let Some(rest) = ptr.strip_prefix(from.as_str()) {
+                    if rest.is_empty() || rest.starts_with('/') {
+                        return format!("{to}{rest}");
+                    }
+                }
+            }
+        }
+        ptr.to_owned()
+    };
+
+    // rewrite refs --
+    let new = Index::new(docs)?;
+    let mut count = 0;
+    for site in &old.refs {
+        let ptr = move_ptr(site.doc, &site.ptr);
+        let Some(new_site) = new
+            .refs
+            .iter()
+            .find(|s| s.doc == site.doc && s.ptr == ptr && s.kw == site.kw)
+        else {
+            continue;
+        };
+
+        // target after rename
+        let want = match old.resolve(&site.value, &site.base)? {
+            Target::Internal { res: r, frag } => {
+                let r = &old.resources[r];
+                let r_ptr = move_ptr(r.doc, &r.ptr);
+                let Some(r) = new
+                    .resources
+                    .iter()
+                    .find(|nr| nr.doc == r.doc && nr.ptr == r_ptr)
+                else {
+                    continue;
+                };
+                let frag = match frag {
+                    Fragment::Anchor(a) if Some(&a.0) == anchor.as_ref() && r.url == url => {
+                        new_name.to_owned()
+                    }
+                    Fragment::Anchor(a) => a.0,
+                    Fragment::JsonPointer(p) => {
+                        let abs = format!("{}{}", r.ptr, p);
+                        let abs = move_ptr(r.doc, &abs);
+                        abs[r.ptr.len()..].to_owned()
+                    }
+                };
+                (r.url.clone(), frag)
+            }
+            Target::External(url, frag) => (url, frag.as_str().to_owned()),
+        };
+        let got = UrlFrag::join(&new_site.base, &site.value)?;
+        if got.url == want.0 && got.frag.as_str() == want.1 {
+            continue;
+        }
+
+        let (want_url, want_frag) = want;
+        let frag = if want_frag.is_empty() {
+            String::new()
+        } else {
+            format!("#{}", Fragment::encode(&want_frag))
+        };
+        let absolute = Url::parse(split(&site.value).0).is_ok();
+        let value = if want_url == new_site.base {
+            if frag.is_empty() {
+                "#".to_owned()
+            } else {
+                frag
+            }
+        } else if absolute {
+            format!("{want_url}{frag}")
+        } else {
+            match new_site.base.make_relative(&want_url) {
+                Some(rel) if !rel.is_empty() => format!("{rel}{frag}"),
+                _ => format!("{want_url}{frag}"),
+            }
+        };
+        let obj = object_mut(docs, new_site.doc, &new_site.ptr)?;
+        obj.insert(site.kw.to_owned(), Value::String(value));
+        count += 1;
+    }
+    Ok(count)
+}
+
+fn object_mut<'a>(
+    docs: &'a mut [(String, Value)],
+    doc: usize,
+    ptr: &str,
+) -> Result<&'a mut Map<String, Value>, Box<dyn Error>> {
+    match docs[doc].1.pointer_mut(ptr) {
+        Some(Value::Object(obj)) => Ok(obj),
+        _ => Err(format!("no object at {ptr:?} in {}", docs[doc].0))?,
+    }
+}
+
+struct Resource {
+    doc: usize,
+    ptr: String,
+    url: Url,
+}
+
+struct AnchorSite {
+    res: usize,
+    doc: usize,
+    ptr: String,
+    name: String,
+    legacy: bool, // fragment-only $id
+}
+
+struct RefSite {
+    doc: usize,
+    ptr: String,
+    kw: &'static str,
+    value: String,
+    base: Url,
+}
+
+enum Target {
+    Internal { res: usize, frag: Fragment },
+    External(Url, Fragment),
+}
+
+#[derive(Default)]
+struct Index {
+    resources: Vec<Resource>,
+    anchors: Vec<AnchorSite>,
+    refs: Vec<RefSite>,
+}
+
+impl Index {
+    fn new(docs: &[(String, Value)]) -> Result<Self, Box<dyn Error>> {
+        let mut index = Index::default();
+        for (doc, (url, v)) in docs.iter().enumerate() {
+            let url = UrlFrag::absolute(url)?.url;
+            index.resources.push(Resource {
+                doc,
+                ptr: String::new(),
+                url: url.clone(),
+            });
+            let res = index.resources.len() - 1;
+            index.walk(doc, v, &JsonPointer::from(""), res, &url)?;
+        }
+        Ok(index)
+    }
+
+    fn walk(
+        &mut self,
+        doc: usize,
+        v: &Value,
+        ptr: &JsonPointer,
+        mut res: usize,
+        base: &Url,
+    ) -> Result<(), Box<dyn Error>> {
+        let obj = match v {
+            Value::Object(obj) => obj,
+            Value::Array(arr) => {
+                for (i, item) in arr.iter().enumerate() {
+                    self.walk(doc, item, &ptr.append(&i.to_string()), res, base)?;
+                }
+                return Ok(());
+            }
+            _ => return Ok(()),
+        };
+
+        let mut base = base.clone();
+        if let Some(Value::String(id)) = obj.get("$id") {
+            if let Some(name) = id.strip_prefix('#') {
+                self.anchors.push(AnchorSite {
+                    res,
+                    doc,
+                    ptr: ptr.to_string(),
+                    name: name.to_owned(),
+                    legacy: true,
+                });
+            } else if !ptr.is_empty() || !id.is_empty() {
+                let url = UrlFrag::join(&base, id)?.url;
+                if ptr.is_empty() {
+                    self.resources[res].url = url.clone();
+                } else {
+                    self.resources.push(Resource {
+                        doc,
+                        ptr: ptr.to_string(),
+                        url: url.clone(),
+                    });
+                    res = self.resources.len() - 1;
+                }
+                base = url;
+            }
+        }
+        if let Some(Value::String(name)) = obj.get("$anchor") {
+            self.anchors.push(AnchorSite {
+                res,
+                doc,
+                ptr: ptr.to_string(),
+                name: name.clone(),
+                legacy: false,
+            });
+        }
+        for kw in ["$ref", "$dynamicRef", "$recursiveRef"] {
+            if let Some(Value::String(value)) = obj.get(kw) {
+                self.refs.push(RefSite {
+                    doc,
+                    ptr: ptr.to_string(),
+                    kw,
+                    value: value.clone(),
+                    base: base.clone(),
+                });
+            }
+        }
+        for (kw, v) in obj {
+            match kw.as_str() {
+                "enum" | "const" | "examples" | "default" => {}
+                _ => self.walk(doc, v, &ptr.append(kw), res, &base)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve(&self, value: &str, base: &Url) -> Result<Target, Box<dyn Error>> {
+        let uf = UrlFrag::join(base, value)?;
+        match self.resources.iter().position(|r| r.url == uf.url) {
+            Some(res) => Ok(Target::Internal { res, frag: uf.frag }),
+            None => Ok(Target::External(uf.url, uf.frag)),
+        }
+    }
+}
@@ -1,5 +1,3 @@
-use std::collections::{HashMap, HashSet};
-
 use crate::{compiler::CompileError, draft::*, util::*};
 
 use serde_json::Value;
@@ -7,7 +5,7 @@ use url::Url;
 
 pub(crate) struct Root {
     pub(crate) draft: &'static Draft,
-    pub(crate) resources: HashMap<JsonPointer, Resource>, // ptr => _
+    pub(crate) resources: FastHashMap<JsonPointer, Resource>, // ptr => _
     pub(crate) url: Url,
     pub(crate) meta_vocabs: Option<Vec<String>>,
 }
@@ -112,8 +110,8 @@ impl Root {
 pub(crate) struct Resource {
     pub(crate) ptr: JsonPointer, // from root
     pub(crate) id: Url,
-    pub(crate) anchors: HashMap<Anchor, JsonPointer>, // anchor => ptr
-    pub(crate) dynamic_anchors: HashSet<Anchor>,
+    pub(crate) anchors: FastHashMap<Anchor, JsonPointer>, // anchor => ptr
+    pub(crate) dynamic_anchors: FastHashSet<Anchor>,
 }
 
 impl Resource {
@@ -121,8 +119,8 @@ impl Resource {
         Self {
             ptr,
             id,
-            anchors: HashMap::new(),
-            dynamic_anchors: HashSet::new(),
+            anchors: FastHashMap::new(),
+            dynamic_anchors: FastHashSet::new(),
         }
     }
 }
@@ -1,6 +1,9 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
-use crate::{compiler::CompileError, draft::*, loader::DefaultUrlLoader, root::Root, util::*};
+use crate::{
+    compiler::CompileError, data::strip_data, draft::*, loader::DefaultUrlLoader, root::Root,
+    util::*,
+};
 
 use serde_json::Value;
 use url::Url;
@@ -9,7 +12,9 @@ use url::Url;
 
 pub(crate) struct Roots {
     pub(crate) default_draft: &'static Draft,
-    map: HashMap<Url, Root>,
+    pub(crate) data: bool,     // `$data` references enabled
+    pub(crate) validate: bool, // against metaschema
+    map: FastHashMap<Url, Root>,
     pub(crate) loader: DefaultUrlLoader,
 }
 
@@ -17,6 +22,8 @@ impl Roots {
     fn new() -> Self {
         Self {
             default_draft: latest(),
+            data: false,
+            validate: true,
             map: Default::default(),
             loader: DefaultUrlLoader::new(),
         }
@@ -30,6 +37,10 @@ impl Default for Roots {
 }
 
 impl Roots {
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Root> {
+        self.map.values()
+    }
+
     pub(crate) fn get(&self, url: &Url) -> Option<&Root> {
         self.map.get(url)
     }
@@ -50,7 +61,11 @@ impl Roots {
         if !root.draft.is_subschema(up.ptr.as_str()) {
             let doc = self.loader.load(&root.url)?;
             let v = up.ptr.lookup(doc, &up.url)?;
-            root.draft.validate(up, v)?;
+            if self.validate && self.data {
+                root.draft.validate(up, &strip_data(v))?;
+            } else if self.validate {
+                root.draft.validate(up, v)?;
+            }
             root.add_subschema(doc, &up.ptr)?;
         }
         Ok(())
@@ -68,6 +83,16 @@ impl Roots {
     }
 
     pub(crate) fn create_root(&self, url: Url, doc: &Value) -> Result<Root, CompileError> {
+        let (draft, vocabs) = self.get_draft(&url, doc)?;
+        build_root(url, doc, draft, vocabs, self.data, self.validate)
+    }
+
+    // returns draft and vocabularies of `doc`, loading its metaschema if needed.
+    pub(crate) fn get_draft(
+        &self,
+        url: &Url,
+        doc: &Value,
+    ) -> Result<(&'static Draft, Option<Vec<String>>), CompileError> {
         let draft = {
             let up = UrlPtr {
                 url: url.clone(),
@@ -77,31 +102,50 @@ impl Roots {
                 .get_draft(&up, doc, self.default_draft, HashSet::new())?
         };
         let vocabs = self.loader.get_meta_vocabs(doc, draft)?;
-        let resources = {
-            let mut m = HashMap::default();
-            draft.collect_resources(doc, &url, "".into(), &url, &mut m)?;
-            m
-        };
-
-        if !matches!(url.host_str(), Some("json-schema.org")) {
-            draft.validate(
-                &UrlPtr {
-                    url: url.clone(),
-                    ptr: "".into(),
-                },
-                doc,
-            )?;
-        }
+        Ok((draft, vocabs))
+    }
 
-        Ok(Root {
-            draft,
-            resources,
-            url: url.clone(),
-            meta_vocabs: vocabs,
-        })
+    pub(crate) fn remove(&mut self, url: &Url) -> Option<Root> {
+        self.map.remove(url)
     }
 
-    pub(crate) fn insert(&mut self, roots: &mut HashMap<Url, Root>) {
+    pub(crate) fn insert(&mut self, roots: &mut FastHashMap<Url, Root>) {
         self.map.extend(roots.drain());
     }
 }
+
+// creates root for `doc`, validating it against metaschema if `validate`.
+// this does not need loader, so can be run concurrently.
+pub(crate) fn build_root(
+    url: Url,
+    doc: &Value,
+    draft: &'static Draft,
+    vocabs: Option<Vec<String>>,
+    data: bool,
+    validate: bool,
+) -> Result<Root, CompileError> {
+    let resources = {
+        let mut m = FastHashMap::default();
+        draft.collect_resources(doc, &url, "".into(), &url, &mut m)?;
+        m
+    };
+
+    if validate && !matches!(url.host_str(), Some("json-schema.org")) {
+        let up = UrlPtr {
+            url: url.clone(),
+            ptr: "".into(),
+        };
+        if data {
+            draft.validate(&up, &strip_data(doc))?;
+        } else {
+            draft.validate(&up, doc)?;
+        }
+    }
+
+    Ok(Root {
+        draft,
+        resources,
+        url,
+        meta_vocabs: vocabs,
+    })
+}
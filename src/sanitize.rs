@@ -0,0 +1,141 @@
+use serde_json::Value;
+
+use crate::{defaults::*, util::*, *};
+
+/// Which properties [`Schemas::remove_additional`] removes.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RemoveAdditional {
+    /// properties rejected by `additionalProperties: false`.
+    #[default]
+    Rejected,
+    /// properties not covered by `properties` or `patternProperties` of
+    /// any applicable schema, regardless of `additionalProperties`. Applies
+    /// only to objects whose schema uses any of these keywords.
+    All,
+}
+
+impl Schemas {
+    /**
+    Removes additional properties from `v`, instead of failing validation,
+    and returns json-pointers of the properties removed.
+
+    Object keywords are taken from the schema identified by `sch_index` and
+    the subschemas it applies unconditionally i.e. `$ref` and `allOf`. With
+    [`RemoveAdditional::Rejected`], a property is removed if any of them
+    rejects it. `anyOf`, `oneOf`, `if`/`then`/`else`, `unevaluatedProperties`
+    etc. are not used, because which of them apply is not known before
+    validation.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let schema = json!({
+        "properties": {
+            "name": {"type": "string"}
+        },
+        "additionalProperties": false
+    });
+    compiler.add_resource("user.json", schema)?;
+    let sch_index = compiler.compile("user.json", &mut schemas)?;
+
+    let mut user = json!({"name": "john", "isAdmin": true});
+    let removed = schemas.remove_additional(&mut user, sch_index, RemoveAdditional::Rejected);
+    assert_eq!(user, json!({"name": "john"}));
+    assert_eq!(removed, ["/isAdmin"]);
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn remove_additional(
+        &self,
+        v: &mut Value,
+        sch_index: SchemaIndex,
+        mode: RemoveAdditional,
+    ) -> Vec<String> {
+        let mut removed = vec![];
+        self.strip_additional(v, sch_index, mode, &JsonPointer::from(""), &mut removed);
+        removed
+    }
+
+    fn strip_additional(
+        &self,
+        v: &mut Value,
+        sch: SchemaIndex,
+        mode: RemoveAdditional,
+        ptr: &JsonPointer,
+        removed: &mut Vec<String>,
+    ) {
+        let list = self.applied_schemas(sch);
+        match v {
+            Value::Object(obj) => {
+                let rejected = obj
+                    .keys()
+                    .filter(|pname| self.rejects(&list, pname, mode))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                for pname in rejected {
+                    obj.remove(&pname);
+                    removed.push(ptr.append(&pname).to_string());
+                }
+                for (pname, pvalue) in obj.iter_mut() {
+                    for &sch in &list {
+                        for psch in prop_schemas(self.get(sch), pname) {
+                            let ptr = ptr.append(pname);
+                            self.strip_additional(pvalue, psch, mode, &ptr, removed);
+                        }
+                    }
+                }
+            }
+            Value::Array(arr) => {
+                for (i, item) in arr.iter_mut().enumerate() {
+                    for &sch in &list {
+                        if let Some(isch) = item_schema(self, self.get(sch), i) {
+                            let ptr = ptr.append(&i.to_string());
+                            self.strip_additional(item, isch, mode, &ptr, removed);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // tells whether `pname` is additional property to be removed, for
+    // object validated against all schemas in `list`.
+    fn rejects(&self, list: &[SchemaIndex], pname: &str, mode: RemoveAdditional) -> bool {
+        let covers = |s: &Schema| {
+            s.properties.contains_key(pname)
+                || s.pattern_properties
+                    .iter()
+                    .any(|(re, _)| re.is_match(pname))
+        };
+        let schemas = list.iter().map(|&sch| self.get(sch));
+        match mode {
+            RemoveAdditional::Rejected => schemas
+                .filter(|s| matches!(s.additional_properties, Some(Additional::Bool(false))))
+                .any(|s| !covers(s)),
+            RemoveAdditional::All => {
+                let mut applies = false;
+                for s in schemas {
+                    if covers(s) {
+                        return false;
+                    }
+                    applies |= !s.properties.is_empty()
+                        || !s.pattern_properties.is_empty()
+                        || s.additional_properties.is_some();
+                }
+                applies
+            }
+        }
+    }
+}
@@ -0,0 +1,275 @@
+use std::{collections::HashMap, fmt::Display};
+
+use serde_json::Value;
+use url::Url;
+
+use crate::{compiler::CompileError, tooling, util::*, Compiler};
+
+/// Location of a json value within source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// byte offset of the first character of the value.
+    pub start: usize,
+    /// byte offset just past the last character of the value.
+    pub end: usize,
+    /// line number, starting from 1.
+    pub line: usize,
+    /// column number in characters, starting from 1.
+    pub col: usize,
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
+
+// spans of all values in a json document, keyed by json-pointer.
+#[derive(Debug, Default)]
+pub(crate) struct SourceMap(HashMap<String, Span>);
+
+impl SourceMap {
+    // returns None, if `text` is not valid json.
+    pub(crate) fn from_json(text: &str) -> Option<Self> {
+        let mut scanner = Scanner {
+            text,
+            pos: 0,
+            line: 1,
+            line_start: 0,
+            spans: HashMap::new(),
+        };
+        scanner.value(&mut String::new())?;
+        Some(Self(scanner.spans))
+    }
+
+    // returns span of value at `ptr`, or that of its nearest ancestor.
+    pub(crate) fn get(&self, mut ptr: &str) -> Option<Span> {
+        loop {
+            if let Some(span) = self.0.get(ptr) {
+                return Some(*span);
+            }
+            ptr = &ptr[..ptr.rfind('/')?];
+        }
+    }
+}
+
+struct Scanner<'a> {
+    text: &'a str,
+    pos: usize,
+    line: usize,
+    line_start: usize,
+    spans: HashMap<String, Span>,
+}
+
+impl Scanner<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_ws(&mut self) {
+        while let Some(b) = self.peek() {
+            match b {
+                b'\n' => {
+                    self.line += 1;
+                    self.line_start = self.pos + 1;
+                }
+                b' ' | b'\t' | b'\r' => {}
+                _ => return,
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> Option<()> {
+        self.skip_ws();
+        (self.peek()? == b).then(|| self.pos += 1)
+    }
+
+    fn value(&mut self, ptr: &mut String) -> Option<()> {
+        self.skip_ws();
+        let start = self.pos;
+        let line = self.line;
+        let col = self.text.get(self.line_start..start)?.chars().count() + 1;
+        match self.peek()? {
+            b'{' => {
+                self.pos += 1;
+                self.skip_ws();
+                if self.peek()? == b'}' {
+                    self.pos += 1;
+                } else {
+                    loop {
+                        self.skip_ws();
+                        let key = self.string()?;
+                        self.expect(b':')?;
+                        let len = ptr.len();
+                        ptr.push('/');
+                        ptr.push_str(&escape(&key));
+                        self.value(ptr)?;
+                        ptr.truncate(len);
+                        self.skip_ws();
+                        match self.peek()? {
+                            b',' => self.pos += 1,
+                            b'}' => {
+                                self.pos += 1;
+                                break;
+                            }
+                            _ => return None,
+                        }
+                    }
+                }
+            }
+            b'[' => {
+                self.pos += 1;
+                self.skip_ws();
+                if self.peek()? == b']' {
+                    self.pos += 1;
+                } else {
+                    for i in 0.. {
+                        let len = ptr.len();
+                        ptr.push_str(&format!("/{i}"));
+                        self.value(ptr)?;
+                        ptr.truncate(len);
+                        self.skip_ws();
+                        match self.peek()? {
+                            b',' => self.pos += 1,
+                            b']' => {
+                                self.pos += 1;
+                                break;
+                            }
+                            _ => return None,
+                        }
+                    }
+                }
+            }
+            b'"' => {
+                self.string()?;
+            }
+            _ => {
+                while let Some(b) = self.peek() {
+                    if b",]} \t\r\n".contains(&b) {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                serde_json::from_str::<Value>(&self.text[start..self.pos]).ok()?;
+            }
+        }
+        let span = Span {
+            start,
+            end: self.pos,
+            line,
+            col,
+        };
+        self.spans.insert(ptr.clone(), span);
+        Some(())
+    }
+
+    // returns decoded string.
+    fn string(&mut self) -> Option<String> {
+        let start = self.pos;
+        if self.peek()? != b'"' {
+            return None;
+        }
+        self.pos += 1;
+        loop {
+            match self.peek()? {
+                b'"' => break,
+                b'\\' => self.pos += 2,
+                _ => self.pos += 1,
+            }
+        }
+        self.pos += 1;
+        serde_json::from_str(self.text.get(start..self.pos)?).ok()
+    }
+}
+
+impl Compiler {
+    /**
+    Adds schema resource `loc`, parsing json `text`.
+
+    Unlike [`Compiler::add_resource`], location of each value in `text` is
+    recorded, so that compile errors can be located using
+    [`Compiler::error_span`]. Locations are recorded for json documents
+    loaded using [`UrlLoader::load_content`](crate::UrlLoader::load_content)
+    too.
+
+    # Errors
+
+    returns [`CompileError`] if url parsing failed, or `text` is not
+    valid json.
+    */
+    pub fn add_resource_text(&mut self, loc: &str, text: &str) -> Result<(), CompileError> {
+        let uf = UrlFrag::absolute(loc)?;
+        let json = serde_json::from_str(text).map_err(|e| CompileError::LoadUrlError {
+            url: uf.url.to_string(),
+            src: e.into(),
+        })?;
+        if let Some(source) = SourceMap::from_json(text) {
+            self.roots
+                .loader
+                .sources
+                .get_mut()
+                .insert(uf.url.clone(), source);
+        }
+        self.roots.loader.add_doc(uf.url, json);
+        Ok(())
+    }
+
+    /**
+    Returns location of `loc` within source text of its document.
+
+    `loc` is url with json-pointer fragment. If there is no value at the
+    json-pointer, location of its nearest ancestor is returned. Returns
+    `None` if source text of the document is not known.
+    */
+    pub fn source_span(&self, loc: &str) -> Option<Span> {
+        let UrlFrag {
+            url,
+            frag: Fragment::JsonPointer(ptr),
+        } = UrlFrag::absolute(loc).ok()?
+        else {
+            return None;
+        };
+        self.roots
+            .loader
+            .sources
+            .borrow()
+            .get(&url)?
+            .get(ptr.as_str())
+    }
+
+    /**
+    Returns document url and location within its source text, where
+    `err` occurred.
+
+    For metaschema validation failures, location of the first failing
+    keyword value is returned.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource_text("http://example.com/schema.json", r#"{
+      "properties": {
+        "name": {"pattern": "^(abc"}
+      }
+    }"#)?;
+    let err = compiler
+        .compile("http://example.com/schema.json", &mut schemas)
+        .unwrap_err();
+    let (url, span) = compiler.error_span(&err).unwrap();
+    assert_eq!(url, "http://example.com/schema.json");
+    assert_eq!((span.line, span.col), (3, 25));
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn error_span(&self, err: &CompileError) -> Option<(String, Span)> {
+        let d = tooling::diagnostics(err).into_iter().next()?;
+        let url = Url::parse(&d.url).ok()?;
+        let span = self.roots.loader.sources.borrow().get(&url)?.get(&d.ptr)?;
+        Some((d.url, span))
+    }
+}
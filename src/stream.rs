@@ -0,0 +1,583 @@
+use std::{borrow::Cow, collections::HashSet, error::Error};
+
+use serde_json::{Map, Number, Value};
+
+use crate::{util::*, *};
+
+/// Parse event consumed by [`StreamValidator`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonEvent<'a> {
+    StartObject,
+    EndObject,
+    StartArray,
+    EndArray,
+    /// property name. must be followed by its value.
+    Key(&'a str),
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(&'a str),
+}
+
+/// Error reported by [`StreamValidator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamError {
+    /// json-pointer to the value which failed.
+    pub instance_location: String,
+    /// location of the schema containing failed keyword.
+    pub schema_url: String,
+    pub message: String,
+}
+
+/**
+Experimental validator which consumes stream of [`JsonEvent`]s, as
+produced by pull parsers, without building the document in memory.
+
+Objects and arrays are validated incrementally, if their schemas use only
+keywords which can be evaluated one property or item at a time, i.e.
+`type`, `$ref`, `allOf`, `properties`, `patternProperties`,
+`additionalProperties`, `required`, `minProperties`, `maxProperties`,
+`items`, `prefixItems`, `additionalItems`, `minItems` and `maxItems`.
+Otherwise the value is buffered, and validated once it is complete.
+Scalar values are always validated as soon as they are read.
+
+If the schema uses `$recursiveRef` or `$dynamicRef`, entire document is
+buffered because their resolution depends on evaluation path. Likewise
+with `$data`, whose pointers may refer outside the value being validated.
+
+Note that errors are reported as [`StreamError`]s, which are not nested.
+
+```
+# use std::error::Error;
+# use boon::*;
+# use serde_json::json;
+# fn main() -> Result<(), Box<dyn Error>> {
+let mut schemas = Schemas::new();
+let mut compiler = Compiler::new();
+compiler.add_resource("schema.json", json!({"items": {"type": "integer"}}))?;
+let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+let mut validator = StreamValidator::new(&schemas, sch_index);
+validator.event(JsonEvent::StartArray)?;
+validator.event(JsonEvent::Number(1.into()))?;
+validator.event(JsonEvent::String("two"))?;
+validator.event(JsonEvent::EndArray)?;
+let errors = validator.finish()?;
+assert_eq!(errors[0].instance_location, "/1");
+# Ok(())
+# }
+```
+
+# Panics
+
+[`StreamValidator::new`] panics if `sch_index` is not generated for `schemas`.
+*/
+pub struct StreamValidator<'s> {
+    schemas: &'s Schemas,
+    root: SchemaIndex,
+    buffer_all: bool,
+    started: bool,
+    stack: Vec<Frame<'s>>,
+    errors: Vec<StreamError>,
+}
+
+enum Frame<'s> {
+    Object {
+        loc: JsonPointer,
+        schs: Vec<SchemaIndex>,
+        count: usize,
+        key: Option<String>,
+        seen: HashSet<&'s str>, // required properties seen
+    },
+    Array {
+        loc: JsonPointer,
+        schs: Vec<SchemaIndex>,
+        count: usize,
+    },
+    // value with no constraints
+    Skip {
+        depth: usize,
+    },
+    Buffer {
+        loc: JsonPointer,
+        schs: Vec<SchemaIndex>,
+        stack: Vec<(Value, Option<String>)>, // container, pending key
+    },
+}
+
+impl<'s> StreamValidator<'s> {
+    pub fn new(schemas: &'s Schemas, sch_index: SchemaIndex) -> Self {
+        assert!(
+            schemas.contains(sch_index),
+            "StreamValidator::new: schema index out of bounds"
+        );
+        // check if $recursiveRef, $dynamicRef or $data is reachable
+        let mut buffer_all = false;
+        let mut visited = HashSet::new();
+        let mut queue = vec![sch_index];
+        while let Some(idx) = queue.pop() {
+            if visited.insert(idx) {
+                let sch = schemas.get(idx);
+                buffer_all |= sch.recursive_ref.is_some()
+                    || sch.dynamic_ref.is_some()
+                    || !sch.data.is_empty();
+                queue.extend(sch.children(schemas).into_iter().map(|(_, sch)| sch));
+            }
+        }
+        Self {
+            schemas,
+            root: sch_index,
+            buffer_all,
+            started: false,
+            stack: vec![],
+            errors: vec![],
+        }
+    }
+
+    /// Returns errors found so far.
+    pub fn errors(&self) -> &[StreamError] {
+        &self.errors
+    }
+
+    /**
+    Consumes next event.
+
+    # Errors
+
+    returns error if the event is not valid at this position,
+    for example [`JsonEvent::EndArray`] inside object.
+    */
+    pub fn event(&mut self, event: JsonEvent) -> Result<(), Box<dyn Error>> {
+        match self.stack.last_mut() {
+            Some(Frame::Skip { depth }) => {
+                match event {
+                    JsonEvent::StartObject | JsonEvent::StartArray => *depth += 1,
+                    JsonEvent::EndObject | JsonEvent::EndArray => *depth -= 1,
+                    _ => {}
+                }
+                if let Frame::Skip { depth: 0 } = self.stack[self.stack.len() - 1] {
+                    self.stack.pop();
+                }
+                return Ok(());
+            }
+            Some(Frame::Buffer { stack, .. }) => {
+                let done = buffer(stack, event)?;
+                if done {
+                    if let Some(Frame::Buffer { loc, schs, stack }) = self.stack.pop() {
+                        if let Some((v, _)) = stack.into_iter().next() {
+                            self.validate_value(&loc, &schs, &v);
+                        }
+                    }
+                }
+                return Ok(());
+            }
+            Some(Frame::Object { key, .. }) if key.is_none() => {
+                return match event {
+                    JsonEvent::Key(k) => {
+                        *key = Some(k.to_owned());
+                        Ok(())
+                    }
+                    JsonEvent::EndObject => {
+                        self.end_object();
+                        Ok(())
+                    }
+                    _ => Err("expected property name or end of object")?,
+                };
+            }
+            Some(Frame::Array { .. }) if event == JsonEvent::EndArray => {
+                self.end_array();
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        // start of value --
+        let (loc, schs) = self.next_slot()?;
+        match event {
+            JsonEvent::StartObject | JsonEvent::StartArray => {
+                self.start_container(loc, schs, event == JsonEvent::StartObject)
+            }
+            JsonEvent::EndObject | JsonEvent::EndArray | JsonEvent::Key(_) => {
+                Err("expected value")?
+            }
+            _ => {
+                self.validate_value(&loc, &schs, &scalar(event));
+            }
+        }
+        Ok(())
+    }
+
+    /**
+    Completes validation, returning all errors found.
+
+    # Errors
+
+    returns error if the document is incomplete.
+    */
+    pub fn finish(self) -> Result<Vec<StreamError>, Box<dyn Error>> {
+        if !self.started || !self.stack.is_empty() {
+            Err("incomplete document")?;
+        }
+        Ok(self.errors)
+    }
+
+    // returns location and schemas of the value being started.
+    fn next_slot(&mut self) -> Result<(JsonPointer, Vec<SchemaIndex>), Box<dyn Error>> {
+        let mut schs = vec![];
+        match self.stack.last_mut() {
+            None => {
+                if self.started {
+                    Err("multiple root values")?;
+                }
+                self.started = true;
+                Ok((JsonPointer::from(""), vec![self.root]))
+            }
+            Some(Frame::Object {
+                loc,
+                schs: parents,
+                count,
+                key,
+                seen,
+            }) => {
+                let Some(key) = key.take() else {
+                    Err("expected property name")?
+                };
+                *count += 1;
+                let ploc = loc.append(&key);
+                for &idx in parents.iter() {
+                    let sch = self.schemas.get(idx);
+                    if let Some(req) = sch.required.iter().find(|r| ***r == *key) {
+                        seen.insert(req.as_ref());
+                    }
+                    let mut matched = false;
+                    if let Some(&psch) = sch.properties.get(key.as_str()) {
+                        schs.push(psch);
+                        matched = true;
+                    }
+                    for (regex, psch) in &sch.pattern_properties {
+                        match regex.try_match(&key) {
+                            Ok(true) => {
+                                schs.push(*psch);
+                                matched = true;
+                            }
+                            Ok(false) => {}
+                            Err(_) => {
+                                matched = true;
+                                let kind = ErrorKind::RegexLimit {
+                                    kw: "patternProperties",
+                                    got: Cow::Owned(key.clone()),
+                                    want: regex.as_str(),
+                                };
+                                self.errors.push(error(loc, sch, kind));
+                            }
+                        }
+                    }
+                    if !matched {
+                        match sch.additional_properties {
+                            Some(Additional::SchemaRef(psch)) => schs.push(psch),
+                            Some(Additional::Bool(false)) => {
+                                let got = vec![Cow::Borrowed(key.as_str())];
+                                let kind = ErrorKind::AdditionalProperties { got };
+                                self.errors.push(error(loc, sch, kind));
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Ok((ploc, schs))
+            }
+            Some(Frame::Array {
+                loc,
+                schs: parents,
+                count,
+            }) => {
+                let i = *count;
+                *count += 1;
+                for &idx in parents.iter() {
+                    let sch = self.schemas.get(idx);
+                    let item = match &sch.items {
+                        Some(Items::SchemaRef(items)) => Some(*items),
+                        Some(Items::SchemaRefs(list)) => match self.schemas.indexes(*list).get(i) {
+                            Some(&item) => Some(item),
+                            None => match sch.additional_items {
+                                Some(Additional::SchemaRef(item)) => Some(item),
+                                _ => None,
+                            },
+                        },
+                        None => (self.schemas.indexes(sch.prefix_items).get(i))
+                            .cloned()
+                            .or(sch.items2020),
+                    };
+                    schs.extend(item);
+                }
+                Ok((loc.append(&i.to_string()), schs))
+            }
+            Some(Frame::Skip { .. } | Frame::Buffer { .. }) => {
+                Err("StreamValidator: unexpected frame")?
+            }
+        }
+    }
+
+    fn start_container(&mut self, loc: JsonPointer, schs: Vec<SchemaIndex>, object: bool) {
+        let mut expanded = vec![];
+        let streamable = schs
+            .iter()
+            .all(|&idx| !self.buffer_all && self.expand(idx, &mut expanded));
+        if !streamable {
+            let v = if object {
+                Value::Object(Map::new())
+            } else {
+                Value::Array(vec![])
+            };
+            self.stack.push(Frame::Buffer {
+                loc,
+                schs,
+                stack: vec![(v, None)],
+            });
+            return;
+        }
+
+        let got = if object { Type::Object } else { Type::Array };
+        let schemas = self.schemas;
+        expanded.retain(|&idx| {
+            let sch = schemas.get(idx);
+            if let Some(b) = sch.boolean {
+                if !b {
+                    self.errors.push(error(&loc, sch, ErrorKind::FalseSchema));
+                }
+                return false;
+            }
+            if !sch.types.is_empty() && !sch.types.contains(got) {
+                let kind = ErrorKind::Type {
+                    got,
+                    want: sch.types,
+                };
+                self.errors.push(error(&loc, sch, kind));
+                return false;
+            }
+            true
+        });
+        let frame = if expanded.is_empty() {
+            Frame::Skip { depth: 1 }
+        } else if object {
+            Frame::Object {
+                loc,
+                schs: expanded,
+                count: 0,
+                key: None,
+                seen: HashSet::new(),
+            }
+        } else {
+            Frame::Array {
+                loc,
+                schs: expanded,
+                count: 0,
+            }
+        };
+        self.stack.push(frame);
+    }
+
+    // collects schemas applicable in place. returns false if any
+    // of them cannot be evaluated incrementally.
+    fn expand(&self, idx: SchemaIndex, out: &mut Vec<SchemaIndex>) -> bool {
+        if out.contains(&idx) {
+            return true;
+        }
+        let sch = self.schemas.get(idx);
+        if let (Some(ref_), true) = (sch.ref_, sch.draft_version < 2019) {
+            // siblings of $ref are ignored
+            return self.expand(ref_, out);
+        }
+        let streamable = sch.enum_.is_none()
+            && sch.constant.is_none()
+            && sch.not.is_none()
+            && sch.any_of.is_empty()
+            && sch.one_of.is_empty()
+            && sch.if_.is_none()
+            && sch.asserted_format().is_none()
+            && sch.property_names.is_none()
+            && sch.dependent_required.is_empty()
+            && sch.dependent_schemas.is_empty()
+            && sch.dependencies.is_empty()
+            && sch.unevaluated_properties.is_none()
+            && !sch.unique_items
+            && sch.contains.is_none()
+            && sch.unevaluated_items.is_none()
+            && sch.keywords.is_empty()
+            && sch.data.is_empty()
+            && sch.bson_types.is_empty()
+            && !sch.lazy;
+        if !streamable {
+            return false;
+        }
+        out.push(idx);
+        sch.ref_.into_iter().all(|ref_| self.expand(ref_, out))
+            && (self.schemas.indexes(sch.all_of))
+                .iter()
+                .all(|&sub| self.expand(sub, out))
+    }
+
+    fn end_object(&mut self) {
+        let Some(Frame::Object {
+            loc,
+            schs,
+            count,
+            seen,
+            ..
+        }) = self.stack.pop()
+        else {
+            return;
+        };
+        for idx in schs {
+            let sch = self.schemas.get(idx);
+            let want = sch
+                .required
+                .iter()
+                .map(Name::as_ref)
+                .filter(|p| !seen.contains(p))
+                .collect::<Vec<_>>();
+            if !want.is_empty() {
+                self.errors
+                    .push(error(&loc, sch, ErrorKind::Required { want }));
+            }
+            if let Some(min) = sch.min_properties.filter(|&min| count < min) {
+                let kind = ErrorKind::MinProperties {
+                    got: count,
+                    want: min,
+                };
+                self.errors.push(error(&loc, sch, kind));
+            }
+            if let Some(max) = sch.max_properties.filter(|&max| count > max) {
+                let kind = ErrorKind::MaxProperties {
+                    got: count,
+                    want: max,
+                };
+                self.errors.push(error(&loc, sch, kind));
+            }
+        }
+    }
+
+    fn end_array(&mut self) {
+        let Some(Frame::Array { loc, schs, count }) = self.stack.pop() else {
+            return;
+        };
+        for idx in schs {
+            let sch = self.schemas.get(idx);
+            if let Some(min) = sch.min_items.filter(|&min| count < min) {
+                let kind = ErrorKind::MinItems {
+                    got: count,
+                    want: min,
+                };
+                self.errors.push(error(&loc, sch, kind));
+            }
+            if let Some(max) = sch.max_items.filter(|&max| count > max) {
+                let kind = ErrorKind::MaxItems {
+                    got: count,
+                    want: max,
+                };
+                self.errors.push(error(&loc, sch, kind));
+            }
+            if let (Some(Items::SchemaRefs(list)), Some(Additional::Bool(false))) =
+                (&sch.items, &sch.additional_items)
+            {
+                if count > list.len() {
+                    let kind = ErrorKind::AdditionalItems {
+                        got: count - list.len(),
+                    };
+                    self.errors.push(error(&loc, sch, kind));
+                }
+            }
+        }
+    }
+
+    fn validate_value(&mut self, loc: &JsonPointer, schs: &[SchemaIndex], v: &Value) {
+        for &idx in schs {
+            if let Err(e) = self.schemas.validate(v, idx) {
+                self.add_leaves(loc, &e);
+            }
+        }
+    }
+
+    fn add_leaves(&mut self, loc: &JsonPointer, e: &ValidationError) {
+        if e.causes.is_empty() {
+            self.errors.push(StreamError {
+                instance_location: format!("{}{}", loc, e.instance_location),
+                schema_url: e.schema_url.to_owned(),
+                message: e.kind.to_string(),
+            });
+        }
+        for cause in &e.causes {
+            self.add_leaves(loc, cause);
+        }
+    }
+}
+
+fn error(loc: &JsonPointer, sch: &Schema, kind: ErrorKind) -> StreamError {
+    StreamError {
+        instance_location: loc.to_string(),
+        schema_url: sch.loc.clone(),
+        message: kind.to_string(),
+    }
+}
+
+fn scalar(event: JsonEvent) -> Value {
+    match event {
+        JsonEvent::Bool(b) => Value::Bool(b),
+        JsonEvent::Number(n) => Value::Number(n),
+        JsonEvent::String(s) => Value::String(s.to_owned()),
+        _ => Value::Null,
+    }
+}
+
+// adds event to the value being buffered. returns true if value is complete.
+fn buffer(
+    stack: &mut Vec<(Value, Option<String>)>,
+    event: JsonEvent,
+) -> Result<bool, Box<dyn Error>> {
+    let v = match event {
+        JsonEvent::StartObject => {
+            stack.push((Value::Object(Map::new()), None));
+            return Ok(false);
+        }
+        JsonEvent::StartArray => {
+            stack.push((Value::Array(vec![]), None));
+            return Ok(false);
+        }
+        JsonEvent::Key(k) => {
+            match stack.last_mut() {
+                Some((Value::Object(_), key @ None)) => *key = Some(k.to_owned()),
+                _ => Err("unexpected property name")?,
+            }
+            return Ok(false);
+        }
+        JsonEvent::EndObject | JsonEvent::EndArray => {
+            let matches = match stack.last() {
+                Some((Value::Object(_), None)) => event == JsonEvent::EndObject,
+                Some((Value::Array(_), None)) => event == JsonEvent::EndArray,
+                _ => false,
+            };
+            if !matches {
+                Err("unexpected end of container")?;
+            }
+            if stack.len() == 1 {
+                return Ok(true);
+            }
+            let Some((v, _)) = stack.pop() else {
+                return Ok(true);
+            };
+            v
+        }
+        _ => scalar(event),
+    };
+    match stack.last_mut() {
+        Some((Value::Object(obj), key)) => {
+            let Some(key) = key.take() else {
+                Err("expected property name")?
+            };
+            obj.insert(key, v);
+        }
+        Some((Value::Array(arr), _)) => arr.push(v),
+        _ => Err("unexpected value")?,
+    }
+    Ok(false)
+}
@@ -0,0 +1,93 @@
+use serde_json::{json, Map, Value};
+
+use crate::{fixtures::redact, util::*, *};
+
+impl Compiler {
+    /**
+    Returns a json document capturing everything needed to reproduce
+    validation of `instance` against schema identified by `sch_index`,
+    for attaching to bug reports.
+
+    The document has:
+    - `version`: version of this crate
+    - `schema`: location of the schema
+    - `options`: options of this compiler, such as format assertions
+    - `documents`: map of url to content of every schema document involved,
+      along with the draft used. Standard metaschemas are not included
+    - `instance`: `instance` with `redactions` applied
+    - `valid`: result of validation
+    - `output`: detailed output of validation error, if any
+
+    Error messages may include instance values. So when `redactions` are
+    given, `output` has only keyword locations and instance locations of
+    the errors.
+
+    `schemas` must have been compiled using this compiler.
+
+    # Panics
+
+    Panics if `sch_index` is not generated for `schemas`.
+    */
+    pub fn debug_bundle(
+        &self,
+        schemas: &Schemas,
+        sch_index: SchemaIndex,
+        instance: &Value,
+        redactions: &[Redaction],
+    ) -> Value {
+        let mut documents = Map::new();
+        schemas.walk(sch_index, |sub| {
+            let (url, _) = split(sub.location);
+            if documents.contains_key(url) {
+                return;
+            }
+            let Ok(uf) = UrlFrag::absolute(url) else {
+                return;
+            };
+            if matches!(uf.url.host_str(), Some("json-schema.org")) {
+                return;
+            }
+            let Ok(content) = self.roots.loader.load(&uf.url) else {
+                return;
+            };
+            let draft = self.roots.get(&uf.url).map(|root| root.draft.url);
+            let doc = json!({"draft": draft, "content": content});
+            documents.insert(url.to_owned(), doc);
+        });
+
+        let result = schemas.validate(instance, sch_index);
+        let output = match &result {
+            Ok(_) => Value::Null,
+            Err(e) if redactions.is_empty() => {
+                serde_json::to_value(e.detailed_output()).unwrap_or_default()
+            }
+            Err(e) => {
+                let errors = match e.basic_output().error {
+                    OutputError::Leaf(_) => vec![],
+                    OutputError::Branch(units) => units
+                        .iter()
+                        .map(|u| {
+                            json!({
+                                "keywordLocation": u.keyword_location.to_string(),
+                                "instanceLocation": u.instance_location.to_string(),
+                            })
+                        })
+                        .collect(),
+                };
+                json!({"valid": false, "errors": errors})
+            }
+        };
+
+        let mut instance = instance.clone();
+        redact(&mut instance, redactions);
+        json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "schema": schemas.get(sch_index).loc,
+            "options": self.options(),
+            "documents": documents,
+            "instance": instance,
+            "valid": result.is_ok(),
+            "output": output,
+        })
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::{export::*, util::*, Schema, SchemaIndex, Schemas, Type};
+
+/// Column type derived from property schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Boolean,
+    /// 64-bit integer
+    BigInt,
+    /// 64-bit floating point
+    Double,
+    Text,
+    /// from `date` format
+    Date,
+    /// from `time` format
+    Time,
+    /// from `date-time` format
+    Timestamp,
+    /// from `uuid` format
+    Uuid,
+    /// nested objects and arrays, or values of multiple types
+    Json,
+}
+
+/// Column derived from property of object schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    /// property name.
+    pub name: String,
+    pub data_type: ColumnType,
+    /// `true` if property is not required, or allows `null`.
+    pub nullable: bool,
+    /// `maxLength` of string property.
+    pub max_length: Option<usize>,
+}
+
+/// Result of [`derive_table`].
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub columns: Vec<Column>,
+    /// constraints not representable as column definitions.
+    pub unrepresentable: Vec<Unrepresentable>,
+}
+
+/**
+Derives table columns from flat object schema identified by `sch_index`.
+
+Columns are ordered by property name. Nested objects and arrays are
+mapped to [`ColumnType::Json`], and reported as unrepresentable. Value
+constraints other than `maxLength` and `format` are also reported.
+
+Note that formats are used only if format assertions are enabled, see
+[`Compiler::enable_format_assertions`](crate::Compiler::enable_format_assertions),
+because otherwise the validated values are not guaranteed to match the
+format.
+
+# Panics
+
+Panics if `sch_index` is not generated for `schemas`.
+*/
+pub fn derive_table(schemas: &Schemas, sch_index: SchemaIndex) -> Table {
+    let mut table = Table {
+        columns: vec![],
+        unrepresentable: vec![],
+    };
+    // properties next to `$ref` apply along with those of its target
+    let chain = table.ref_chain(schemas, sch_index, |_| true);
+    let sch = chain[0];
+    if !chain.iter().any(|s| is_object(s)) {
+        table.report(sch, "type", "schema must be object");
+        return table;
+    }
+    let mut props = BTreeMap::new();
+    for s in &chain {
+        for (pname, &pidx) in &s.properties {
+            props.entry(pname.as_ref()).or_insert(pidx);
+        }
+    }
+    let required = |pname: &str| {
+        chain
+            .iter()
+            .any(|s| s.required.iter().any(|r| **r == *pname))
+    };
+    let mut visited = HashSet::new();
+    for (pname, pidx) in props {
+        let psch = *table
+            .ref_chain(schemas, pidx, |s| s.types.is_empty())
+            .last()
+            .unwrap();
+        if visited.insert(psch.idx) {
+            let format = psch.asserted_format().map(|f| f.name);
+            for kw in constraints(psch) {
+                let mapped = match kw {
+                    "maxLength" => true,
+                    "format" => matches!(format, Some("date" | "time" | "date-time" | "uuid")),
+                    _ => false,
+                };
+                if !mapped {
+                    table.report(psch, kw, "no column equivalent");
+                }
+            }
+            if psch.enum_.is_some() {
+                table.report(psch, "enum", "no column equivalent");
+            }
+        }
+        let mut types = psch.types;
+        if types.is_empty() {
+            if let Some(e) = &psch.enum_ {
+                types = e.types;
+            } else if let Some(c) = &psch.constant {
+                types.add(Type::of(c));
+            }
+        }
+        let mut non_null = types
+            .iter()
+            .filter(|&t| t != Type::Null)
+            .collect::<Vec<_>>();
+        if non_null == [Type::Number, Type::Integer] {
+            non_null = vec![Type::Number];
+        }
+        let data_type = match non_null.as_slice() {
+            [Type::Boolean] => ColumnType::Boolean,
+            [Type::Integer] => ColumnType::BigInt,
+            [Type::Number] => ColumnType::Double,
+            [Type::String] => match psch.asserted_format().map(|f| f.name) {
+                Some("date") => ColumnType::Date,
+                Some("time") => ColumnType::Time,
+                Some("date-time") => ColumnType::Timestamp,
+                Some("uuid") => ColumnType::Uuid,
+                _ => ColumnType::Text,
+            },
+            [Type::Array] | [Type::Object] => {
+                table.report(psch, "type", "nested value stored as json");
+                ColumnType::Json
+            }
+            _ => {
+                table.report(psch, "type", "untyped value stored as json");
+                ColumnType::Json
+            }
+        };
+        let max_length = match data_type {
+            ColumnType::Text => psch.max_length,
+            _ => None,
+        };
+        table.columns.push(Column {
+            name: pname.to_string(),
+            data_type,
+            nullable: types.is_empty() || types.contains(Type::Null) || !required(pname),
+            max_length,
+        });
+    }
+    table
+}
+
+impl Table {
+    // returns schemas from `sch_index`, following `$ref` while `follow`
+    // returns true. stops at a cycle, which is reported.
+    fn ref_chain<'a>(
+        &mut self,
+        schemas: &'a Schemas,
+        sch_index: SchemaIndex,
+        follow: impl Fn(&Schema) -> bool,
+    ) -> Vec<&'a Schema> {
+        let mut chain = vec![schemas.get(sch_index)];
+        let mut seen = HashSet::from([sch_index]);
+        while let Some(ref_) = chain.last().unwrap().ref_ {
+            let last = *chain.last().unwrap();
+            if !follow(last) {
+                break;
+            }
+            if !seen.insert(ref_) {
+                self.report(last, "$ref", "cyclic reference");
+                break;
+            }
+            chain.push(schemas.get(ref_));
+        }
+        chain
+    }
+
+    fn report(&mut self, sch: &Schema, keyword: &'static str, reason: &str) {
+        self.unrepresentable.push(Unrepresentable {
+            schema_url: sch.loc.clone(),
+            keyword,
+            reason: reason.to_owned(),
+        });
+    }
+
+    /// Returns `CREATE TABLE` statement using ANSI SQL types.
+    pub fn sql_ddl(&self, table_name: &str) -> String {
+        let columns = self.columns.iter().map(|c| {
+            let ty = match c.data_type {
+                ColumnType::Boolean => "BOOLEAN".to_owned(),
+                ColumnType::BigInt => "BIGINT".to_owned(),
+                ColumnType::Double => "DOUBLE PRECISION".to_owned(),
+                ColumnType::Text => match c.max_length {
+                    Some(n) => format!("VARCHAR({n})"),
+                    None => "TEXT".to_owned(),
+                },
+                ColumnType::Date => "DATE".to_owned(),
+                ColumnType::Time => "TIME".to_owned(),
+                ColumnType::Timestamp => "TIMESTAMP WITH TIME ZONE".to_owned(),
+                ColumnType::Uuid => "UUID".to_owned(),
+                ColumnType::Json => "JSON".to_owned(),
+            };
+            let not_null = if c.nullable { "" } else { " NOT NULL" };
+            format!("  {} {ty}{not_null}", sql_ident(&c.name))
+        });
+        format!(
+            "CREATE TABLE {} (\n{}\n);",
+            sql_ident(table_name),
+            join_iter(columns, ",\n")
+        )
+    }
+
+    /**
+    Returns arrow schema.
+
+    Json columns are mapped to `Utf8`. `maxLength` is recorded in field
+    metadata with key `max_length`.
+    */
+    #[cfg(feature = "arrow")]
+    pub fn arrow_schema(&self) -> arrow_schema::Schema {
+        use arrow_schema::{DataType, Field, TimeUnit};
+
+        let fields = self.columns.iter().map(|c| {
+            let data_type = match c.data_type {
+                ColumnType::Boolean => DataType::Boolean,
+                ColumnType::BigInt => DataType::Int64,
+                ColumnType::Double => DataType::Float64,
+                ColumnType::Date => DataType::Date32,
+                ColumnType::Time => DataType::Time64(TimeUnit::Microsecond),
+                ColumnType::Timestamp => {
+                    DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+                }
+                ColumnType::Text | ColumnType::Uuid | ColumnType::Json => DataType::Utf8,
+            };
+            let mut field = Field::new(c.name.clone(), data_type, c.nullable);
+            if let Some(n) = c.max_length {
+                field.set_metadata([("max_length".to_owned(), n.to_string())].into());
+            }
+            field
+        });
+        arrow_schema::Schema::new(fields.collect::<Vec<_>>())
+    }
+}
+
+fn sql_ident(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
@@ -0,0 +1,513 @@
+/*!
+Building blocks for language servers and editors.
+
+[`Session`] keeps the schema documents open in an editor compiled,
+recompiling only the documents affected by an edit. [`diagnostics`]
+locates compile errors within documents, [`hover`], [`completion`] and
+[`completion_at`] provide the data shown in tooltips and completion popups.
+
+```
+# use std::error::Error;
+# use boon::tooling::*;
+# use serde_json::json;
+# fn main() -> Result<(), Box<dyn Error>> {
+let mut session = Session::new();
+session.update("http://example.com/person.json", json!({
+    "title": "Person",
+    "properties": {
+        "name": {"type": "string", "description": "full name"},
+        "age": {"type": "integer", "minimum": "zero"}
+    }
+}))?;
+
+let diagnostics = session.diagnostics("http://example.com/person.json");
+assert_eq!(diagnostics[0].ptr, "/properties/age/minimum");
+
+session.update("http://example.com/person.json", json!({
+    "title": "Person",
+    "properties": {
+        "name": {"type": "string", "description": "full name"},
+        "age": {"type": "integer", "minimum": 0}
+    }
+}))?;
+assert!(session.diagnostics("http://example.com/person.json").is_empty());
+
+let (schemas, sch) = session
+    .schema("http://example.com/person.json#/properties/name")
+    .unwrap();
+assert_eq!(hover(schemas, sch).description.as_deref(), Some("full name"));
+# Ok(())
+# }
+```
+*/
+
+use std::collections::{HashMap, HashSet};
+
+use serde_json::Value;
+use url::Url;
+
+use crate::{defaults::*, util::*, *};
+
+/// Compile error located within a schema document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// url of the document.
+    pub url: String,
+    /// json-pointer within the document.
+    pub ptr: String,
+    /// error message.
+    pub message: String,
+}
+
+/**
+Returns located diagnostics for `err`.
+
+Metaschema validation failures result in one diagnostic per failing
+keyword value. Errors which cannot be located within a document, such as
+[`CompileError::Bug`], are reported with empty `url`.
+*/
+pub fn diagnostics(err: &CompileError) -> Vec<Diagnostic> {
+    if let CompileError::ValidationError { url, src } = err {
+        let (url, ptr) = locate(url);
+        let mut list = vec![];
+        collect_leaves(src, &url, &ptr, &mut list);
+        return list;
+    }
+    let loc = match err {
+        CompileError::ParseUrlError { url, .. }
+        | CompileError::LoadUrlError { url, .. }
+        | CompileError::UnsupportedUrlScheme { url }
+        | CompileError::OfflineLoadError { url }
+        | CompileError::InvalidMetaSchemaUrl { url, .. }
+        | CompileError::UnsupportedDraft { url }
+        | CompileError::MetaSchemaCycle { url }
+        | CompileError::AnchorNotFound { url, .. }
+        | CompileError::UnsupportedVocabulary { url, .. }
+        | CompileError::InvalidRegex { url, .. }
+        | CompileError::UnsupportedKeyword { url, .. }
+        | CompileError::InvalidKeyword { url, .. }
+        | CompileError::InvalidBsonType { url, .. }
+        | CompileError::InvalidPatch { url, .. }
+        | CompileError::InvalidAsyncApi { url, .. }
+        | CompileError::LimitExceeded { url, .. } => url.as_str(),
+        CompileError::ParseIdError { loc }
+        | CompileError::ParseAnchorError { loc }
+        | CompileError::InvalidJsonPointer(loc)
+        | CompileError::JsonPointerNotFound(loc) => loc.as_str(),
+        CompileError::DuplicateId { url, ptr2, .. }
+        | CompileError::DuplicateAnchor { url, ptr2, .. } => {
+            return vec![Diagnostic {
+                url: url.clone(),
+                ptr: ptr2.clone(),
+                message: format!("{err:#}"),
+            }];
+        }
+        _ => "",
+    };
+    let (url, ptr) = locate(loc);
+    vec![Diagnostic {
+        url,
+        ptr,
+        message: format!("{err:#}"),
+    }]
+}
+
+// splits `loc` into url and json-pointer.
+fn locate(loc: &str) -> (String, String) {
+    match UrlFrag::absolute(loc) {
+        Ok(UrlFrag {
+            url,
+            frag: Fragment::JsonPointer(ptr),
+        }) => (url.to_string(), ptr.to_string()),
+        Ok(UrlFrag { url, .. }) => (url.to_string(), String::new()),
+        Err(_) => (split(loc).0.to_owned(), String::new()),
+    }
+}
+
+fn collect_leaves(e: &ValidationError, url: &str, ptr: &str, list: &mut Vec<Diagnostic>) {
+    if e.causes.is_empty() {
+        list.push(Diagnostic {
+            url: url.to_owned(),
+            ptr: format!("{ptr}{}", e.instance_location),
+            message: e.kind.to_string(),
+        });
+    }
+    for cause in &e.causes {
+        collect_leaves(cause, url, ptr, list);
+    }
+}
+
+/// Metadata of a schema, shown in tooltips.
+#[derive(Debug, Clone, Default)]
+pub struct Hover {
+    /// value of `title`.
+    pub title: Option<String>,
+    /// value of `description`.
+    pub description: Option<String>,
+    /// allowed types. empty means any type, unless `unsatisfiable`.
+    pub types: Vec<Type>,
+    /// whether the schemas allow no type in common, so that no value is valid.
+    pub unsatisfiable: bool,
+}
+
+/**
+Returns metadata of schema `sch_index`, including the subschemas it
+applies unconditionally i.e. `$ref` and `allOf`.
+
+# Panics
+
+Panics if `sch_index` is not generated for `schemas`.
+*/
+pub fn hover(schemas: &Schemas, sch_index: SchemaIndex) -> Hover {
+    let mut hover = Hover::default();
+    let mut types = None::<Types>;
+    for sch in schemas.applied_schemas(sch_index) {
+        let s = schemas.get(sch);
+        hover.title = hover.title.or_else(|| s.title.clone());
+        hover.description = hover.description.or_else(|| s.description.clone());
+        if !s.types.is_empty() {
+            types = Some(match types {
+                Some(t) => t.intersect(s.types),
+                None => s.types,
+            });
+        }
+    }
+    hover.unsatisfiable = types.is_some_and(|t| t.is_empty());
+    hover.types = types.map(|t| t.iter().collect()).unwrap_or_default();
+    hover
+}
+
+/// Completion data of a schema.
+#[derive(Debug, Clone, Default)]
+pub struct Completion {
+    /// property names along with their schema, sorted by name.
+    pub properties: Vec<(String, SchemaIndex)>,
+    /// values allowed by `enum` or `const`.
+    pub values: Vec<Value>,
+}
+
+/**
+Returns completion data of schema `sch_index`, including the subschemas
+it applies unconditionally i.e. `$ref` and `allOf`.
+
+# Panics
+
+Panics if `sch_index` is not generated for `schemas`.
+*/
+pub fn completion(schemas: &Schemas, sch_index: SchemaIndex) -> Completion {
+    completion_of(schemas, &schemas.applied_schemas(sch_index))
+}
+
+/**
+Returns completion data at location `ptr` of `instance`, validated
+against schema `sch_index`.
+
+Schemas applicable at each location are found by following `$ref`,
+`allOf`, `dependentSchemas` and `dependencies` of present properties,
+`then` or `else` depending on whether `if` matches, and the subschemas of
+`anyOf`/`oneOf` which match. If none of the subschemas of `anyOf`/`oneOf`
+match, or the value at a location does not exist yet, all of them are used.
+
+`ptr` need not exist in `instance`, so that completion data for a property
+being added can be queried. Returns `None` if `ptr` is not a valid
+json-pointer.
+
+```
+# use std::error::Error;
+# use boon::{*, tooling::*};
+# use serde_json::json;
+# fn main() -> Result<(), Box<dyn Error>> {
+let mut schemas = Schemas::new();
+let mut compiler = Compiler::new();
+let schema = json!({
+    "properties": {"kind": {"enum": ["file", "url"]}},
+    "if": {"properties": {"kind": {"const": "url"}}},
+    "then": {"properties": {"href": {"type": "string"}}},
+    "else": {"properties": {"path": {"type": "string"}}}
+});
+compiler.add_resource("source.json", schema)?;
+let sch_index = compiler.compile("source.json", &mut schemas)?;
+
+let instance = json!({"kind": "url"});
+let root = completion_at(&schemas, &instance, sch_index, "").unwrap();
+let props = root.properties.iter().map(|(p, _)| p.as_str()).collect::<Vec<_>>();
+assert_eq!(props, ["href", "kind"]);
+
+let kind = completion_at(&schemas, &instance, sch_index, "/kind").unwrap();
+assert_eq!(kind.values, [json!("file"), json!("url")]);
+# Ok(())
+# }
+```
+
+# Panics
+
+Panics if `sch_index` is not generated for `schemas`.
+*/
+pub fn completion_at(
+    schemas: &Schemas,
+    instance: &Value,
+    sch_index: SchemaIndex,
+    ptr: &str,
+) -> Option<Completion> {
+    let list = schemas.schemas_at(instance, sch_index, ptr)?;
+    Some(completion_of(schemas, &list))
+}
+
+impl Schemas {
+    // returns schemas applicable at location `ptr` of `instance`, validated
+    // against `sch_index`. see `completion_at` for details.
+    pub(crate) fn schemas_at(
+        &self,
+        instance: &Value,
+        sch_index: SchemaIndex,
+        ptr: &str,
+    ) -> Option<Vec<SchemaIndex>> {
+        let tokens = ptr_tokens(ptr)?;
+        let mut v = Some(instance);
+        let mut list = vec![];
+        applicable(self, sch_index, v, &mut list);
+        for tok in tokens {
+            let mut next = vec![];
+            for &sch in &list {
+                let s = self.get(sch);
+                match v {
+                    Some(Value::Array(_)) => {
+                        next.extend(tok.parse().ok().and_then(|i| item_schema(self, s, i)))
+                    }
+                    _ => next.extend(prop_schemas(s, &tok)),
+                }
+            }
+            v = match v {
+                Some(Value::Object(obj)) => obj.get(&tok),
+                Some(Value::Array(arr)) => tok.parse().ok().and_then(|i: usize| arr.get(i)),
+                _ => None,
+            };
+            list.clear();
+            for sch in next {
+                applicable(self, sch, v, &mut list);
+            }
+        }
+        Some(list)
+    }
+}
+
+// returns unescaped tokens of json-pointer `ptr`, or None if not valid.
+pub(crate) fn ptr_tokens(ptr: &str) -> Option<Vec<String>> {
+    let mut tokens = vec![];
+    if !ptr.is_empty() {
+        let ptr = ptr.strip_prefix('/')?;
+        for tok in ptr.split('/') {
+            tokens.push(JsonPointer::unescape(tok).ok()?.into_owned());
+        }
+    }
+    Some(tokens)
+}
+
+// adds schemas applicable to value `v`, validated against `sch`, into `list`.
+// `v` is None, if value does not exist.
+fn applicable(schemas: &Schemas, sch: SchemaIndex, v: Option<&Value>, list: &mut Vec<SchemaIndex>) {
+    if list.contains(&sch) {
+        return;
+    }
+    list.push(sch);
+    let s = schemas.get(sch);
+    let is_valid = |sch: SchemaIndex| v.is_some_and(|v| schemas.validate(v, sch).is_ok());
+
+    let mut subschemas = vec![];
+    let all_of = schemas.indexes(s.all_of);
+    subschemas.extend(s.ref_.iter().chain(&s.recursive_ref).chain(all_of));
+    subschemas.extend(s.dynamic_ref.iter().map(|dref| dref.sch));
+    for group in [s.any_of, s.one_of] {
+        let group = schemas.indexes(group);
+        let matched = group.iter().copied().filter(|&sch| is_valid(sch));
+        let matched = matched.collect::<Vec<_>>();
+        subschemas.extend(if matched.is_empty() { group } else { &matched });
+    }
+    if let Some(if_) = s.if_ {
+        match v {
+            Some(_) if is_valid(if_) => subschemas.extend(s.then),
+            Some(_) => subschemas.extend(s.else_),
+            None => subschemas.extend(s.then.iter().chain(&s.else_)),
+        }
+    }
+    if let Some(Value::Object(obj)) = v {
+        for (pname, sch) in &s.dependent_schemas {
+            if obj.contains_key(pname) {
+                subschemas.push(*sch);
+            }
+        }
+        for (pname, dep) in &s.dependencies {
+            if let (true, Dependency::SchemaRef(sch)) = (obj.contains_key(pname), dep) {
+                subschemas.push(*sch);
+            }
+        }
+    }
+    for sch in subschemas {
+        applicable(schemas, sch, v, list);
+    }
+}
+
+fn completion_of(schemas: &Schemas, list: &[SchemaIndex]) -> Completion {
+    let mut completion = Completion::default();
+    for &sch in list {
+        let s = schemas.get(sch);
+        for (pname, &psch) in &s.properties {
+            if !completion.properties.iter().any(|(p, _)| **p == **pname) {
+                completion.properties.push((pname.to_string(), psch));
+            }
+        }
+        let values = s.enum_.iter().flat_map(|e| &e.values).chain(&s.constant);
+        for v in values {
+            if !completion.values.iter().any(|cv| equals(cv, v)) {
+                completion.values.push(v.clone());
+            }
+        }
+    }
+    completion.properties.sort_by(|a, b| a.0.cmp(&b.0));
+    completion
+}
+
+struct Compiled {
+    schemas: Schemas,
+    sch_index: Option<SchemaIndex>,
+    deps: Option<HashSet<Url>>, // None means unknown, due to compile error
+    diagnostics: Vec<Diagnostic>,
+}
+
+/**
+Set of schema documents open in an editor, kept compiled.
+
+Each document is compiled separately, using the other open documents
+in place of what the loader returns. On [`Session::update`], only the
+documents which refer to the updated document, directly or indirectly,
+are recompiled.
+*/
+pub struct Session {
+    new_compiler: Box<dyn Fn() -> Compiler>,
+    docs: HashMap<Url, Value>,
+    compiled: HashMap<Url, Compiled>,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Session {
+    /// Creates session using [`Compiler::new`].
+    pub fn new() -> Self {
+        Self::with_compiler(Compiler::new)
+    }
+
+    /// Creates session using compilers returned by `new_compiler`, so
+    /// that drafts, formats, loaders etc. can be configured.
+    pub fn with_compiler(new_compiler: impl Fn() -> Compiler + 'static) -> Self {
+        Self {
+            new_compiler: Box::new(new_compiler),
+            docs: HashMap::new(),
+            compiled: HashMap::new(),
+        }
+    }
+
+    /**
+    Adds or replaces document at `loc` with `doc`, and recompiles the
+    affected documents. Returns urls of the documents recompiled.
+
+    # Errors
+
+    returns error if `loc` is not a valid url or file path.
+    */
+    pub fn update(&mut self, loc: &str, doc: Value) -> Result<Vec<String>, CompileError> {
+        let url = UrlFrag::absolute(loc)?.url;
+        self.docs.insert(url.clone(), doc);
+        Ok(self.recompile(&url))
+    }
+
+    /**
+    Removes document at `loc`, and recompiles the documents which referred
+    to it. Returns urls of the documents recompiled.
+
+    # Errors
+
+    returns error if `loc` is not a valid url or file path.
+    */
+    pub fn remove(&mut self, loc: &str) -> Result<Vec<String>, CompileError> {
+        let url = UrlFrag::absolute(loc)?.url;
+        self.docs.remove(&url);
+        self.compiled.remove(&url);
+        Ok(self.recompile(&url))
+    }
+
+    /// Returns diagnostics of document at `loc`.
+    pub fn diagnostics(&self, loc: &str) -> &[Diagnostic] {
+        UrlFrag::absolute(loc)
+            .ok()
+            .and_then(|uf| self.compiled.get(&uf.url))
+            .map_or(&[], |c| &c.diagnostics)
+    }
+
+    /**
+    Returns compiled schema at `loc`, which is url of an open document
+    optionally followed by json-pointer fragment.
+
+    Returns `None`, if the document did not compile or `loc` is not a
+    schema location.
+    */
+    pub fn schema(&self, loc: &str) -> Option<(&Schemas, SchemaIndex)> {
+        let uf = UrlFrag::absolute(loc).ok()?;
+        let compiled = self.compiled.get(&uf.url)?;
+        compiled.sch_index?;
+        let Fragment::JsonPointer(ptr) = uf.frag else {
+            return None;
+        };
+        let up = UrlPtr { url: uf.url, ptr };
+        let &idx = compiled.schemas.map.get(&up)?;
+        Some((&compiled.schemas, SchemaIndex(idx)))
+    }
+
+    // recompiles `changed` and documents depending on it.
+    fn recompile(&mut self, changed: &Url) -> Vec<String> {
+        let mut affected = self
+            .compiled
+            .iter()
+            .filter(|(_, c)| c.deps.as_ref().map_or(true, |deps| deps.contains(changed)))
+            .map(|(url, _)| url.clone())
+            .collect::<Vec<_>>();
+        if self.docs.contains_key(changed) && !affected.contains(changed) {
+            affected.push(changed.clone());
+        }
+        affected.sort();
+        for url in &affected {
+            let compiled = self.compile(url);
+            self.compiled.insert(url.clone(), compiled);
+        }
+        affected.into_iter().map(|url| url.to_string()).collect()
+    }
+
+    fn compile(&self, url: &Url) -> Compiled {
+        let mut schemas = Schemas::new();
+        let mut compiler = (self.new_compiler)();
+        let mut result = Ok(());
+        for (url, doc) in &self.docs {
+            result = result.and_then(|_| compiler.add_resource(url.as_str(), doc.clone()));
+        }
+        match result.and_then(|_| compiler.compile(url.as_str(), &mut schemas)) {
+            Ok(sch_index) => {
+                let deps = schemas.map.keys().map(|up| up.url.clone()).collect();
+                Compiled {
+                    schemas,
+                    sch_index: Some(sch_index),
+                    deps: Some(deps),
+                    diagnostics: vec![],
+                }
+            }
+            Err(e) => Compiled {
+                schemas,
+                sch_index: None,
+                deps: None,
+                diagnostics: diagnostics(&e),
+            },
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use std::fmt::Display;
+
+use serde_json::Value;
+
+use crate::{util::*, validator, InstanceLocation, SchemaIndex, Schemas, ValidationError};
+
+/**
+Evaluation of a schema against an instance value, reported by
+[`Schemas::validate_with_trace`].
+
+Events are reported in the order schemas are entered, so an event is
+followed by the events of its subschemas.
+*/
+#[derive(Debug)]
+pub struct TraceEvent<'s, 'v> {
+    /// The absolute, dereferenced schema location.
+    pub schema_url: &'s str,
+    /// The location of the JSON value within the instance being validated
+    pub instance_location: InstanceLocation<'v>,
+    /// locations of schemas on the evaluation path, outermost first and
+    /// ending with `schema_url`. This is the dynamic scope used to resolve
+    /// `$dynamicRef` and `$recursiveRef`.
+    pub scope: Vec<&'s str>,
+    /// keyword through which this schema was entered, if it is a reference
+    /// such as `$ref` or `$dynamicRef`.
+    pub ref_keyword: Option<&'static str>,
+    /// validation keywords of the schema, along with their outcome.
+    pub keywords: Vec<(&'static str, KeywordOutcome)>,
+    /// whether the instance value is valid against the schema.
+    pub valid: bool,
+    /// properties not evaluated by the schema or its subschemas. These are
+    /// tracked only where `unevaluatedProperties` of some enclosing schema
+    /// needs them.
+    pub unevaluated_properties: Vec<&'v str>,
+    /// indexes of items not evaluated by the schema or its subschemas.
+    /// These are tracked only where `unevaluatedItems` of some enclosing
+    /// schema needs them.
+    pub unevaluated_items: Vec<usize>,
+}
+
+/// Outcome of keyword in [`TraceEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeywordOutcome {
+    /// keyword is satisfied.
+    Valid,
+    /// keyword is not satisfied.
+    Invalid,
+    /// keyword does not apply to the type of instance value, or is not
+    /// evaluated because an earlier keyword such as `type` failed.
+    Skipped,
+}
+
+impl Display for KeywordOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Valid => write!(f, "valid"),
+            Self::Invalid => write!(f, "invalid"),
+            Self::Skipped => write!(f, "skipped"),
+        }
+    }
+}
+
+impl Display for TraceEvent<'_, '_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = self.scope.len().saturating_sub(1) * 2;
+        write!(
+            f,
+            "{:indent$}{} at {}: {}",
+            "",
+            self.schema_url,
+            quote(&self.instance_location.to_string()),
+            if self.valid { "valid" } else { "invalid" }
+        )?;
+        if let Some(kw) = self.ref_keyword {
+            write!(f, " (via {kw})")?;
+        }
+        if !self.keywords.is_empty() {
+            let kws = self
+                .keywords
+                .iter()
+                .map(|(kw, outcome)| format!("{kw}={outcome}"));
+            write!(f, " [{}]", join_iter(kws, ", "))?;
+        }
+        if !self.unevaluated_properties.is_empty() {
+            let props = self.unevaluated_properties.iter().map(quote);
+            write!(f, " unevaluated properties: {}", join_iter(props, ", "))?;
+        }
+        if !self.unevaluated_items.is_empty() {
+            write!(
+                f,
+                " unevaluated items: {}",
+                join_iter(&self.unevaluated_items, ", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Schemas {
+    /**
+    Same as [`Schemas::validate_with`], but also returns a trace of every
+    schema evaluated, with the outcome of its keywords and the dynamic
+    scope at that point.
+
+    This is meant for debugging surprising results involving
+    `unevaluatedProperties` or `$dynamicRef`, so it evaluates all keywords
+    even where the result is already known, and is slower than
+    [`Schemas::validate_with`]. Subschemas of `propertyNames` and
+    `contentSchema` are not traced.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let schema = json!({
+        "allOf": [{"properties": {"a": true}}],
+        "unevaluatedProperties": false
+    });
+    compiler.add_resource("schema.json", schema)?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+    let instance = json!({"a": 1, "b": 2});
+    let options = ValidationOptions::new();
+    let (result, trace) = schemas.validate_with_trace(&instance, sch_index, &options);
+    assert!(result.is_err());
+    assert!(trace[1].schema_url.ends_with("#/allOf/0"));
+    assert_eq!(trace[1].unevaluated_properties, ["b"]);
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn validate_with_trace<'s, 'v>(
+        &'s self,
+        v: &'v Value,
+        sch_index: SchemaIndex,
+        options: &crate::ValidationOptions,
+    ) -> (Result<(), ValidationError<'s, 'v>>, Vec<TraceEvent<'s, 'v>>) {
+        let Some(sch) = self.list.get(sch_index.0) else {
+            panic!("Schemas::validate: schema index out of bounds");
+        };
+        let mut trace = vec![];
+        let result = validator::validate(v, sch, self, options, None, Some(&mut trace));
+        (result, trace)
+    }
+}
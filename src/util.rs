@@ -1,5 +1,6 @@
 use std::{
     borrow::{Borrow, Cow},
+    cmp::Ordering,
     fmt::Display,
     hash::{Hash, Hasher},
     path,
@@ -8,11 +9,23 @@ use std::{
 
 use ahash::{AHashMap, AHasher};
 use percent_encoding::{percent_decode_str, AsciiSet, CONTROLS};
-use serde_json::Value;
+use serde_json::{Number, Value};
 use url::Url;
 
 use crate::CompileError;
 
+// maps keyed by property names, anchors etc, hashed with ahash by default.
+// feature `siphash` switches them to std's DoS-resistant hasher, for users
+// compiling schemas from untrusted sources.
+#[cfg(not(feature = "siphash"))]
+pub(crate) type FastHashMap<K, V> = AHashMap<K, V>;
+#[cfg(not(feature = "siphash"))]
+pub(crate) type FastHashSet<T> = ahash::AHashSet<T>;
+#[cfg(feature = "siphash")]
+pub(crate) type FastHashMap<K, V> = std::collections::HashMap<K, V>;
+#[cfg(feature = "siphash")]
+pub(crate) type FastHashSet<T> = std::collections::HashSet<T>;
+
 // --
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -301,12 +314,66 @@ impl Display for UrlPtr {
 pub(crate) fn is_integer(v: &Value) -> bool {
     match v {
         Value::Number(n) => {
-            n.is_i64() || n.is_u64() || n.as_f64().filter(|n| n.fract() == 0.0).is_some()
+            n.is_i64() || n.is_u64() || {
+                #[cfg(feature = "bigdecimal")]
+                {
+                    big_decimal(n).filter(|n| n.is_integer()).is_some()
+                }
+                #[cfg(not(feature = "bigdecimal"))]
+                {
+                    n.as_f64().filter(|n| n.fract() == 0.0).is_some()
+                }
+            }
         }
         _ => false,
     }
 }
 
+// compares numbers. comparison is exact with `bigdecimal` feature.
+#[cfg(not(feature = "bigdecimal"))]
+pub(crate) fn num_cmp(n1: &Number, n2: &Number) -> Option<Ordering> {
+    n1.as_f64()?.partial_cmp(&n2.as_f64()?)
+}
+
+// compares numbers. comparison is exact with `bigdecimal` feature.
+#[cfg(feature = "bigdecimal")]
+pub(crate) fn num_cmp(n1: &Number, n2: &Number) -> Option<Ordering> {
+    // integers are exact already, and need no allocation
+    if let (Some(n1), Some(n2)) = (n1.as_i64(), n2.as_i64()) {
+        return Some(n1.cmp(&n2));
+    }
+    if let (Some(n1), Some(n2)) = (n1.as_u64(), n2.as_u64()) {
+        return Some(n1.cmp(&n2));
+    }
+    Some(big_decimal(n1)?.cmp(&big_decimal(n2)?))
+}
+
+// checks whether `n` is multiple of `m`. check is exact with `bigdecimal` feature.
+#[cfg(not(feature = "bigdecimal"))]
+pub(crate) fn is_multiple_of(n: &Number, m: &Number) -> bool {
+    match (n.as_f64(), m.as_f64()) {
+        (Some(n), Some(m)) => (n / m).fract() == 0.0,
+        _ => true,
+    }
+}
+
+// checks whether `n` is multiple of `m`. check is exact with `bigdecimal` feature.
+#[cfg(feature = "bigdecimal")]
+pub(crate) fn is_multiple_of(n: &Number, m: &Number) -> bool {
+    use bigdecimal::Zero;
+    match (big_decimal(n), big_decimal(m)) {
+        (Some(n), Some(m)) if !m.is_zero() => (n % m).is_zero(),
+        _ => true,
+    }
+}
+
+// with serde_json's `arbitrary_precision`, to_string returns the number
+// exactly as it appeared in json.
+#[cfg(feature = "bigdecimal")]
+fn big_decimal(n: &Number) -> Option<bigdecimal::BigDecimal> {
+    bigdecimal::BigDecimal::from_str(&n.to_string()).ok()
+}
+
 fn starts_with_windows_drive(p: &str) -> bool {
     p.chars().next().filter(char::is_ascii_uppercase).is_some() && p[1..].starts_with(":\\")
 }
@@ -349,6 +416,9 @@ pub(crate) fn equals(v1: &Value, v2: &Value) -> bool {
     match (v1, v2) {
         (Value::Null, Value::Null) => true,
         (Value::Bool(b1), Value::Bool(b2)) => b1 == b2,
+        #[cfg(feature = "bigdecimal")]
+        (Value::Number(n1), Value::Number(n2)) => num_cmp(n1, n2) == Some(Ordering::Equal),
+        #[cfg(not(feature = "bigdecimal"))]
         (Value::Number(n1), Value::Number(n2)) => {
             if let (Some(n1), Some(n2)) = (n1.as_u64(), n2.as_u64()) {
                 return n1 == n2;
@@ -428,6 +498,13 @@ pub(crate) fn duplicates(arr: &Vec<Value>) -> Option<(usize, usize)> {
 
 // HashedValue --
 
+/// hash of `v`, consistent with [`equals`].
+pub(crate) fn hash_value(v: &Value) -> u64 {
+    let mut hasher = AHasher::default();
+    HashedValue(v).hash(&mut hasher);
+    hasher.finish()
+}
+
 // Based on implementation proposed by Sven Marnach:
 // https://stackoverflow.com/questions/60882381/what-is-the-fastest-correct-way-to-detect-that-there-are-no-duplicates-in-a-json
 pub(crate) struct HashedValue<'a>(pub(crate) &'a Value);
@@ -447,6 +524,8 @@ impl Hash for HashedValue<'_> {
             Value::Bool(ref b) => b.hash(state),
             Value::Number(ref num) => {
                 if let Some(num) = num.as_f64() {
+                    // -0.0 equals 0.0, so must hash same
+                    let num = if num == 0.0 { 0.0 } else { num };
                     num.to_bits().hash(state);
                 } else if let Some(num) = num.as_u64() {
                     num.hash(state);
@@ -541,5 +620,25 @@ mod tests {
         assert!(equals(&v1, &v2));
         assert!(seen.insert(HashedValue(&v1), 1).is_none());
         assert!(seen.insert(HashedValue(&v2), 1).is_some());
+
+        let (v1, v2) = (json!(0), json!(-0.0));
+        assert!(equals(&v1, &v2));
+        assert!(seen.insert(HashedValue(&v1), 1).is_none());
+        assert!(seen.insert(HashedValue(&v2), 1).is_some());
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let mut arr = (0..100_000)
+            .map(|i| json!({"id": i, "tags": [i % 7]}))
+            .collect::<Vec<_>>();
+        assert_eq!(duplicates(&arr), None);
+        arr.push(json!({"tags": [3.0], "id": 500.0}));
+        assert_eq!(duplicates(&arr), Some((500, 100_000)));
+
+        let mut arr = (1..30).map(|i| json!(i)).collect::<Vec<_>>();
+        arr.push(json!(0));
+        arr.push(json!(-0.0));
+        assert_eq!(duplicates(&arr), Some((29, 30)));
     }
 }
@@ -1,4 +1,11 @@
-use std::{borrow::Cow, cmp::min, collections::HashSet, fmt::Write};
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    cmp::{min, Ordering},
+    collections::HashSet,
+    fmt::Write,
+    time::Instant,
+};
 
 use serde_json::{Map, Value};
 
@@ -20,44 +27,100 @@ pub(crate) fn validate<'s, 'v>(
     v: &'v Value,
     schema: &'s Schema,
     schemas: &'s Schemas,
+    options: &ValidationOptions,
+    warnings: Option<&mut Vec<ValidationWarning<'s, 'v>>>,
+    trace: Option<&mut Vec<TraceEvent<'s, 'v>>>,
 ) -> Result<(), ValidationError<'s, 'v>> {
-    let scope = Scope {
-        sch: schema.idx,
-        ref_kw: None,
-        vid: 0,
-        parent: None,
-    };
-    let mut vloc = Vec::with_capacity(8);
-    let result = Validator {
-        v,
-        vloc: &mut vloc,
-        schema,
-        schemas,
-        scope,
-        uneval: Uneval::from(v, schema, false),
-        errors: vec![],
-        bool_result: false,
-    }
-    .validate();
-    match result {
+    let limits = Limits::new(options);
+    match run(v, schema, schemas, options, warnings, trace, &limits, false) {
         Err(err) => {
             let mut e = ValidationError {
                 schema_url: &schema.loc,
                 instance_location: InstanceLocation::new(),
                 kind: ErrorKind::Schema { url: &schema.loc },
                 causes: vec![],
+                provenance: None,
             };
             if let ErrorKind::Group = err.kind {
                 e.causes = err.causes;
             } else {
                 e.causes.push(err);
             }
+            schemas.set_provenance(&mut e);
             Err(e)
         }
         Ok(_) => Ok(()),
     }
 }
 
+// same as validate, but faster as it does not collect errors.
+#[cfg(feature = "rayon")]
+pub(crate) fn is_valid(
+    v: &Value,
+    schema: &Schema,
+    schemas: &Schemas,
+    options: &ValidationOptions,
+) -> bool {
+    let limits = Limits::new(options);
+    run(v, schema, schemas, options, None, None, &limits, true).is_ok()
+}
+
+// same as validate, but returns the lazy schema reached, if any.
+// otherwise returns whether `v` is valid.
+pub(crate) fn validate_lazy(
+    v: &Value,
+    schema: &Schema,
+    schemas: &Schemas,
+    options: &ValidationOptions,
+) -> Result<bool, SchemaIndex> {
+    let limits = Limits::new(options);
+    let valid = run(v, schema, schemas, options, None, None, &limits, false).is_ok();
+    match limits.lazy.get() {
+        Some(sch) => Err(sch),
+        None => Ok(valid),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run<'s, 'v>(
+    v: &'v Value,
+    schema: &'s Schema,
+    schemas: &'s Schemas,
+    options: &ValidationOptions,
+    warnings: Option<&mut Vec<ValidationWarning<'s, 'v>>>,
+    trace: Option<&mut Vec<TraceEvent<'s, 'v>>>,
+    limits: &Limits<'s, 'v>,
+    bool_result: bool,
+) -> Result<(), ValidationError<'s, 'v>> {
+    let scope = Scope {
+        sch: schema.idx,
+        ref_kw: None,
+        vid: 0,
+        token: None,
+        depth: 1,
+        parent: None,
+    };
+    let result = Validator {
+        v,
+        root: v,
+        schema,
+        schemas,
+        options,
+        warnings,
+        trace,
+        limits,
+        scope,
+        uneval: Uneval::from(v, schema, false),
+        errors: vec![],
+        bool_result,
+    }
+    .validate();
+    match limits.aborted.take() {
+        Some(err) => Err(err),
+        None => result.map(|_| ()),
+    }
+}
+
 macro_rules! kind {
     ($kind:ident, $name:ident: $value:expr) => {
         ErrorKind::$kind { $name: $value }
@@ -82,10 +145,14 @@ macro_rules! kind {
 
 struct Validator<'v, 's, 'd, 'e> {
     v: &'v Value,
-    vloc: &'e mut Vec<InstanceToken<'v>>,
+    root: &'v Value, // instance being validated by Schemas::validate
     schema: &'s Schema,
     schemas: &'s Schemas,
-    scope: Scope<'d>,
+    options: &'e ValidationOptions,
+    warnings: Option<&'e mut Vec<ValidationWarning<'s, 'v>>>, // None means not interested
+    trace: Option<&'e mut Vec<TraceEvent<'s, 'v>>>,           // None means not interested
+    limits: &'e Limits<'s, 'v>,
+    scope: Scope<'d, 'v>,
     uneval: Uneval<'v>,
     errors: Vec<ValidationError<'s, 'v>>,
     bool_result: bool, // is interested to know valid or not (but not actuall error)
@@ -93,17 +160,79 @@ struct Validator<'v, 's, 'd, 'e> {
 
 impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
     fn validate(mut self) -> Result<Uneval<'v>, ValidationError<'s, 'v>> {
+        if self.limits.aborted.borrow().is_some() {
+            return Err(self.error(kind!(Group)));
+        }
+        if self.trace.is_none() {
+            return self.validate_keywords().map(|_| self.uneval);
+        }
+        let event = TraceEvent {
+            schema_url: &self.schema.loc,
+            instance_location: self.instance_location(),
+            scope: self.scope_locs(),
+            ref_keyword: self.scope.ref_kw,
+            keywords: vec![],
+            valid: false,
+            unevaluated_properties: vec![],
+            unevaluated_items: vec![],
+        };
+        let index = self.trace.as_deref_mut().map(|trace| {
+            trace.push(event);
+            trace.len() - 1
+        });
+        let result = self.validate_keywords();
+        let keywords = self.keyword_outcomes(&result);
+        if let (Some(trace), Some(i)) = (self.trace.as_deref_mut(), index) {
+            let event = &mut trace[i];
+            event.keywords = keywords;
+            event.valid = result.is_ok();
+            event.unevaluated_properties = self.uneval.props.iter().copied().collect();
+            event.unevaluated_properties.sort();
+            event.unevaluated_items = self.uneval.items.iter().copied().collect();
+            event.unevaluated_items.sort();
+        }
+        result.map(|_| self.uneval)
+    }
+
+    fn validate_keywords(&mut self) -> Result<(), ValidationError<'s, 'v>> {
         let s = self.schema;
         let v = self.v;
 
+        // lazy --
+        if s.lazy {
+            return Err(self.not_compiled(s));
+        }
+
         // boolean --
         if let Some(b) = s.boolean {
             return match b {
                 false => Err(self.error(kind!(FalseSchema))),
-                true => Ok(self.uneval),
+                true => Ok(()),
             };
         }
 
+        // max depth --
+        if let Some(max) = self.options.max_depth() {
+            if self.scope.depth > max {
+                return Err(self.abort(kind!(MaxDepth, want: max)));
+            }
+        }
+
+        // budget --
+        let evaluations = self.limits.evaluations.get() + 1;
+        self.limits.evaluations.set(evaluations);
+        if let Some(max) = self.options.max_evaluations() {
+            if evaluations > max {
+                return Err(self.abort(kind!(MaxEvaluations, want: max)));
+            }
+        }
+        if let (Some(deadline), Some(limit)) = (self.limits.deadline, self.options.time_limit()) {
+            // reading clock is not cheap
+            if evaluations & 63 == 0 && Instant::now() > deadline {
+                return Err(self.abort(kind!(TimeLimit, want: limit)));
+            }
+        }
+
         // check cycle --
         if let Some(scp) = self.scope.check_cycle() {
             let kind = ErrorKind::RefCycle {
@@ -114,6 +243,25 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
             return Err(self.error(kind));
         }
 
+        // deprecated --
+        if s.deprecated && self.warnings.is_some() {
+            let instance_location = self.instance_location();
+            if let Some(warnings) = self.warnings.as_deref_mut() {
+                warnings.push(ValidationWarning {
+                    schema_url: &s.loc,
+                    instance_location,
+                    kind: WarningKind::Deprecated,
+                });
+            }
+        }
+
+        // readOnly, writeOnly --
+        match self.options.access_context() {
+            Some(AccessContext::Write) if s.read_only => self.add_error(kind!(ReadOnly)),
+            Some(AccessContext::Read) if s.write_only => self.add_error(kind!(WriteOnly)),
+            _ => {}
+        }
+
         // type --
         if !s.types.is_empty() {
             let v_type = Type::of(v);
@@ -124,6 +272,12 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
             }
         }
 
+        // bsonType --
+        if !s.bson_types.is_empty() && !s.bson_types.iter().any(|t| bson::is_bson_type(v, t)) {
+            let want = s.bson_types.iter().map(String::as_str).collect();
+            return Err(self.error(kind!(BsonType, bson::bson_type(v), want)));
+        }
+
         // constant --
         if let Some(c) = &s.constant {
             if !equals(v, c) {
@@ -132,24 +286,40 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
         }
 
         // enum --
-        if let Some(Enum { types, values }) = &s.enum_ {
-            if !types.contains(Type::of(v)) || !values.iter().any(|e| equals(e, v)) {
-                return Err(self.error(kind!(Enum, want: values)));
+        if let Some(e) = &s.enum_ {
+            if !e.contains(v) {
+                return Err(self.error(kind!(Enum, want: &e.values)));
             }
         }
 
+        // $data --
+        if !s.data.is_empty() {
+            self.data_validate();
+        }
+
         // format --
         if let Some(format) = &s.format {
-            if let Err(e) = (format.func)(v) {
-                self.add_error(kind!(Format, Cow::Borrowed(v), format.name, e));
+            if s.format_assert || self.options.is_format_asserted(format.name) {
+                let func = self
+                    .options
+                    .format(format.name)
+                    .unwrap_or(Some(&format.func));
+                if let Some(Err(e)) = func.map(|func| func(v)) {
+                    self.add_error(kind!(Format, Cow::Borrowed(v), format.name, e));
+                }
             }
         }
 
+        // custom keywords --
+        if !s.keywords.is_empty() {
+            self.keywords_validate(Phase::Assertion);
+        }
+
         // $ref --
         if let Some(ref_) = s.ref_ {
             let result = self.validate_ref(ref_, "$ref");
             if s.draft_version < 2019 {
-                return result.map(|_| self.uneval);
+                return result;
             }
             self.errors.extend(result.err());
         }
@@ -168,17 +338,23 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
                 self.refs_validate();
             }
             self.cond_validate();
+            if !s.keywords.is_empty() {
+                self.keywords_validate(Phase::BeforeUnevaluated);
+            }
             if s.draft_version >= 2019 {
                 self.uneval_validate();
             }
+            if !s.keywords.is_empty() {
+                self.keywords_validate(Phase::Last);
+            }
         }
 
         match self.errors.len() {
-            0 => Ok(self.uneval),
+            0 => Ok(()),
             1 => Err(self.errors.remove(0)),
             _ => {
                 let mut e = self.error(kind!(Group));
-                e.causes = self.errors;
+                e.causes = std::mem::take(&mut self.errors);
                 Err(e)
             }
         }
@@ -213,7 +389,11 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
 
         // required --
         if !s.required.is_empty() {
-            if let Some(missing) = self.find_missing(obj, &s.required) {
+            let missing = match &s.required_bits {
+                Some(bits) => self.find_missing_bits(obj, &s.required, bits),
+                None => self.find_missing(obj, &s.required),
+            };
+            if let Some(missing) = missing {
                 self.add_error(kind!(Required, want: missing));
             }
         }
@@ -246,16 +426,35 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
             let mut evaluated = false;
 
             // properties --
-            if let Some(sch) = s.properties.get(pname) {
+            if let Some(sch) = s.properties.get(pname.as_str()) {
                 evaluated = true;
                 add_err!(self.validate_val(*sch, pvalue, prop!(pname)));
             }
 
             // patternProperties --
-            for (regex, sch) in &s.pattern_properties {
-                if regex.is_match(pname) {
+            if let Some(set) = &s.pattern_properties_set {
+                for i in set.matches(pname) {
                     evaluated = true;
-                    add_err!(self.validate_val(*sch, pvalue, prop!(pname)));
+                    let sch = s.pattern_properties[i].1;
+                    add_err!(self.validate_val(sch, pvalue, prop!(pname)));
+                }
+            } else {
+                for (regex, sch) in &s.pattern_properties {
+                    match regex.try_match(pname) {
+                        Ok(true) => {
+                            evaluated = true;
+                            add_err!(self.validate_val(*sch, pvalue, prop!(pname)));
+                        }
+                        Ok(false) => {}
+                        Err(_) => {
+                            evaluated = true;
+                            self.add_error(ErrorKind::RegexLimit {
+                                kw: "patternProperties",
+                                got: pname.as_str().into(),
+                                want: regex.as_str(),
+                            });
+                        }
+                    }
                 }
             }
 
@@ -277,7 +476,7 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
             }
 
             if evaluated {
-                self.uneval.props.remove(pname);
+                self.uneval.props.remove(pname.as_str());
             }
         }
         if !additional_props.is_empty() {
@@ -369,7 +568,9 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
                         debug_assert!(self.uneval.items.is_empty());
                     }
                     Items::SchemaRefs(list) => {
-                        for (i, (item, sch)) in arr.iter().zip(list).enumerate() {
+                        for (i, (item, sch)) in
+                            arr.iter().zip(self.schemas.indexes(*list)).enumerate()
+                        {
                             add_err!(self.validate_val(*sch, item, item!(i)));
                         }
                         evaluated = min(list.len(), len);
@@ -395,7 +596,13 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
             }
         } else {
             // prefixItems --
-            for (i, (sch, item)) in s.prefix_items.iter().zip(arr).enumerate() {
+            for (i, (sch, item)) in self
+                .schemas
+                .indexes(s.prefix_items)
+                .iter()
+                .zip(arr)
+                .enumerate()
+            {
                 add_err!(self.validate_val(*sch, item, item!(i)));
             }
 
@@ -410,29 +617,25 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
         }
 
         // contains --
-        if let Some(sch) = &s.contains {
-            let mut matched = vec![];
-            let mut errors = vec![];
-
-            for (i, item) in arr.iter().enumerate() {
-                if let Err(e) = self.validate_val(*sch, item, item!(i)) {
-                    errors.push(e);
-                } else {
-                    matched.push(i);
-                    if s.draft_version >= 2020 {
-                        self.uneval.items.remove(&i);
-                    }
-                }
+        if let Some(sch) = s.contains {
+            let quick = self.quick();
+            let num_warnings = self.warnings.as_ref().map_or(0, |w| w.len());
+            let (mut count, mut matched, mut errors) = self.contains_validate(sch, arr, quick);
+            let failed = count < s.min_contains.unwrap_or(1)
+                || s.max_contains.is_some_and(|max| count > max);
+            if failed && quick {
+                self.truncate_warnings(num_warnings);
+                (count, matched, errors) = self.contains_validate(sch, arr, false);
             }
 
             // minContains --
             if let Some(min) = s.min_contains {
-                if matched.len() < min {
+                if count < min {
                     let mut e = self.error(kind!(MinContains, matched.clone(), min));
                     e.causes = errors;
                     self.errors.push(e);
                 }
-            } else if matched.is_empty() {
+            } else if count == 0 {
                 let mut e = self.error(kind!(Contains));
                 e.causes = errors;
                 self.errors.push(e);
@@ -440,20 +643,49 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
 
             // maxContains --
             if let Some(max) = s.max_contains {
-                if matched.len() > max {
+                if count > max {
                     self.add_error(kind!(MaxContains, matched, max));
                 }
             }
         }
     }
 
+    // returns number of items matching `sch`, along with their indexes
+    // and errors of other items, which are not collected if `quick`.
+    fn contains_validate(
+        &mut self,
+        sch: SchemaIndex,
+        arr: &'v [Value],
+        quick: bool,
+    ) -> (usize, Vec<usize>, Vec<ValidationError<'s, 'v>>) {
+        let mut count = 0;
+        let mut matched = vec![];
+        let mut errors = vec![];
+        for (i, item) in arr.iter().enumerate() {
+            if let Err(e) = self._validate_val(sch, item, item!(i), quick) {
+                if !quick {
+                    errors.push(e);
+                }
+            } else {
+                count += 1;
+                if !quick {
+                    matched.push(i);
+                }
+                if self.schema.draft_version >= 2020 {
+                    self.uneval.items.remove(&i);
+                }
+            }
+        }
+        (count, matched, errors)
+    }
+
     fn str_validate(&mut self, str: &'v String) {
         let s = self.schema;
         let mut len = None;
 
         // minLength --
         if let Some(min) = s.min_length {
-            let len = len.get_or_insert_with(|| str.chars().count());
+            let len = len.get_or_insert_with(|| self.options.length_unit().len(str));
             if *len < min {
                 self.add_error(kind!(MinLength, *len, min));
             }
@@ -461,7 +693,7 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
 
         // maxLength --
         if let Some(max) = s.max_length {
-            let len = len.get_or_insert_with(|| str.chars().count());
+            let len = len.get_or_insert_with(|| self.options.length_unit().len(str));
             if *len > max {
                 self.add_error(kind!(MaxLength, *len, max));
             }
@@ -469,8 +701,14 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
 
         // pattern --
         if let Some(regex) = &s.pattern {
-            if !regex.is_match(str) {
-                self.add_error(kind!(Pattern, str.into(), regex.as_str()));
+            match regex.try_match(str) {
+                Ok(true) => {}
+                Ok(false) => self.add_error(kind!(Pattern, str.into(), regex.as_str())),
+                Err(_) => self.add_error(ErrorKind::RegexLimit {
+                    kw: "pattern",
+                    got: str.into(),
+                    want: regex.as_str(),
+                }),
             }
         }
 
@@ -519,49 +757,110 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
 
         // minimum --
         if let Some(min) = &s.minimum {
-            if let (Some(minf), Some(numf)) = (min.as_f64(), num.as_f64()) {
-                if numf < minf {
-                    self.add_error(kind!(Minimum, Cow::Borrowed(num), min));
-                }
+            if num_cmp(num, min) == Some(Ordering::Less) {
+                self.add_error(kind!(Minimum, Cow::Borrowed(num), min));
             }
         }
 
         // maximum --
         if let Some(max) = &s.maximum {
-            if let (Some(maxf), Some(numf)) = (max.as_f64(), num.as_f64()) {
-                if numf > maxf {
-                    self.add_error(kind!(Maximum, Cow::Borrowed(num), max));
-                }
+            if num_cmp(num, max) == Some(Ordering::Greater) {
+                self.add_error(kind!(Maximum, Cow::Borrowed(num), max));
             }
         }
 
         // exclusiveMinimum --
         if let Some(ex_min) = &s.exclusive_minimum {
-            if let (Some(ex_minf), Some(numf)) = (ex_min.as_f64(), num.as_f64()) {
-                if numf <= ex_minf {
-                    self.add_error(kind!(ExclusiveMinimum, Cow::Borrowed(num), ex_min));
-                }
+            if matches!(num_cmp(num, ex_min), Some(Ordering::Less | Ordering::Equal)) {
+                self.add_error(kind!(ExclusiveMinimum, Cow::Borrowed(num), ex_min));
             }
         }
 
         // exclusiveMaximum --
         if let Some(ex_max) = &s.exclusive_maximum {
-            if let (Some(ex_maxf), Some(numf)) = (ex_max.as_f64(), num.as_f64()) {
-                if numf >= ex_maxf {
-                    self.add_error(kind!(ExclusiveMaximum, Cow::Borrowed(num), ex_max));
-                }
+            if matches!(
+                num_cmp(num, ex_max),
+                Some(Ordering::Greater | Ordering::Equal)
+            ) {
+                self.add_error(kind!(ExclusiveMaximum, Cow::Borrowed(num), ex_max));
             }
         }
 
         // multipleOf --
         if let Some(mul) = &s.multiple_of {
-            if let (Some(mulf), Some(numf)) = (mul.as_f64(), num.as_f64()) {
-                if (numf / mulf).fract() != 0.0 {
-                    self.add_error(kind!(MultipleOf, Cow::Borrowed(num), mul));
-                }
+            if !is_multiple_of(num, mul) {
+                self.add_error(kind!(MultipleOf, Cow::Borrowed(num), mul));
+            }
+        }
+    }
+}
+
+// $data validation
+impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
+    fn data_validate(&mut self) {
+        for (kw, data) in &self.schema.data {
+            // keyword is ignored, if $data resolves to nothing
+            let Some(want) = data.resolve(self.root, &self.scope.vloc()) else {
+                continue;
+            };
+            if let Some(kind) = self.data_check(kw, want) {
+                self.add_error(kind);
             }
         }
     }
+
+    fn data_check(&self, kw: &'static str, want: Cow<'v, Value>) -> Option<ErrorKind<'s, 'v>> {
+        let v = self.v;
+        let invalid = |got| Some(ErrorKind::InvalidData { kw, got });
+        let failed = match kw {
+            "const" => !equals(v, &want),
+            "minimum" | "maximum" | "exclusiveMinimum" | "exclusiveMaximum" | "multipleOf" => {
+                let Value::Number(want_num) = want.as_ref() else {
+                    return invalid(want);
+                };
+                if kw == "multipleOf" && num_cmp(want_num, &0.into()) != Some(Ordering::Greater) {
+                    return invalid(want);
+                }
+                let Value::Number(num) = v else {
+                    return None;
+                };
+                let ord = num_cmp(num, want_num);
+                match kw {
+                    "minimum" => ord == Some(Ordering::Less),
+                    "maximum" => ord == Some(Ordering::Greater),
+                    "exclusiveMinimum" => matches!(ord, Some(Ordering::Less | Ordering::Equal)),
+                    "exclusiveMaximum" => matches!(ord, Some(Ordering::Greater | Ordering::Equal)),
+                    _ => !is_multiple_of(num, want_num),
+                }
+            }
+            _ => {
+                let Some(want) = want.as_u64().map(|n| n as usize) else {
+                    return invalid(want);
+                };
+                let got = match (kw, v) {
+                    ("minLength" | "maxLength", Value::String(s)) => {
+                        self.options.length_unit().len(s)
+                    }
+                    ("minItems" | "maxItems", Value::Array(arr)) => arr.len(),
+                    ("minProperties" | "maxProperties", Value::Object(obj)) => obj.len(),
+                    _ => return None,
+                };
+                return match kw {
+                    "minLength" => (got < want).then_some(kind!(MinLength, got, want)),
+                    "maxLength" => (got > want).then_some(kind!(MaxLength, got, want)),
+                    "minItems" => (got < want).then_some(kind!(MinItems, got, want)),
+                    "maxItems" => (got > want).then_some(kind!(MaxItems, got, want)),
+                    "minProperties" => (got < want).then_some(kind!(MinProperties, got, want)),
+                    _ => (got > want).then_some(kind!(MaxProperties, got, want)),
+                };
+            }
+        };
+        failed.then(|| ErrorKind::Data {
+            kw,
+            got: Cow::Borrowed(v),
+            want: Box::new(want.into_owned()),
+        })
+    }
 }
 
 // references validation
@@ -622,6 +921,9 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
         loop {
             let scope_sch = self.schemas.get(scope.sch);
             let base_sch = self.schemas.get(scope_sch.resource);
+            if base_sch.lazy {
+                self.not_compiled(base_sch);
+            }
             if base_sch.recursive_anchor {
                 sch = scope.sch
             }
@@ -639,6 +941,9 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
         loop {
             let scope_sch = self.schemas.get(scope.sch);
             let base_sch = self.schemas.get(scope_sch.resource);
+            if base_sch.lazy {
+                self.not_compiled(base_sch);
+            }
             debug_assert_eq!(base_sch.idx, base_sch.resource);
             if let Some(dsch) = base_sch.dynamic_anchors.get(name) {
                 sch = *dsch
@@ -666,7 +971,10 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
 
         // not --
         if let Some(not) = s.not {
-            if self._validate_self(not, None, true).is_ok() {
+            let num_warnings = self.warnings.as_ref().map_or(0, |w| w.len());
+            let result = self._validate_self(not, None, true);
+            self.truncate_warnings(num_warnings);
+            if result.is_ok() {
                 self.add_error(kind!(Not));
             }
         }
@@ -674,7 +982,7 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
         // allOf --
         if !s.all_of.is_empty() {
             let mut errors = vec![];
-            for sch in &s.all_of {
+            for sch in self.schemas.indexes(s.all_of) {
                 if let Err(e) = self.validate_self(*sch) {
                     errors.push(e);
                     if self.bool_result {
@@ -689,10 +997,11 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
 
         // anyOf --
         if !s.any_of.is_empty() {
+            let quick = self.quick();
             let mut matched = false;
             let mut errors = vec![];
-            for sch in &s.any_of {
-                match self.validate_self(*sch) {
+            for sch in self.schemas.indexes(s.any_of) {
+                match self._validate_self(*sch, None, quick) {
                     Ok(_) => {
                         matched = true;
                         // for uneval, all schemas must be checked
@@ -700,10 +1009,17 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
                             break;
                         }
                     }
-                    Err(e) => errors.push(e),
+                    Err(e) => {
+                        if !quick {
+                            errors.push(e);
+                        }
+                    }
                 }
             }
             if !matched {
+                if quick {
+                    errors = self.errors_of(s.any_of);
+                }
                 self.add_errors(errors, kind!(AnyOf));
             }
         }
@@ -712,9 +1028,10 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
         if !s.one_of.is_empty() {
             let mut matched = None;
             let mut errors = vec![];
-            for (i, sch) in s.one_of.iter().enumerate() {
-                if let Err(e) = self._validate_self(*sch, None, matched.is_some()) {
-                    if matched.is_none() {
+            let quick = self.quick();
+            for (i, sch) in self.schemas.indexes(s.one_of).iter().enumerate() {
+                if let Err(e) = self._validate_self(*sch, None, quick || matched.is_some()) {
+                    if !quick && matched.is_none() {
                         errors.push(e);
                     }
                 } else {
@@ -728,6 +1045,9 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
                 }
             }
             if matched.is_none() {
+                if quick {
+                    errors = self.errors_of(s.one_of);
+                }
                 self.add_errors(errors, ErrorKind::OneOf(None));
             }
         }
@@ -743,6 +1063,17 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
             }
         }
     }
+
+    // errors of subschemas in `list`, which are known to fail.
+    fn errors_of(&mut self, list: IndexRange) -> Vec<ValidationError<'s, 'v>> {
+        let mut errors = vec![];
+        for sch in self.schemas.indexes(list) {
+            if let Err(e) = self.validate_self(*sch) {
+                errors.push(e);
+            }
+        }
+        errors
+    }
 }
 
 // uneval validation
@@ -782,33 +1113,63 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
     }
 }
 
+// custom keywords validation
+impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
+    fn keywords_validate(&mut self, phase: Phase) {
+        let s = self.schema;
+        for k in s.keywords.iter().filter(|k| k.phase == phase) {
+            let annotations = match phase {
+                Phase::Assertion => Annotations::none(),
+                _ => Annotations::new(&self.uneval.props, &self.uneval.items),
+            };
+            let result = match self.options.keyword(k.name) {
+                None => k.keyword.validate(self.v, &annotations),
+                Some(None) => continue,
+                Some(Some(func)) => func(&k.value, self.v, &annotations),
+            };
+            if let Err(err) = result {
+                self.add_error(ErrorKind::Keyword {
+                    keyword: k.name,
+                    err,
+                });
+            }
+        }
+    }
+}
+
 // validation helpers
 impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
-    fn validate_val(
+    fn _validate_val(
         &mut self,
         sch: SchemaIndex,
         v: &'v Value,
         token: InstanceToken<'v>,
+        bool_result: bool,
     ) -> Result<(), ValidationError<'s, 'v>> {
-        if self.vloc.len() == self.scope.vid {
-            self.vloc.push(token);
-        } else {
-            self.vloc[self.scope.vid] = token;
-        }
-        let scope = self.scope.child(sch, None, self.scope.vid + 1);
+        let mut scope = self.scope.child(sch, None, self.scope.vid + 1);
+        scope.token = Some(token);
         let schema = &self.schemas.get(sch);
-        Validator {
+        let num_warnings = self.warnings.as_ref().map_or(0, |w| w.len());
+        let bool_result = (self.bool_result || bool_result) && self.trace.is_none();
+        let result = Validator {
             v,
-            vloc: self.vloc,
+            root: self.root,
             schema,
             schemas: self.schemas,
+            options: self.options,
+            warnings: self.warnings.as_deref_mut(),
+            trace: self.trace.as_deref_mut(),
+            limits: self.limits,
             scope,
             uneval: Uneval::from(v, schema, false),
             errors: vec![],
-            bool_result: self.bool_result,
+            bool_result,
         }
-        .validate()
-        .map(|_| ())
+        .validate();
+        if result.is_err() {
+            self.truncate_warnings(num_warnings);
+        }
+        result.map(|_| ())
     }
 
     fn _validate_self(
@@ -819,27 +1180,131 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
     ) -> Result<(), ValidationError<'s, 'v>> {
         let scope = self.scope.child(sch, ref_kw, self.scope.vid);
         let schema = &self.schemas.get(sch);
+        let num_warnings = self.warnings.as_ref().map_or(0, |w| w.len());
+        // trace needs all keywords evaluated
+        let bool_result = (self.bool_result || bool_result) && self.trace.is_none();
+        let caller_needs = !self.uneval.is_empty();
         let result = Validator {
             v: self.v,
-            vloc: self.vloc,
+            root: self.root,
             schema,
             schemas: self.schemas,
+            options: self.options,
+            warnings: self.warnings.as_deref_mut(),
+            trace: self.trace.as_deref_mut(),
+            limits: self.limits,
             scope,
-            uneval: Uneval::from(self.v, schema, !self.uneval.is_empty()),
+            uneval: Uneval::from(self.v, schema, caller_needs),
             errors: vec![],
-            bool_result: self.bool_result || bool_result,
+            bool_result,
         }
         .validate();
-        if let Ok(reply) = &result {
-            self.uneval.merge(reply);
+        match &result {
+            Ok(reply) => {
+                if caller_needs {
+                    self.uneval.merge(reply, schema);
+                }
+            }
+            Err(_) => self.truncate_warnings(num_warnings),
         }
         result.map(|_| ())
     }
 
+    // discards warnings collected by subschema which failed.
+    fn truncate_warnings(&mut self, len: usize) {
+        if let Some(warnings) = self.warnings.as_deref_mut() {
+            warnings.truncate(len);
+        }
+    }
+
     #[inline(always)]
     fn validate_self(&mut self, sch: SchemaIndex) -> Result<(), ValidationError<'s, 'v>> {
         self._validate_self(sch, None, false)
     }
+
+    #[inline(always)]
+    fn validate_val(
+        &mut self,
+        sch: SchemaIndex,
+        v: &'v Value,
+        token: InstanceToken<'v>,
+    ) -> Result<(), ValidationError<'s, 'v>> {
+        self._validate_val(sch, v, token, false)
+    }
+
+    // tells whether subschemas, which are expected to fail even on valid
+    // instance, can be tried first without building their errors. such
+    // errors are built by trying them again, only if they are reported.
+    fn quick(&self) -> bool {
+        !self.bool_result && self.trace.is_none()
+    }
+}
+
+// trace helpers
+impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
+    fn scope_locs(&self) -> Vec<&'s str> {
+        let mut locs = vec![];
+        let mut scope = Some(&self.scope);
+        while let Some(scp) = scope {
+            locs.push(self.schemas.get(scp.sch).loc.as_str());
+            scope = scp.parent;
+        }
+        locs.reverse();
+        locs
+    }
+
+    fn keyword_outcomes(
+        &self,
+        result: &Result<(), ValidationError<'s, 'v>>,
+    ) -> Vec<(&'static str, KeywordOutcome)> {
+        let s = self.schema;
+        let errors = match result {
+            Ok(_) => &[][..],
+            Err(e) if matches!(e.kind, ErrorKind::Group) => &e.causes[..],
+            Err(e) => std::slice::from_ref(e),
+        };
+
+        // in draft < 2019, $ref overrides sibling keywords
+        let ref_only = s.ref_.is_some() && s.draft_version < 2019;
+        let mut stopped = ref_only;
+        let mut failed = vec![];
+        for e in errors {
+            if e.schema_url == s.loc {
+                stopped |= matches!(
+                    e.kind,
+                    ErrorKind::Type { .. }
+                        | ErrorKind::BsonType { .. }
+                        | ErrorKind::Const { .. }
+                        | ErrorKind::Enum { .. }
+                        | ErrorKind::RefCycle { .. }
+                );
+                failed.extend(e.kind.keyword_path().map(|kp| kp.keyword));
+            } else if let Some(ptr) = e.schema_url.strip_prefix(s.loc.as_str()) {
+                // error reported by subschema as is
+                if let Some(kw) = ptr.strip_prefix('/').and_then(|p| p.split('/').next()) {
+                    failed.push(kw);
+                }
+            }
+        }
+
+        let v_type = Type::of(self.v);
+        self.schemas
+            .keywords(s.idx)
+            .into_iter()
+            .map(|kw| {
+                let outcome = if failed.contains(&kw) {
+                    KeywordOutcome::Invalid
+                } else if (stopped && !(ref_only && kw == "$ref"))
+                    || lint::keyword_type(kw).is_some_and(|t| t != v_type)
+                {
+                    KeywordOutcome::Skipped
+                } else {
+                    KeywordOutcome::Valid
+                };
+                (kw, outcome)
+            })
+            .collect()
+    }
 }
 
 // error helpers
@@ -852,6 +1317,7 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
                 instance_location: InstanceLocation::new(),
                 kind: ErrorKind::Group,
                 causes: vec![],
+                provenance: None,
             };
         }
         ValidationError {
@@ -859,7 +1325,33 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
             instance_location: self.instance_location(),
             kind,
             causes: vec![],
+            provenance: None,
+        }
+    }
+
+    // aborts validation with error of given `kind`. once aborted, remaining
+    // subschemas fail fast, and Schemas::validate reports only this error.
+    fn abort(&self, kind: ErrorKind<'s, 'v>) -> ValidationError<'s, 'v> {
+        let err = ValidationError {
+            schema_url: &self.schema.loc,
+            instance_location: self.instance_location(),
+            kind,
+            causes: vec![],
+            provenance: None,
+        };
+        let mut aborted = self.limits.aborted.borrow_mut();
+        if aborted.is_none() {
+            *aborted = Some(err);
+        }
+        self.error(kind!(Group))
+    }
+
+    // aborts validation, as lazy schema `sch` is needed.
+    fn not_compiled(&self, sch: &'s Schema) -> ValidationError<'s, 'v> {
+        if self.limits.lazy.get().is_none() {
+            self.limits.lazy.set(Some(sch.idx));
         }
+        self.abort(ErrorKind::NotCompiled { url: &sch.loc })
     }
 
     #[inline(always)]
@@ -894,15 +1386,15 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
         loc
     }
 
-    fn find_missing(
+    fn find_missing<S: AsRef<str>>(
         &self,
         obj: &'v Map<String, Value>,
-        required: &'s [String],
+        required: &'s [S],
     ) -> Option<Vec<&'s str>> {
         let mut missing = required
             .iter()
-            .filter(|p| !obj.contains_key(p.as_str()))
-            .map(|p| p.as_str());
+            .map(|p| p.as_ref())
+            .filter(|p| !obj.contains_key(*p));
         if self.bool_result {
             missing.next().map(|_| Vec::new())
         } else {
@@ -915,13 +1407,65 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
         }
     }
 
+    // same as find_missing, but looks up each property of `obj` once,
+    // instead of each name in `required`.
+    fn find_missing_bits(
+        &self,
+        obj: &'v Map<String, Value>,
+        required: &'s [Name],
+        bits: &FastHashMap<Name, usize>,
+    ) -> Option<Vec<&'s str>> {
+        let mut found = vec![0u64; bits.len().div_ceil(64)];
+        let mut count = 0;
+        for pname in obj.keys() {
+            if let Some(&bit) = bits.get(pname.as_str()) {
+                found[bit / 64] |= 1 << (bit % 64);
+                count += 1;
+            }
+        }
+        if count == bits.len() {
+            return None;
+        }
+        if self.bool_result {
+            return Some(Vec::new());
+        }
+        let missing = required
+            .iter()
+            .filter(|p| {
+                let bit = bits[*p];
+                found[bit / 64] & (1 << (bit % 64)) == 0
+            })
+            .map(|p| p.as_ref())
+            .collect();
+        Some(missing)
+    }
+
     fn instance_location(&self) -> InstanceLocation<'v> {
-        let len = self.scope.vid;
-        let mut tokens = Vec::with_capacity(len);
-        for tok in &self.vloc[..len] {
-            tokens.push(tok.clone());
+        InstanceLocation {
+            tokens: self.scope.vloc(),
+        }
+    }
+}
+
+// Limits --
+
+// state shared by all validators of single Schemas::validate call,
+// to enforce limits set in ValidationOptions.
+struct Limits<'s, 'v> {
+    aborted: RefCell<Option<ValidationError<'s, 'v>>>, // error which aborted validation
+    evaluations: Cell<usize>,                          // number of schemas evaluated
+    deadline: Option<Instant>,
+    lazy: Cell<Option<SchemaIndex>>, // lazy schema which aborted validation
+}
+
+impl Limits<'_, '_> {
+    fn new(options: &ValidationOptions) -> Self {
+        Self {
+            aborted: RefCell::new(None),
+            evaluations: Cell::new(0),
+            deadline: options.time_limit().map(|limit| Instant::now() + limit),
+            lazy: Cell::new(None),
         }
-        InstanceLocation { tokens }
     }
 }
 
@@ -929,7 +1473,7 @@ impl<'v, 's, 'd, 'e> Validator<'v, 's, 'd, 'e> {
 
 #[derive(Default)]
 struct Uneval<'v> {
-    props: HashSet<&'v String>,
+    props: HashSet<&'v str>,
     items: HashSet<usize>,
 }
 
@@ -940,17 +1484,23 @@ impl<'v> Uneval<'v> {
 
     fn from(v: &'v Value, sch: &Schema, caller_needs: bool) -> Self {
         let mut uneval = Self::default();
+        // custom keywords after applicators see annotations
+        let keywords_need = sch.keywords.iter().any(|k| k.phase > Phase::Assertion);
         match v {
             Value::Object(obj) => {
                 if !sch.all_props_evaluated
-                    && (caller_needs || sch.unevaluated_properties.is_some())
+                    && ((caller_needs && sch.evaluates_props)
+                        || sch.unevaluated_properties.is_some()
+                        || keywords_need)
                 {
-                    uneval.props = obj.keys().collect();
+                    uneval.props = obj.keys().map(String::as_str).collect();
                 }
             }
             Value::Array(arr) => {
                 if !sch.all_items_evaluated
-                    && (caller_needs || sch.unevaluated_items.is_some())
+                    && ((caller_needs && sch.evaluates_items)
+                        || sch.unevaluated_items.is_some()
+                        || keywords_need)
                     && sch.num_items_evaluated < arr.len()
                 {
                     uneval.items = (sch.num_items_evaluated..arr.len()).collect();
@@ -961,16 +1511,24 @@ impl<'v> Uneval<'v> {
         uneval
     }
 
-    fn merge(&mut self, other: &Uneval) {
-        self.props.retain(|p| other.props.contains(p));
-        self.items.retain(|i| other.items.contains(i));
+    // `other` is result of subschema `sch`, applied in-place.
+    fn merge(&mut self, other: &Uneval, sch: &Schema) {
+        // other is not tracked, if sch evaluates nothing
+        if sch.evaluates_props {
+            self.props.retain(|p| other.props.contains(p));
+        }
+        if sch.evaluates_items {
+            self.items.retain(|i| other.items.contains(i));
+        }
     }
 }
 
 // Scope ---
 
+// scopes live on call stack, linked to their parent, so that
+// validation does not allocate to track them.
 #[derive(Debug)]
-struct Scope<'a> {
+struct Scope<'a, 'v> {
     sch: SchemaIndex,
     // if None, compute from self.sch and self.parent.sh
     // not None only when there is jump i.e $ref, $XXXRef
@@ -978,25 +1536,44 @@ struct Scope<'a> {
     /// unique id of value being validated
     // if two scope validate same value, they will have same vid
     vid: usize,
-    parent: Option<&'a Scope<'a>>,
+    // token of value being validated, in its parent value.
+    // not None only when vid differs from parent
+    token: Option<InstanceToken<'v>>,
+    /// number of schemas in evaluation path
+    depth: usize,
+    parent: Option<&'a Scope<'a, 'v>>,
 }
 
-impl<'a> Scope<'a> {
+impl<'a, 'v> Scope<'a, 'v> {
     fn child<'x>(
         &'x self,
         sch: SchemaIndex,
         ref_kw: Option<&'static str>,
         vid: usize,
-    ) -> Scope<'x> {
+    ) -> Scope<'x, 'v> {
         Scope {
             sch,
             ref_kw,
             vid,
+            token: None,
+            depth: self.depth + 1,
             parent: Some(self),
         }
     }
 
-    fn check_cycle(&self) -> Option<&Scope> {
+    // location of value being validated, from root value.
+    fn vloc(&self) -> Vec<InstanceToken<'v>> {
+        let mut tokens = Vec::with_capacity(self.vid);
+        let mut scope = Some(self);
+        while let Some(scp) = scope {
+            tokens.extend(scp.token.clone());
+            scope = scp.parent;
+        }
+        tokens.reverse();
+        tokens
+    }
+
+    fn check_cycle(&self) -> Option<&Scope<'a, 'v>> {
         let mut scope = self.parent;
         while let Some(scp) = scope {
             if scp.vid != self.vid {
@@ -1106,6 +1683,11 @@ impl<'s, 'v> ErrorKind<'s, 'v> {
                 got: got.into_owned().into(),
                 want,
             },
+            RegexLimit { kw, got, want } => RegexLimit {
+                kw,
+                got: got.into_owned().into(),
+                want,
+            },
             Minimum { got, want } => Minimum {
                 got: Cow::Owned(got.into_owned()),
                 want,
@@ -1126,6 +1708,16 @@ impl<'s, 'v> ErrorKind<'s, 'v> {
                 got: Cow::Owned(got.into_owned()),
                 want,
             },
+            Data { kw, got, want } => Data {
+                kw,
+                got: Cow::Owned(got.into_owned()),
+                want,
+            },
+            InvalidData { kw, got } => InvalidData {
+                kw,
+                got: Cow::Owned(got.into_owned()),
+            },
+            Keyword { keyword, err } => Keyword { keyword, err },
             // #[cfg(not(debug_assertions))]
             // _ => unsafe { std::mem::transmute(self) },
             Group => Group,
@@ -1142,8 +1734,15 @@ impl<'s, 'v> ErrorKind<'s, 'v> {
                 kw_loc1,
                 kw_loc2,
             },
+            MaxDepth { want } => MaxDepth { want },
+            MaxEvaluations { want } => MaxEvaluations { want },
+            TimeLimit { want } => TimeLimit { want },
+            NotCompiled { url } => NotCompiled { url },
             FalseSchema => FalseSchema,
+            ReadOnly => ReadOnly,
+            WriteOnly => WriteOnly,
             Type { got, want } => Type { got, want },
+            BsonType { got, want } => BsonType { got, want },
             Enum { want } => Enum { want },
             Const { want } => Const { want },
             MinProperties { got, want } => MinProperties { got, want },
@@ -0,0 +1,89 @@
+use crate::*;
+
+/// Subschema visited by [`Schemas::walk`].
+#[derive(Debug, Clone)]
+pub struct Subschema<'s> {
+    /// index of the subschema.
+    pub sch_index: SchemaIndex,
+    /// absolute location of the subschema.
+    pub location: &'s str,
+    /// schema through which the subschema is first reached. `None` for root.
+    pub parent: Option<SchemaIndex>,
+    /// keyword path of the subschema in `parent`. `None` for root.
+    pub keyword_path: Option<KeywordPath<'s>>,
+    /// draft used to compile the subschema.
+    pub draft: Draft,
+    /// number of subschemas between root and the subschema.
+    pub depth: usize,
+}
+
+impl Schemas {
+    /**
+    Calls `f` for schema identified by `sch_index`, and every subschema
+    reachable from it.
+
+    Subschemas are visited depth first, in the order of keywords. `$ref`,
+    `$dynamicRef` and `$recursiveRef` are followed to the schemas they
+    resolve to, so subschemas in other documents are visited too. Each
+    subschema is visited only once, even if it is reachable through
+    multiple paths or cycles.
+
+    This is useful to build linters, doc generators and analyzers
+    without re-implementing reference resolution.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let schema = json!({
+        "properties": {"children": {"items": {"$ref": "#"}}}
+    });
+    compiler.add_resource("http://example.com/tree.json", schema)?;
+    let sch_index = compiler.compile("http://example.com/tree.json", &mut schemas)?;
+
+    let mut paths = vec![];
+    schemas.walk(sch_index, |v| {
+        paths.push(v.keyword_path.as_ref().map(ToString::to_string));
+    });
+    assert_eq!(paths, [None, Some("properties/children".to_owned()), Some("items".to_owned())]);
+    # Ok(())
+    # }
+    ```
+
+    # Panics
+
+    Panics if `sch_index` is not generated for this instance.
+    [`Schemas::contains`] can be used too ensure that it does not panic.
+    */
+    pub fn walk<F>(&self, sch_index: SchemaIndex, mut f: F)
+    where
+        F: FnMut(&Subschema),
+    {
+        let mut visited = vec![false; self.list.len()];
+        let mut stack = vec![(sch_index, None, None, 0)];
+        while let Some((sch, parent, keyword_path, depth)) = stack.pop() {
+            if visited[sch.0] {
+                continue;
+            }
+            visited[sch.0] = true;
+            let s = self.get(sch);
+            f(&Subschema {
+                sch_index: sch,
+                location: &s.loc,
+                parent,
+                keyword_path,
+                draft: Draft::from_version(s.draft_version),
+                depth,
+            });
+            let children = s.children(self);
+            for (kp, child) in children.into_iter().rev() {
+                if !visited[child.0] {
+                    stack.push((child, Some(sch), Some(kp), depth + 1));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use std::{error::Error, fmt};
+
+use serde::{
+    de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use serde_json::{Map, Number, Value};
+
+use crate::{compiler::CompileError, util::*, Compiler};
+
+impl Compiler {
+    /**
+    Adds schema resource `loc`, parsing `text` as YAML.
+
+    Mapping keys which are not strings, such as status codes in OpenAPI
+    `responses`, are converted to strings. Files with `.yaml` or `.yml`
+    extension are parsed as YAML too, when loaded from file system.
+
+    ```
+    # use std::error::Error;
+    # use boon::*;
+    # use serde_json::json;
+    # fn main() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource_yaml("schema.yaml", "
+    type: object
+    properties:
+      port:
+        type: integer
+        maximum: 65535
+    ")?;
+    let sch_index = compiler.compile("schema.yaml", &mut schemas)?;
+    assert!(schemas.validate(&json!({"port": 80}), sch_index).is_ok());
+    # Ok(())
+    # }
+    ```
+
+    # Errors
+
+    returns [`CompileError`] if url parsing failed, or `text` is not valid
+    YAML or cannot be represented as json. The error message contains
+    line and column of the offending yaml node.
+    */
+    pub fn add_resource_yaml(&mut self, loc: &str, text: &str) -> Result<(), CompileError> {
+        let uf = UrlFrag::absolute(loc)?;
+        let json = parse(text).map_err(|src| CompileError::LoadUrlError {
+            url: uf.url.to_string(),
+            src,
+        })?;
+        self.roots.loader.add_doc(uf.url, json);
+        Ok(())
+    }
+}
+
+/// parses YAML `text` into json value.
+pub(crate) fn parse(text: &str) -> Result<Value, Box<dyn Error>> {
+    // deserializing directly from yaml, rather than via serde_yaml::Value,
+    // so that errors carry the location of the offending node.
+    let YamlValue(v) = YamlValue::deserialize(serde_yaml::Deserializer::from_str(text))?;
+    Ok(v)
+}
+
+struct YamlValue(Value);
+
+impl<'de> Deserialize<'de> for YamlValue {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        d.deserialize_any(ValueVisitor).map(YamlValue)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("value representable as json")
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_bool<E>(self, b: bool) -> Result<Value, E> {
+        Ok(Value::Bool(b))
+    }
+
+    fn visit_i64<E>(self, n: i64) -> Result<Value, E> {
+        Ok(Value::from(n))
+    }
+
+    fn visit_u64<E>(self, n: u64) -> Result<Value, E> {
+        Ok(Value::from(n))
+    }
+
+    fn visit_f64<E: de::Error>(self, n: f64) -> Result<Value, E> {
+        match Number::from_f64(n) {
+            Some(n) => Ok(Value::Number(n)),
+            None => Err(E::custom(format!("{n} is not supported in json"))),
+        }
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<Value, E> {
+        Ok(Value::String(s.to_owned()))
+    }
+
+    fn visit_string<E>(self, s: String) -> Result<Value, E> {
+        Ok(Value::String(s))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut arr = vec![];
+        while let Some(YamlValue(v)) = seq.next_element()? {
+            arr.push(v);
+        }
+        Ok(Value::Array(arr))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut obj = Map::new();
+        while let Some(key) = map.next_key_seed(KeyVisitor)? {
+            if obj.contains_key(&key) {
+                return Err(de::Error::custom(format!("duplicate key {key:?}")));
+            }
+            let YamlValue(v) = map.next_value()?;
+            obj.insert(key, v);
+        }
+        Ok(Value::Object(obj))
+    }
+}
+
+// converts scalar keys to string, as json allows only string keys.
+struct KeyVisitor;
+
+impl<'de> DeserializeSeed<'de> for KeyVisitor {
+    type Value = String;
+
+    fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<String, D::Error> {
+        d.deserialize_any(self)
+    }
+}
+
+impl Visitor<'_> for KeyVisitor {
+    type Value = String;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("scalar mapping key")
+    }
+
+    fn visit_unit<E>(self) -> Result<String, E> {
+        Ok("null".to_owned())
+    }
+
+    fn visit_bool<E>(self, b: bool) -> Result<String, E> {
+        Ok(b.to_string())
+    }
+
+    fn visit_i64<E>(self, n: i64) -> Result<String, E> {
+        Ok(n.to_string())
+    }
+
+    fn visit_u64<E>(self, n: u64) -> Result<String, E> {
+        Ok(n.to_string())
+    }
+
+    fn visit_f64<E>(self, n: f64) -> Result<String, E> {
+        Ok(n.to_string())
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<String, E> {
+        Ok(s.to_owned())
+    }
+
+    fn visit_string<E>(self, s: String) -> Result<String, E> {
+        Ok(s)
+    }
+}
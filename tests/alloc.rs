@@ -0,0 +1,90 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    error::Error,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use boon::{Compiler, Schemas};
+use serde_json::json;
+
+struct Counting;
+
+static COUNTING: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.load(Ordering::Relaxed) {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+// this is the only test in this file, so that no other thread
+// allocates while counting.
+#[test]
+fn test_valid_instance_no_alloc() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "$defs": {
+            "name": { "type": "string", "minLength": 1, "pattern": "^[a-z]+$" }
+        },
+        "type": "object",
+        "required": ["name", "tags"],
+        "properties": {
+            "name": { "$ref": "#/$defs/name" },
+            "age": { "type": "integer", "minimum": 0 },
+            "tags": {
+                "type": "array",
+                "items": { "$ref": "#/$defs/name" },
+                "contains": { "const": "x" },
+                "maxContains": 2
+            },
+            "id": {
+                "anyOf": [{ "type": "integer" }, { "type": "string" }]
+            },
+            "kind": {
+                "oneOf": [{ "const": "a" }, { "const": "b" }, { "type": "null" }]
+            }
+        },
+        "additionalProperties": false
+    });
+    let instance = json!({
+        "name": "john",
+        "age": 30,
+        "tags": ["a", "x", "b"],
+        "id": "abc",
+        "kind": "b"
+    });
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", schema)?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+    // warm up lazily initialized state, such as regex caches
+    assert!(schemas.validate(&instance, sch_index).is_ok());
+
+    COUNTING.store(true, Ordering::Relaxed);
+    let result = schemas.validate(&instance, sch_index);
+    COUNTING.store(false, Ordering::Relaxed);
+    assert!(result.is_ok());
+    assert_eq!(ALLOCATIONS.load(Ordering::Relaxed), 0);
+
+    // errors are still reported in full
+    let instance = json!({ "name": "john", "tags": ["a"], "id": true });
+    let Err(e) = schemas.validate(&instance, sch_index) else {
+        panic!("validation must fail");
+    };
+    let got = format!("{e:#}");
+    assert!(got.contains("anyOf failed"), "{got}");
+    assert!(got.contains("/contains"), "{got}");
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use std::error::Error;
+
+use boon::{CompileError, Compiler, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_asyncapi2() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "asyncapi.json",
+        json!({
+            "asyncapi": "2.6.0",
+            "info": {"title": "shop", "version": "1.0.0"},
+            "channels": {
+                "orders": {
+                    "publish": {
+                        "message": {
+                            "oneOf": [
+                                {"$ref": "#/components/messages/OrderPlaced"},
+                                {"$ref": "#/components/messages/OrderAvro"}
+                            ]
+                        }
+                    }
+                }
+            },
+            "components": {
+                "messages": {
+                    "OrderPlaced": {
+                        "payload": {
+                            "type": "object",
+                            "required": ["id"],
+                            "properties": {"id": {"type": "integer"}},
+                            "discriminator": "kind",
+                            "examples": [{"id": 1}]
+                        }
+                    },
+                    "OrderAvro": {
+                        "schemaFormat": "application/vnd.apache.avro;version=1.9.0",
+                        "payload": {"type": "record", "fields": []}
+                    }
+                }
+            }
+        }),
+    )?;
+    let payloads = compiler.compile_asyncapi("asyncapi.json", &mut schemas)?;
+    assert_eq!(payloads.len(), 1);
+    assert_eq!(payloads[0].name.as_deref(), Some("OrderPlaced"));
+    assert!(payloads[0]
+        .message
+        .ends_with("asyncapi.json#/components/messages/OrderPlaced"));
+
+    let sch = payloads[0].sch_index;
+    assert!(schemas.validate(&json!({"id": 1}), sch).is_ok());
+    assert!(schemas.validate(&json!({"id": "1"}), sch).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_asyncapi3() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "asyncapi.json",
+        json!({
+            "asyncapi": "3.0.0",
+            "info": {"title": "sensors", "version": "1.0.0"},
+            "channels": {
+                "readings": {
+                    "messages": {
+                        "reading": {
+                            "payload": {
+                                "schemaFormat": "application/schema+json;version=draft-07",
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {"celsius": {"type": "number"}}
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }),
+    )?;
+    let payloads = compiler.compile_asyncapi("asyncapi.json", &mut schemas)?;
+    assert_eq!(payloads.len(), 1);
+    assert_eq!(payloads[0].name.as_deref(), Some("reading"));
+
+    let sch = payloads[0].sch_index;
+    assert!(schemas.validate(&json!({"celsius": 21.5}), sch).is_ok());
+    assert!(schemas.validate(&json!({"celsius": "hot"}), sch).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_not_asyncapi() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("openapi.json", json!({"openapi": "3.1.0"}))?;
+    let result = compiler.compile_asyncapi("openapi.json", &mut schemas);
+    assert!(matches!(result, Err(CompileError::InvalidAsyncApi { .. })));
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use std::{env, error::Error, fs};
+
+use boon::{build_schemas, Compiler, Schemas, SchemeUrlLoader};
+use serde_json::{json, Value};
+
+#[test]
+fn test_build_schemas() -> Result<(), Box<dyn Error>> {
+    let out_dir = env::temp_dir().join("boon-build-schemas");
+    env::set_var("OUT_DIR", &out_dir);
+
+    let mut compiler = Compiler::new();
+    build_schemas(&mut compiler, &["tests/corpus/person.json"])?;
+    let bundle = fs::read_to_string(out_dir.join("boon/person.json"))?;
+    // host paths do not leak
+    assert!(!bundle.contains("file:"), "{bundle}");
+    let bundle = serde_json::from_str::<Value>(&bundle)?;
+    assert_eq!(bundle["$id"], "boon://boon/tests/corpus/person.json");
+
+    // compiles without file access
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.use_loader(Box::new(SchemeUrlLoader::new()));
+    let sch = compiler.compile_value(bundle, &mut schemas)?;
+    assert!(schemas
+        .validate(&json!({"address": {"zip": "x"}}), sch)
+        .is_ok());
+    assert!(schemas
+        .validate(&json!({"address": {"zip": 1}}), sch)
+        .is_err());
+
+    // invalid schema fails
+    let mut compiler = Compiler::new();
+    let result = build_schemas(&mut compiler, &["tests/corpus/invalid.json"]);
+    assert!(result.unwrap_err().to_string().contains("invalid.json"));
+
+    // duplicate file names
+    let mut compiler = Compiler::new();
+    let result = build_schemas(
+        &mut compiler,
+        &["tests/corpus/person.json", "tests/corpus/person.json"],
+    );
+    assert!(result.is_err());
+    Ok(())
+}
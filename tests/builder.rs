@@ -0,0 +1,48 @@
+use std::error::Error;
+
+use boon::{Compiler, Draft, Schemas};
+use serde_json::{json, Value};
+
+#[test]
+fn test_builder() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::builder()
+        .default_draft(Draft::V2019_09)
+        .assert_format(true)
+        .format_assertion("hostname", false)
+        .data_refs(true)
+        .format_fn("even", |v| match v {
+            Value::Number(n) if n.as_u64().is_some_and(|n| n % 2 == 1) => Err("odd")?,
+            _ => Ok(()),
+        })
+        .build();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "properties": {
+                "email": {"format": "email"},
+                "host": {"format": "hostname"},
+                "n": {"format": "even", "maximum": {"$data": "1/max"}}
+            }
+        }),
+    )?;
+    let mut schemas = Schemas::new();
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    assert!(schemas.validate(&json!({"host": "-", "n": 2}), sch).is_ok());
+    assert!(schemas.validate(&json!({"email": "x"}), sch).is_err());
+    assert!(schemas.validate(&json!({"n": 3}), sch).is_err());
+    assert!(schemas.validate(&json!({"n": 4, "max": 2}), sch).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_builder_default() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::builder()
+        .assert_format(true)
+        .assert_format(false)
+        .build();
+    compiler.add_resource("schema.json", json!({"format": "email"}))?;
+    let mut schemas = Schemas::new();
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    assert!(schemas.validate(&json!("x"), sch).is_ok());
+    Ok(())
+}
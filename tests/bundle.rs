@@ -0,0 +1,226 @@
+use std::error::Error;
+
+use boon::{Compiler, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_bundle() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://example.com/schemas/order.json",
+        json!({
+            "properties": {
+                "customer": {"$ref": "customer.json"},
+                "items": {"type": "array", "items": {"$ref": "item.json#/$defs/item"}}
+            },
+            "$defs": {
+                "customer": {"type": "string"}
+            }
+        }),
+    )?;
+    compiler.add_resource(
+        "http://example.com/schemas/customer.json",
+        json!({
+            "required": ["name"],
+            "properties": {
+                "name": {"type": "string"},
+                "address": {"$ref": "common/address.json"}
+            }
+        }),
+    )?;
+    compiler.add_resource(
+        "http://example.com/schemas/item.json",
+        json!({
+            "$id": "http://example.com/v1/item.json",
+            "$defs": {
+                "item": {"required": ["sku"], "properties": {"sku": {"type": "string"}}}
+            }
+        }),
+    )?;
+    compiler.add_resource(
+        "http://example.com/schemas/common/address.json",
+        json!({
+            "required": ["city"],
+            "properties": {"parent": {"$ref": "../order.json"}}
+        }),
+    )?;
+    let bundle = compiler.bundle("http://example.com/schemas/order.json")?;
+
+    let defs = bundle["$defs"].as_object().unwrap();
+    assert!(defs.contains_key("customer"));
+    assert_eq!(
+        defs["customer2"]["$id"],
+        "http://example.com/schemas/customer.json"
+    );
+    assert_eq!(defs["item"]["$id"], "http://example.com/v1/item.json");
+    assert_eq!(
+        defs["address"]["$id"],
+        "http://example.com/schemas/common/address.json"
+    );
+    // ref to document with different $id is rewritten
+    assert_eq!(
+        bundle["properties"]["items"]["items"]["$ref"],
+        "http://example.com/v1/item.json#/$defs/item"
+    );
+    // others are left as is
+    assert_eq!(bundle["properties"]["customer"]["$ref"], "customer.json");
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://example.com/schemas/order.json", bundle)?;
+    let sch = compiler.compile("http://example.com/schemas/order.json", &mut schemas)?;
+    let valid = json!({
+        "customer": {"name": "john", "address": {"city": "x"}},
+        "items": [{"sku": "a"}]
+    });
+    assert!(schemas.validate(&valid, sch).is_ok());
+    for invalid in [
+        json!({"customer": {}}),
+        json!({"customer": {"name": "john", "address": {}}}),
+        json!({"customer": {"name": "john", "address": {"city": "x", "parent": {"items": [{}]}}}}),
+        json!({"items": [{}]}),
+    ] {
+        assert!(schemas.validate(&invalid, sch).is_err(), "{invalid}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_bundle_draft7() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://example.com/a.json",
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "allOf": [{"$ref": "http://json-schema.org/draft-07/schema#"}],
+            "properties": {"b": {"$ref": "b.json"}}
+        }),
+    )?;
+    compiler.add_resource("http://example.com/b.json", json!({"type": "integer"}))?;
+    let bundle = compiler.bundle("http://example.com/a.json")?;
+    assert_eq!(
+        bundle["definitions"]["b"],
+        json!({"$id": "http://example.com/b.json", "type": "integer"})
+    );
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://example.com/a.json", bundle)?;
+    let sch = compiler.compile("http://example.com/a.json", &mut schemas)?;
+    assert!(schemas.validate(&json!({"b": 1}), sch).is_ok());
+    assert!(schemas.validate(&json!({"b": "1"}), sch).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_bundle_no_refs() -> Result<(), Box<dyn Error>> {
+    let schema = json!({"properties": {"a": {"$ref": "#/$defs/a"}}, "$defs": {"a": true}});
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", schema.clone())?;
+    assert_eq!(compiler.bundle("schema.json")?, schema);
+    Ok(())
+}
+
+#[test]
+fn test_dereference() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://example.com/person.json",
+        json!({
+            "$id": "http://example.com/person.json",
+            "properties": {
+                "name": {"$ref": "#/$defs/name", "minLength": 1},
+                "address": {"$ref": "address.json"},
+                "friends": {"type": "array", "items": {"$ref": "#"}}
+            },
+            "$defs": {
+                "name": {"$anchor": "name", "type": "string"}
+            }
+        }),
+    )?;
+    compiler.add_resource(
+        "http://example.com/address.json",
+        json!({
+            "$id": "http://example.com/address.json",
+            "properties": {
+                "city": {"$ref": "#city"},
+                "owner": {"$ref": "person.json"}
+            },
+            "$defs": {
+                "city": {"$anchor": "city", "type": "string"}
+            }
+        }),
+    )?;
+    let deref = compiler.dereference("http://example.com/person.json")?;
+    assert_eq!(
+        deref.schema,
+        json!({
+            "$id": "http://example.com/person.json",
+            "properties": {
+                "name": {"minLength": 1, "allOf": [{"type": "string"}]},
+                "address": {
+                    "properties": {
+                        "city": {"type": "string"},
+                        "owner": {"$ref": "#"}
+                    }
+                },
+                "friends": {"type": "array", "items": {"$ref": "#"}}
+            }
+        })
+    );
+    assert_eq!(
+        deref.cycles,
+        [
+            "/properties/address/properties/owner",
+            "/properties/friends/items"
+        ]
+    );
+
+    // cyclic refs resolve to location of inlined schema
+    let deref = compiler.dereference("http://example.com/address.json")?;
+    assert_eq!(
+        deref.schema["properties"]["owner"]["properties"]["address"],
+        json!({"$ref": "#"})
+    );
+    assert_eq!(
+        deref.schema["properties"]["owner"]["properties"]["friends"]["items"],
+        json!({"$ref": "#/properties/owner"})
+    );
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("deref.json", deref.schema)?;
+    let sch = compiler.compile("deref.json", &mut schemas)?;
+    let valid = json!({"city": "x", "owner": {"name": "a", "friends": [{"address": {}}]}});
+    assert!(schemas.validate(&valid, sch).is_ok());
+    let invalid = json!({"owner": {"friends": [{"address": {"city": 1}}]}});
+    assert!(schemas.validate(&invalid, sch).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_dereference_draft7() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "properties": {
+                "a": {"$ref": "#/definitions/a", "type": "string"}
+            },
+            "definitions": {
+                "a": {"$id": "#a", "type": "integer"}
+            }
+        }),
+    )?;
+    let deref = compiler.dereference("schema.json")?;
+    assert_eq!(
+        deref.schema,
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "properties": {"a": {"type": "integer"}}
+        })
+    );
+    assert!(deref.cycles.is_empty());
+    Ok(())
+}
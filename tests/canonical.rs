@@ -0,0 +1,122 @@
+use std::error::Error;
+
+use boon::{Compiler, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_canonicalize_equivalent() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://example.com/a.json",
+        json!({
+            "$id": "http://example.com/schemas/person.json#",
+            "type": ["object"],
+            "required": ["name", "age"],
+            "properties": {
+                "name": {"$ref": "#/definitions/name", "description": "full name"},
+                "age": {"type": ["number", "integer"], "enum": [1]},
+                "address": {"$ref": "address.json"}
+            },
+            "dependencies": {"age": ["name"], "name": {"minProperties": 1}},
+            "definitions": {
+                "name": {"$id": "name.json", "$ref": "#/$defs/str", "$defs": {"str": {"type": "string"}}}
+            }
+        }),
+    )?;
+    compiler.add_resource(
+        "http://example.com/b.json",
+        json!({
+            "$id": "http://example.com/schemas/person.json",
+            "required": ["age", "name"],
+            "dependentSchemas": {"name": {"minProperties": 1}},
+            "dependentRequired": {"age": ["name"]},
+            "$defs": {
+                "name": {
+                    "$defs": {"str": {"type": "string"}},
+                    "$ref": "http://example.com/schemas/name.json#/$defs/str",
+                    "$id": "name.json"
+                }
+            },
+            "properties": {
+                "address": {"$ref": "http://example.com/schemas/address.json"},
+                "age": {"const": 1, "type": ["integer", "number"]},
+                "name": {"$ref": "person.json#/$defs/name"}
+            },
+            "type": "object"
+        }),
+    )?;
+    let a = compiler.canonicalize("http://example.com/a.json", true)?;
+    let b = compiler.canonicalize("http://example.com/b.json", true)?;
+    assert_eq!(a, b);
+    // relative to the innermost resource
+    assert_eq!(
+        a["properties"]["name"]["$ref"],
+        "http://example.com/schemas/name.json"
+    );
+    assert_eq!(
+        a["$defs"]["name"]["$ref"],
+        "http://example.com/schemas/name.json#/$defs/str"
+    );
+    assert_eq!(a["$id"], "http://example.com/schemas/person.json");
+    assert_eq!(a["required"], json!(["age", "name"]));
+
+    // annotations are kept by default
+    let a = compiler.canonicalize("http://example.com/a.json", false)?;
+    assert_eq!(a["properties"]["name"]["description"], "full name");
+    Ok(())
+}
+
+#[test]
+fn test_canonicalize_draft7() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://example.com/tree.json",
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "tree",
+            "properties": {
+                "kind": {"enum": ["node"]},
+                "title": {"type": "string"},
+                "children": {"items": {"$ref": "#"}},
+                "meta": {"$ref": "#/definitions/meta"}
+            },
+            "dependencies": {"children": ["kind"]},
+            "definitions": {"meta": {"type": "object"}}
+        }),
+    )?;
+    let canonical = compiler.canonicalize("http://example.com/tree.json", true)?;
+    assert_eq!(
+        canonical,
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "properties": {
+                "kind": {"const": "node"},
+                "title": {"type": "string"},
+                "children": {"items": {"$ref": "http://example.com/tree.json"}},
+                "meta": {"$ref": "http://example.com/tree.json#/definitions/meta"}
+            },
+            "dependencies": {"children": ["kind"]},
+            "definitions": {"meta": {"type": "object"}}
+        })
+    );
+
+    // canonical form validates the same
+    let mut schemas = Schemas::new();
+    let old = compiler.compile("http://example.com/tree.json", &mut schemas)?;
+    compiler.add_resource("http://example.com/canonical.json", canonical)?;
+    let new = compiler.compile("http://example.com/canonical.json", &mut schemas)?;
+    for instance in [
+        json!({"kind": "node", "children": [{"kind": "node"}]}),
+        json!({"kind": "leaf"}),
+        json!({"children": []}),
+        json!({"kind": "node", "children": [{"meta": 1}]}),
+        json!({"title": 1}),
+    ] {
+        assert_eq!(
+            schemas.validate(&instance, old).is_ok(),
+            schemas.validate(&instance, new).is_ok(),
+            "for {instance}"
+        );
+    }
+    Ok(())
+}
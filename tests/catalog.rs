@@ -0,0 +1,59 @@
+use std::{env, error::Error, fs};
+
+use boon::{CompileError, Compiler, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_catalog() -> Result<(), Box<dyn Error>> {
+    let dir = env::temp_dir().join("boon-catalog");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("mirror"))?;
+    let person = json!({
+        "properties": {
+            "name": {"$ref": "name.json"},
+            "address": {"$ref": "https://schemas.example.com/address.json"}
+        }
+    });
+    fs::write(dir.join("person.json"), person.to_string())?;
+    let address = json!({"required": ["city"]});
+    fs::write(dir.join("mirror/address.json"), address.to_string())?;
+    let catalog = json!({
+        "uri": {
+            "https://example.com/person.json": "person.json",
+            "https://example.com/name.json": {"type": "string"}
+        },
+        "rewriteUri": {
+            "https://schemas.example.com/": "mirror/"
+        }
+    });
+    fs::write(dir.join("catalog.json"), catalog.to_string())?;
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_catalog(dir.join("catalog.json"))?;
+    let sch = compiler.compile("https://example.com/person.json", &mut schemas)?;
+    assert!(schemas
+        .validate(&json!({"name": "x", "address": {"city": "y"}}), sch)
+        .is_ok());
+    assert!(schemas.validate(&json!({"name": 1}), sch).is_err());
+    assert!(schemas.validate(&json!({"address": {}}), sch).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_catalog_invalid() -> Result<(), Box<dyn Error>> {
+    let dir = env::temp_dir().join("boon-catalog-invalid");
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join("catalog.json"), json!({"uris": {}}).to_string())?;
+
+    let mut compiler = Compiler::new();
+    let result = compiler.add_catalog(dir.join("catalog.json"));
+    let Err(e @ CompileError::LoadUrlError { .. }) = result else {
+        panic!("catalog must be invalid");
+    };
+    assert!(
+        format!("{e:#}").contains("unknown catalog entry uris"),
+        "{e:#}"
+    );
+    Ok(())
+}
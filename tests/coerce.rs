@@ -0,0 +1,85 @@
+use std::error::Error;
+
+use boon::{Compiler, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_coerce_types() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "$ref": "#/$defs/paging",
+            "properties": {
+                "price": {"type": "number"},
+                "active": {"type": "boolean"},
+                "name": {"type": "string"},
+                "parent": {"type": "null"},
+                "ids": {"type": "array", "items": {"type": "integer"}},
+                "flags": {"type": ["boolean", "null"]},
+                "bad": {"type": "integer"}
+            },
+            "$defs": {
+                "paging": {
+                    "properties": {"page": {"type": "integer"}}
+                }
+            }
+        }),
+    )?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+
+    let mut v = json!({
+        "page": "3",
+        "price": "4.5",
+        "active": "false",
+        "name": 42,
+        "parent": "",
+        "ids": "7",
+        "flags": 1,
+        "bad": "4.5"
+    });
+    let mut coerced = schemas.coerce_types(&mut v, sch);
+    coerced.sort();
+    assert_eq!(
+        coerced,
+        ["/active", "/flags", "/ids", "/ids/0", "/name", "/page", "/parent", "/price"]
+    );
+    assert_eq!(
+        v,
+        json!({
+            "page": 3,
+            "price": 4.5,
+            "active": false,
+            "name": "42",
+            "parent": null,
+            "ids": [7],
+            "flags": true,
+            "bad": "4.5"
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn test_validate_coerced() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "properties": {"limit": {"type": "integer", "maximum": 100}}
+        }),
+    )?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+
+    let mut v = json!({"limit": "50"});
+    assert_eq!(
+        schemas.validate_coerced(&mut v, sch).ok(),
+        Some(vec!["/limit".to_owned()])
+    );
+
+    let mut v = json!({"limit": "500"});
+    assert!(schemas.validate_coerced(&mut v, sch).is_err());
+    Ok(())
+}
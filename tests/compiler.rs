@@ -1,6 +1,9 @@
 use std::error::Error;
 
-use boon::{Compiler, Schemas};
+use boon::{
+    CompileError, CompileLimit, CompileLimits, Compiler, Draft, EmailOptions, ErrorKind,
+    HostnameOptions, RegexLimits, Schemas, ValidationOptions,
+};
 use serde_json::json;
 
 #[test]
@@ -52,6 +55,330 @@ fn test_compile_anchor() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_location_index_of() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "$id": "http://example.com/root.json",
+        "properties": {
+            "a": {"$ref": "#foo"},
+            "b": {"$ref": "other.json#/$defs/c"}
+        },
+        "$defs": {
+            "x": {"$anchor": "foo", "type": "number"},
+            "y": {
+                "$id": "other.json",
+                "$defs": {"c": {"$anchor": "bar", "type": "string"}}
+            }
+        }
+    });
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://example.com/schema.json", schema)?;
+    let sch = compiler.compile("http://example.com/schema.json", &mut schemas)?;
+    assert_eq!(schemas.location(sch), "http://example.com/schema.json#");
+
+    let x = schemas.index_of("http://example.com/schema.json#/$defs/x");
+    let Some(x) = x else {
+        panic!("$defs/x must be compiled");
+    };
+    assert_eq!(
+        schemas.location(x),
+        "http://example.com/schema.json#/$defs/x"
+    );
+    for loc in [
+        "http://example.com/root.json#foo",
+        "http://example.com/root.json#/$defs/x",
+    ] {
+        assert_eq!(schemas.index_of(loc), Some(x), "{loc}");
+    }
+
+    let c = schemas.index_of("http://example.com/schema.json#/$defs/y/$defs/c");
+    assert!(c.is_some());
+    for loc in [
+        "http://example.com/other.json#bar",
+        "http://example.com/other.json#/$defs/c",
+    ] {
+        assert_eq!(schemas.index_of(loc), c, "{loc}");
+    }
+
+    // not compiled or not found
+    for loc in [
+        "http://example.com/schema.json#/$defs/z",
+        "http://example.com/root.json#baz",
+        "http://example.com/missing.json",
+    ] {
+        assert_eq!(schemas.index_of(loc), None, "{loc}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_iter_keywords() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "type": "object",
+        "required": ["a"],
+        "properties": {"a": {"$ref": "#/$defs/a"}},
+        "$defs": {
+            "a": {"type": "string", "minLength": 1, "title": "a"},
+            "unused": true
+        }
+    });
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://example.com/schema.json", schema)?;
+    let sch = compiler.compile("http://example.com/schema.json", &mut schemas)?;
+
+    let all = schemas.iter().collect::<Vec<_>>();
+    assert_eq!(all.len(), schemas.size());
+    assert_eq!(all[0], (sch, "http://example.com/schema.json#"));
+    assert!(all.iter().all(|(_, loc)| !loc.ends_with("unused")));
+    for (idx, loc) in all {
+        assert_eq!(schemas.index_of(loc), Some(idx));
+    }
+
+    assert_eq!(schemas.keywords(sch), ["type", "required", "properties"]);
+    let Some(a) = schemas.index_of("http://example.com/schema.json#/$defs/a") else {
+        panic!("$defs/a must be compiled");
+    };
+    assert_eq!(schemas.keywords(a), ["type", "minLength"]);
+    Ok(())
+}
+
+#[test]
+fn test_source() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "properties": {"a": {"$ref": "other.json#/$defs/a"}}
+    });
+    let other = json!({"$defs": {"a": {"type": "string"}}});
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://example.com/schema.json", schema.clone())?;
+    compiler.add_resource("http://example.com/other.json", other.clone())?;
+    assert_eq!(
+        compiler.resource("http://example.com/schema.json"),
+        Some(&schema)
+    );
+    assert_eq!(compiler.resource("http://example.com/missing.json"), None);
+
+    let sch = compiler.compile("http://example.com/schema.json", &mut schemas)?;
+    assert_eq!(schemas.source(sch), Some(&schema));
+    let Some(a) = schemas.index_of("http://example.com/other.json#/$defs/a") else {
+        panic!("$defs/a must be compiled");
+    };
+    assert_eq!(schemas.source(a), Some(&other["$defs"]["a"]));
+    drop(compiler);
+    assert_eq!(schemas.source(a), Some(&json!({"type": "string"})));
+    Ok(())
+}
+
+#[test]
+fn test_replace_resource() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://example.com/schema.json",
+        json!({"properties": {"a": {"$ref": "other.json"}}}),
+    )?;
+    compiler.add_resource("http://example.com/other.json", json!({"type": "string"}))?;
+    compiler.add_resource("http://example.com/unrelated.json", json!({"type": "null"}))?;
+    let old = compiler.compile("http://example.com/schema.json", &mut schemas)?;
+    let unrelated = compiler.compile("http://example.com/unrelated.json", &mut schemas)?;
+
+    compiler.replace_resource("http://example.com/other.json", json!({"type": "number"}))?;
+    assert_eq!(
+        compiler.resource("http://example.com/other.json"),
+        Some(&json!({"type": "number"}))
+    );
+    let stale = schemas.invalidate("http://example.com/other.json");
+    assert_eq!(stale.len(), 3); // schema, a, other
+    assert!(stale.contains(&old));
+    assert!(schemas.is_stale(old));
+    assert!(!schemas.is_stale(unrelated));
+    assert!(schemas.index_of("http://example.com/schema.json").is_none());
+    assert_eq!(schemas.iter().count(), 1);
+
+    let new = compiler.compile("http://example.com/schema.json", &mut schemas)?;
+    assert_ne!(old, new);
+    assert_eq!(
+        compiler.compile("http://example.com/unrelated.json", &mut schemas)?,
+        unrelated
+    );
+    let v = json!({"a": 1});
+    assert!(schemas.validate(&v, new).is_ok());
+    assert!(schemas.validate(&v, old).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_remove_resource() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://example.com/a.json", json!({"type": "string"}))?;
+    compiler.add_resource("http://example.com/b.json", json!({"type": "number"}))?;
+    compiler.compile("http://example.com/a.json", &mut schemas)?;
+
+    assert!(compiler.remove_resource("http://example.com/a.json")?);
+    assert!(!compiler.remove_resource("http://example.com/a.json")?);
+    assert_eq!(compiler.resource("http://example.com/a.json"), None);
+    assert_eq!(
+        compiler.resource("http://example.com/b.json"),
+        Some(&json!({"type": "number"}))
+    );
+    assert_eq!(schemas.invalidate("http://example.com/a.json").len(), 1);
+    assert!(compiler
+        .compile("http://example.com/a.json", &mut schemas)
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_recompile() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://example.com/schema.json",
+        json!({"properties": {"a": {"$ref": "defs.json#/$defs/a"}}}),
+    )?;
+    compiler.add_resource(
+        "http://example.com/defs.json",
+        json!({"$defs": {"a": {"type": "string"}, "b": {"type": "string"}}}),
+    )?;
+    compiler.add_resource("http://example.com/unrelated.json", json!({"type": "null"}))?;
+    let sch = compiler.compile("http://example.com/schema.json", &mut schemas)?;
+    let b = compiler.compile("http://example.com/defs.json#/$defs/b", &mut schemas)?;
+    let unrelated = compiler.compile("http://example.com/unrelated.json", &mut schemas)?;
+
+    compiler.replace_resource(
+        "http://example.com/defs.json",
+        json!({"$defs": {"a": {"type": "number"}}}),
+    )?;
+    let mapping = compiler.recompile("http://example.com/defs.json", &mut schemas)?;
+    assert!(!mapping.contains_key(&unrelated));
+    assert!(!mapping.contains_key(&b)); // no longer exists
+    assert!(schemas.is_stale(b));
+    let new = mapping[&sch];
+    assert!(!schemas.is_stale(new));
+    assert!(schemas.validate(&json!({"a": 1}), new).is_ok());
+    assert!(schemas.validate(&json!({"a": "x"}), new).is_err());
+    assert_eq!(
+        compiler.compile("http://example.com/schema.json", &mut schemas)?,
+        new
+    );
+    Ok(())
+}
+
+#[test]
+fn test_compile_value() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let a = compiler.compile_value(
+        json!({"items": {"$ref": "#/$defs/a"}, "$defs": {"a": {"type": "string"}}}),
+        &mut schemas,
+    )?;
+    let b = compiler.compile_value(json!({"type": "string"}), &mut schemas)?;
+    assert_ne!(a, b);
+    assert!(schemas.location(a).starts_with("urn:boon:value:"));
+    assert!(schemas.validate(&json!(["x"]), a).is_ok());
+    assert!(schemas.validate(&json!([1]), a).is_err());
+
+    // with $id, relative refs resolve to other resources
+    compiler.add_resource("http://example.com/name.json", json!({"type": "string"}))?;
+    let c = compiler.compile_value(
+        json!({"$id": "http://example.com/person.json", "properties": {"name": {"$ref": "name.json"}}}),
+        &mut schemas,
+    )?;
+    assert!(schemas.validate(&json!({"name": 1}), c).is_err());
+
+    // invalid schema
+    assert!(compiler
+        .compile_value(json!({"type": 1}), &mut schemas)
+        .is_err());
+
+    // different compilers, same target
+    let d = Compiler::new().compile_value(json!({"type": "string"}), &mut schemas)?;
+    let e = Compiler::new().compile_value(json!({"type": "integer"}), &mut schemas)?;
+    assert_ne!(d, e);
+    assert!(schemas.validate(&json!(1), e).is_ok());
+    Ok(())
+}
+
+#[test]
+fn test_offline() -> Result<(), Box<dyn Error>> {
+    let path = std::fs::canonicalize("tests/examples/schema.json")?;
+    let file_url = url::Url::from_file_path(path).unwrap();
+    let mut compiler = Compiler::new();
+    compiler.enable_offline();
+    compiler.add_resource(
+        "http://example.com/a.json",
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "properties": {"b": {"$ref": "b.json"}}
+        }),
+    )?;
+    compiler.add_resource("http://example.com/b.json", json!({"type": "string"}))?;
+    compiler.add_resource(
+        "http://example.com/c.json",
+        json!({"$ref": file_url.as_str()}),
+    )?;
+
+    let mut schemas = Schemas::new();
+    compiler.compile("http://example.com/a.json", &mut schemas)?;
+    let Err(CompileError::OfflineLoadError { url }) =
+        compiler.compile("http://example.com/c.json", &mut schemas)
+    else {
+        panic!("file ref must fail in offline mode");
+    };
+    assert_eq!(url, file_url.as_str());
+
+    // without offline mode, it is loaded
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://example.com/c.json",
+        json!({"$ref": file_url.as_str()}),
+    )?;
+    compiler.compile("http://example.com/c.json", &mut schemas)?;
+    Ok(())
+}
+
+#[test]
+fn test_map_url_prefix() -> Result<(), Box<dyn Error>> {
+    let dir = std::fs::canonicalize("tests/corpus")?;
+    let dir_url = url::Url::from_directory_path(dir).unwrap().to_string();
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.map_url_prefix("https://schemas.example.com/", &dir_url)?;
+    let sch = compiler.compile("https://schemas.example.com/person.json", &mut schemas)?;
+    assert_eq!(
+        schemas.location(sch),
+        "https://schemas.example.com/person.json#"
+    );
+    assert!(schemas
+        .index_of("https://schemas.example.com/nested/address.json")
+        .is_some());
+    assert!(schemas
+        .validate(&json!({"address": {"zip": 1}}), sch)
+        .is_err());
+
+    // longest prefix wins
+    let mut compiler = Compiler::new();
+    compiler.map_url_prefix("https://schemas.example.com/", &dir_url)?;
+    compiler.map_url_prefix("https://schemas.example.com/nested/", "file:///missing/")?;
+    let Err(CompileError::LoadUrlError { url, .. }) = compiler.compile(
+        "https://schemas.example.com/person.json",
+        &mut Schemas::new(),
+    ) else {
+        panic!("nested/address.json must fail to load");
+    };
+    assert_eq!(url, "https://schemas.example.com/nested/address.json");
+
+    assert!(compiler.map_url_prefix("relative/", &dir_url).is_err());
+    Ok(())
+}
+
 #[test]
 fn test_compile_nonstd() -> Result<(), Box<dyn Error>> {
     let schema = json!({
@@ -85,3 +412,736 @@ fn test_compile_nonstd() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_unsupported_pattern() -> Result<(), Box<dyn Error>> {
+    // valid ecma regex, but not supported by regex crate
+    let schema = json!({"pattern": "^(?=.*\\d)\\w+$"});
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", schema)?;
+    let result = compiler.compile("schema.json", &mut schemas);
+    assert!(matches!(result, Err(CompileError::InvalidRegex { .. })));
+
+    Ok(())
+}
+
+#[test]
+fn test_shared_patterns() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    for (i, pattern) in ["^a+$", "^a+$", "^(?=a)", "^(?=a)"].iter().enumerate() {
+        let url = format!("schema{i}.json");
+        let schema = json!({
+            "pattern": pattern,
+            "patternProperties": {(*pattern): {"type": "number"}}
+        });
+        compiler.add_resource(&url, schema)?;
+        let result = compiler.compile(&url, &mut schemas);
+        if i < 2 {
+            let sch = result?;
+            assert!(schemas.validate(&json!("aa"), sch).is_ok());
+            assert!(schemas.validate(&json!("ab"), sch).is_err());
+            assert!(schemas.validate(&json!({"aa": "x"}), sch).is_err());
+        } else {
+            assert!(matches!(result, Err(CompileError::InvalidRegex { .. })));
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_regex_limits() -> Result<(), Box<dyn Error>> {
+    let schema = json!({"pattern": "^(a{100}){100}$"});
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", schema)?;
+    compiler.compile("schema.json", &mut schemas)?;
+
+    // cached regex is not reused after limits change
+    let mut schemas = Schemas::new();
+    compiler.set_regex_limits(RegexLimits {
+        size_limit: 1 << 16,
+        ..Default::default()
+    });
+    let result = compiler.compile("schema.json", &mut schemas);
+    assert!(matches!(result, Err(CompileError::InvalidRegex { .. })));
+
+    let mut compiler = Compiler::new();
+    compiler.set_regex_limits(RegexLimits {
+        nest_limit: 2,
+        ..Default::default()
+    });
+    compiler.add_resource("schema.json", json!({"pattern": "((((a)+)+)+)+"}))?;
+    let result = compiler.compile("schema.json", &mut schemas);
+    assert!(matches!(result, Err(CompileError::InvalidRegex { .. })));
+
+    Ok(())
+}
+
+#[test]
+fn test_compile_limits() -> Result<(), Box<dyn Error>> {
+    let limit = |limits| -> Result<_, Box<dyn Error>> {
+        let mut schemas = Schemas::new();
+        let mut compiler = Compiler::new();
+        compiler.set_compile_limits(limits);
+        for i in 0..5 {
+            compiler.add_resource(
+                &format!("http://example.com/s{i}.json"),
+                json!({"$ref": format!("s{}.json", i + 1), "type": "object"}),
+            )?;
+        }
+        compiler.add_resource(
+            "http://example.com/s5.json",
+            json!({"items": {"type": "string"}}),
+        )?;
+        match compiler.compile("http://example.com/s0.json", &mut schemas) {
+            Err(CompileError::LimitExceeded { url, limit }) => Ok(Some((url, limit))),
+            Err(e) => Err(e)?,
+            Ok(_) => Ok(None),
+        }
+    };
+
+    assert_eq!(limit(CompileLimits::default())?, None);
+    assert_eq!(
+        limit(CompileLimits {
+            max_schemas: Some(6),
+            ..Default::default()
+        })?,
+        Some((
+            "http://example.com/s5.json#/items".to_owned(),
+            CompileLimit::Schemas(6)
+        ))
+    );
+    assert_eq!(
+        limit(CompileLimits {
+            max_documents: Some(3),
+            ..Default::default()
+        })?,
+        Some((
+            "http://example.com/s3.json#".to_owned(),
+            CompileLimit::Documents(3)
+        ))
+    );
+    assert_eq!(
+        limit(CompileLimits {
+            max_ref_depth: Some(4),
+            ..Default::default()
+        })?,
+        Some((
+            "http://example.com/s5.json#".to_owned(),
+            CompileLimit::RefDepth(4)
+        ))
+    );
+    // subschema of s5 has same ref depth
+    assert_eq!(
+        limit(CompileLimits {
+            max_schemas: Some(7),
+            max_documents: Some(6),
+            max_ref_depth: Some(5),
+        })?,
+        None
+    );
+    Ok(())
+}
+
+#[test]
+fn test_format_options() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "properties": {
+            "host": {"format": "hostname"},
+            "email": {"format": "email"}
+        }
+    });
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions();
+    compiler.add_resource("schema.json", schema.clone())?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    assert!(schemas
+        .validate(&json!({"host": "example.com."}), sch)
+        .is_ok());
+    assert!(schemas
+        .validate(&json!({"host": "_sip._tcp.example.com"}), sch)
+        .is_err());
+    assert!(schemas
+        .validate(&json!({"email": "\"joe\"@example.com"}), sch)
+        .is_ok());
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions();
+    compiler.set_hostname_options(HostnameOptions {
+        allow_trailing_dot: false,
+        allow_underscore: true,
+        ..Default::default()
+    });
+    compiler.set_email_options(EmailOptions {
+        allow_quoted_local: false,
+        ..Default::default()
+    });
+    compiler.add_resource("schema.json", schema)?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    assert!(schemas
+        .validate(&json!({"host": "example.com."}), sch)
+        .is_err());
+    assert!(schemas
+        .validate(&json!({"host": "_sip._tcp.example.com"}), sch)
+        .is_ok());
+    assert!(schemas
+        .validate(&json!({"email": "\"joe\"@example.com"}), sch)
+        .is_err());
+    assert!(schemas
+        .validate(&json!({"email": "joe@example.com."}), sch)
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_format_error_messages() -> Result<(), Box<dyn Error>> {
+    let tests = [
+        ("date", "2021-02-30", "february has 28 days only"),
+        ("date", "2021-13-01", "13 months in year"),
+        ("date-time", "2021-01-01t25:00:00Z", "invalid time element:"),
+        ("duration", "P1D2Y", "unit Y out of order"),
+        (
+            "uuid",
+            "2eb8aa08-aa98-11ea-b4aa-73b441d1638",
+            "element 5 must be 12",
+        ),
+        ("email", "joe@-example.com", "invalid domain: label starts"),
+        ("hostname", "-example.com", "label starts with hyphen"),
+    ];
+    for (format, instance, want) in tests {
+        let mut schemas = Schemas::new();
+        let mut compiler = Compiler::new();
+        compiler.enable_format_assertions();
+        compiler.add_resource("schema.json", json!({ "format": format }))?;
+        let sch = compiler.compile("schema.json", &mut schemas)?;
+        let instance = json!(instance);
+        let Err(e) = schemas.validate(&instance, sch) else {
+            panic!("{instance} must not be valid {format}");
+        };
+        let got = format!("{e:#}");
+        assert!(got.contains(want), "{format}: {got}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_unknown_refs() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "properties": {
+            "a": {"format": "email"},
+            "b": {"format": "palindrome"},
+            "c": {"contentEncoding": "hex", "contentMediaType": "application/json"}
+        }
+    });
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    assert!(compiler.formats().contains(&"email"));
+    assert!(!compiler.formats().contains(&"palindrome"));
+    assert_eq!(compiler.content_encodings(), ["base64"]);
+    assert_eq!(compiler.content_media_types(), ["application/json"]);
+
+    compiler.add_resource("schema.json", schema)?;
+    compiler.compile("schema.json", &mut schemas)?;
+    let mut unknown = compiler
+        .unknown_refs()
+        .iter()
+        .map(|r| (r.keyword, r.name.as_str()))
+        .collect::<Vec<_>>();
+    unknown.sort();
+    assert_eq!(
+        unknown,
+        [("contentEncoding", "hex"), ("format", "palindrome")]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_take_warnings() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://example.com/schema.json",
+        json!({
+            "properties": {
+                "a": {"$ref": "#used"},
+                "b": {"format": "palindrome"},
+                "c": {"additionalItems": false}
+            },
+            "$defs": {
+                "used": {"$anchor": "used"},
+                "unused": {"$anchor": "unused"},
+                "dynamic": {"$dynamicAnchor": "dynamic"}
+            }
+        }),
+    )?;
+    let sch = compiler.compile("http://example.com/schema.json", &mut schemas)?;
+    let warnings = compiler
+        .take_warnings()
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        warnings,
+        [
+            "unknown format 'palindrome' at http://example.com/schema.json#/properties/b",
+            "keyword 'additionalItems' at http://example.com/schema.json#/properties/c/additionalItems is ignored, as it is not defined in V2020_12",
+            "anchor 'unused' in http://example.com/schema.json is never referenced",
+        ]
+    );
+    assert!(compiler.take_warnings().is_empty());
+
+    // compiling with warnings does not fail
+    assert!(schemas.validate(&json!({"c": [1, 2]}), sch).is_ok());
+    Ok(())
+}
+
+#[test]
+fn test_strict_mode() -> Result<(), Box<dyn Error>> {
+    let compile = |schema: serde_json::Value, setup: fn(&mut Compiler)| {
+        let mut schemas = Schemas::new();
+        let mut compiler = Compiler::new();
+        compiler.enable_strict_mode();
+        setup(&mut compiler);
+        compiler.add_resource("schema.json", schema)?;
+        compiler.compile("schema.json", &mut schemas)
+    };
+
+    let schema = json!({"properties": {"a": {"type": "string", "minLenght": 1}}});
+    let Err(CompileError::UnsupportedKeyword { url, keyword }) = compile(schema.clone(), |_| {})
+    else {
+        panic!("minLenght must be rejected");
+    };
+    assert_eq!(keyword, "minLenght");
+    assert!(
+        url.ends_with("schema.json#/properties/a/minLenght"),
+        "{url}"
+    );
+    assert!(compile(schema, |c| c.allow_keyword("minLenght")).is_ok());
+
+    // keywords of other drafts
+    let schema = json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "$defs": {}
+    });
+    assert!(compile(schema, |_| {}).is_err());
+
+    // vendor keywords of dialect
+    let schema = json!({"x-kubernetes-int-or-string": true, "nullable": true});
+    assert!(compile(schema.clone(), |_| {}).is_err());
+    assert!(compile(schema, |c| c.use_dialect(boon::Dialect::Kubernetes)).is_ok());
+
+    // not strict by default
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", json!({"requird": []}))?;
+    compiler.compile("schema.json", &mut schemas)?;
+    Ok(())
+}
+
+#[test]
+fn test_format_assertion_overrides() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "properties": {
+            "host": {"format": "hostname"},
+            "time": {"format": "date-time"}
+        }
+    });
+    let instance = |host, time| json!({"host": host, "time": time});
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertion("date-time");
+    compiler.add_resource("schema.json", schema.clone())?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    assert!(schemas
+        .validate(&instance("-", "2023-01-01T00:00:00Z"), sch)
+        .is_ok());
+    assert!(schemas.validate(&instance("x", "yesterday"), sch).is_err());
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions();
+    compiler.disable_format_assertion("hostname");
+    compiler.add_resource("schema.json", schema)?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    assert!(schemas
+        .validate(&instance("-", "2023-01-01T00:00:00Z"), sch)
+        .is_ok());
+    assert!(schemas.validate(&instance("x", "yesterday"), sch).is_err());
+
+    Ok(())
+}
+
+#[cfg(feature = "fancy-regex")]
+#[test]
+fn test_fancy_regex() -> Result<(), Box<dyn Error>> {
+    use boon::RegexEngine;
+
+    let schema = json!({
+        "pattern": "^(?=.*\\d)\\w+$",
+        "patternProperties": {"^(\\w)\\1$": {"type": "number"}}
+    });
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.use_regex_engine(RegexEngine::FancyRegex);
+    compiler.add_resource("schema.json", schema)?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    assert!(schemas.validate(&json!("abc1"), sch).is_ok());
+    assert!(schemas.validate(&json!("abc"), sch).is_err());
+    assert!(schemas.validate(&json!({"aa": 1}), sch).is_ok());
+    assert!(schemas.validate(&json!({"aa": "x"}), sch).is_err());
+    assert!(schemas.validate(&json!({"ab": "x"}), sch).is_ok());
+
+    Ok(())
+}
+
+#[cfg(feature = "fancy-regex")]
+#[test]
+fn test_fancy_regex_backtrack_limit() -> Result<(), Box<dyn Error>> {
+    use boon::{RegexEngine, RegexLimits};
+
+    // catastrophic backtracking
+    let schema = json!({"pattern": "^(?=x|.)(a+)+$"});
+    let instance = json!(format!("{}b", "a".repeat(30)));
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.use_regex_engine(RegexEngine::FancyRegex);
+    compiler.set_regex_limits(RegexLimits {
+        backtrack_limit: 10_000,
+        ..Default::default()
+    });
+    compiler.add_resource("schema.json", schema)?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    let Err(e) = schemas.validate(&instance, sch) else {
+        panic!("validation must fail");
+    };
+    let leaf = &e.causes[0];
+    assert!(matches!(
+        leaf.kind,
+        ErrorKind::RegexLimit { kw: "pattern", .. }
+    ));
+    let output = serde_json::to_value(e.basic_output())?;
+    assert_eq!(output["errors"][0]["keywordLocation"], "/pattern");
+    assert!(schemas.validate(&json!("aaa"), sch).is_ok());
+
+    // patternProperties
+    let schema = json!({"patternProperties": {"^(?=x|.)(a+)+$": true}});
+    let mut schemas = Schemas::new();
+    compiler.add_resource("props.json", schema)?;
+    let sch = compiler.compile("props.json", &mut schemas)?;
+    let instance = json!({format!("{}b", "a".repeat(30)): 1});
+    let Err(e) = schemas.validate(&instance, sch) else {
+        panic!("validation must fail");
+    };
+    let leaf = &e.causes[0];
+    assert!(matches!(
+        leaf.kind,
+        ErrorKind::RegexLimit {
+            kw: "patternProperties",
+            ..
+        }
+    ));
+    let output = serde_json::to_value(e.basic_output())?;
+    assert_eq!(
+        output["errors"][0]["keywordLocation"],
+        "/patternProperties/^(?=x|.)(a+)+$"
+    );
+    assert!(
+        format!("{e:#}").contains("exceeded backtrack limit"),
+        "{e:#}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_ref_inlining() -> Result<(), Box<dyn Error>> {
+    let compile = |inline| -> Result<_, Box<dyn Error>> {
+        let mut schemas = Schemas::new();
+        let mut compiler = Compiler::builder().ref_inlining(inline).build();
+        compiler.add_resource(
+            "http://example.com/schema.json",
+            json!({
+                "properties": {
+                    "a": {"$ref": "#/$defs/str"},
+                    "b": {"$ref": "#/$defs/chain"},
+                    "c": {"$ref": "#/$defs/titled"},
+                    "d": {"$ref": "other.json"},
+                    "e": {"$ref": "#/$defs/str", "minLength": 2}
+                },
+                "$defs": {
+                    "str": {"type": "string", "maxLength": 3},
+                    "chain": {"$ref": "#/$defs/str"},
+                    "titled": {"type": "string", "title": "titled"}
+                }
+            }),
+        )?;
+        compiler.add_resource("http://example.com/other.json", json!({"type": "string"}))?;
+        let sch = compiler.compile("http://example.com/schema.json", &mut schemas)?;
+        Ok((schemas, sch))
+    };
+    let (schemas, sch) = compile(true)?;
+
+    let instance = json!({"a": 1, "b": 1, "c": 1, "d": 1, "e": 1});
+    let Err(e) = schemas.validate(&instance, sch) else {
+        panic!("instance should be invalid");
+    };
+    let mut locs = vec![];
+    for cause in &e.causes {
+        let mut leaf = cause;
+        while let Some(c) = leaf.causes.first() {
+            leaf = c;
+        }
+        locs.push(leaf.schema_url);
+    }
+    locs.sort();
+    assert_eq!(
+        locs,
+        [
+            "http://example.com/other.json#",
+            "http://example.com/schema.json#/$defs/str",
+            "http://example.com/schema.json#/$defs/titled",
+            "http://example.com/schema.json#/properties/a",
+            "http://example.com/schema.json#/properties/b",
+        ]
+    );
+
+    // same validation result, with and without inlining
+    let (plain, plain_sch) = compile(false)?;
+    for instance in [
+        json!({"a": "abc", "b": "abc", "e": "abc"}),
+        json!({"a": "abcd"}),
+        json!({"b": "abcd"}),
+        json!({"e": "a"}),
+        json!({"c": "x", "d": "y"}),
+    ] {
+        assert_eq!(
+            schemas.validate(&instance, sch).is_ok(),
+            plain.validate(&instance, plain_sch).is_ok(),
+            "{instance}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_lazy_compilation() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "properties": {
+            "id": {"type": "integer"},
+            "error": {"$ref": "#/$defs/error"}
+        },
+        "anyOf": [{"required": ["id"]}, {"$ref": "#/$defs/cold"}],
+        "$defs": {
+            "error": {"properties": {"code": {"$ref": "#/$defs/code"}}},
+            "code": {"type": "integer", "minimum": 100},
+            "cold": {"required": ["error"]}
+        }
+    });
+    let compile = |lazy| -> Result<_, Box<dyn Error>> {
+        let mut schemas = Schemas::new();
+        let mut compiler = Compiler::builder().lazy_compilation(lazy).build();
+        compiler.add_resource("http://example.com/schema.json", schema.clone())?;
+        let sch = compiler.compile("http://example.com/schema.json", &mut schemas)?;
+        Ok((compiler, schemas, sch))
+    };
+    let (mut compiler, mut schemas, sch) = compile(true)?;
+    let (_, plain, plain_sch) = compile(false)?;
+    let size = schemas.size();
+    assert!(size < plain.size());
+
+    // cold branches are not needed
+    let options = ValidationOptions::default();
+    let instance = json!({"id": 1});
+    assert!(schemas.validate(&instance, sch).is_ok());
+    assert!(compiler
+        .validate_lazily(&instance, sch, &mut schemas, &options)?
+        .is_ok());
+    assert_eq!(schemas.size(), size);
+
+    // cold branch is reached
+    let instance = json!({"error": {"code": 500}});
+    let Err(e) = schemas.validate(&instance, sch) else {
+        panic!("instance must not be validated");
+    };
+    assert!(
+        matches!(e.causes[0].kind, ErrorKind::NotCompiled { .. }),
+        "{e}"
+    );
+    assert!(compiler
+        .validate_lazily(&instance, sch, &mut schemas, &options)?
+        .is_ok());
+    assert!(schemas.size() > size);
+    assert!(schemas.validate(&instance, sch).is_ok());
+
+    // same validation result, with and without lazy compilation
+    for instance in [
+        json!({"id": 1, "error": {"code": 1}}),
+        json!({"error": {"code": 99}}),
+        json!({"error": {}}),
+        json!({"id": 1, "error": {"code": 200}}),
+        json!({}),
+    ] {
+        let want = plain
+            .validate(&instance, plain_sch)
+            .map_err(|e| format!("{e:#}"));
+        let got = compiler
+            .validate_lazily(&instance, sch, &mut schemas, &options)?
+            .map_err(|e| format!("{e:#}"));
+        assert_eq!(got, want, "{instance}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_subschema_dedup() -> Result<(), Box<dyn Error>> {
+    let compile = |dedup| -> Result<_, Box<dyn Error>> {
+        let mut schemas = Schemas::new();
+        let mut compiler = Compiler::builder().subschema_dedup(dedup).build();
+        let name = json!({"type": "string", "minLength": 1});
+        compiler.add_resource(
+            "http://example.com/schema.json",
+            json!({
+                "properties": {
+                    "first": name,
+                    "last": name,
+                    "nick": {"minLength": 1, "type": "string"},
+                    "other": {"$id": "other.json", "properties": {"name": name}},
+                    "tags": {"items": name}
+                }
+            }),
+        )?;
+        let sch = compiler.compile("http://example.com/schema.json", &mut schemas)?;
+        Ok((schemas, sch))
+    };
+    let (schemas, sch) = compile(true)?;
+    let (plain, plain_sch) = compile(false)?;
+    assert_eq!(plain.size() - schemas.size(), 3);
+
+    let instance = json!({"last": "", "other": {"name": ""}});
+    let Err(e) = schemas.validate(&instance, sch) else {
+        panic!("instance should be invalid");
+    };
+    let mut locs = vec![];
+    for cause in &e.causes {
+        let mut leaf = cause;
+        while let Some(c) = leaf.causes.first() {
+            leaf = c;
+        }
+        locs.push(leaf.schema_url);
+    }
+    locs.sort();
+    assert_eq!(
+        locs,
+        [
+            "http://example.com/schema.json#/properties/first",
+            "http://example.com/schema.json#/properties/other/properties/name",
+        ]
+    );
+
+    // same validation result, with and without dedup
+    for instance in [
+        json!({"first": "a", "last": "b", "nick": "c", "tags": ["d"]}),
+        json!({"tags": ["a", ""]}),
+        json!({"nick": 1}),
+        json!({"other": {"name": "x"}}),
+    ] {
+        assert_eq!(
+            schemas.validate(&instance, sch).is_ok(),
+            plain.validate(&instance, plain_sch).is_ok(),
+            "{instance}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_disable_metaschema_validation() -> Result<(), Box<dyn Error>> {
+    let schema = json!({"type": "string", "minLength": "1"});
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://example.com/schema.json", schema.clone())?;
+    let result = compiler.compile("http://example.com/schema.json", &mut Schemas::new());
+    assert!(matches!(result, Err(CompileError::ValidationError { .. })));
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::builder().metaschema_validation(false).build();
+    compiler.add_resource("http://example.com/schema.json", schema)?;
+    let sch = compiler.compile("http://example.com/schema.json", &mut schemas)?;
+    assert!(schemas.validate(&json!(""), sch).is_ok());
+    assert!(schemas.validate(&json!(1), sch).is_err());
+
+    // invalid keywords must not panic
+    let invalid = [
+        json!({"type": 5}),
+        json!({"properties": 1}),
+        json!({"required": "a"}),
+        json!({"items": "x"}),
+        json!({"$ref": 1}),
+        json!({"pattern": 1}),
+        json!({"enum": {}}),
+        json!({"allOf": {}}),
+        json!({"$defs": [], "dependentRequired": {"a": 1}}),
+    ];
+    for (i, schema) in invalid.into_iter().enumerate() {
+        let url = format!("http://example.com/invalid{i}.json");
+        compiler.add_resource(&url, schema)?;
+        if let Ok(sch) = compiler.compile(&url, &mut schemas) {
+            let _ = schemas.validate(&json!({"a": 1}), sch);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_validate_schema() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    assert!(compiler
+        .validate_schema(&json!({"type": "string"}))?
+        .is_ok());
+
+    // draft is detected from $schema
+    let doc = json!({"exclusiveMinimum": true, "minimum": 1});
+    let Err(e) = compiler.validate_schema(&doc)? else {
+        panic!("exclusiveMinimum must be number in 2020-12");
+    };
+    let mut leaf = &e;
+    while let Some(c) = leaf.causes.first() {
+        leaf = c;
+    }
+    assert_eq!(leaf.instance_location.to_string(), "/exclusiveMinimum");
+    let doc = json!({"$schema": "http://json-schema.org/draft-04/schema#", "exclusiveMinimum": true, "minimum": 1});
+    assert!(compiler.validate_schema(&doc)?.is_ok());
+    compiler.set_default_draft(Draft::V4);
+    let doc = json!({"exclusiveMinimum": true, "minimum": 1});
+    assert!(compiler.validate_schema(&doc)?.is_ok());
+
+    // custom metaschema
+    compiler.add_resource(
+        "http://example.com/meta.json",
+        json!({"$schema": "https://json-schema.org/draft/2019-09/schema"}),
+    )?;
+    let doc = json!({"$schema": "http://example.com/meta.json", "$defs": 1});
+    assert!(compiler.validate_schema(&doc)?.is_err());
+    let doc = json!({"$schema": "http://example.com/missing.json"});
+    assert!(compiler.validate_schema(&doc).is_err());
+
+    // $data
+    let doc = json!({"minimum": {"$data": "1/min"}});
+    assert!(compiler.validate_schema(&doc)?.is_err());
+    compiler.enable_data_refs();
+    assert!(compiler.validate_schema(&doc)?.is_ok());
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use std::{error::Error, fs};
+
+use boon::{Compiler, Draft, LintKind, ResourceConflict, Schemas, SchemeUrlLoader};
+use serde_json::{json, Value};
+
+#[test]
+fn test_compile_corpus() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let report = compiler.compile_corpus("tests/corpus", &mut schemas)?;
+    let summary = serde_json::from_str::<Value>(&format!("{report:#}"))?;
+    assert_eq!(summary["valid"], false);
+    assert_eq!(summary["failed"], 2);
+    assert_eq!(summary["drafts"], json!({"2020-12": 1, "7": 1}));
+    assert_eq!(summary["files"][3]["path"], "person.json");
+    assert_eq!(summary["files"][3]["draft"], "7");
+
+    let paths = report
+        .files
+        .iter()
+        .map(|f| f.path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        [
+            "broken-ref.json",
+            "invalid.json",
+            "nested/address.json",
+            "person.json"
+        ]
+    );
+    assert!(!report.is_ok());
+    assert_eq!(
+        report.drafts().into_iter().collect::<Vec<_>>(),
+        [(Draft::V7, 1), (Draft::V2020_12, 1)]
+    );
+
+    let broken = &report.files[0];
+    assert!(broken.error.is_some());
+    assert!(broken
+        .unresolved_ref
+        .as_ref()
+        .unwrap()
+        .ends_with("/missing.json"));
+
+    let invalid = &report.files[1];
+    assert!(invalid.error.is_some());
+    assert_eq!(invalid.unresolved_ref, None);
+    assert!(invalid.lints.is_empty());
+
+    let address = &report.files[2];
+    assert_eq!(address.draft, Some(Draft::V2020_12));
+    assert_eq!(address.unknown_refs[0].name, "zip-code");
+
+    let person = &report.files[3];
+    assert_eq!(person.draft, Some(Draft::V7));
+    assert!(person.unknown_refs.is_empty()); // already compiled
+    assert_eq!(person.lints.len(), 1);
+    assert!(person.lints[0]
+        .url
+        .ends_with("/person.json#/properties/name/minimum"));
+    assert!(matches!(
+        person.lints[0].kind,
+        LintKind::InapplicableKeyword { .. }
+    ));
+    assert!(address.lints.is_empty());
+
+    let json = serde_json::to_value(&report.files[3])?;
+    assert_eq!(json["lints"][0]["url"], json!(person.lints[0].url));
+
+    Ok(())
+}
+
+#[test]
+fn test_add_resources_from_dir() -> Result<(), Box<dyn Error>> {
+    let dir = std::env::temp_dir().join("boon-resources-from-dir");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("sub"))?;
+    let write = |path: &str, v: Value| fs::write(dir.join(path), v.to_string());
+    write(
+        "a.json",
+        json!({"$id": "http://example.com/a.json", "items": {"$ref": "b.json"}}),
+    )?;
+    write(
+        "b.json",
+        json!({"$id": "http://example.com/b.json", "type": "string"}),
+    )?;
+    write(
+        "sub/c.json",
+        json!({"$id": "http://example.com/b.json", "type": "number"}),
+    )?;
+    write("sub/d.json", json!({"$ref": "../b.json"}))?;
+    fs::write(dir.join("notes.txt"), "not json")?;
+
+    let mut compiler = Compiler::new();
+    let conflicts = compiler.add_resources_from_dir(&dir, "**/*.json")?;
+    assert_eq!(
+        conflicts,
+        [ResourceConflict {
+            url: "http://example.com/b.json".into(),
+            paths: vec!["b.json".into(), "sub/c.json".into()],
+        }]
+    );
+
+    let mut schemas = Schemas::new();
+    let sch = compiler.compile("http://example.com/a.json", &mut schemas)?;
+    assert!(schemas.validate(&json!(["x"]), sch).is_ok());
+    assert!(schemas.validate(&json!([1]), sch).is_err());
+
+    // glob matches within segment only
+    let mut compiler = Compiler::new();
+    compiler.use_loader(Box::new(SchemeUrlLoader::new()));
+    assert!(compiler
+        .add_resources_from_dir(&dir, "sub/*.json")?
+        .is_empty());
+    assert!(compiler
+        .compile("http://example.com/b.json", &mut Schemas::new())
+        .is_ok());
+    assert!(compiler
+        .compile("http://example.com/a.json", &mut Schemas::new())
+        .is_err());
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use std::error::Error;
+
+use boon::{Compiler, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_ref_cycles() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://example.com/a.json",
+        json!({
+            "properties": {
+                "b": {"$ref": "b.json"},
+                "self": {"$ref": "#"}
+            }
+        }),
+    )?;
+    compiler.add_resource(
+        "http://example.com/b.json",
+        json!({"allOf": [{"$ref": "c.json"}]}),
+    )?;
+    compiler.add_resource("http://example.com/c.json", json!({"$ref": "b.json"}))?;
+    compiler.compile("http://example.com/a.json", &mut schemas)?;
+
+    let cycles = schemas.ref_cycles();
+    assert_eq!(cycles.len(), 2);
+
+    assert_eq!(
+        cycles[0].schemas,
+        [
+            "http://example.com/b.json#",
+            "http://example.com/b.json#/allOf/0",
+            "http://example.com/c.json#",
+        ]
+    );
+    assert_eq!(cycles[0].keywords, ["allOf/0", "$ref", "$ref"]);
+    assert!(cycles[0].in_place);
+
+    assert_eq!(
+        cycles[1].schemas,
+        [
+            "http://example.com/a.json#",
+            "http://example.com/a.json#/properties/self"
+        ]
+    );
+    assert!(!cycles[1].in_place);
+    assert_eq!(
+        cycles[1].to_string(),
+        "http://example.com/a.json# -[properties/self]-> http://example.com/a.json#/properties/self -[$ref]-> http://example.com/a.json#"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_ref_cycles_none() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "properties": {"a": {"$ref": "#/$defs/a"}, "b": {"$ref": "#/$defs/a"}},
+            "$defs": {"a": {"type": "string"}}
+        }),
+    )?;
+    compiler.compile("schema.json", &mut schemas)?;
+    assert!(schemas.ref_cycles().is_empty());
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use std::error::Error;
+
+use boon::{CompileError, Compiler, Schemas};
+use serde_json::{json, Value};
+
+fn compile(schema: Value) -> Result<(Schemas, boon::SchemaIndex), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_data_refs();
+    compiler.add_resource("schema.json", schema)?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+    Ok((schemas, sch_index))
+}
+
+#[test]
+fn test_data_refs() -> Result<(), Box<dyn Error>> {
+    let (schemas, sch) = compile(json!({
+        "properties": {
+            "max": {"type": "integer"},
+            "items": {
+                "maxItems": {"$data": "/max"},
+                "items": {
+                    "properties": {
+                        "id": {"const": {"$data": "1#"}},
+                        "name": {"maxLength": {"$data": "3/max"}}
+                    }
+                }
+            }
+        }
+    }))?;
+
+    let valid = [
+        json!({"max": 2, "items": [{"id": 0, "name": "ab"}, {"id": 1}]}),
+        json!({"items": [{"id": 0, "name": "abcdef"}]}), // resolves to nothing
+    ];
+    for v in valid {
+        assert!(schemas.validate(&v, sch).is_ok(), "{v} should be valid");
+    }
+
+    let invalid = [
+        (json!({"max": 1, "items": [{"id": 0}, {"id": 1}]}), "/items"),
+        (json!({"items": [{"id": 1}]}), "/items/0/id"),
+        (
+            json!({"max": 2, "items": [{"id": 0, "name": "abc"}]}),
+            "/items/0/name",
+        ),
+        (json!({"max": "2", "items": []}), "/items"), // invalid $data
+    ];
+    for (v, loc) in invalid {
+        let Err(e) = schemas.validate(&v, sch) else {
+            panic!("{v} should be invalid");
+        };
+        let mut leaf = &e;
+        while let Some(cause) = leaf.causes.first() {
+            leaf = cause;
+        }
+        assert_eq!(leaf.instance_location.to_string(), loc, "{v}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_data_refs_numbers() -> Result<(), Box<dyn Error>> {
+    let (schemas, sch) = compile(json!({
+        "properties": {
+            "larger": {"exclusiveMinimum": {"$data": "1/smaller"}},
+            "even": {"multipleOf": {"$data": "/step"}}
+        }
+    }))?;
+    assert!(schemas
+        .validate(&json!({"smaller": 5, "larger": 6}), sch)
+        .is_ok());
+    assert!(schemas
+        .validate(&json!({"smaller": 5, "larger": 5}), sch)
+        .is_err());
+    assert!(schemas
+        .validate(&json!({"step": 2, "even": 4}), sch)
+        .is_ok());
+    assert!(schemas
+        .validate(&json!({"step": 2, "even": 3}), sch)
+        .is_err());
+    assert!(schemas
+        .validate(&json!({"step": 0, "even": 4}), sch)
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_data_refs_disabled() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", json!({"maximum": {"$data": "/max"}}))?;
+    let result = compiler.compile("schema.json", &mut schemas);
+    assert!(matches!(result, Err(CompileError::ValidationError { .. })));
+    Ok(())
+}
+
+#[test]
+fn test_invalid_data_ref() {
+    let Err(err) = compile(json!({"maximum": {"$data": "01/max"}})) else {
+        panic!("should fail to compile");
+    };
+    let err = err.downcast_ref::<CompileError>();
+    assert!(matches!(err, Some(CompileError::InvalidJsonPointer(_))));
+}
@@ -0,0 +1,87 @@
+use std::error::Error;
+
+use boon::{Compiler, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_apply_defaults() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "$ref": "#/$defs/base",
+            "properties": {
+                "server": {
+                    "properties": {
+                        "host": {"default": "localhost"},
+                        "port": {"default": 8080}
+                    }
+                },
+                "users": {
+                    "items": {
+                        "properties": {"admin": {"default": false}}
+                    }
+                },
+                "point": {
+                    "prefixItems": [{}, {"default": 0}, {"default": 0}]
+                },
+                "mode": {"anyOf": [{"default": "x"}]}
+            },
+            "$defs": {
+                "base": {
+                    "properties": {"debug": {"default": false}}
+                }
+            }
+        }),
+    )?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+
+    let mut v = json!({
+        "server": {"port": 80},
+        "users": [{"name": "a"}, {"name": "b", "admin": true}],
+        "point": [1]
+    });
+    let defaulted = schemas.apply_defaults(&mut v, sch);
+    assert_eq!(
+        v,
+        json!({
+            "debug": false,
+            "server": {"host": "localhost", "port": 80},
+            "users": [{"name": "a", "admin": false}, {"name": "b", "admin": true}],
+            "point": [1, 0, 0]
+        })
+    );
+    let mut defaulted = defaulted;
+    defaulted.sort();
+    assert_eq!(
+        defaulted,
+        [
+            "/debug",
+            "/point/1",
+            "/point/2",
+            "/server/host",
+            "/users/0/admin"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_validate_defaults() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "required": ["port"],
+            "properties": {"port": {"type": "integer", "default": "80"}}
+        }),
+    )?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    let mut v = json!({"port": 80});
+    assert_eq!(schemas.validate_defaults(&mut v, sch).ok(), Some(vec![]));
+    let mut v = json!({});
+    assert!(schemas.validate_defaults(&mut v, sch).is_err()); // invalid default
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use std::error::Error;
+
+use boon::{Compiler, Schemas, ValidationOptions, WarningKind};
+use serde_json::json;
+
+#[test]
+fn test_deprecated() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "properties": {
+                "fax": {"$ref": "#/$defs/fax"},
+                "phone": {
+                    "anyOf": [
+                        {"type": "string", "deprecated": true},
+                        {"type": "object"}
+                    ]
+                },
+                "email": {"not": {"deprecated": true, "type": "integer"}},
+                "age": {"type": "integer"}
+            },
+            "$defs": {
+                "fax": {"type": "string", "deprecated": true}
+            }
+        }),
+    )?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    let options = ValidationOptions::new();
+
+    let instance = json!({"fax": "555-0100", "phone": {}, "email": "a@b.com"});
+    let (result, warnings) = schemas.validate_with_warnings(&instance, sch, &options);
+    assert!(result.is_ok());
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::Deprecated);
+    assert_eq!(warnings[0].instance_location.to_string(), "/fax");
+    assert!(warnings[0].schema_url.ends_with("schema.json#/$defs/fax"));
+
+    // warnings are reported, even if validation fails
+    let instance = json!({"phone": "555-0101", "age": "ten"});
+    let (result, warnings) = schemas.validate_with_warnings(&instance, sch, &options);
+    assert!(result.is_err());
+    let locs = warnings
+        .iter()
+        .map(|w| w.instance_location.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(locs, ["/phone"]);
+    Ok(())
+}
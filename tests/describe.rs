@@ -0,0 +1,128 @@
+use std::error::Error;
+
+use boon::{Compiler, Schemas, Type};
+use serde_json::json;
+
+#[test]
+fn test_describe_at() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "properties": {
+                "contact": {
+                    "properties": {"kind": {"enum": ["email", "phone"]}},
+                    "if": {"properties": {"kind": {"const": "email"}}},
+                    "then": {
+                        "properties": {
+                            "value": {"type": "string", "format": "email", "maxLength": 64}
+                        }
+                    },
+                    "else": {
+                        "properties": {
+                            "value": {"type": "string", "pattern": "^[0-9]+$"}
+                        }
+                    },
+                    "allOf": [{
+                        "properties": {"value": {"maxLength": 32, "description": "contact value"}}
+                    }],
+                    "required": ["kind"]
+                }
+            }
+        }),
+    )?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+
+    let instance = json!({"contact": {"kind": "email"}});
+    let d = schemas
+        .describe_at(&instance, sch, "/contact/value")
+        .unwrap();
+    assert_eq!(d.types, [Type::String]);
+    assert_eq!(d.formats, ["email"]);
+    assert!(d.patterns.is_empty());
+    assert_eq!(d.max_length, Some(32));
+    assert_eq!(d.description.as_deref(), Some("contact value"));
+
+    let instance = json!({"contact": {"kind": "phone"}});
+    let d = schemas
+        .describe_at(&instance, sch, "/contact/value")
+        .unwrap();
+    assert!(d.formats.is_empty());
+    assert_eq!(d.patterns, ["^[0-9]+$"]);
+
+    let d = schemas.describe_at(&instance, sch, "/contact").unwrap();
+    assert_eq!(d.required, ["kind"]);
+    let d = schemas
+        .describe_at(&instance, sch, "/contact/kind")
+        .unwrap();
+    assert_eq!(d.values, Some(vec![json!("email"), json!("phone")]));
+
+    assert!(schemas.describe_at(&instance, sch, "contact").is_none());
+    Ok(())
+}
+
+#[test]
+fn test_describe_at_alternatives() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "properties": {
+                "id": {
+                    "type": "number",
+                    "anyOf": [
+                        {"type": "integer", "minimum": 1},
+                        {"type": "string", "maxLength": 8}
+                    ]
+                },
+                "count": {"type": ["number", "null"], "allOf": [{"type": "integer"}]},
+                "never": {"type": "string", "allOf": [{"type": "integer"}]}
+            },
+            "oneOf": [
+                {"properties": {"kind": {"const": "a"}}},
+                {"properties": {"kind": {"const": "b"}}}
+            ]
+        }),
+    )?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+
+    // branches are not combined
+    let d = schemas.describe_at(&json!({}), sch, "/id").unwrap();
+    assert_eq!(d.types, [Type::Number]);
+    assert_eq!(d.minimum, None);
+    assert_eq!(d.max_length, None);
+    assert_eq!(d.alternatives.len(), 2);
+    assert_eq!(d.alternatives[0].types, [Type::Integer]);
+    assert_eq!(d.alternatives[0].minimum, Some(1.into()));
+    assert_eq!(d.alternatives[1].types, [Type::String]);
+    assert_eq!(d.alternatives[1].max_length, Some(8));
+
+    // only branch valid is combined
+    let d = schemas.describe_at(&json!({"id": 5}), sch, "/id").unwrap();
+    assert_eq!(d.types, [Type::Integer]);
+    assert_eq!(d.minimum, Some(1.into()));
+    assert!(d.alternatives.is_empty());
+
+    // branches of parent
+    let d = schemas.describe_at(&json!({}), sch, "/kind").unwrap();
+    assert!(d.schemas.is_empty());
+    let values = d
+        .alternatives
+        .iter()
+        .map(|d| d.values.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(values, [Some(vec![json!("a")]), Some(vec![json!("b")])]);
+
+    // integer is number
+    let d = schemas.describe_at(&json!({}), sch, "/count").unwrap();
+    assert_eq!(d.types, [Type::Integer]);
+    assert!(!d.unsatisfiable);
+
+    let d = schemas.describe_at(&json!({}), sch, "/never").unwrap();
+    assert!(d.types.is_empty());
+    assert!(d.unsatisfiable);
+    Ok(())
+}
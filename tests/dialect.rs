@@ -0,0 +1,106 @@
+use std::error::Error;
+
+use boon::{Compiler, Dialect, Schemas};
+use serde_json::{json, Value};
+
+fn compile(
+    dialect: Dialect,
+    schema: Value,
+) -> Result<(Schemas, boon::SchemaIndex), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.use_dialect(dialect);
+    compiler.add_resource("schema.json", schema)?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+    Ok((schemas, sch_index))
+}
+
+#[test]
+fn test_dialect_names() {
+    for d in Dialect::ALL {
+        assert_eq!(Dialect::from_name(d.name()), Some(d));
+    }
+    assert_eq!(Dialect::from_name("OpenAPI-3.1"), Some(Dialect::OpenApi31));
+    assert_eq!(Dialect::from_name("swagger"), None);
+}
+
+#[test]
+fn test_openapi31() -> Result<(), Box<dyn Error>> {
+    let (schemas, sch) = compile(
+        Dialect::OpenApi31,
+        json!({
+            "$schema": "https://spec.openapis.org/oas/3.1/dialect/base",
+            "discriminator": {"propertyName": "kind"},
+            "properties": {
+                "id": {"type": "integer", "format": "int32"},
+                "email": {"format": "email"}
+            }
+        }),
+    )?;
+    // formats are annotations
+    let v = json!({"id": 4294967296_u64, "email": "x"});
+    assert!(schemas.validate(&v, sch).is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn test_kubernetes() -> Result<(), Box<dyn Error>> {
+    let (schemas, sch) = compile(
+        Dialect::Kubernetes,
+        json!({
+            "properties": {
+                "replicas": {"type": "integer", "format": "int32", "maximum": 10, "exclusiveMaximum": true},
+                "data": {"type": "string", "format": "byte"}
+            }
+        }),
+    )?;
+    assert!(schemas
+        .validate(&json!({"replicas": 9, "data": "aGk="}), sch)
+        .is_ok());
+    assert!(schemas.validate(&json!({"replicas": 10}), sch).is_err());
+    assert!(schemas.validate(&json!({"data": "!"}), sch).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_kubernetes_nullable() -> Result<(), Box<dyn Error>> {
+    let (schemas, sch) = compile(
+        Dialect::Kubernetes,
+        json!({
+            "properties": {
+                "name": {"type": "string", "nullable": true},
+                "id": {"type": "string", "nullable": false},
+                "size": {"type": "integer", "format": "int32", "nullable": true, "enum": [1, 2]}
+            }
+        }),
+    )?;
+    assert!(schemas.validate(&json!({"name": null}), sch).is_ok());
+    assert!(schemas.validate(&json!({"name": "x"}), sch).is_ok());
+    assert!(schemas.validate(&json!({"name": 1}), sch).is_err());
+    assert!(schemas.validate(&json!({"id": null}), sch).is_err());
+    assert!(schemas.validate(&json!({"size": null}), sch).is_err());
+
+    // annotation in other dialects
+    let (schemas, sch) = compile(
+        Dialect::AsyncApi,
+        json!({"type": "string", "nullable": true}),
+    )?;
+    assert!(schemas.validate(&json!(null), sch).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_asyncapi() -> Result<(), Box<dyn Error>> {
+    let (schemas, sch) = compile(
+        Dialect::AsyncApi,
+        json!({"type": "integer", "format": "int64"}),
+    )?;
+    assert!(schemas
+        .validate(&json!(-9223372036854775808_i64), sch)
+        .is_ok());
+    assert!(schemas
+        .validate(&json!(9223372036854775808_u64), sch)
+        .is_err());
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::error::Error;
+
+use boon::{Compiler, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_to_dot() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://example.com/tree.json",
+        json!({
+            "$id": "http://example.com/tree.json",
+            "$dynamicAnchor": "node",
+            "properties": {
+                "children": {"type": "array", "items": {"$dynamicRef": "#node"}},
+                "label": {"$ref": "#/$defs/label"}
+            },
+            "$defs": {"label": {"patternProperties": {"^\"\\w+\"$": true}}}
+        }),
+    )?;
+    compiler.add_resource(
+        "http://example.com/strict-tree.json",
+        json!({
+            "$id": "http://example.com/strict-tree.json",
+            "$dynamicAnchor": "node",
+            "$ref": "tree.json",
+            "unevaluatedProperties": false
+        }),
+    )?;
+    compiler.compile("http://example.com/strict-tree.json", &mut schemas)?;
+
+    let dot = schemas.to_dot();
+    let lines = dot.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "digraph schemas {");
+    assert_eq!(lines.last(), Some(&"}"));
+    assert!(lines.contains(&"    label=\"http://example.com/strict-tree.json\";"));
+    assert!(lines.contains(&"    label=\"http://example.com/tree.json\";"));
+    assert!(lines.contains(&"    s0 [label=\"#\\n$dynamicAnchor: node\", peripheries=2];"));
+    assert!(lines.contains(&"  s0 -> s1 [label=\"$ref\", style=dashed, color=red];"));
+    assert!(lines.contains(&"  s0 -> s2 [label=\"unevaluatedProperties\"];"));
+
+    let edge = |label: &str| {
+        lines
+            .iter()
+            .find(|l| l.contains(&format!("[label=\"{label}\"")))
+            .copied()
+    };
+    // dynamic ref is dashed, to its static target
+    assert!(
+        edge("$dynamicRef").is_some_and(|l| l.ends_with("[label=\"$dynamicRef\", style=dashed];"))
+    );
+    assert!(edge("properties/children").is_some());
+    assert!(edge("properties/label").is_some());
+    // labels are escaped
+    assert!(dot.contains(r#"[label="patternProperties/^\"\\w+\"$"];"#));
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use std::error::Error;
+
+use boon::{Compiler, SchemaIndex, Schemas};
+use serde_json::{json, Value};
+
+fn compile(schemas: &mut Schemas, url: &str, schema: Value) -> Result<SchemaIndex, Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_resource(url, schema)?;
+    Ok(compiler.compile(url, schemas)?)
+}
+
+#[test]
+fn test_equivalent_ignores_annotations() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let a = compile(
+        &mut schemas,
+        "http://a.com/schema.json",
+        json!({
+            "title": "person",
+            "type": ["object", "null"],
+            "required": ["name", "age"],
+            "properties": {"age": {"minimum": 0, "examples": [1]}},
+            "$comment": "v1"
+        }),
+    )?;
+    let b = compile(
+        &mut schemas,
+        "http://b.com/schema.json",
+        json!({
+            "description": "a person",
+            "type": ["null", "object"],
+            "required": ["age", "name"],
+            "properties": {"age": {"minimum": 0.0}},
+            "x-owner": "me"
+        }),
+    )?;
+    assert!(schemas.equivalent(a, &schemas, b));
+    assert!(schemas.equivalent(b, &schemas, a));
+    Ok(())
+}
+
+#[test]
+fn test_equivalent_ref_layout() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let a = compile(
+        &mut schemas,
+        "http://a.com/schema.json",
+        json!({
+            "properties": {
+                "name": {"$ref": "#/$defs/name"},
+                "alias": {"$ref": "#/$defs/alias"}
+            },
+            "$defs": {
+                "alias": {"$ref": "#/$defs/name"},
+                "name": {"type": "string", "maxLength": 10}
+            }
+        }),
+    )?;
+    let b = compile(
+        &mut schemas,
+        "http://b.com/schema.json",
+        json!({
+            "properties": {
+                "name": {"type": "string", "maxLength": 10},
+                "alias": {"type": "string", "maxLength": 10}
+            }
+        }),
+    )?;
+    assert!(schemas.equivalent(a, &schemas, b));
+    Ok(())
+}
+
+#[test]
+fn test_equivalent_recursive() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let a = compile(
+        &mut schemas,
+        "http://a.com/tree.json",
+        json!({
+            "type": "object",
+            "properties": {"children": {"type": "array", "items": {"$ref": "#"}}}
+        }),
+    )?;
+    let b = compile(
+        &mut schemas,
+        "http://b.com/tree.json",
+        json!({
+            "$ref": "#/$defs/node",
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {"children": {"type": "array", "items": {"$ref": "#/$defs/node"}}}
+                }
+            }
+        }),
+    )?;
+    assert!(schemas.equivalent(a, &schemas, b));
+
+    let c = compile(
+        &mut schemas,
+        "http://c.com/tree.json",
+        json!({
+            "type": "object",
+            "properties": {"children": {"type": "array", "items": {"$ref": "#", "minProperties": 1}}}
+        }),
+    )?;
+    assert!(!schemas.equivalent(a, &schemas, c));
+    Ok(())
+}
+
+#[test]
+fn test_not_equivalent() -> Result<(), Box<dyn Error>> {
+    let base = json!({
+        "type": "object",
+        "properties": {"age": {"type": "integer", "minimum": 0}},
+        "enum": [{"age": 1}, {"age": 2}]
+    });
+    let changes = [
+        json!({"type": "array"}),
+        json!({"properties": {"age": {"type": "integer", "minimum": 1}}}),
+        json!({"properties": {"years": {"type": "integer", "minimum": 0}}}),
+        json!({"enum": [{"age": 1}]}),
+        json!({"required": ["age"]}),
+        json!({"additionalProperties": false}),
+    ];
+    for change in changes {
+        let mut other = base.clone();
+        for (k, v) in change.as_object().unwrap() {
+            other[k] = v.clone();
+        }
+        let mut schemas = Schemas::new();
+        let a = compile(&mut schemas, "http://a.com/schema.json", base.clone())?;
+        let b = compile(&mut schemas, "http://b.com/schema.json", other)?;
+        assert!(!schemas.equivalent(a, &schemas, b), "{change}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_equivalent_across_schemas() -> Result<(), Box<dyn Error>> {
+    let mut schemas1 = Schemas::new();
+    let a = compile(
+        &mut schemas1,
+        "http://a.com/schema.json",
+        json!({"enum": ["b", "a", 1]}),
+    )?;
+    let mut schemas2 = Schemas::new();
+    compile(
+        &mut schemas2,
+        "http://x.com/schema.json",
+        json!({"type": "null"}),
+    )?;
+    let b = compile(
+        &mut schemas2,
+        "http://b.com/schema.json",
+        json!({"enum": [1, "a", "b"]}),
+    )?;
+    assert!(schemas1.equivalent(a, &schemas2, b));
+    assert!(schemas2.equivalent(b, &schemas1, a));
+    Ok(())
+}
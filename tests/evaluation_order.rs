@@ -0,0 +1,152 @@
+use std::{
+    error::Error,
+    sync::{Arc, Mutex},
+};
+
+use boon::{Annotations, CompileError, Compiler, Keyword, Phase, Schemas};
+use serde_json::{json, Value};
+
+#[test]
+fn test_evaluation_order() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "$defs": { "named": { "properties": { "name": { "type": "string" } } } },
+        "unevaluatedProperties": false,
+        "allOf": [{ "properties": { "age": { "minimum": 0 } } }],
+        "$ref": "#/$defs/named",
+        "required": ["id"],
+        "properties": { "id": true },
+        "patternProperties": { "^x-": true },
+        "additionalProperties": { "type": "integer" }
+    });
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", schema)?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+    // additionalProperties applies to name, age and a, as
+    // they are not matched by properties and patternProperties
+    let instance = json!({"name": 1, "age": -1, "x-y": "z", "a": 0.5});
+    let Err(e) = schemas.validate(&instance, sch_index) else {
+        panic!("validation must fail");
+    };
+    let got = e
+        .causes
+        .iter()
+        .map(|e| {
+            let kw = e.kind.keyword_path().map(|p| p.to_string());
+            (e.instance_location.to_string(), kw.unwrap_or_default())
+        })
+        .collect::<Vec<_>>();
+    let want = [
+        ("", "$ref"),
+        ("", "required"),
+        ("/a", "type"),
+        ("/age", "minimum"),
+    ];
+    let want = want.map(|(loc, kw)| (loc.to_owned(), kw.to_owned()));
+    assert_eq!(got, want);
+    Ok(())
+}
+
+// records properties evaluated, when it is evaluated
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+impl Keyword for Recorder {
+    fn validate(&self, v: &Value, annotations: &Annotations) -> Result<(), Box<dyn Error>> {
+        if let Value::Object(obj) = v {
+            let mut evaluated = obj
+                .keys()
+                .filter(|p| annotations.is_property_evaluated(p))
+                .cloned()
+                .collect::<Vec<_>>();
+            evaluated.sort();
+            self.0.lock().unwrap().push(evaluated.join(","));
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_custom_keyword_phases() -> Result<(), Box<dyn Error>> {
+    let log = Arc::new(Mutex::new(vec![]));
+    let mut compiler = Compiler::new();
+    for (name, phase) in [
+        ("x-assertion", Phase::Assertion),
+        ("x-before", Phase::BeforeUnevaluated),
+        ("x-last", Phase::Last),
+    ] {
+        let log = log.clone();
+        compiler.register_keyword(name, phase, move |_| Ok(Box::new(Recorder(log.clone()))));
+    }
+    let schema = json!({
+        "x-last": true,
+        "x-before": true,
+        "x-assertion": true,
+        "$ref": "#/$defs/named",
+        "properties": { "id": true },
+        "allOf": [{ "properties": { "age": true } }],
+        "unevaluatedProperties": true,
+        "$defs": { "named": { "properties": { "name": true } } }
+    });
+    let mut schemas = Schemas::new();
+    let sch_index = compiler.compile_value(schema, &mut schemas)?;
+    let instance = json!({"id": 1, "name": "x", "age": 2, "extra": 3});
+    assert!(schemas.validate(&instance, sch_index).is_ok());
+    assert_eq!(
+        *log.lock().unwrap(),
+        ["", "age,id,name", "age,extra,id,name"]
+    );
+    Ok(())
+}
+
+struct Fail;
+
+impl Keyword for Fail {
+    fn validate(&self, _v: &Value, _annotations: &Annotations) -> Result<(), Box<dyn Error>> {
+        Err("always fails")?
+    }
+}
+
+#[test]
+fn test_custom_keyword_errors() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.enable_strict_mode();
+    compiler.register_keyword("x-first", Phase::Assertion, |_| Ok(Box::new(Fail)));
+    compiler.register_keyword("x-last", Phase::Last, |v| match v {
+        Value::Bool(_) => Ok(Box::new(Fail)),
+        _ => Err("must be boolean")?,
+    });
+    assert_eq!(compiler.custom_keywords(), ["x-first", "x-last"]);
+
+    let mut schemas = Schemas::new();
+    let schema = json!({"x-last": true, "x-first": 1, "required": ["a"]});
+    let sch_index = compiler.compile_value(schema, &mut schemas)?;
+    let instance = json!({});
+    let Err(e) = schemas.validate(&instance, sch_index) else {
+        panic!("validation must fail");
+    };
+    let got = e
+        .causes
+        .iter()
+        .map(|e| e.kind.keyword_path().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(got, ["x-first", "required", "x-last"]);
+    assert_eq!(e.causes[0].kind.to_string(), "x-first failed: always fails");
+
+    // invalid value
+    let Err(e) = compiler.compile_value(json!({"x-last": 1}), &mut schemas) else {
+        panic!("compilation must fail");
+    };
+    assert!(matches!(e, CompileError::InvalidKeyword { keyword, .. } if keyword == "x-last"));
+
+    // siblings of $ref are ignored before 2019-09
+    let schema = json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "x-first": true,
+        "$ref": "#/definitions/any",
+        "definitions": {"any": true}
+    });
+    let sch_index = compiler.compile_value(schema, &mut schemas)?;
+    assert!(schemas.validate(&json!({}), sch_index).is_ok());
+    Ok(())
+}
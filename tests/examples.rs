@@ -1,4 +1,4 @@
-use std::{error::Error, fs::File};
+use std::{collections::HashSet, error::Error, fs::File};
 
 use boon::{Compiler, Decoder, FileLoader, Format, MediaType, Schemas, SchemeUrlLoader, UrlLoader};
 use serde::de::IgnoredAny;
@@ -154,6 +154,34 @@ fn example_custom_format() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn example_custom_format_fn() -> Result<(), Box<dyn Error>> {
+    let schema_url = "http://tmp/schema.json";
+    let schema: Value = json!({"type": "string", "format": "country"});
+
+    // loaded at runtime
+    let countries: HashSet<String> = ["IN".to_owned(), "US".to_owned()].into();
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions(); // in draft2020-12 format assertions are not enabled by default
+    compiler.register_format_fn("country", move |v| {
+        let Value::String(s) = v else {
+            return Ok(()); // applicable only on strings
+        };
+        if !countries.contains(s) {
+            Err(format!("unknown country {s:?}"))?;
+        }
+        Ok(())
+    });
+    compiler.add_resource(schema_url, schema)?;
+    let sch_index = compiler.compile(schema_url, &mut schemas)?;
+    assert!(schemas.validate(&json!("IN"), sch_index).is_ok());
+    assert!(schemas.validate(&json!("XX"), sch_index).is_err());
+
+    Ok(())
+}
+
 #[test]
 fn example_custom_content_encoding() -> Result<(), Box<dyn Error>> {
     let schema_url = "http://tmp/schema.json";
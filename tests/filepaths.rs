@@ -1,6 +1,6 @@
 use std::fs;
 
-use boon::{CompileError, Compiler, Schemas};
+use boon::{CompileError, Compiler, Schemas, SchemeUrlLoader};
 
 fn test(path: &str) -> Result<(), CompileError> {
     let mut schemas = Schemas::new();
@@ -42,3 +42,39 @@ fn test_relative_slash_space() -> Result<(), CompileError> {
 fn test_relative_backslash_space() -> Result<(), CompileError> {
     test("tests\\examples\\sample schema.json")
 }
+
+#[test]
+fn test_compile_file() -> Result<(), CompileError> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let sch = compiler.compile_file("tests/corpus/person.json", &mut schemas)?;
+    let invalid = serde_json::json!({"address": {"zip": 1}});
+    assert!(schemas.validate(&invalid, sch).is_err());
+
+    // compiled only once
+    let path = fs::canonicalize("tests/corpus/person.json").unwrap();
+    assert_eq!(compiler.compile_file(path, &mut schemas)?, sch);
+    Ok(())
+}
+
+#[test]
+fn test_compile_file_special_chars() -> Result<(), CompileError> {
+    let dir = std::env::temp_dir().join("boon #50%");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("schema.json");
+    fs::copy("tests/examples/schema.json", &path).unwrap();
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.use_loader(Box::new(SchemeUrlLoader::new()));
+    compiler.compile_file(&path, &mut schemas)?;
+    Ok(())
+}
+
+#[test]
+fn test_compile_file_missing() {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let result = compiler.compile_file("tests/examples/missing.json", &mut schemas);
+    assert!(matches!(result, Err(CompileError::LoadUrlError { .. })));
+}
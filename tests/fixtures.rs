@@ -0,0 +1,76 @@
+use std::error::Error;
+
+use boon::{replay_fixtures, Compiler, FixtureRecorder, Redaction, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_record_and_replay() -> Result<(), Box<dyn Error>> {
+    let url = "http://tmp.com/user.json";
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "email": { "type": "string" },
+            "age": { "type": "integer", "minimum": 18 }
+        },
+        "required": ["email"]
+    });
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(url, schema)?;
+    let sch = compiler.compile(url, &mut schemas)?;
+
+    let mut recorder = FixtureRecorder::new();
+    recorder.add_redaction(Redaction::Property {
+        name: "email".into(),
+        with: json!("user@example.com"),
+    });
+    let data = json!({"email": "a@b.com", "age": 20});
+    assert!(recorder.validate(&schemas, &data, sch).is_ok());
+    let data = json!({"email": "john@secret.com", "age": 10});
+    assert!(recorder.validate(&schemas, &data, sch).is_err());
+    assert!(recorder.validate(&schemas, &data, sch).is_err()); // duplicate
+    assert_eq!(recorder.len(), 1);
+
+    let corpus = recorder.corpus();
+    assert_eq!(
+        corpus[0]["tests"][0]["data"],
+        json!({"email": "user@example.com", "age": 10})
+    );
+
+    // replay against same schema
+    let regressions = replay_fixtures(&corpus, &mut compiler, &mut schemas)?;
+    assert!(regressions.is_empty());
+
+    // replay against relaxed schema
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(url, json!({"type": "object"}))?;
+    let regressions = replay_fixtures(&corpus, &mut compiler, &mut schemas)?;
+    assert_eq!(regressions.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_redact_pointer() -> Result<(), Box<dyn Error>> {
+    let url = "http://tmp.com/users.json";
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(url, json!({"maxItems": 1}))?;
+    let sch = compiler.compile(url, &mut schemas)?;
+
+    let mut recorder = FixtureRecorder::new();
+    recorder.add_redaction(Redaction::Pointer {
+        ptr: "/*/name".into(),
+        with: json!("x"),
+    });
+    let data = json!([{"name": "john"}, {"name": "jane"}]);
+    assert!(recorder.validate(&schemas, &data, sch).is_err());
+    assert_eq!(
+        recorder.corpus()[0]["tests"][0]["data"],
+        json!([{"name": "x"}, {"name": "x"}])
+    );
+
+    Ok(())
+}
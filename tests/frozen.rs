@@ -0,0 +1,36 @@
+use std::error::Error;
+
+use boon::{CompiledSchemas, Compiler, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_freeze() -> Result<(), Box<dyn Error>> {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<CompiledSchemas>();
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", json!({"type": "integer"}))?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    let schemas = schemas.freeze();
+    assert!(schemas.contains(sch));
+    assert!(schemas.validate(&json!(1), sch).is_ok());
+
+    // unfreeze fails while shared
+    let shared = schemas.clone();
+    let Err(schemas) = schemas.try_unfreeze() else {
+        panic!("try_unfreeze must fail while shared");
+    };
+    drop(shared);
+
+    // compile more after unfreeze
+    let Ok(mut schemas) = schemas.try_unfreeze() else {
+        panic!("try_unfreeze must succeed");
+    };
+    compiler.add_resource("schema2.json", json!({"type": "string"}))?;
+    let sch2 = compiler.compile("schema2.json", &mut schemas)?;
+    let schemas = schemas.freeze();
+    assert!(schemas.validate(&json!(1), sch).is_ok());
+    assert!(schemas.validate(&json!(1), sch2).is_err());
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use std::error::Error;
+
+use boon::{generate_instance, Compiler, Schemas};
+use serde_json::{json, Value};
+
+#[test]
+fn test_generate_instance() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "type": "object",
+            "required": ["id", "kind", "email", "sku", "price", "tags", "owner"],
+            "properties": {
+                "id": {"type": "integer", "minimum": 10, "maximum": 20},
+                "kind": {"enum": ["a", "b"]},
+                "version": {"const": 2},
+                "email": {"type": "string", "format": "email"},
+                "sku": {"type": "string", "pattern": "^[A-Z]{3}-[0-9]{2,4}$"},
+                "price": {"type": "number", "exclusiveMinimum": 0, "multipleOf": 0.5},
+                "tags": {"type": "array", "items": {"type": "string", "minLength": 2}, "minItems": 2},
+                "owner": {"$ref": "#/$defs/owner"},
+                "choice": {"oneOf": [{"type": "boolean"}, {"type": "null"}]}
+            },
+            "additionalProperties": false,
+            "$defs": {
+                "owner": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": {"name": {"type": "string", "maxLength": 5}}
+                }
+            }
+        }),
+    )?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+
+    for seed in 0..20 {
+        let Some(v) = generate_instance(&schemas, sch, seed) else {
+            panic!("no instance generated for seed {seed}");
+        };
+        assert!(schemas.validate(&v, sch).is_ok(), "{v}");
+        let Value::String(sku) = &v["sku"] else {
+            panic!("sku must be string");
+        };
+        assert!(sku.len() >= 6 && sku.len() <= 8, "{sku}");
+        assert_eq!(v["email"], "user@example.com");
+    }
+
+    // deterministic for given seed
+    assert_eq!(
+        generate_instance(&schemas, sch, 7),
+        generate_instance(&schemas, sch, 7)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_generate_instance_examples() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "type": "string",
+            "minLength": 3,
+            "examples": ["x", "hello"]
+        }),
+    )?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    assert_eq!(generate_instance(&schemas, sch, 1), Some(json!("hello")));
+    Ok(())
+}
+
+#[test]
+fn test_generate_instance_unsatisfiable() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({"allOf": [{"type": "string"}, {"type": "integer"}]}),
+    )?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    assert_eq!(generate_instance(&schemas, sch, 1), None);
+
+    // integer is number
+    let schema = json!({"type": ["number", "boolean"], "allOf": [{"type": ["integer", "null"]}]});
+    let sch = compiler.compile_value(schema, &mut schemas)?;
+    for seed in 0..10 {
+        let v = generate_instance(&schemas, sch, seed).unwrap();
+        assert!(v.is_i64() || v.is_u64(), "{v}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_generate_instance_too_deep_or_large() -> Result<(), Box<dyn Error>> {
+    for schema in [
+        json!({"type": "object", "required": ["next"], "properties": {"next": {"$ref": "#"}}}),
+        json!({"type": "object", "required": ["a", "b"], "properties": {"a": {"$ref": "#"}, "b": {"$ref": "#"}}}),
+        json!({"type": "array", "minItems": 1, "items": {"$ref": "#"}}),
+        json!({"type": "array", "minItems": 1_000_000_000}),
+        json!({"type": "string", "minLength": 1_000_000_000}),
+        json!({"type": "object", "minProperties": 1_000_000_000}),
+    ] {
+        let mut schemas = Schemas::new();
+        let mut compiler = Compiler::new();
+        compiler.add_resource("schema.json", schema.clone())?;
+        let sch = compiler.compile("schema.json", &mut schemas)?;
+        assert_eq!(generate_instance(&schemas, sch, 1), None, "{schema}");
+    }
+    Ok(())
+}
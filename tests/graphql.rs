@@ -0,0 +1,119 @@
+use std::error::Error;
+
+use boon::{export_graphql, Compiler, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_export_graphql() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "name": { "type": "string", "minLength": 1 },
+            "age": { "type": ["integer", "null"] },
+            "color": { "enum": ["RED", "GREEN"] },
+            "tags": { "type": "array", "items": { "type": "string" } },
+            "address": { "$ref": "#/$defs/address" },
+            "pet": { "oneOf": [{ "$ref": "#/$defs/cat" }, { "$ref": "#/$defs/dog" }] }
+        },
+        "required": ["name", "age", "tags"],
+        "$defs": {
+            "address": {
+                "type": "object",
+                "properties": { "street-name": { "type": "string" } }
+            },
+            "cat": { "type": "object", "properties": { "lives": { "type": "integer" } } },
+            "dog": { "type": "object", "properties": { "breed": { "type": "string" } } }
+        }
+    });
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("person.json", schema)?;
+    let sch_index = compiler.compile("person.json", &mut schemas)?;
+    let export = export_graphql(&schemas, &[("Person", sch_index)]);
+
+    let want = "\
+enum PersonColor {
+  RED
+  GREEN
+}
+
+union PersonPet = Cat | Dog
+
+type Person {
+  address: Address
+  age: Int
+  color: PersonColor
+  name: String!
+  pet: PersonPet
+  tags: [String!]!
+}
+
+type Address {
+  street_name: String
+}
+
+type Cat {
+  lives: Int
+}
+
+type Dog {
+  breed: String
+}";
+    assert_eq!(export.sdl, want);
+
+    let mut kws = export
+        .unrepresentable
+        .iter()
+        .map(|u| u.keyword)
+        .collect::<Vec<_>>();
+    kws.sort();
+    assert_eq!(kws, ["minLength", "properties"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_export_graphql_cycles() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "first-name": { "type": "string" },
+            "first_name": { "type": "string" },
+            "loop": { "$ref": "#/$defs/a" },
+            "nested": { "$ref": "#/$defs/nested" }
+        },
+        "$defs": {
+            "a": { "$ref": "#/$defs/b" },
+            "b": { "$ref": "#/$defs/a" },
+            "nested": { "type": "array", "items": { "$ref": "#/$defs/nested" } }
+        }
+    });
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("person.json", schema)?;
+    let sch_index = compiler.compile("person.json", &mut schemas)?;
+    let export = export_graphql(&schemas, &[("Person", sch_index)]);
+
+    let want = "\
+scalar JSON
+
+type Person {
+  first_name_2: String
+  first_name: String
+  loop: JSON
+  nested: [JSON]
+}";
+    assert_eq!(export.sdl, want);
+
+    let mut kws = export
+        .unrepresentable
+        .iter()
+        .map(|u| u.keyword)
+        .collect::<Vec<_>>();
+    kws.sort();
+    assert_eq!(kws, ["$ref", "$ref", "properties"]);
+
+    Ok(())
+}
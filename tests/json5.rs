@@ -0,0 +1,75 @@
+#![cfg(feature = "json5")]
+
+use std::error::Error;
+
+use boon::{CompileError, Compiler, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_add_resource_json5() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource_json5(
+        "schema.json",
+        r#"{
+            // comment
+            "$id": 'http://example.com/schema.json',
+            properties: {
+                name: {type: "string", pattern: '^\x41B'},
+                size: {multipleOf: 0x10, minimum: -.5e1},
+            },
+        }"#,
+    )?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    assert!(schemas
+        .validate(&json!({"name": "AB", "size": 32}), sch)
+        .is_ok());
+    assert!(schemas.validate(&json!({"name": "ab"}), sch).is_err());
+    assert!(schemas.validate(&json!({"size": 8}), sch).is_err());
+    assert!(schemas.validate(&json!({"size": -16}), sch).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_json5_file() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let sch = compiler.compile("tests/examples/schema.json5", &mut schemas)?;
+    assert!(schemas.validate(&json!({"port": 80}), sch).is_ok());
+    assert!(schemas.validate(&json!({"port": 65536}), sch).is_err());
+    assert!(schemas
+        .validate(&json!({"port": 1, "ratio": 0}), sch)
+        .is_err());
+    assert!(schemas.validate(&json!({}), sch).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_json5_errors() {
+    let deep = "[".repeat(200_000);
+    for (text, want) in [
+        (deep.as_str(), "recursion limit"),
+        ("{a: 1,, }", "line 1 column 7"),
+        ("{\n  a: 'x\n'}", "line 2 column 8"),
+        ("{a: Infinity}", "Infinity"),
+        ("[1] 2", "after value"),
+        ("/* open", "unterminated comment"),
+    ] {
+        let mut compiler = Compiler::new();
+        let Err(e) = compiler.add_resource_json5("schema.json", text) else {
+            panic!("{text:?} must fail");
+        };
+        let CompileError::LoadUrlError { src, .. } = e else {
+            panic!("{text:?}: want LoadUrlError got {e:?}");
+        };
+        assert!(src.to_string().contains(want), "{text:?}: {src}");
+    }
+}
+
+#[test]
+fn test_json5_nesting() -> Result<(), Box<dyn Error>> {
+    let text = format!("{}{}", "[".repeat(128), "]".repeat(128));
+    let mut compiler = Compiler::new();
+    compiler.add_resource_json5("schema.json", &text)?;
+    Ok(())
+}
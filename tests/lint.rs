@@ -0,0 +1,111 @@
+use std::error::Error;
+
+use boon::{Compiler, LintKind, Schemas, Type};
+use serde_json::json;
+
+#[test]
+fn test_lint() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://example.com/schema.json",
+        json!({
+            "properties": {
+                "age": {"type": ["string", "null"], "minimum": 0, "maxLength": 3},
+                "count": {"type": "integer", "exclusiveMinimum": 5, "maximum": 5},
+                "pair": {"items": [{"$ref": "#/$defs/used"}, {"$ref": "#anchored"}]},
+                "flag": {"exclusiveMaximum": true}
+            },
+            "$defs": {
+                "used": {"type": "string"},
+                "anchored": {"$anchor": "anchored"},
+                "identified": {"$id": "http://example.com/other.json"},
+                "unused": {"type": "object"}
+            }
+        }),
+    )?;
+    let lints = compiler.lint("http://example.com/schema.json")?;
+    let got = lints
+        .iter()
+        .map(|l| (l.url.as_str(), l.kind.clone()))
+        .collect::<Vec<_>>();
+    let url = |ptr: &str| format!("http://example.com/schema.json#{ptr}");
+    assert_eq!(
+        got,
+        [
+            (
+                url("/$defs/unused").as_str(),
+                LintKind::UnreachableDef {
+                    name: "unused".into()
+                }
+            ),
+            (
+                url("/properties/age/minimum").as_str(),
+                LintKind::InapplicableKeyword {
+                    keyword: "minimum".into(),
+                    types: vec![Type::String, Type::Null]
+                }
+            ),
+            (
+                url("/properties/count/exclusiveMinimum").as_str(),
+                LintKind::ContradictoryBounds {
+                    min: "exclusiveMinimum".into(),
+                    max: "maximum".into()
+                }
+            ),
+            (
+                url("/properties/flag/exclusiveMaximum").as_str(),
+                LintKind::ExclusiveBoundMisuse {
+                    keyword: "exclusiveMaximum".into()
+                }
+            ),
+            (url("/properties/pair/items").as_str(), LintKind::ItemsArray),
+        ]
+    );
+    assert_eq!(
+        lints[1].to_string(),
+        "'minimum' at http://example.com/schema.json#/properties/age/minimum has no effect, as type is string or null"
+    );
+
+    // document is not compilable
+    let mut schemas = Schemas::new();
+    assert!(compiler
+        .compile("http://example.com/schema.json", &mut schemas)
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_lint_draft4() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "properties": {
+                "a": {"minimum": 1, "maximum": 1, "exclusiveMaximum": true},
+                "b": {"minimum": 1, "maximum": 1},
+                "c": {"exclusiveMinimum": 1}
+            },
+            "definitions": {
+                "x": {"id": "#x"},
+                "y": {"$ref": "#x"}
+            }
+        }),
+    )?;
+    let lints = compiler.lint("schema.json")?;
+    let got = lints.iter().map(|l| l.kind.clone()).collect::<Vec<_>>();
+    assert_eq!(
+        got,
+        [
+            LintKind::UnreachableDef { name: "y".into() },
+            LintKind::ContradictoryBounds {
+                min: "minimum".into(),
+                max: "maximum".into()
+            },
+            LintKind::ExclusiveBoundMisuse {
+                keyword: "exclusiveMinimum".into()
+            },
+        ]
+    );
+    Ok(())
+}
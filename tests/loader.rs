@@ -0,0 +1,271 @@
+use std::{cell::Cell, error::Error, rc::Rc, time::Duration};
+
+use boon::{
+    metaschema, metaschemas, CompileError, Compiler, LoadAttemptsError, LoadPolicy, MediaType,
+    Schemas, SchemeUrlLoader, UrlContent, UrlLoader,
+};
+use serde_json::{json, Value};
+use url::Url;
+
+// fails first `failures` loads.
+struct FlakyLoader {
+    failures: usize,
+    calls: Rc<Cell<usize>>,
+}
+
+impl UrlLoader for FlakyLoader {
+    fn load(&self, _url: &str) -> Result<Value, Box<dyn Error>> {
+        self.calls.set(self.calls.get() + 1);
+        if self.calls.get() <= self.failures {
+            Err("connection reset")?;
+        }
+        Ok(json!({"type": "string"}))
+    }
+}
+
+fn flaky_compiler(failures: usize, policy: LoadPolicy) -> (Compiler, Rc<Cell<usize>>) {
+    let calls = Rc::new(Cell::new(0));
+    let mut loader = SchemeUrlLoader::new();
+    let flaky = FlakyLoader {
+        failures,
+        calls: Rc::clone(&calls),
+    };
+    loader.register("test", Box::new(flaky));
+    let mut compiler = Compiler::new();
+    compiler.use_loader(Box::new(loader));
+    compiler.set_load_policy("test", policy);
+    (compiler, calls)
+}
+
+#[test]
+fn test_load_policy_retries() -> Result<(), Box<dyn Error>> {
+    let policy = LoadPolicy {
+        retries: 2,
+        backoff: Duration::from_millis(1),
+        retry_budget: None,
+    };
+    let (mut compiler, calls) = flaky_compiler(2, policy);
+    compiler.compile("test://schema.json", &mut Schemas::new())?;
+    assert_eq!(calls.get(), 3);
+
+    let (mut compiler, calls) = flaky_compiler(3, policy);
+    let Err(CompileError::LoadUrlError { url, src }) =
+        compiler.compile("test://schema.json", &mut Schemas::new())
+    else {
+        panic!("must fail after retries");
+    };
+    assert_eq!(url, "test://schema.json");
+    assert_eq!(calls.get(), 3);
+    let Some(err) = src.downcast_ref::<LoadAttemptsError>() else {
+        panic!("source must be LoadAttemptsError");
+    };
+    assert_eq!(err.attempts.len(), 3);
+    assert!(!err.budget_exhausted);
+    assert_eq!(err.to_string(), "failed after 3 attempts: connection reset");
+    Ok(())
+}
+
+#[test]
+fn test_load_policy_retry_budget() {
+    let policy = LoadPolicy {
+        retries: 10,
+        backoff: Duration::from_millis(20),
+        retry_budget: Some(Duration::from_millis(50)),
+    };
+    let (mut compiler, calls) = flaky_compiler(usize::MAX, policy);
+    let Err(CompileError::LoadUrlError { src, .. }) =
+        compiler.compile("test://schema.json", &mut Schemas::new())
+    else {
+        panic!("must exhaust retry budget");
+    };
+    let Some(err) = src.downcast_ref::<LoadAttemptsError>() else {
+        panic!("source must be LoadAttemptsError");
+    };
+    assert!(err.budget_exhausted);
+    assert_eq!(err.attempts.len(), calls.get());
+    assert!(calls.get() < 11);
+}
+
+#[test]
+fn test_load_policy_permanent() {
+    struct MissingLoader(Rc<Cell<usize>>);
+    impl UrlLoader for MissingLoader {
+        fn load(&self, _url: &str) -> Result<Value, Box<dyn Error>> {
+            self.0.set(self.0.get() + 1);
+            Err(std::io::Error::new(std::io::ErrorKind::NotFound, "404"))?
+        }
+    }
+
+    let calls = Rc::new(Cell::new(0));
+    let mut loader = SchemeUrlLoader::new();
+    loader.register("test", Box::new(MissingLoader(Rc::clone(&calls))));
+    let mut compiler = Compiler::new();
+    compiler.use_loader(Box::new(loader));
+    compiler.set_load_policy(
+        "test",
+        LoadPolicy {
+            retries: 5,
+            backoff: Duration::from_millis(1),
+            retry_budget: None,
+        },
+    );
+    let Err(CompileError::LoadUrlError { src, .. }) =
+        compiler.compile("test://schema.json", &mut Schemas::new())
+    else {
+        panic!("must fail");
+    };
+    let Some(err) = src.downcast_ref::<LoadAttemptsError>() else {
+        panic!("source must be LoadAttemptsError");
+    };
+    assert_eq!(calls.get(), 1);
+    assert_eq!(err.attempts.len(), 1);
+    assert!(!err.budget_exhausted);
+}
+
+#[test]
+fn test_data_url() -> Result<(), Box<dyn Error>> {
+    let mut compiler = Compiler::new();
+    compiler.enable_offline();
+    compiler.add_resource(
+        "http://example.com/schema.json",
+        json!({
+            "properties": {
+                // {"type":"string"}
+                "a": {"$ref": "data:application/schema+json;base64,eyJ0eXBlIjoic3RyaW5nIn0="},
+                "b": {"$ref": "data:application/json,%7B%22minimum%22:1%7D"},
+                "c": {"$ref": "data:,%5B%5D"}
+            }
+        }),
+    )?;
+    let mut schemas = Schemas::new();
+    let Err(e) = compiler.compile("http://example.com/schema.json", &mut schemas) else {
+        panic!("array is not valid schema");
+    };
+    assert!(e.to_string().contains("data:,%5B%5D"), "{e:#}");
+
+    compiler.replace_resource(
+        "http://example.com/schema.json",
+        json!({
+            "properties": {
+                "a": {"$ref": "data:application/schema+json;base64,eyJ0eXBlIjoic3RyaW5nIn0="},
+                "b": {"$ref": "data:application/json,%7B%22minimum%22:1%7D"}
+            }
+        }),
+    )?;
+    let sch = compiler.compile("http://example.com/schema.json", &mut schemas)?;
+    assert!(schemas.validate(&json!({"a": "x", "b": 1}), sch).is_ok());
+    assert!(schemas.validate(&json!({"a": 1}), sch).is_err());
+    assert!(schemas.validate(&json!({"b": 0}), sch).is_err());
+    Ok(())
+}
+
+// serves schema in format given by host of url.
+struct HttpLoader;
+
+impl UrlLoader for HttpLoader {
+    fn load(&self, _url: &str) -> Result<Value, Box<dyn Error>> {
+        unreachable!("load_content is overridden")
+    }
+
+    fn load_content(&self, url: &str) -> Result<UrlContent, Box<dyn Error>> {
+        let (bytes, media_type) = match Url::parse(url)?.host_str() {
+            Some("json.com") => ("{\"type\": \"string\"}", "application/schema+json"),
+            Some("yaml.com") => ("type: string\n", "application/yaml; charset=utf-8"),
+            Some("custom.com") => ("type=string", "application/x-custom"),
+            _ => ("{\"type\": \"string\"}", "text/plain"),
+        };
+        Ok(UrlContent::Bytes {
+            bytes: bytes.as_bytes().to_vec(),
+            media_type: Some(media_type.to_owned()),
+        })
+    }
+}
+
+fn parse_custom(bytes: &[u8], _deserialize: bool) -> Result<Option<Value>, Box<dyn Error>> {
+    let s = std::str::from_utf8(bytes)?;
+    let Some((k, v)) = s.split_once('=') else {
+        Err("missing =")?
+    };
+    Ok(Some(json!({ k: v })))
+}
+
+#[test]
+fn test_load_content() -> Result<(), Box<dyn Error>> {
+    let mut loader = SchemeUrlLoader::new();
+    loader.register("http", Box::new(HttpLoader));
+    let mut compiler = Compiler::new();
+    compiler.use_loader(Box::new(loader));
+    compiler.register_content_media_type(MediaType {
+        name: "application/x-custom",
+        json_compatible: true,
+        func: parse_custom,
+    });
+
+    let mut hosts = vec!["json.com", "custom.com", "other.com"];
+    if cfg!(feature = "yaml") {
+        hosts.push("yaml.com");
+    }
+    for host in hosts {
+        let mut schemas = Schemas::new();
+        let sch = compiler.compile(&format!("http://{host}/schema"), &mut schemas)?;
+        assert!(schemas.validate(&json!("x"), sch).is_ok(), "{host}");
+        assert!(schemas.validate(&json!(1), sch).is_err(), "{host}");
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "yaml")]
+fn test_yaml_data_url() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let sch = compiler.compile("data:application/yaml,type:%20string", &mut schemas)?;
+    assert!(schemas.validate(&json!(1), sch).is_err());
+    Ok(())
+}
+
+// fails any load.
+struct NoNetwork;
+
+impl UrlLoader for NoNetwork {
+    fn load(&self, url: &str) -> Result<Value, Box<dyn Error>> {
+        panic!("must not load {url}");
+    }
+}
+
+#[test]
+fn test_metaschemas_embedded() -> Result<(), Box<dyn Error>> {
+    let mut loader = SchemeUrlLoader::new();
+    loader.register("http", Box::new(NoNetwork));
+    loader.register("https", Box::new(NoNetwork));
+    let mut compiler = Compiler::new();
+    compiler.use_loader(Box::new(loader));
+    compiler.enable_offline();
+    let mut schemas = Schemas::new();
+    for (i, meta) in metaschemas().enumerate() {
+        // every metaschema is compilable, as schema and as `$schema`
+        let json = meta.json();
+        if let Some(id) = json.get("$id").and_then(Value::as_str) {
+            assert_eq!(id.trim_end_matches('#'), meta.url);
+        }
+        compiler.compile(meta.url, &mut schemas)?;
+        if !meta.vocabulary {
+            let url = format!("http://example.com/{i}.json");
+            compiler.add_resource(&url, json!({"$schema": meta.url, "type": "string"}))?;
+            let sch_index = compiler.compile(&url, &mut schemas)?;
+            assert!(schemas.validate(&json!(1), sch_index).is_err());
+        }
+    }
+    assert_eq!(metaschemas().filter(|m| !m.vocabulary).count(), 5);
+    assert_eq!(metaschemas().count(), 19);
+
+    let meta = metaschema("http://json-schema.org/draft/2019-09/meta/core#");
+    assert_eq!(
+        meta.map(|m| m.url),
+        Some("https://json-schema.org/draft/2019-09/meta/core")
+    );
+    let latest = metaschema("https://json-schema.org/schema").map(|m| m.draft);
+    assert_eq!(latest, Some(boon::Draft::default()));
+    assert!(metaschema("https://json-schema.org/draft/2020-12/meta/missing").is_none());
+    Ok(())
+}
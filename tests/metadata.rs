@@ -0,0 +1,100 @@
+use std::error::Error;
+
+use boon::{Compiler, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_metadata() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "title": "user",
+            "properties": {
+                "id": {
+                    "allOf": [{"$ref": "#/$defs/id"}],
+                    "readOnly": true
+                },
+                "kind": {
+                    "if": {"const": "a"},
+                    "then": {"description": "kind a"},
+                    "else": {"description": "other kind", "deprecated": true}
+                }
+            },
+            "$defs": {
+                "id": {
+                    "title": "identifier",
+                    "default": 0,
+                    "examples": [1, 2]
+                }
+            }
+        }),
+    )?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+
+    let m = schemas.metadata(sch);
+    assert_eq!(m.title.as_deref(), Some("user"));
+    assert!(m.examples.is_empty());
+
+    let instance = json!({"kind": "b"});
+    let Some(m) = schemas.metadata_at(&instance, sch, "/id") else {
+        panic!("metadata_at must return Some");
+    };
+    assert_eq!(m.title.as_deref(), Some("identifier"));
+    assert_eq!(m.default, Some(json!(0)));
+    assert_eq!(m.examples, [json!(1), json!(2)]);
+    assert!(m.read_only);
+    assert!(!m.deprecated);
+
+    let Some(m) = schemas.metadata_at(&instance, sch, "/kind") else {
+        panic!("metadata_at must return Some");
+    };
+    assert_eq!(m.description.as_deref(), Some("other kind"));
+    assert!(m.deprecated);
+
+    let instance = json!({"kind": "a"});
+    let Some(m) = schemas.metadata_at(&instance, sch, "/kind") else {
+        panic!("metadata_at must return Some");
+    };
+    assert_eq!(m.description.as_deref(), Some("kind a"));
+
+    assert!(schemas.metadata_at(&instance, sch, "kind").is_none());
+    Ok(())
+}
+
+#[test]
+fn test_unknown_keywords() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "properties": {
+                "name": {
+                    "allOf": [{"$ref": "#/definitions/name"}],
+                    "x-order": 1
+                }
+            },
+            "definitions": {
+                "name": {"type": "string", "x-order": 2, "x-widget": "text", "$defs": {}}
+            }
+        }),
+    )?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    assert!(schemas.unknown_keywords(sch).is_empty());
+
+    let name = schemas.index_of("schema.json#/definitions/name").unwrap();
+    let kws = schemas
+        .unknown_keywords(name)
+        .iter()
+        .map(|(kw, _)| *kw)
+        .collect::<Vec<_>>();
+    assert_eq!(kws, ["$defs", "x-order", "x-widget"]);
+
+    let m = schemas.metadata_at(&json!({}), sch, "/name").unwrap();
+    assert_eq!(m.unknown_keywords["x-order"], 1);
+    assert_eq!(m.unknown_keywords["x-widget"], "text");
+    Ok(())
+}
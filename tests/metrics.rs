@@ -0,0 +1,98 @@
+use std::error::Error;
+
+use boon::{Compiler, SchemaMetrics, Schemas, SchemasStats};
+use serde_json::json;
+
+#[test]
+fn test_metrics() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "$defs": {
+            "node": {
+                "properties": {
+                    "value": { "pattern": "^[a-z]+$" },
+                    "children": { "items": { "$ref": "#/$defs/node" } }
+                }
+            }
+        },
+        "oneOf": [
+            { "$ref": "#/$defs/node" },
+            { "type": "string" },
+            { "type": "number" }
+        ]
+    });
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", schema)?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+    let metrics = schemas.metrics(sch_index);
+    assert_eq!(
+        metrics,
+        SchemaMetrics {
+            subschemas: 8,
+            max_depth: 5,
+            max_branching: 3,
+            regexes: 1,
+            recursive: true,
+        }
+    );
+    assert_eq!(
+        metrics.to_string(),
+        r#"{"subschemas":8,"maxDepth":5,"maxBranching":3,"regexes":1,"recursive":true}"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_stats() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    assert_eq!(schemas.stats(), SchemasStats::default());
+
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "properties": {
+                "a": { "pattern": "^[a-z]+$" },
+                "b": { "pattern": "^[a-z]+$" }
+            },
+            "required": ["a"]
+        }),
+    )?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+    let stats = schemas.stats();
+    assert_eq!(stats.schemas, 3);
+    assert_eq!(stats.regexes, 1);
+    assert_eq!(stats.documents, 1);
+    // property names are interned, so "a" is stored once
+    assert_eq!(stats.strings, stats.distinct_strings);
+    assert!(stats.heap_bytes > 0);
+
+    // grows with each compile
+    compiler.add_resource("other.json", json!({"enum": ["x", "y", "z"]}))?;
+    compiler.compile("other.json", &mut schemas)?;
+    let more = schemas.stats();
+    assert_eq!(more.schemas, 4);
+    assert_eq!(more.documents, 2);
+    assert!(more.heap_bytes > stats.heap_bytes);
+    assert!(schemas.contains(sch_index));
+    Ok(())
+}
+
+#[test]
+fn test_interned_names() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let item = json!({"properties": {"id": true, "name": true}, "required": ["id"]});
+    compiler.add_resource("a.json", json!({"items": item, "properties": {"id": item}}))?;
+    compiler.add_resource("b.json", json!({"properties": {"name": item}}))?;
+    compiler.compile("a.json", &mut schemas)?;
+    compiler.compile("b.json", &mut schemas)?;
+
+    // one location per schema, and "id" and "name" once across compiles
+    let stats = schemas.stats();
+    assert_eq!(stats.strings, stats.schemas + 2);
+    assert_eq!(stats.strings, stats.distinct_strings);
+    Ok(())
+}
@@ -0,0 +1,219 @@
+use std::error::Error;
+
+use boon::{migrate, Compiler, Draft, MigrationNoteKind, Schemas};
+use serde_json::{json, Value};
+
+// checks that `instances` are validated same, before and after migration.
+fn assert_same_validation(
+    draft: Draft,
+    schema: &Value,
+    migrated: &Value,
+    instances: &[Value],
+) -> Result<(), Box<dyn Error>> {
+    let url = "http://example.com/schema.json";
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.set_default_draft(draft);
+    compiler.add_resource(url, schema.clone())?;
+    let old = compiler.compile(url, &mut schemas)?;
+
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions();
+    compiler.add_resource(url, migrated.clone())?;
+    let new = compiler.compile(url, &mut schemas)?;
+    for instance in instances {
+        assert_eq!(
+            schemas.validate(instance, old).is_ok(),
+            schemas.validate(instance, new).is_ok(),
+            "for {instance}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_migrate_draft4() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "id": "http://example.com/schema.json",
+        "type": "object",
+        "properties": {
+            "age": {"minimum": 0, "maximum": 150, "exclusiveMaximum": true, "exclusiveMinimum": false},
+            "point": {"$ref": "#/definitions/point"},
+            "name": {"$ref": "schema.json#/definitions/name"},
+            "nick": {"$ref": "#nick"}
+        },
+        "dependencies": {
+            "age": ["name"],
+            "point": {"required": ["age"]}
+        },
+        "definitions": {
+            "point": {
+                "type": "array",
+                "items": [{"type": "number"}, {"$ref": "#/definitions/point/items/0"}],
+                "additionalItems": false
+            },
+            "name": {"type": "string"},
+            "nick": {"id": "#nick", "type": "string", "maxLength": 5}
+        }
+    });
+    let mut migrated = schema.clone();
+    let notes = migrate(&mut migrated, Draft::V4);
+    assert_eq!(notes, []);
+    assert_eq!(
+        migrated,
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$id": "http://example.com/schema.json",
+            "type": "object",
+            "properties": {
+                "age": {"minimum": 0, "exclusiveMaximum": 150},
+                "point": {"$ref": "#/$defs/point"},
+                "name": {"$ref": "schema.json#/$defs/name"},
+                "nick": {"$ref": "#nick"}
+            },
+            "dependentRequired": {"age": ["name"]},
+            "dependentSchemas": {"point": {"required": ["age"]}},
+            "$defs": {
+                "point": {
+                    "type": "array",
+                    "prefixItems": [{"type": "number"}, {"$ref": "#/$defs/point/prefixItems/0"}],
+                    "items": false
+                },
+                "name": {"type": "string"},
+                "nick": {"$anchor": "nick", "type": "string", "maxLength": 5}
+            }
+        })
+    );
+    let instances = [
+        json!({"age": 150, "name": "x"}),
+        json!({"age": 0, "name": "x"}),
+        json!({"age": 10}),
+        json!({"point": [1, 2]}),
+        json!({"age": 1, "name": "x", "point": [1, 2]}),
+        json!({"age": 1, "name": "x", "point": [1, 2, 3]}),
+        json!({"age": 1, "name": "x", "point": [1, "2"]}),
+        json!({"nick": "abcdef"}),
+        json!({"name": 1}),
+    ];
+    assert_same_validation(Draft::V4, &schema, &migrated, &instances)
+}
+
+#[test]
+fn test_migrate_notes() -> Result<(), Box<dyn Error>> {
+    let mut doc = json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "properties": {
+            "email": {"type": "string", "format": "email"},
+            "a": {"$ref": "#/definitions/a", "type": "string", "description": "a"},
+            "b": {"$ref": "other.json#/definitions/b"},
+            "c": {"$ref": "other.json#/properties/c"}
+        },
+        "definitions": {"a": true},
+        "$defs": {"a": false},
+        "items": {
+            "$schema": "https://json-schema.org/draft/2019-09/schema",
+            "$recursiveAnchor": true,
+            "items": [{"$recursiveRef": "#"}]
+        },
+        "contains": {"$schema": "http://example.com/meta.json"}
+    });
+    let notes = migrate(&mut doc, Draft::V7);
+    let got = notes
+        .iter()
+        .map(|n| (n.ptr.as_str(), n.kind.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        got,
+        [
+            (
+                "",
+                MigrationNoteKind::Conflict {
+                    keyword: "definitions",
+                    replacement: "$defs"
+                }
+            ),
+            (
+                "/contains",
+                MigrationNoteKind::CustomMetaschema {
+                    url: "http://example.com/meta.json".to_owned()
+                }
+            ),
+            (
+                "/items",
+                MigrationNoteKind::Unsupported {
+                    keyword: "$recursiveAnchor"
+                }
+            ),
+            (
+                "/items/prefixItems/0",
+                MigrationNoteKind::Unsupported {
+                    keyword: "$recursiveRef"
+                }
+            ),
+            (
+                "/properties/a",
+                MigrationNoteKind::RefSiblings {
+                    keywords: vec!["type".to_owned()]
+                }
+            ),
+            (
+                "/properties/b",
+                MigrationNoteKind::ExternalRef {
+                    ref_: "other.json#/definitions/b".to_owned()
+                }
+            ),
+            (
+                "/properties/email",
+                MigrationNoteKind::FormatAssertion {
+                    format: "email".to_owned()
+                }
+            ),
+        ]
+    );
+    assert_eq!(doc["definitions"], json!({"a": true}));
+    assert_eq!(doc["properties"]["a"]["$ref"], "#/definitions/a");
+    assert_eq!(
+        doc["items"]["$schema"],
+        "https://json-schema.org/draft/2020-12/schema"
+    );
+    assert_eq!(doc["contains"]["$schema"], "http://example.com/meta.json");
+    assert_eq!(
+        notes[4].to_string(),
+        "'type' at '/properties/a' were ignored beside $ref, but now apply"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_migrate_draft7() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "$id": "http://example.com/schema.json#",
+        "if": {"properties": {"kind": {"const": "point"}}},
+        "then": {"properties": {"value": {"$ref": "#/definitions/pair"}}},
+        "else": {"properties": {"value": {"$ref": "#/definitions/pair/items/1"}}},
+        "definitions": {
+            "pair": {"items": [{"type": "integer"}, {"type": "string"}], "minItems": 2}
+        }
+    });
+    let mut migrated = schema.clone();
+    assert_eq!(migrate(&mut migrated, Draft::V7), []);
+    assert_eq!(migrated["$id"], "http://example.com/schema.json#");
+    assert_eq!(
+        migrated["else"]["properties"]["value"]["$ref"],
+        "#/$defs/pair/prefixItems/1"
+    );
+    let instances = [
+        json!({"kind": "point", "value": [1, "x"]}),
+        json!({"kind": "point", "value": ["x", 1]}),
+        json!({"kind": "point", "value": [1, "x", null]}),
+        json!({"kind": "other", "value": "x"}),
+        json!({"kind": "other", "value": 1}),
+    ];
+    assert_same_validation(Draft::V7, &schema, &migrated, &instances)?;
+
+    // nothing to migrate
+    let mut doc = json!({"$schema": "https://json-schema.org/draft/2020-12/schema", "items": []});
+    assert_eq!(migrate(&mut doc, Draft::V4), []);
+    assert_eq!(doc["items"], json!([]));
+    Ok(())
+}
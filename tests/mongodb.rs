@@ -0,0 +1,78 @@
+use std::error::Error;
+
+use boon::{CompileError, Compiler, Dialect, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_bson_type() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "bsonType": "object",
+        "required": ["_id", "count"],
+        "properties": {
+            "_id": {"bsonType": "objectId"},
+            "count": {"bsonType": "int"},
+            "total": {"bsonType": ["long", "decimal"]},
+            "price": {"bsonType": "number"}
+        }
+    });
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.use_dialect(Dialect::MongoDb);
+    compiler.add_resource("schema.json", schema)?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+
+    let valid = json!({
+        "_id": {"$oid": "5f1a2b3c4d5e6f7a8b9c0d1e"},
+        "count": 3,
+        "total": {"$numberDecimal": "12.50"},
+        "price": 9.99
+    });
+    assert!(schemas.validate(&valid, sch).is_ok());
+
+    let invalid = json!({"_id": "5f1a2b3c4d5e6f7a8b9c0d1e", "count": 3});
+    let Err(e) = schemas.validate(&invalid, sch) else {
+        panic!("want validation error");
+    };
+    assert_eq!(
+        e.causes[0].kind.to_string(),
+        "want bsonType objectId, but got string"
+    );
+
+    let invalid = json!({"_id": {"$oid": "5f1a2b3c4d5e6f7a8b9c0d1e"}, "count": 4294967296_u64});
+    assert!(schemas.validate(&invalid, sch).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_unsupported_keywords() -> Result<(), Box<dyn Error>> {
+    for (schema, keyword) in [
+        (json!({"properties": {"a": {"$ref": "#"}}}), "$ref"),
+        (json!({"properties": {"a": {"format": "email"}}}), "format"),
+        (
+            json!({"$schema": "http://json-schema.org/draft-04/schema#"}),
+            "$schema",
+        ),
+    ] {
+        let mut schemas = Schemas::new();
+        let mut compiler = Compiler::new();
+        compiler.use_dialect(Dialect::MongoDb);
+        compiler.add_resource("schema.json", schema)?;
+        let result = compiler.compile("schema.json", &mut schemas);
+        let Err(CompileError::UnsupportedKeyword { keyword: got, .. }) = result else {
+            panic!("want UnsupportedKeyword for {keyword}");
+        };
+        assert_eq!(got, keyword);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_invalid_bson_type() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.use_dialect(Dialect::MongoDb);
+    compiler.add_resource("schema.json", json!({"bsonType": "integer"}))?;
+    let result = compiler.compile("schema.json", &mut schemas);
+    assert!(matches!(result, Err(CompileError::InvalidBsonType { .. })));
+    Ok(())
+}
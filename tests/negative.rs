@@ -0,0 +1,107 @@
+use std::error::Error;
+
+use boon::{near_misses, Compiler, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_near_misses() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "name": { "type": "string", "minLength": 1, "maxLength": 5 },
+            "age": { "type": "integer", "minimum": 0, "maximum": 150 },
+            "tags": { "type": "array", "items": { "enum": ["a", "b"] }, "uniqueItems": true }
+        },
+        "required": ["name"],
+        "additionalProperties": false
+    });
+    let valid = json!({"name": "joe", "age": 30, "tags": ["a"]});
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", schema)?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+    let misses = near_misses(&schemas, sch_index, &valid).map_err(|e| e.to_string())?;
+
+    let mut got = misses
+        .iter()
+        .map(|m| (m.instance_location.as_str(), m.keyword))
+        .collect::<Vec<_>>();
+    got.sort();
+    let want = [
+        ("", "additionalProperties"),
+        ("", "required"),
+        ("", "type"),
+        ("/age", "maximum"),
+        ("/age", "minimum"),
+        ("/age", "type"),
+        ("/age", "type"), // fractional
+        ("/name", "maxLength"),
+        ("/name", "minLength"),
+        ("/name", "type"),
+        ("/tags", "type"),
+        ("/tags", "uniqueItems"),
+        ("/tags/0", "enum"),
+    ];
+    assert_eq!(got, want);
+    for m in &misses {
+        assert!(schemas.validate(&m.instance, sch_index).is_err());
+    }
+
+    // invalid seed
+    assert!(near_misses(&schemas, sch_index, &json!({})).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_near_misses_large_bounds() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "properties": {
+            "name": { "maxLength": 10 },
+            "big": { "maxLength": 1_000_000_000 },
+            "list": { "maxItems": 3 },
+            "huge": { "maxItems": usize::MAX },
+            "obj": { "maxProperties": 2 },
+            "wide": { "maxProperties": 1_000_000_000 }
+        }
+    });
+    let valid = json!({
+        "name": "joe",
+        "big": "joe",
+        "list": [1],
+        "huge": [1],
+        "obj": {"a": 1},
+        "wide": {"a": 1}
+    });
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let sch_index = compiler.compile_value(schema, &mut schemas)?;
+    let misses = near_misses(&schemas, sch_index, &valid).map_err(|e| e.to_string())?;
+
+    let mut got = misses
+        .iter()
+        .map(|m| (m.instance_location.as_str(), m.keyword))
+        .collect::<Vec<_>>();
+    got.sort();
+    assert_eq!(
+        got,
+        [
+            ("/list", "maxItems"),
+            ("/name", "maxLength"),
+            ("/obj", "maxProperties")
+        ]
+    );
+    let name = misses.iter().find(|m| m.keyword == "maxLength").unwrap();
+    assert_eq!(name.instance["name"], json!("joexxxxxxxx"));
+    let list = misses.iter().find(|m| m.keyword == "maxItems").unwrap();
+    assert_eq!(list.instance["list"], json!([1, 1, 1, 1]));
+    let obj = misses
+        .iter()
+        .find(|m| m.keyword == "maxProperties")
+        .unwrap();
+    assert_eq!(obj.instance["obj"].as_object().unwrap().len(), 3);
+
+    Ok(())
+}
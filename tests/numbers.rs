@@ -0,0 +1,76 @@
+#![cfg(feature = "bigdecimal")]
+
+use std::error::Error;
+
+use boon::{Compiler, Schemas};
+use serde_json::{json, Value};
+
+fn validate(schema: Value, instance: Value) -> Result<bool, Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", schema)?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+    Ok(schemas.validate(&instance, sch_index).is_ok())
+}
+
+#[test]
+fn test_exact_numbers() -> Result<(), Box<dyn Error>> {
+    // loses precision as f64
+    assert!(validate(json!({"multipleOf": 0.1}), json!(0.3))?);
+    assert!(validate(json!({"multipleOf": 1e-10}), json!(1e200))?);
+    assert!(!validate(json!({"multipleOf": 0.123456789}), json!(1e308))?);
+    assert!(!validate(
+        json!({"maximum": 9007199254740992_u64}),
+        json!(9007199254740993_u64)
+    )?);
+    assert!(!validate(
+        json!({"exclusiveMinimum": 9007199254740993_u64}),
+        json!(9007199254740993_u64)
+    )?);
+    assert!(!validate(
+        json!({"const": 9007199254740992_u64}),
+        json!(9007199254740993_u64)
+    )?);
+    assert!(!validate(
+        json!({"enum": [9007199254740992_u64]}),
+        json!(9007199254740993_u64)
+    )?);
+    assert!(validate(json!({"const": 1}), json!(1.0))?);
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "arbitrary_precision")]
+fn test_arbitrary_precision() -> Result<(), Box<dyn Error>> {
+    let num = |s: &str| serde_json::from_str::<Value>(s);
+    // beyond f64 range
+    assert!(!validate(json!({"maximum": num("1e400")?}), num("2e400")?)?);
+    assert!(validate(
+        json!({"minimum": num("-1e400")?}),
+        num("1e-400")?
+    )?);
+    assert!(validate(json!({"type": "integer"}), num("1e400")?)?);
+    assert!(!validate(json!({"const": num("1e400")?}), num("1e401")?)?);
+    // beyond f64 precision
+    assert!(!validate(
+        json!({"type": "integer"}),
+        num("1.0000000000000000001")?
+    )?);
+    assert!(!validate(
+        json!({"multipleOf": 0.1}),
+        num("0.30000000000000000001")?
+    )?);
+    assert!(validate(
+        json!({"exclusiveMaximum": 1}),
+        num("0.99999999999999999999")?
+    )?);
+    assert!(validate(
+        json!({"uniqueItems": true}),
+        num("[1e400, 1e401]")?
+    )?);
+    assert!(!validate(
+        json!({"uniqueItems": true}),
+        num("[1e400, 10e399]")?
+    )?);
+    Ok(())
+}
@@ -0,0 +1,310 @@
+use std::{error::Error, time::Duration};
+
+use boon::{
+    AccessContext, Annotations, Compiler, ErrorKind, HostnameOptions, Keyword, LengthUnit, Phase,
+    Schemas, ValidationOptions,
+};
+use serde_json::{json, Value};
+
+#[test]
+fn test_format_overrides() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "properties": {
+            "host": { "format": "hostname" },
+            "code": { "format": "country" },
+            "date": { "format": "date" }
+        }
+    });
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions();
+    compiler.register_format_fn("country", |v| match v {
+        Value::String(s) if s.len() != 2 => Err("not a country code")?,
+        _ => Ok(()),
+    });
+    compiler.add_resource("schema.json", schema)?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+    let mut tenant1 = ValidationOptions::new();
+    tenant1.override_format("country", |v| match v {
+        Value::String(s) if s != "IN" => Err("unsupported country")?,
+        _ => Ok(()),
+    });
+    tenant1.set_hostname_options(HostnameOptions {
+        allow_underscore: true,
+        ..Default::default()
+    });
+    let mut tenant2 = ValidationOptions::new();
+    tenant2.skip_format("date");
+
+    let instance = json!({"host": "my_host", "code": "US", "date": "2024-13-01"});
+    let errors = |options: &ValidationOptions| {
+        let Err(e) = schemas.validate_with(&instance, sch_index, options) else {
+            return vec![];
+        };
+        let mut locs = e
+            .causes
+            .iter()
+            .map(|e| e.instance_location.to_string())
+            .collect::<Vec<_>>();
+        locs.sort();
+        locs
+    };
+    assert_eq!(errors(&ValidationOptions::new()), ["/date", "/host"]);
+    assert_eq!(errors(&tenant1), ["/code", "/date"]);
+    assert_eq!(errors(&tenant2), ["/host"]);
+    Ok(())
+}
+
+#[test]
+fn test_format_asserted_per_call() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "properties": {
+            "email": { "format": "email" },
+            "code": { "format": "country" }
+        }
+    });
+
+    // format assertions not enabled
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.register_format_fn("country", |v| match v {
+        Value::String(s) if s.len() != 2 => Err("not a country code")?,
+        _ => Ok(()),
+    });
+    compiler.add_resource("schema.json", schema)?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+    let instance = json!({"email": "x", "code": "USA"});
+    assert!(schemas.validate(&instance, sch_index).is_ok());
+
+    let mut options = ValidationOptions::new();
+    options.assert_format("email");
+    let e = schemas
+        .validate_with(&instance, sch_index, &options)
+        .unwrap_err();
+    assert_eq!(e.causes.len(), 1);
+    assert_eq!(e.causes[0].instance_location.to_string(), "/email");
+
+    // override is used, if asserted
+    options.assert_format("country");
+    options.override_format("country", |_| Ok(()));
+    let e = schemas
+        .validate_with(&instance, sch_index, &options)
+        .unwrap_err();
+    assert_eq!(e.causes.len(), 1);
+
+    options.skip_format("email");
+    assert!(schemas
+        .validate_with(&instance, sch_index, &options)
+        .is_ok());
+    Ok(())
+}
+
+struct Even;
+
+impl Keyword for Even {
+    fn validate(&self, v: &Value, _annotations: &Annotations) -> Result<(), Box<dyn Error>> {
+        match v.as_u64() {
+            Some(n) if n % 2 != 0 => Err("not even")?,
+            _ => Ok(()),
+        }
+    }
+}
+
+#[test]
+fn test_keyword_overrides() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.register_keyword("x-even", Phase::Assertion, |_| Ok(Box::new(Even)));
+    compiler.add_resource("schema.json", json!({"x-even": "odd numbers allowed for"}))?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+    let instance = json!(3);
+    assert!(schemas.validate(&instance, sch_index).is_err());
+
+    let mut options = ValidationOptions::new();
+    options.disable_keyword("x-even");
+    assert!(schemas
+        .validate_with(&instance, sch_index, &options)
+        .is_ok());
+
+    // replacement gets keyword value
+    options.override_keyword("x-even", |kw, v, _| match (kw, v.as_u64()) {
+        (Value::String(s), Some(3)) if s.starts_with("odd") => Ok(()),
+        _ => Err("not allowed")?,
+    });
+    assert!(schemas
+        .validate_with(&instance, sch_index, &options)
+        .is_ok());
+    let instance2 = json!(2);
+    let e = schemas
+        .validate_with(&instance2, sch_index, &options)
+        .unwrap_err();
+    assert_eq!(e.causes[0].kind.to_string(), "x-even failed: not allowed");
+
+    options.enable_keyword("x-even");
+    assert!(schemas
+        .validate_with(&instance, sch_index, &options)
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_length_unit() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", json!({"maxLength": 2}))?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+    let mut bytes = ValidationOptions::new();
+    bytes.set_length_unit(LengthUnit::Bytes);
+    let instance = json!("né");
+    assert!(schemas.validate(&instance, sch_index).is_ok());
+    assert!(schemas.validate_with(&instance, sch_index, &bytes).is_err());
+
+    // e followed by combining acute accent
+    let instance = json!("ne\u{301}");
+    assert!(schemas.validate(&instance, sch_index).is_err());
+    #[cfg(feature = "unicode-segmentation")]
+    {
+        let mut graphemes = ValidationOptions::new();
+        graphemes.set_length_unit(LengthUnit::Graphemes);
+        assert!(schemas
+            .validate_with(&instance, sch_index, &graphemes)
+            .is_ok());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_access_context() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "properties": {
+            "id": {"type": "integer", "readOnly": true},
+            "password": {"type": "string", "writeOnly": true},
+            "name": {"type": "string"}
+        }
+    });
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", schema)?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+    let mut write = ValidationOptions::new();
+    write.set_access_context(AccessContext::Write);
+    let mut read = ValidationOptions::new();
+    read.set_access_context(AccessContext::Read);
+
+    let instance = json!({"id": 1, "password": "secret", "name": "john"});
+    assert!(schemas.validate(&instance, sch_index).is_ok());
+
+    let Err(e) = schemas.validate_with(&instance, sch_index, &write) else {
+        panic!("readOnly must fail in write context");
+    };
+    assert_eq!(e.causes.len(), 1);
+    assert_eq!(e.causes[0].instance_location.to_string(), "/id");
+    assert!(matches!(e.causes[0].kind, ErrorKind::ReadOnly));
+
+    let Err(e) = schemas.validate_with(&instance, sch_index, &read) else {
+        panic!("writeOnly must fail in read context");
+    };
+    assert_eq!(e.causes.len(), 1);
+    assert_eq!(e.causes[0].instance_location.to_string(), "/password");
+    assert!(matches!(e.causes[0].kind, ErrorKind::WriteOnly));
+
+    let instance = json!({"password": "secret", "name": "john"});
+    assert!(schemas.validate_with(&instance, sch_index, &write).is_ok());
+    Ok(())
+}
+
+#[test]
+fn test_max_depth() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "tree.json",
+        json!({
+            "anyOf": [
+                {"type": "array", "items": {"$ref": "#"}},
+                true
+            ]
+        }),
+    )?;
+    let sch = compiler.compile("tree.json", &mut schemas)?;
+
+    let mut instance = json!(1);
+    for _ in 0..1000 {
+        instance = Value::Array(vec![instance]);
+    }
+    let mut options = ValidationOptions::new();
+    options.set_max_depth(100);
+    // anyOf does not swallow the error
+    let Err(err) = schemas.validate_with(&instance, sch, &options) else {
+        panic!("want max depth error");
+    };
+    assert_eq!(err.causes.len(), 1);
+    assert!(matches!(
+        err.causes[0].kind,
+        ErrorKind::MaxDepth { want: 100 }
+    ));
+    assert_eq!(err.causes[0].instance_location.tokens.len(), 33);
+
+    // shallow instances are unaffected
+    assert!(schemas.validate_with(&json!([[1]]), sch, &options).is_ok());
+    Ok(())
+}
+
+#[test]
+fn test_budget() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    // exponential number of evaluations
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "$defs": {
+                "a": {"anyOf": [{"$ref": "#/$defs/b"}, {"$ref": "#/$defs/b"}]},
+                "b": {"anyOf": [{"$ref": "#/$defs/c"}, {"$ref": "#/$defs/c"}]},
+                "c": {"anyOf": [{"$ref": "#/$defs/d"}, {"$ref": "#/$defs/d"}]},
+                "d": {"items": {"$ref": "#/$defs/a"}, "type": "array"}
+            },
+            "$ref": "#/$defs/a"
+        }),
+    )?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    let mut instance = json!([]);
+    for _ in 0..6 {
+        instance = Value::Array(vec![instance, json!(1)]);
+    }
+
+    let mut options = ValidationOptions::new();
+    options.set_max_evaluations(1000);
+    let Err(err) = schemas.validate_with(&instance, sch, &options) else {
+        panic!("want max evaluations error");
+    };
+    assert_eq!(err.causes.len(), 1);
+    assert!(matches!(
+        err.causes[0].kind,
+        ErrorKind::MaxEvaluations { want: 1000 }
+    ));
+
+    let mut options = ValidationOptions::new();
+    options.set_time_limit(Duration::ZERO);
+    let Err(err) = schemas.validate_with(&instance, sch, &options) else {
+        panic!("want time limit error");
+    };
+    assert!(matches!(err.causes[0].kind, ErrorKind::TimeLimit { .. }));
+    assert_eq!(err.causes[0].kind.to_string(), "time limit of 0ns exceeded");
+
+    // small instances are unaffected
+    let mut options = ValidationOptions::new();
+    options.set_max_evaluations(1000);
+    options.set_time_limit(Duration::from_secs(60));
+    assert!(schemas
+        .validate_with(&json!([[], 1]), sch, &options)
+        .is_err());
+    assert!(schemas.validate_with(&json!([[]]), sch, &options).is_ok());
+    Ok(())
+}
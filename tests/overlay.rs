@@ -0,0 +1,198 @@
+use std::error::Error;
+
+use boon::{CompileError, Compiler, Provenance, SchemaPatch, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_merge_patch() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://a.com/person.json",
+        json!({
+            "$id": "http://a.com/person.json",
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"$ref": "#/$defs/age"}
+            },
+            "$defs": {"age": {"type": "integer"}}
+        }),
+    )?;
+    let patch = json!({
+        "properties": {"name": {"maxLength": 5}},
+        "$defs": {"age": {"minimum": 18}},
+        "required": ["name"]
+    });
+    compiler.add_overlay(
+        "http://a.com/prod/person.json",
+        "http://a.com/person.json",
+        &SchemaPatch::Merge(patch),
+    )?;
+    let base = compiler.compile("http://a.com/person.json", &mut schemas)?;
+    let prod = compiler.compile("http://a.com/prod/person.json", &mut schemas)?;
+
+    let instance = json!({"name": "John Doe", "age": 10});
+    assert!(schemas.validate(&instance, base).is_ok());
+    let Err(e) = schemas.validate(&instance, prod) else {
+        panic!("instance should be invalid");
+    };
+    let mut locs = vec![];
+    for cause in &e.causes {
+        let mut leaf = cause;
+        while let Some(c) = leaf.causes.first() {
+            leaf = c;
+        }
+        locs.push(leaf.schema_url.to_owned());
+    }
+    locs.sort();
+    assert_eq!(
+        locs,
+        [
+            "http://a.com/prod/person.json#/$defs/age",
+            "http://a.com/prod/person.json#/properties/name"
+        ]
+    );
+
+    // provenance --
+    assert_eq!(
+        compiler.provenance("http://a.com/prod/person.json#/properties/name/maxLength"),
+        Some(Provenance::Patch {
+            ptr: "/properties/name/maxLength".into()
+        })
+    );
+    assert_eq!(
+        compiler.provenance("http://a.com/prod/person.json#/properties/name/type"),
+        Some(Provenance::Base {
+            loc: "http://a.com/person.json#/properties/name/type".into()
+        })
+    );
+    assert_eq!(
+        compiler.provenance("http://a.com/person.json#/properties/name"),
+        None
+    );
+    Ok(())
+}
+
+#[test]
+fn test_json_patch() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://a.com/schema.json",
+        json!({"$defs": {"item": {"enum": ["a", "b", "c"]}}}),
+    )?;
+    let patch = json!([
+        {"op": "test", "path": "/enum/0", "value": "a"},
+        {"op": "remove", "path": "/enum/2"},
+        {"op": "add", "path": "/enum/-", "value": "d"},
+    ]);
+    compiler.add_overlay(
+        "http://a.com/item.json",
+        "http://a.com/schema.json#/$defs/item",
+        &SchemaPatch::Json(patch),
+    )?;
+    let sch = compiler.compile("http://a.com/item.json", &mut schemas)?;
+    assert!(schemas.validate(&json!("d"), sch).is_ok());
+    assert!(schemas.validate(&json!("c"), sch).is_err());
+    assert_eq!(
+        compiler.provenance("http://a.com/item.json#/enum/1"),
+        Some(Provenance::Patch {
+            ptr: "/enum".into()
+        })
+    );
+
+    // failed test op
+    let patch = json!([{"op": "test", "path": "/enum/0", "value": "x"}]);
+    let result = compiler.add_overlay(
+        "http://a.com/item2.json",
+        "http://a.com/schema.json#/$defs/item",
+        &SchemaPatch::Json(patch),
+    );
+    assert!(matches!(result, Err(CompileError::InvalidPatch { .. })));
+    Ok(())
+}
+
+#[test]
+fn test_overlay_provenance_in_errors() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://a.com/person.json",
+        json!({
+            "properties": {
+                "name": {"type": "string", "minLength": 1},
+                "age": {"type": "integer", "x-unit": "years"}
+            }
+        }),
+    )?;
+    let patch = json!([
+        {"op": "add", "path": "/properties/name/maxLength", "value": 5},
+        {"op": "remove", "path": "/properties/age/x-unit"}
+    ]);
+    compiler.add_overlay(
+        "http://a.com/prod/person.json",
+        "http://a.com/person.json",
+        &SchemaPatch::Json(patch),
+    )?;
+    assert_eq!(
+        compiler.provenance("http://a.com/prod/person.json#/properties/age/x-unit"),
+        Some(Provenance::Patch {
+            ptr: "/properties/age/x-unit".into()
+        })
+    );
+
+    let sch = compiler.compile("http://a.com/prod/person.json", &mut schemas)?;
+    let leaf = |e: &boon::ValidationError| {
+        let mut leaf = e;
+        while let Some(c) = leaf.causes.first() {
+            leaf = c;
+        }
+        leaf.provenance.clone()
+    };
+    let instance = json!({"name": "John Doe"});
+    let e = schemas.validate(&instance, sch).unwrap_err();
+    let patched = Provenance::Patch {
+        ptr: "/properties/name/maxLength".into(),
+    };
+    assert_eq!(leaf(&e), Some(patched.clone()));
+    assert!(format!("{e:#}").contains("from patch at '/properties/name/maxLength'"));
+    let output = serde_json::to_value(e.basic_output())?;
+    let unit = output["errors"]
+        .as_array()
+        .and_then(|units| {
+            units
+                .iter()
+                .find(|u| u["keywordLocation"] == "/properties/name/maxLength")
+        })
+        .unwrap();
+    assert_eq!(unit["provenance"], json!(patched.to_string()));
+
+    let instance = json!({"name": ""});
+    let e = schemas.validate(&instance, sch).unwrap_err();
+    assert_eq!(
+        leaf(&e),
+        Some(Provenance::Base {
+            loc: "http://a.com/person.json#/properties/name/minLength".into()
+        })
+    );
+
+    // metaschema validation of patched schema
+    let patch = json!({"properties": {"name": {"type": 1}}});
+    compiler.add_overlay(
+        "http://a.com/bad/person.json",
+        "http://a.com/person.json",
+        &SchemaPatch::Merge(patch),
+    )?;
+    let Err(CompileError::ValidationError { src, .. }) =
+        compiler.compile("http://a.com/bad/person.json", &mut schemas)
+    else {
+        panic!("patched schema must be invalid");
+    };
+    assert_eq!(
+        leaf(&src),
+        Some(Provenance::Patch {
+            ptr: "/properties/name/type".into()
+        })
+    );
+    Ok(())
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "rayon")]
+
+use std::error::Error;
+
+use boon::{CompileError, Compiler, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_compile_parallel() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let mut locs = vec![];
+    for i in 0..20 {
+        let loc = format!("http://example.com/s{i}.json");
+        compiler.add_resource(
+            &loc,
+            json!({
+                "properties": {"next": {"$ref": format!("s{}.json", (i + 1) % 20)}},
+                "required": [format!("p{i}")]
+            }),
+        )?;
+        locs.push(loc);
+    }
+    let locs = locs.iter().map(String::as_str).collect::<Vec<_>>();
+    let indexes = compiler.compile_parallel(&locs, &mut schemas)?;
+    assert_eq!(indexes.len(), 20);
+    assert!(schemas.validate(&json!({"p0": 1}), indexes[0]).is_ok());
+    assert!(schemas
+        .validate(&json!({"p0": 1, "next": {}}), indexes[0])
+        .is_err());
+    assert!(schemas
+        .validate(&json!({"p5": 1, "next": {"p6": 1}}), indexes[5])
+        .is_ok());
+
+    // same as compile
+    let sch = compiler.compile("http://example.com/s3.json", &mut schemas)?;
+    assert_eq!(sch, indexes[3]);
+    Ok(())
+}
+
+#[test]
+fn test_compile_parallel_invalid() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("http://example.com/a.json", json!({"type": "string"}))?;
+    compiler.add_resource("http://example.com/b.json", json!({"type": 1}))?;
+    let result = compiler.compile_parallel(
+        &["http://example.com/a.json", "http://example.com/b.json"],
+        &mut schemas,
+    );
+    let Err(CompileError::ValidationError { url, .. }) = result else {
+        panic!("want metaschema validation error");
+    };
+    assert_eq!(url, "http://example.com/b.json#");
+    Ok(())
+}
+
+#[test]
+fn test_validate_all_parallel() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let sch = compiler.compile_value(
+        json!({"properties": {"id": {"type": "integer", "minimum": 0}}}),
+        &mut schemas,
+    )?;
+    let instances = (-500..500).map(|id| json!({"id": id})).collect::<Vec<_>>();
+    let results = schemas.validate_all_parallel(&instances, sch);
+    assert_eq!(results.len(), 1000);
+    for (i, result) in results.iter().enumerate() {
+        assert_eq!(result.is_ok(), i >= 500, "{i}");
+    }
+    let sequential = schemas.validate_all(&instances, sch);
+    for (par, seq) in results.iter().zip(&sequential) {
+        assert_eq!(
+            par.as_ref().map_err(|e| format!("{e:#}")),
+            seq.as_ref().map_err(|e| format!("{e:#}"))
+        );
+    }
+    assert_eq!(
+        results[0].as_ref().unwrap_err().causes[0]
+            .instance_location
+            .to_string(),
+        "/id"
+    );
+    Ok(())
+}
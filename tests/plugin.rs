@@ -0,0 +1,147 @@
+use std::{error::Error, os::raw::c_char};
+
+use boon::{
+    Compiler, PluginFormat, PluginKeyword, PluginV1, PluginV2, Schemas, PLUGIN_ABI_VERSION,
+};
+use serde_json::json;
+
+unsafe extern "C" fn validate_even(
+    json: *const u8,
+    json_len: usize,
+    err: *mut u8,
+    err_cap: usize,
+    err_len: *mut usize,
+) -> bool {
+    let json = std::slice::from_raw_parts(json, json_len);
+    match std::str::from_utf8(json)
+        .ok()
+        .and_then(|s| s.parse::<i64>().ok())
+    {
+        Some(n) if n % 2 != 0 => {
+            let msg = b"odd number";
+            let n = msg.len().min(err_cap);
+            std::ptr::copy_nonoverlapping(msg.as_ptr(), err, n);
+            *err_len = n;
+            false
+        }
+        _ => true,
+    }
+}
+
+unsafe extern "C" fn validate_multiple(
+    value: *const u8,
+    value_len: usize,
+    json: *const u8,
+    json_len: usize,
+    err: *mut u8,
+    err_cap: usize,
+    err_len: *mut usize,
+) -> bool {
+    let parse = |p, n| {
+        std::str::from_utf8(std::slice::from_raw_parts(p, n))
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+    };
+    match (parse(value, value_len), parse(json, json_len)) {
+        (Some(m), Some(n)) if m != 0 && n % m != 0 => {
+            let msg = b"not a multiple";
+            let n = msg.len().min(err_cap);
+            std::ptr::copy_nonoverlapping(msg.as_ptr(), err, n);
+            *err_len = n;
+            false
+        }
+        _ => true,
+    }
+}
+
+#[test]
+fn test_register_plugin() -> Result<(), Box<dyn Error>> {
+    let formats = [PluginFormat {
+        name: c"even".as_ptr() as *const c_char,
+        validate: validate_even,
+    }];
+    let plugin = PluginV1 {
+        abi_version: 1,
+        formats: formats.as_ptr(),
+        num_formats: formats.len(),
+    };
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions();
+    unsafe { compiler.register_plugin(&plugin)? };
+    compiler.add_resource("schema.json", json!({"format": "even"}))?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    assert!(schemas.validate(&json!(2), sch).is_ok());
+    let odd = json!(3);
+    let Err(e) = schemas.validate(&odd, sch) else {
+        panic!("validation must fail");
+    };
+    assert!(e.to_string().contains("odd number"), "{e}");
+
+    let plugin = PluginV1 {
+        abi_version: PLUGIN_ABI_VERSION + 1,
+        formats: std::ptr::null(),
+        num_formats: 0,
+    };
+    assert!(unsafe { compiler.register_plugin(&plugin) }.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_register_plugin_keywords() -> Result<(), Box<dyn Error>> {
+    let formats = [PluginFormat {
+        name: c"even".as_ptr() as *const c_char,
+        validate: validate_even,
+    }];
+    let keywords = [PluginKeyword {
+        name: c"x-multiple".as_ptr() as *const c_char,
+        phase: 0,
+        validate: validate_multiple,
+    }];
+    let plugin = PluginV2 {
+        v1: PluginV1 {
+            abi_version: PLUGIN_ABI_VERSION,
+            formats: formats.as_ptr(),
+            num_formats: formats.len(),
+        },
+        keywords: keywords.as_ptr(),
+        num_keywords: keywords.len(),
+    };
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions();
+    unsafe { compiler.register_plugin(&plugin.v1)? };
+    assert_eq!(compiler.custom_keywords(), ["x-multiple"]);
+    let schema = json!({"format": "even", "x-multiple": 3});
+    let sch = compiler.compile_value(schema, &mut schemas)?;
+    assert!(schemas.validate(&json!(6), sch).is_ok());
+    assert!(schemas.validate(&json!(3), sch).is_err());
+    let four = json!(4);
+    let Err(e) = schemas.validate(&four, sch) else {
+        panic!("validation must fail");
+    };
+    assert!(e.to_string().contains("not a multiple"), "{e}");
+
+    // invalid phase
+    let keywords = [PluginKeyword {
+        name: c"x-other".as_ptr() as *const c_char,
+        phase: 3,
+        validate: validate_multiple,
+    }];
+    let plugin = PluginV2 {
+        v1: PluginV1 {
+            abi_version: PLUGIN_ABI_VERSION,
+            formats: std::ptr::null(),
+            num_formats: 0,
+        },
+        keywords: keywords.as_ptr(),
+        num_keywords: keywords.len(),
+    };
+    assert!(unsafe { compiler.register_plugin(&plugin.v1) }.is_err());
+    assert_eq!(compiler.custom_keywords(), ["x-multiple"]);
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use std::error::Error;
+
+use boon::{Compiler, PrettyFormat, Schemas};
+use serde_json::{json, Value};
+
+fn compile() -> Result<(Schemas, boon::SchemaIndex), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "$ref": "#/$defs/base",
+            "properties": {
+                "servers": {
+                    "description": "servers to connect",
+                    "type": "array",
+                    "items": {
+                        "properties": {
+                            "port": {"description": "port number\nof server"},
+                            "host": {"type": "string"}
+                        }
+                    }
+                },
+                "tags": {"description": "tags"}
+            },
+            "$defs": {
+                "base": {
+                    "properties": {
+                        "name": {"description": "name of config"}
+                    }
+                }
+            }
+        }),
+    )?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    Ok((schemas, sch))
+}
+
+fn instance() -> Value {
+    json!({
+        "tags": [],
+        "servers": [{"port": 80, "host": "localhost", "x-extra": null}],
+        "name": "no",
+        "other": {}
+    })
+}
+
+#[test]
+fn test_pretty_print_json() -> Result<(), Box<dyn Error>> {
+    let (schemas, sch) = compile()?;
+    let v = instance();
+    let s = schemas.pretty_print(&v, sch, PrettyFormat::Json);
+    assert!(!s.contains("//"));
+    assert_eq!(serde_json::from_str::<Value>(&s)?, v);
+    Ok(())
+}
+
+#[test]
+fn test_pretty_print_json5() -> Result<(), Box<dyn Error>> {
+    let (schemas, sch) = compile()?;
+    let s = schemas.pretty_print(&instance(), sch, PrettyFormat::Json5);
+    assert!(s.contains("  // name of config\n  name: \"no\",\n"), "{s}");
+    assert!(
+        s.contains("      // port number\n      // of server\n      port: 80,\n"),
+        "{s}"
+    );
+    assert!(s.contains("\"x-extra\": null\n"), "{s}");
+    Ok(())
+}
+
+#[test]
+fn test_pretty_print_yaml() -> Result<(), Box<dyn Error>> {
+    let (schemas, sch) = compile()?;
+    let v = instance();
+    let s = schemas.pretty_print(&v, sch, PrettyFormat::Yaml);
+    assert!(s.contains("# servers to connect\nservers:\n  - "), "{s}");
+    assert!(s.contains("    # of server\n    port: 80\n"), "{s}");
+    assert!(s.contains("name: \"no\"\n"), "{s}");
+    assert_eq!(serde_yaml::from_str::<Value>(&s)?, v);
+    Ok(())
+}
+
+#[test]
+fn test_pretty_print_order() -> Result<(), Box<dyn Error>> {
+    let (schemas, sch) = compile()?;
+    let s = schemas.pretty_print(&instance(), sch, PrettyFormat::Json);
+    let pos = |key: &str| s.find(&format!("\"{key}\":")).unwrap();
+    // declared properties first, then those of $ref, then the rest
+    assert!(pos("tags") < pos("name"));
+    assert!(pos("name") < pos("other"));
+    assert!(pos("port") < pos("x-extra"));
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use std::error::Error;
+
+use boon::{export_proto, Compiler, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_export_proto() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "name": { "type": "string", "pattern": "^[a-z]+$" },
+            "age": { "type": ["integer", "null"] },
+            "color": { "enum": ["red", "dark-green"] },
+            "tags": { "type": "array", "items": { "type": "string" } },
+            "labels": { "type": "object", "additionalProperties": { "type": "string" } },
+            "home-address": { "$ref": "#/$defs/address" },
+            "pet": { "oneOf": [{ "$ref": "#/$defs/cat" }, { "$ref": "#/$defs/dog" }] }
+        },
+        "required": ["name", "age"],
+        "$defs": {
+            "address": {
+                "type": "object",
+                "properties": { "street": { "type": "string" } }
+            },
+            "cat": { "type": "object", "properties": { "lives": { "type": "integer" } } },
+            "dog": { "type": "object", "properties": { "breed": { "type": "string" } } }
+        }
+    });
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("person.json", schema)?;
+    let sch_index = compiler.compile("person.json", &mut schemas)?;
+    let export = export_proto(&schemas, &[("Person", sch_index)]);
+
+    let want = r#"syntax = "proto3";
+
+enum PersonColor {
+  PERSON_COLOR_UNSPECIFIED = 0;
+  PERSON_COLOR_RED = 1;
+  PERSON_COLOR_DARK_GREEN = 2;
+}
+
+message Person {
+  optional int64 age = 1;
+  optional PersonColor color = 2;
+  optional Address home_address = 3 [json_name = "home-address"];
+  map<string, string> labels = 4;
+  string name = 5;
+  oneof pet {
+    Cat pet_cat = 6;
+    Dog pet_dog = 7;
+  }
+  repeated string tags = 8;
+}
+
+message Address {
+  optional string street = 1;
+}
+
+message Cat {
+  optional int64 lives = 1;
+}
+
+message Dog {
+  optional string breed = 1;
+}"#;
+    assert_eq!(export.proto, want);
+
+    let kws = export
+        .unrepresentable
+        .iter()
+        .map(|u| u.keyword)
+        .collect::<Vec<_>>();
+    assert_eq!(kws, ["pattern"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_export_proto_collisions() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "first-name": { "type": "string" },
+            "first_name": { "type": "string" },
+            "kind": { "enum": ["a-b", "a_b", "c"] },
+            "loop": { "$ref": "#/$defs/a" }
+        },
+        "$defs": {
+            "a": { "$ref": "#/$defs/b" },
+            "b": { "$ref": "#/$defs/a" }
+        }
+    });
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("tk.json", schema)?;
+    let sch_index = compiler.compile("tk.json", &mut schemas)?;
+    let export = export_proto(&schemas, &[("Tk", sch_index)]);
+
+    let want = r#"syntax = "proto3";
+
+import "google/protobuf/struct.proto";
+
+enum TkKind {
+  TK_KIND_UNSPECIFIED = 0;
+  TK_KIND_A_B = 1;
+  TK_KIND_C = 3;
+}
+
+message Tk {
+  optional string first_name_2 = 1 [json_name = "first-name"];
+  optional string first_name = 2;
+  optional TkKind kind = 3;
+  optional google.protobuf.Value loop = 4;
+}"#;
+    assert_eq!(export.proto, want);
+
+    let mut kws = export
+        .unrepresentable
+        .iter()
+        .map(|u| u.keyword)
+        .collect::<Vec<_>>();
+    kws.sort();
+    assert_eq!(kws, ["$ref", "enum"]);
+
+    Ok(())
+}
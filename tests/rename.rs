@@ -0,0 +1,117 @@
+use std::error::Error;
+
+use boon::{rename, RenameTarget};
+use serde_json::json;
+
+#[test]
+fn test_rename_anchor() -> Result<(), Box<dyn Error>> {
+    let mut docs = vec![
+        (
+            "http://a.com/schema.json".to_owned(),
+            json!({
+                "$ref": "#name",
+                "$defs": {"x": {"$anchor": "name", "type": "string"}}
+            }),
+        ),
+        (
+            "http://a.com/other.json".to_owned(),
+            json!({"$ref": "schema.json#name", "enum": [{"$ref": "#name"}]}),
+        ),
+    ];
+    let n = rename(
+        &mut docs,
+        RenameTarget::Anchor {
+            url: "http://a.com/schema.json",
+            name: "name",
+        },
+        "fullName",
+    )?;
+    assert_eq!(n, 2);
+    assert_eq!(docs[0].1["$ref"], "#fullName");
+    assert_eq!(docs[0].1["$defs"]["x"]["$anchor"], "fullName");
+    assert_eq!(docs[1].1["$ref"], "schema.json#fullName");
+    assert_eq!(docs[1].1["enum"][0]["$ref"], "#name"); // not a schema
+    Ok(())
+}
+
+#[test]
+fn test_rename_def() -> Result<(), Box<dyn Error>> {
+    let mut docs = vec![
+        (
+            "http://a.com/schema.json".to_owned(),
+            json!({
+                "$ref": "#/$defs/a/properties/b",
+                "$defs": {
+                    "a": {"properties": {"b": {"$ref": "#/$defs/c"}}},
+                    "c": {}
+                }
+            }),
+        ),
+        (
+            "http://a.com/dir/other.json".to_owned(),
+            json!({"$ref": "../schema.json#/$defs/a"}),
+        ),
+    ];
+    let n = rename(
+        &mut docs,
+        RenameTarget::Def {
+            url: "http://a.com/schema.json",
+            name: "a",
+        },
+        "a b",
+    )?;
+    assert_eq!(n, 2);
+    assert_eq!(docs[0].1["$ref"], "#/$defs/a%20b/properties/b");
+    assert_eq!(
+        docs[0].1["$defs"]["a b"]["properties"]["b"]["$ref"],
+        "#/$defs/c"
+    );
+    assert_eq!(docs[1].1["$ref"], "../schema.json#/$defs/a%20b");
+
+    // name in use
+    let target = RenameTarget::Def {
+        url: "http://a.com/schema.json",
+        name: "c",
+    };
+    assert!(rename(&mut docs, target, "a b").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_rename_id() -> Result<(), Box<dyn Error>> {
+    let mut docs = vec![
+        (
+            "http://a.com/schema.json".to_owned(),
+            json!({
+                "$id": "http://a.com/v1/schema.json",
+                "properties": {
+                    "a": {"$ref": "item.json"},
+                    "b": {"$ref": "http://a.com/v1/item.json#/type"}
+                },
+                "$defs": {
+                    "item": {"$id": "item.json", "$ref": "schema.json"}
+                }
+            }),
+        ),
+        (
+            "http://a.com/other.json".to_owned(),
+            json!({"$ref": "v1/item.json"}),
+        ),
+    ];
+    let n = rename(
+        &mut docs,
+        RenameTarget::Id {
+            url: "http://a.com/v1/item.json",
+        },
+        "items/item.json",
+    )?;
+    assert_eq!(n, 4);
+    assert_eq!(docs[0].1["properties"]["a"]["$ref"], "items/item.json");
+    assert_eq!(
+        docs[0].1["properties"]["b"]["$ref"],
+        "http://a.com/v1/items/item.json#/type"
+    );
+    assert_eq!(docs[0].1["$defs"]["item"]["$ref"], "../schema.json");
+    assert_eq!(docs[1].1["$ref"], "v1/items/item.json");
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use std::error::Error;
+
+use boon::{Compiler, RemoveAdditional, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_remove_additional() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "allOf": [{"$ref": "#/$defs/named"}],
+            "properties": {
+                "name": true,
+                "tags": {
+                    "items": {
+                        "properties": {"key": true},
+                        "additionalProperties": false
+                    }
+                },
+                "meta": {
+                    "properties": {"owner": true},
+                    "patternProperties": {"^x-": true}
+                }
+            },
+            "additionalProperties": false,
+            "$defs": {
+                "named": {
+                    "properties": {"name": {"type": "string"}}
+                }
+            }
+        }),
+    )?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+
+    let instance = json!({
+        "name": "john",
+        "admin": true,
+        "tags": [{"key": "a", "value": 1}],
+        "meta": {"owner": "x", "x-trace": 1, "extra": 2}
+    });
+
+    let mut v = instance.clone();
+    let removed = schemas.remove_additional(&mut v, sch, RemoveAdditional::Rejected);
+    assert_eq!(removed, ["/admin", "/tags/0/value"]);
+    assert_eq!(
+        v,
+        json!({
+            "name": "john",
+            "tags": [{"key": "a"}],
+            "meta": {"owner": "x", "x-trace": 1, "extra": 2}
+        })
+    );
+    assert!(schemas.validate(&v, sch).is_ok());
+
+    let mut v = instance.clone();
+    let removed = schemas.remove_additional(&mut v, sch, RemoveAdditional::All);
+    assert_eq!(removed, ["/admin", "/meta/extra", "/tags/0/value"]);
+    Ok(())
+}
+
+#[test]
+fn test_remove_additional_allof() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "allOf": [
+                {"properties": {"a": true}, "additionalProperties": false},
+                {"properties": {"b": true}}
+            ]
+        }),
+    )?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+
+    // b is rejected by first allOf subschema, though second one allows it
+    let mut v = json!({"a": 1, "b": 2});
+    let removed = schemas.remove_additional(&mut v, sch, RemoveAdditional::Rejected);
+    assert_eq!(removed, ["/b"]);
+    assert_eq!(v, json!({"a": 1}));
+    Ok(())
+}
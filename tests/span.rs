@@ -0,0 +1,70 @@
+use std::error::Error;
+
+use boon::{Compiler, Schemas};
+
+#[test]
+fn test_source_span() -> Result<(), Box<dyn Error>> {
+    let text =
+        "{\n  \"properties\": {\n    \"a/b\": {\"enum\": [1, \"ü\", true]},\n    \"ü\": {}\n  }\n}";
+    let mut compiler = Compiler::new();
+    compiler.add_resource_text("http://example.com/schema.json", text)?;
+    let span = |ptr: &str| {
+        compiler
+            .source_span(&format!("http://example.com/schema.json#{ptr}"))
+            .unwrap()
+    };
+
+    let root = span("");
+    assert_eq!(
+        (root.start, root.end, root.line, root.col),
+        (0, text.len(), 1, 1)
+    );
+    let s = span("/properties/a~1b/enum/1");
+    assert_eq!(&text[s.start..s.end], "\"ü\"");
+    assert_eq!((s.line, s.col), (3, 25));
+    let s = span("/properties/a~1b/enum/2");
+    assert_eq!(&text[s.start..s.end], "true");
+    let s = span("/properties/%C3%BC");
+    assert_eq!(&text[s.start..s.end], "{}");
+    assert_eq!(s.to_string(), "4:10");
+
+    // nearest ancestor
+    assert_eq!(span("/properties/x/type"), span("/properties"));
+
+    // unknown documents
+    compiler.add_resource("http://example.com/other.json", serde_json::json!({}))?;
+    assert!(compiler
+        .source_span("http://example.com/other.json#")
+        .is_none());
+    Ok(())
+}
+
+#[test]
+fn test_error_span_metaschema() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource_text(
+        "http://example.com/schema.json",
+        "{\n  \"properties\": {\n    \"age\": {\"minimum\": \"zero\"}\n  }\n}",
+    )?;
+    let err = compiler
+        .compile("http://example.com/schema.json", &mut schemas)
+        .unwrap_err();
+    let (url, span) = compiler.error_span(&err).unwrap();
+    assert_eq!(url, "http://example.com/schema.json");
+    assert_eq!((span.line, span.col), (3, 24));
+    Ok(())
+}
+
+#[test]
+fn test_error_span_file() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let err = compiler
+        .compile("tests/examples/invalid-regex.json", &mut schemas)
+        .unwrap_err();
+    let (url, span) = compiler.error_span(&err).unwrap();
+    assert!(url.ends_with("/tests/examples/invalid-regex.json"), "{url}");
+    assert_eq!((span.line, span.col), (6, 18));
+    Ok(())
+}
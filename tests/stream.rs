@@ -0,0 +1,175 @@
+use std::error::Error;
+
+use boon::{
+    Annotations, Compiler, Dialect, JsonEvent, Keyword, Phase, SchemaIndex, Schemas,
+    StreamValidator,
+};
+use serde_json::{json, Value};
+
+fn events<'a>(v: &'a Value, out: &mut Vec<JsonEvent<'a>>) {
+    match v {
+        Value::Null => out.push(JsonEvent::Null),
+        Value::Bool(b) => out.push(JsonEvent::Bool(*b)),
+        Value::Number(n) => out.push(JsonEvent::Number(n.clone())),
+        Value::String(s) => out.push(JsonEvent::String(s)),
+        Value::Array(arr) => {
+            out.push(JsonEvent::StartArray);
+            arr.iter().for_each(|item| events(item, out));
+            out.push(JsonEvent::EndArray);
+        }
+        Value::Object(obj) => {
+            out.push(JsonEvent::StartObject);
+            for (pname, pvalue) in obj {
+                out.push(JsonEvent::Key(pname));
+                events(pvalue, out);
+            }
+            out.push(JsonEvent::EndObject);
+        }
+    }
+}
+
+fn stream_errors(
+    schemas: &Schemas,
+    sch_index: SchemaIndex,
+    v: &Value,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut list = vec![];
+    events(v, &mut list);
+    let mut validator = StreamValidator::new(schemas, sch_index);
+    for event in list {
+        validator.event(event)?;
+    }
+    let mut locs = validator
+        .finish()?
+        .into_iter()
+        .map(|e| e.instance_location)
+        .collect::<Vec<_>>();
+    locs.sort();
+    Ok(locs)
+}
+
+#[test]
+fn test_stream() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "$defs": {
+            "item": {
+                "type": "object",
+                "required": ["id"],
+                "properties": { "id": { "type": "integer", "minimum": 1 } },
+                "additionalProperties": false
+            }
+        },
+        "type": "object",
+        "required": ["items"],
+        "properties": {
+            "items": { "type": "array", "items": { "$ref": "#/$defs/item" }, "maxItems": 3 },
+            "tags": { "uniqueItems": true }
+        },
+        "patternProperties": { "^x-": { "type": "string" } },
+        "maxProperties": 3
+    });
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", schema)?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+    let tests = [
+        (json!({"items": [{"id": 1}, {"id": 2}]}), vec![]),
+        (
+            json!({"items": [{"id": 0}, {}, {"id": 1, "x": 1}]}),
+            vec!["/items/0/id", "/items/1", "/items/2"],
+        ),
+        (
+            json!({"items": [], "x-a": 1, "tags": [1, 1]}),
+            vec!["/tags", "/x-a"],
+        ),
+        (
+            json!({"items": [{"id": 1}, {"id": 1}, {"id": 1}, {"id": 1}]}),
+            vec!["/items"],
+        ),
+        (
+            json!({"tags": [], "a": [[[]]], "b": {}, "c": null}),
+            vec!["", ""],
+        ),
+        (json!([]), vec![""]),
+    ];
+    for (instance, want) in tests {
+        let got = stream_errors(&schemas, sch_index, &instance)?;
+        assert_eq!(got, want, "instance: {instance}");
+        assert_eq!(
+            got.is_empty(),
+            schemas.validate(&instance, sch_index).is_ok()
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_stream_invalid_events() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("schema.json", json!({}))?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+    let mut validator = StreamValidator::new(&schemas, sch_index);
+    validator.event(JsonEvent::StartArray)?;
+    assert!(validator.finish().is_err());
+
+    let mut validator = StreamValidator::new(&schemas, sch_index);
+    validator.event(JsonEvent::Null)?;
+    assert!(validator.event(JsonEvent::Null).is_err());
+    Ok(())
+}
+
+struct Fail;
+
+impl Keyword for Fail {
+    fn validate(&self, _v: &Value, _annotations: &Annotations) -> Result<(), Box<dyn Error>> {
+        Err("always fails")?
+    }
+}
+
+#[test]
+fn test_stream_custom_keyword() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.register_keyword("x-fail", Phase::Assertion, |_| Ok(Box::new(Fail)));
+    compiler.add_resource("schema.json", json!({"x-fail": true}))?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+    let instance = json!({});
+    assert!(schemas.validate(&instance, sch_index).is_err());
+    assert_eq!(stream_errors(&schemas, sch_index, &instance)?, [""]);
+    Ok(())
+}
+
+#[test]
+fn test_stream_data_refs() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_data_refs();
+    let schema = json!({"properties": {"a": {"maximum": {"$data": "1/b"}}}});
+    compiler.add_resource("schema.json", schema)?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+    let instance = json!({"b": 1, "a": 5});
+    assert!(schemas.validate(&instance, sch_index).is_err());
+    assert_eq!(stream_errors(&schemas, sch_index, &instance)?, ["/a"]);
+    let instance = json!({"b": 5, "a": 1});
+    assert!(stream_errors(&schemas, sch_index, &instance)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_stream_bson_type() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.use_dialect(Dialect::MongoDb);
+    compiler.add_resource("schema.json", json!({"bsonType": "array"}))?;
+    let sch_index = compiler.compile("schema.json", &mut schemas)?;
+
+    let instance = json!({});
+    assert!(schemas.validate(&instance, sch_index).is_err());
+    assert_eq!(stream_errors(&schemas, sch_index, &instance)?, [""]);
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use std::error::Error;
+
+use boon::{Compiler, Redaction, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_debug_bundle() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions();
+    compiler.add_resource(
+        "http://example.com/user.json",
+        json!({
+            "properties": {
+                "email": {"format": "email"},
+                "address": {"$ref": "address.json"}
+            }
+        }),
+    )?;
+    compiler.add_resource(
+        "http://example.com/address.json",
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "required": ["city"]
+        }),
+    )?;
+    let sch = compiler.compile("http://example.com/user.json", &mut schemas)?;
+    let instance = json!({"email": "secret", "address": {}});
+
+    let bundle = compiler.debug_bundle(&schemas, sch, &instance, &[]);
+    assert_eq!(bundle["schema"], "http://example.com/user.json#");
+    assert_eq!(bundle["options"]["assertFormat"], true);
+    assert_eq!(bundle["valid"], false);
+    assert_eq!(bundle["instance"], instance);
+    let docs = bundle["documents"].as_object().unwrap();
+    assert_eq!(docs.len(), 2);
+    assert_eq!(
+        docs["http://example.com/address.json"]["draft"],
+        "http://json-schema.org/draft-07/schema"
+    );
+    assert_eq!(
+        docs["http://example.com/address.json"]["content"]["required"],
+        json!(["city"])
+    );
+    assert!(bundle["output"].to_string().contains("secret"));
+
+    // redacted
+    let redactions = [Redaction::Property {
+        name: "email".into(),
+        with: json!("***"),
+    }];
+    let bundle = compiler.debug_bundle(&schemas, sch, &instance, &redactions);
+    assert_eq!(bundle["instance"]["email"], "***");
+    assert!(!bundle.to_string().contains("secret"));
+    assert_eq!(bundle["output"]["errors"].as_array().unwrap().len(), 2);
+    Ok(())
+}
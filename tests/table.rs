@@ -0,0 +1,108 @@
+use std::error::Error;
+
+use boon::{derive_table, Compiler, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_derive_table() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "id": { "type": "string", "format": "uuid" },
+            "name": { "type": "string", "maxLength": 100 },
+            "age": { "type": ["integer", "null"], "minimum": 0 },
+            "score": { "type": "number" },
+            "born": { "type": "string", "format": "date" },
+            "tags": { "type": "array", "items": { "type": "string" } }
+        },
+        "required": ["id", "name", "age"]
+    });
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.enable_format_assertions();
+    compiler.add_resource("person.json", schema)?;
+    let sch_index = compiler.compile("person.json", &mut schemas)?;
+    let table = derive_table(&schemas, sch_index);
+
+    let want = r#"CREATE TABLE "person" (
+  "age" BIGINT,
+  "born" DATE,
+  "id" UUID NOT NULL,
+  "name" VARCHAR(100) NOT NULL,
+  "score" DOUBLE PRECISION,
+  "tags" JSON
+);"#;
+    assert_eq!(table.sql_ddl("person"), want);
+
+    let kws = table
+        .unrepresentable
+        .iter()
+        .map(|u| u.keyword)
+        .collect::<Vec<_>>();
+    assert_eq!(kws, ["minimum", "type"]);
+
+    #[cfg(feature = "arrow")]
+    {
+        use arrow_schema::DataType;
+        let schema = table.arrow_schema();
+        let field = schema.field_with_name("name")?;
+        assert_eq!(field.data_type(), &DataType::Utf8);
+        assert!(!field.is_nullable());
+        assert_eq!(field.metadata()["max_length"], "100");
+        let field = schema.field_with_name("born")?;
+        assert_eq!(field.data_type(), &DataType::Date32);
+        assert!(field.is_nullable());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_derive_table_refs() -> Result<(), Box<dyn Error>> {
+    let schema = json!({
+        "$ref": "#/$defs/base",
+        "properties": { "name": { "type": "string" } },
+        "required": ["name"],
+        "$defs": {
+            "base": {
+                "type": "object",
+                "properties": {
+                    "id": { "type": "integer" },
+                    "loop": { "$ref": "#/$defs/loop" }
+                },
+                "required": ["id"]
+            },
+            "loop": { "$ref": "#/$defs/loop2" },
+            "loop2": { "$ref": "#/$defs/loop" }
+        }
+    });
+
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource("person.json", schema)?;
+    let sch_index = compiler.compile("person.json", &mut schemas)?;
+    let table = derive_table(&schemas, sch_index);
+
+    let want = r#"CREATE TABLE "person" (
+  "id" BIGINT NOT NULL,
+  "loop" JSON,
+  "name" TEXT NOT NULL
+);"#;
+    assert_eq!(table.sql_ddl("person"), want);
+    assert!(table.unrepresentable.iter().any(|u| u.keyword == "$ref"));
+
+    // cyclic root
+    for schema in [
+        json!({"$defs": {"a": {"$ref": "#/$defs/b"}, "b": {"$ref": "#/$defs/a"}}, "$ref": "#/$defs/a"}),
+        json!({"properties": {"x": {"$ref": "#/properties/x"}}}),
+    ] {
+        let mut schemas = Schemas::new();
+        let mut compiler = Compiler::new();
+        compiler.add_resource("cycle.json", schema)?;
+        let sch_index = compiler.compile("cycle.json", &mut schemas)?;
+        let table = derive_table(&schemas, sch_index);
+        assert!(table.unrepresentable.iter().any(|u| u.keyword == "$ref"));
+    }
+    Ok(())
+}
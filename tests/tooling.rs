@@ -0,0 +1,162 @@
+use std::error::Error;
+
+use boon::tooling::*;
+use boon::{Compiler, Schemas, Type};
+use serde_json::{json, Value};
+
+#[test]
+fn test_session_recompile() -> Result<(), Box<dyn Error>> {
+    let mut session = Session::new();
+    session.update("http://a.com/b.json", json!({"type": "string"}))?;
+    session.update("http://a.com/c.json", json!({"type": "number"}))?;
+    let recompiled = session.update("http://a.com/a.json", json!({"$ref": "b.json"}))?;
+    assert_eq!(recompiled, ["http://a.com/a.json"]);
+
+    // a.json refers to b.json
+    let recompiled = session.update("http://a.com/b.json", json!({"type": "integer"}))?;
+    assert_eq!(recompiled, ["http://a.com/a.json", "http://a.com/b.json"]);
+    let recompiled = session.update("http://a.com/c.json", json!({"type": "boolean"}))?;
+    assert_eq!(recompiled, ["http://a.com/c.json"]);
+
+    let (schemas, sch) = session.schema("http://a.com/a.json").unwrap();
+    assert_eq!(hover(schemas, sch).types, [Type::Integer]);
+
+    // removing b.json breaks a.json
+    let recompiled = session.remove("http://a.com/b.json")?;
+    assert_eq!(recompiled, ["http://a.com/a.json"]);
+    assert!(session.schema("http://a.com/a.json").is_none());
+    assert!(!session.diagnostics("http://a.com/a.json").is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_hover_types() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let sch = compiler.compile_value(
+        json!({"type": ["number", "null"], "allOf": [{"type": "integer"}]}),
+        &mut schemas,
+    )?;
+    let h = hover(&schemas, sch);
+    assert_eq!(h.types, [Type::Integer]);
+    assert!(!h.unsatisfiable);
+
+    let sch = compiler.compile_value(
+        json!({"type": "string", "allOf": [{"type": "integer"}]}),
+        &mut schemas,
+    )?;
+    let h = hover(&schemas, sch);
+    assert!(h.types.is_empty());
+    assert!(h.unsatisfiable);
+    Ok(())
+}
+
+#[test]
+fn test_diagnostics() -> Result<(), Box<dyn Error>> {
+    let mut session = Session::new();
+    session.update(
+        "http://a.com/a.json",
+        json!({"properties": {"name": {"pattern": "(abc"}}}),
+    )?;
+    let diagnostics = session.diagnostics("http://a.com/a.json");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].url, "http://a.com/a.json");
+    assert_eq!(diagnostics[0].ptr, "/properties/name/pattern");
+    Ok(())
+}
+
+#[test]
+fn test_completion() -> Result<(), Box<dyn Error>> {
+    let mut session = Session::new();
+    session.update(
+        "http://a.com/a.json",
+        json!({
+            "allOf": [{"properties": {"id": true}}],
+            "properties": {
+                "kind": {"enum": ["user", "group"]},
+                "name": {"title": "Name"}
+            }
+        }),
+    )?;
+    let (schemas, sch) = session.schema("http://a.com/a.json").unwrap();
+    let props = completion(schemas, sch)
+        .properties
+        .into_iter()
+        .map(|(pname, _)| pname)
+        .collect::<Vec<_>>();
+    assert_eq!(props, ["id", "kind", "name"]);
+
+    let (schemas, sch) = session
+        .schema("http://a.com/a.json#/properties/kind")
+        .unwrap();
+    assert_eq!(
+        completion(schemas, sch).values,
+        [json!("user"), json!("group")]
+    );
+
+    let (schemas, sch) = session
+        .schema("http://a.com/a.json#/properties/name")
+        .unwrap();
+    assert_eq!(hover(schemas, sch).title.as_deref(), Some("Name"));
+    Ok(())
+}
+
+#[test]
+fn test_completion_at() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "schema.json",
+        json!({
+            "properties": {
+                "servers": {
+                    "type": "array",
+                    "items": {
+                        "oneOf": [
+                            {
+                                "required": ["socket"],
+                                "properties": {"socket": {"type": "string"}}
+                            },
+                            {
+                                "required": ["host"],
+                                "properties": {
+                                    "host": {"type": "string"},
+                                    "scheme": {"enum": ["http", "https"]}
+                                }
+                            }
+                        ]
+                    }
+                }
+            },
+            "dependentSchemas": {
+                "servers": {"properties": {"timeout": {"type": "integer"}}}
+            }
+        }),
+    )?;
+    let sch = compiler.compile("schema.json", &mut schemas)?;
+    let props = |v: &Value, ptr: &str| {
+        completion_at(&schemas, v, sch, ptr)
+            .unwrap()
+            .properties
+            .into_iter()
+            .map(|(pname, _)| pname)
+            .collect::<Vec<_>>()
+    };
+
+    let v = json!({});
+    assert_eq!(props(&v, ""), ["servers"]);
+    let v = json!({"servers": [{"host": "a.com"}, {}]});
+    assert_eq!(props(&v, ""), ["servers", "timeout"]);
+    assert_eq!(props(&v, "/servers/0"), ["host", "scheme"]);
+    // none of oneOf matches
+    assert_eq!(props(&v, "/servers/1"), ["host", "scheme", "socket"]);
+    // does not exist yet
+    assert_eq!(props(&v, "/servers/2"), ["host", "scheme", "socket"]);
+
+    let values = completion_at(&schemas, &v, sch, "/servers/0/scheme")
+        .unwrap()
+        .values;
+    assert_eq!(values, [json!("http"), json!("https")]);
+    assert!(completion_at(&schemas, &v, sch, "servers").is_none());
+    Ok(())
+}
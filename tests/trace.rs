@@ -0,0 +1,104 @@
+use std::error::Error;
+
+use boon::{Compiler, KeywordOutcome, Schemas, ValidationOptions};
+use serde_json::json;
+
+#[test]
+fn test_trace() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://example.com/schema.json",
+        json!({
+            "type": "object",
+            "minLength": 1,
+            "properties": {"a": {"type": "string"}},
+            "anyOf": [{"required": ["a"]}, {"required": ["b"]}],
+            "unevaluatedProperties": false
+        }),
+    )?;
+    let sch = compiler.compile("http://example.com/schema.json", &mut schemas)?;
+
+    let instance = json!({"a": 1, "c": 2});
+    let options = ValidationOptions::new();
+    let (result, trace) = schemas.validate_with_trace(&instance, sch, &options);
+    assert!(result.is_err());
+
+    let root = &trace[0];
+    assert_eq!(root.schema_url, "http://example.com/schema.json#");
+    assert!(!root.valid);
+    let outcome = |kw| root.keywords.iter().find(|(k, _)| *k == kw).unwrap().1;
+    assert_eq!(outcome("type"), KeywordOutcome::Valid);
+    assert_eq!(outcome("minLength"), KeywordOutcome::Skipped);
+    assert_eq!(outcome("properties"), KeywordOutcome::Invalid);
+    assert_eq!(outcome("anyOf"), KeywordOutcome::Valid);
+    assert_eq!(outcome("unevaluatedProperties"), KeywordOutcome::Invalid);
+
+    let urls = trace.iter().map(|e| e.schema_url).collect::<Vec<_>>();
+    assert_eq!(
+        urls,
+        [
+            "http://example.com/schema.json#",
+            "http://example.com/schema.json#/properties/a",
+            "http://example.com/schema.json#/anyOf/0",
+            "http://example.com/schema.json#/anyOf/1",
+            "http://example.com/schema.json#/unevaluatedProperties",
+        ]
+    );
+    // failed anyOf branch is traced too
+    assert!(trace[2].valid);
+    assert!(!trace[3].valid);
+    assert_eq!(trace[4].instance_location.to_string(), "/c");
+    Ok(())
+}
+
+#[test]
+fn test_trace_dynamic_scope() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://example.com/tree.json",
+        json!({
+            "$id": "http://example.com/tree.json",
+            "$dynamicAnchor": "node",
+            "properties": {
+                "children": {"type": "array", "items": {"$dynamicRef": "#node"}}
+            }
+        }),
+    )?;
+    compiler.add_resource(
+        "http://example.com/strict-tree.json",
+        json!({
+            "$id": "http://example.com/strict-tree.json",
+            "$dynamicAnchor": "node",
+            "$ref": "tree.json",
+            "unevaluatedProperties": false
+        }),
+    )?;
+    let sch = compiler.compile("http://example.com/strict-tree.json", &mut schemas)?;
+
+    let instance = json!({"children": [{"x": 1}]});
+    let options = ValidationOptions::new();
+    let (result, trace) = schemas.validate_with_trace(&instance, sch, &options);
+    assert!(result.is_err());
+
+    let event = trace
+        .iter()
+        .find(|e| e.ref_keyword == Some("$dynamicRef"))
+        .unwrap();
+    assert_eq!(event.schema_url, "http://example.com/strict-tree.json#");
+    assert_eq!(event.instance_location.to_string(), "/children/0");
+    assert_eq!(
+        event.scope,
+        [
+            "http://example.com/strict-tree.json#",
+            "http://example.com/tree.json#",
+            "http://example.com/tree.json#/properties/children",
+            "http://example.com/tree.json#/properties/children/items",
+            "http://example.com/strict-tree.json#",
+        ]
+    );
+    assert!(!event.valid);
+    assert!(event.to_string().contains("(via $dynamicRef)"));
+    Ok(())
+}
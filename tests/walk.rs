@@ -0,0 +1,100 @@
+use std::error::Error;
+
+use boon::{Compiler, Draft, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_walk() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource(
+        "http://example.com/root.json",
+        json!({
+            "allOf": [{"$ref": "other.json"}, {"required": ["a"]}],
+            "properties": {
+                "a": {"$ref": "#/$defs/a"},
+                "b": {"$ref": "#/$defs/a"}
+            },
+            "patternProperties": {"^x-": true},
+            "$defs": {"a": {"items": {"$ref": "#"}}}
+        }),
+    )?;
+    compiler.add_resource(
+        "http://example.com/other.json",
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "items": [{"type": "string"}]
+        }),
+    )?;
+    let sch = compiler.compile("http://example.com/root.json", &mut schemas)?;
+
+    let mut visits = vec![];
+    schemas.walk(sch, |v| {
+        let path = v.keyword_path.as_ref().map(ToString::to_string);
+        visits.push((v.location.to_owned(), path, v.draft, v.depth));
+    });
+    let root = "http://example.com/root.json#";
+    let other = "http://example.com/other.json#";
+    assert_eq!(
+        visits,
+        [
+            (root.to_owned(), None, Draft::V2020_12, 0),
+            (
+                format!("{root}/allOf/0"),
+                Some("allOf/0".into()),
+                Draft::V2020_12,
+                1
+            ),
+            (other.to_owned(), Some("$ref".into()), Draft::V7, 2),
+            (
+                format!("{other}/items/0"),
+                Some("items/0".into()),
+                Draft::V7,
+                3
+            ),
+            (
+                format!("{root}/allOf/1"),
+                Some("allOf/1".into()),
+                Draft::V2020_12,
+                1
+            ),
+            (
+                format!("{root}/properties/a"),
+                Some("properties/a".into()),
+                Draft::V2020_12,
+                1
+            ),
+            (
+                format!("{root}/$defs/a"),
+                Some("$ref".into()),
+                Draft::V2020_12,
+                2
+            ),
+            (
+                format!("{root}/$defs/a/items"),
+                Some("items".into()),
+                Draft::V2020_12,
+                3
+            ),
+            (
+                format!("{root}/properties/b"),
+                Some("properties/b".into()),
+                Draft::V2020_12,
+                1
+            ),
+            (
+                format!("{root}/patternProperties/^x-"),
+                Some("patternProperties/^x-".into()),
+                Draft::V2020_12,
+                1
+            ),
+        ]
+    );
+
+    // parent links
+    let mut parents = vec![];
+    schemas.walk(sch, |v| parents.push(v.parent));
+    assert_eq!(parents[0], None);
+    assert_eq!(parents[1], Some(sch));
+    Ok(())
+}
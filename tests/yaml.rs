@@ -0,0 +1,65 @@
+#![cfg(feature = "yaml")]
+
+use std::error::Error;
+
+use boon::{CompileError, Compiler, Schemas};
+use serde_json::json;
+
+#[test]
+fn test_add_resource_yaml() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    compiler.add_resource_yaml(
+        "http://example.com/openapi.yaml",
+        "
+components:
+  schemas:
+    Error:
+      type: object
+      required: [code]
+      properties:
+        code: {type: integer}
+responses:
+  404:
+    $ref: '#/components/schemas/Error'
+",
+    )?;
+    let sch = compiler.compile(
+        "http://example.com/openapi.yaml#/responses/404",
+        &mut schemas,
+    )?;
+    assert!(schemas.validate(&json!({"code": 404}), sch).is_ok());
+    assert!(schemas.validate(&json!({"code": "404"}), sch).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_yaml_file() -> Result<(), Box<dyn Error>> {
+    let mut schemas = Schemas::new();
+    let mut compiler = Compiler::new();
+    let sch = compiler.compile("tests/examples/schema.yml", &mut schemas)?;
+    assert!(schemas
+        .validate(&json!({"firstName": "a", "lastName": "b"}), sch)
+        .is_ok());
+    assert!(schemas.validate(&json!({"firstName": "a"}), sch).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_yaml_errors() {
+    for (text, want) in [
+        ("type: [string\n", "line 2"),
+        ("a: 1\nmaximum: .inf\n", "line 2 column 10"),
+        ("a: 1\na: 2\n", "duplicate key"),
+        ("a:\n  ? [1]\n  : x\n", "line 2"),
+    ] {
+        let mut compiler = Compiler::new();
+        let Err(e) = compiler.add_resource_yaml("schema.yaml", text) else {
+            panic!("{text:?} must fail");
+        };
+        let CompileError::LoadUrlError { src, .. } = e else {
+            panic!("{text:?}: want LoadUrlError got {e:?}");
+        };
+        assert!(src.to_string().contains(want), "{text:?}: {src}");
+    }
+}